@@ -1,21 +1,27 @@
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Attribute, Binary, CosmosMsg, Decimal, Deps,
-    DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    entry_point, from_binary, to_binary, Addr, Api, Attribute, Binary, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128,
 };
 use cw20::Cw20ReceiveMsg;
 use oraiswap::math::Converter128;
 
 use crate::state::{
-    read_config, read_token_ratio, store_config, store_token_ratio, token_ratio_remove, Config,
+    read_config, read_token_from, read_token_ratio, read_token_ratio_to, read_token_ratios,
+    read_token_ratios_paged, read_token_ratios_to, store_config, store_token_from,
+    store_token_ratio, store_token_ratio_to, token_ratio_remove, token_ratio_to_remove, Config,
 };
 
 use oraiswap::converter::{
-    ConfigResponse, ConvertInfoResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg, TokenInfo, TokenRatio,
+    BalancesResponse, ConfigResponse, ConvertInfoResponse, ConvertPairResponse,
+    ConvertPairsResponse, ConvertSourcesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, QueryMsg, TokenInfo, TokenRatio, WithdrawAsset,
 };
 
 use oraiswap::asset::{Asset, AssetInfo};
 
+/// most assets a convert route can go through, the first one included
+const MAX_ROUTE_LENGTH: usize = 5;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -27,6 +33,7 @@ pub fn instantiate(
         deps.storage,
         &Config {
             owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            paused: false,
         },
     )?;
 
@@ -38,11 +45,25 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
-        ExecuteMsg::UpdatePair { from, to } => update_pair(deps, info, from, to),
-        ExecuteMsg::UnregisterPair { from } => unregister_pair(deps, info, from),
-        ExecuteMsg::Convert {} => convert(deps, env, info),
-        ExecuteMsg::ConvertReverse { from_asset } => convert_reverse(deps, env, info, from_asset),
-        ExecuteMsg::WithdrawTokens { asset_infos } => withdraw_tokens(deps, env, info, asset_infos),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, info, paused),
+        ExecuteMsg::UpdatePair {
+            from,
+            to,
+            ratio,
+            set_default,
+        } => update_pair(deps, info, from, to, ratio, set_default),
+        ExecuteMsg::UnregisterPair { from, to } => unregister_pair(deps, info, from, to),
+        ExecuteMsg::Convert {
+            to_asset,
+            recipient,
+            minimum_receive,
+        } => convert(deps, env, info, to_asset, recipient, minimum_receive),
+        ExecuteMsg::ConvertReverse {
+            from_asset,
+            recipient,
+        } => convert_reverse(deps, env, info, from_asset, recipient),
+        ExecuteMsg::ConvertRoute { route } => convert_route(deps, env, info, route),
+        ExecuteMsg::WithdrawTokens { assets } => withdraw_tokens(deps, env, info, assets),
     }
 }
 
@@ -60,18 +81,75 @@ pub fn update_config(deps: DepsMut, info: MessageInfo, owner: Addr) -> StdResult
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+pub fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    config.paused = paused;
+
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_paused"),
+        ("paused", &paused.to_string()),
+    ]))
+}
+
+/// the validated recipient of a conversion, the sender when none is given
+fn read_recipient(api: &dyn Api, recipient: Option<Addr>, sender: &str) -> StdResult<Addr> {
+    api.addr_validate(
+        recipient
+            .as_ref()
+            .map_or(sender, |recipient| recipient.as_str()),
+    )
+}
+
+fn assert_minimum_receive(to_amount: Uint128, minimum_receive: Option<Uint128>) -> StdResult<()> {
+    if let Some(minimum_receive) = minimum_receive {
+        if to_amount < minimum_receive {
+            return Err(StdError::generic_err(format!(
+                "Assertion failed; minimum receive amount: {}, convert amount: {}",
+                minimum_receive, to_amount
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn assert_not_paused(storage: &dyn Storage) -> StdResult<()> {
+    if read_config(storage)?.paused {
+        return Err(StdError::generic_err("paused"));
+    }
+    Ok(())
+}
+
 pub fn receive_cw20(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
+    assert_not_paused(deps.storage)?;
+
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Convert {}) => {
+        Ok(Cw20HookMsg::Convert {
+            to_asset,
+            recipient,
+            minimum_receive,
+        }) => {
             // check permission
             let token_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-            let token_ratio = read_token_ratio(deps.storage, token_raw.as_slice())?;
+            let token_ratio = read_convert_ratio(
+                deps.storage,
+                deps.api,
+                token_raw.as_slice(),
+                to_asset.as_ref(),
+            )?;
             let amount = cw20_msg.amount * token_ratio.ratio;
+            assert_minimum_receive(amount, minimum_receive)?;
             let message = Asset {
                 info: token_ratio.info,
                 amount: amount.clone(),
@@ -79,7 +157,7 @@ pub fn receive_cw20(
             .into_msg(
                 None,
                 &deps.querier,
-                deps.api.addr_validate(cw20_msg.sender.as_str())?,
+                read_recipient(deps.api, recipient, &cw20_msg.sender)?,
             )?;
 
             Ok(Response::new().add_message(message).add_attributes(vec![
@@ -88,9 +166,12 @@ pub fn receive_cw20(
                 ("to_amount", &amount.to_string()),
             ]))
         }
-        Ok(Cw20HookMsg::ConvertReverse { from }) => {
+        Ok(Cw20HookMsg::ConvertReverse { from, recipient }) => {
+            // the sending token selects which target of from is converted back
             let asset_key = from.to_vec(deps.api)?;
-            let token_ratio = read_token_ratio(deps.storage, &asset_key)?;
+            let token_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+            let token_ratio = read_token_ratio_to(deps.storage, &asset_key, token_raw.as_slice())
+                .map_err(|_| StdError::generic_err("invalid cw20 hook message"))?;
 
             if let AssetInfo::Token { contract_addr } = token_ratio.info {
                 if contract_addr != info.sender {
@@ -106,7 +187,7 @@ pub fn receive_cw20(
                 .into_msg(
                     None,
                     &deps.querier,
-                    deps.api.addr_validate(cw20_msg.sender.as_str())?,
+                    read_recipient(deps.api, recipient, &cw20_msg.sender)?,
                 )?;
 
                 Ok(Response::new().add_message(message).add_attributes(vec![
@@ -127,6 +208,8 @@ pub fn update_pair(
     info: MessageInfo,
     from: TokenInfo,
     to: TokenInfo,
+    ratio: Option<Decimal>,
+    set_default: Option<bool>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
@@ -135,20 +218,47 @@ pub fn update_pair(
 
     let asset_key = from.info.to_vec(deps.api)?;
 
-    let token_ratio = TokenRatio {
-        info: to.info,
-        ratio: Decimal::from_ratio(
+    // explicit ratio takes precedence over the decimals formula
+    let ratio = match ratio {
+        Some(ratio) => {
+            if ratio.is_zero() {
+                return Err(StdError::generic_err("ratio must be greater than zero"));
+            }
+            ratio
+        }
+        None => Decimal::from_ratio(
             10u128.pow(to.decimals.into()),
             10u128.pow(from.decimals.into()),
         ),
     };
 
-    store_token_ratio(deps.storage, &asset_key, &token_ratio)?;
+    let token_ratio = TokenRatio {
+        info: to.info,
+        ratio,
+    };
+
+    let to_key = token_ratio.info.to_vec(deps.api)?;
+    store_token_ratio_to(deps.storage, &asset_key, &to_key, &token_ratio)?;
+    // the latest registered target becomes the default one unless set_default is false,
+    // re-pricing the current default keeps it in sync
+    let is_default = match read_token_ratio(deps.storage, &asset_key) {
+        Ok(default_ratio) => set_default.unwrap_or(true) || default_ratio.info == token_ratio.info,
+        Err(_) => true,
+    };
+    if is_default {
+        store_token_ratio(deps.storage, &asset_key, &token_ratio)?;
+    }
+    store_token_from(deps.storage, &asset_key, &from.info)?;
 
     Ok(Response::new().add_attribute("action", "update_pair"))
 }
 
-pub fn unregister_pair(deps: DepsMut, info: MessageInfo, from: TokenInfo) -> StdResult<Response> {
+pub fn unregister_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: TokenInfo,
+    to: Option<TokenInfo>,
+) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
@@ -156,12 +266,49 @@ pub fn unregister_pair(deps: DepsMut, info: MessageInfo, from: TokenInfo) -> Std
 
     let asset_key = from.info.to_vec(deps.api)?;
 
-    token_ratio_remove(deps.storage, &asset_key);
+    match to {
+        Some(to) => {
+            token_ratio_to_remove(deps.storage, &asset_key, &to.info.to_vec(deps.api)?);
+            // fall back to another remaining target when the default one is removed
+            if read_token_ratio(deps.storage, &asset_key)?.info == to.info {
+                match read_token_ratios_to(deps.storage, &asset_key)?.pop() {
+                    Some((_, token_ratio)) => {
+                        store_token_ratio(deps.storage, &asset_key, &token_ratio)?
+                    }
+                    None => token_ratio_remove(deps.storage, &asset_key)?,
+                }
+            }
+        }
+        None => token_ratio_remove(deps.storage, &asset_key)?,
+    }
 
     Ok(Response::new().add_attribute("action", "unregister_convert_info"))
 }
 
-pub fn convert(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Response> {
+/// the ratio to the requested target, or the default one when no target is given
+fn read_convert_ratio(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    from_key: &[u8],
+    to_asset: Option<&AssetInfo>,
+) -> StdResult<TokenRatio> {
+    match to_asset {
+        Some(to_asset) => read_token_ratio_to(storage, from_key, &to_asset.to_vec(api)?),
+        None => read_token_ratio(storage, from_key),
+    }
+}
+
+pub fn convert(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    to_asset: Option<AssetInfo>,
+    recipient: Option<Addr>,
+    minimum_receive: Option<Uint128>,
+) -> StdResult<Response> {
+    assert_not_paused(deps.storage)?;
+    let recipient = read_recipient(deps.api, recipient, info.sender.as_str())?;
+
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut attributes: Vec<Attribute> = vec![];
     attributes.push(("action", "convert_token").into());
@@ -171,8 +318,9 @@ pub fn convert(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Respons
         let amount = native_coin.amount;
         attributes.push(("denom", native_coin.denom.clone()).into());
         attributes.push(("from_amount", amount.to_string()).into());
-        let token_ratio = read_token_ratio(deps.storage, asset_key)?;
+        let token_ratio = read_convert_ratio(deps.storage, deps.api, asset_key, to_asset.as_ref())?;
         let to_amount = amount * token_ratio.ratio;
+        assert_minimum_receive(to_amount, minimum_receive)?;
 
         attributes.push(("to_amount", to_amount).into());
 
@@ -180,7 +328,7 @@ pub fn convert(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Respons
             info: token_ratio.info,
             amount: to_amount.clone(),
         }
-        .into_msg(None, &deps.querier, info.sender.clone())?;
+        .into_msg(None, &deps.querier, recipient.clone())?;
 
         messages.push(message);
     }
@@ -195,19 +343,36 @@ pub fn convert_reverse(
     _env: Env,
     info: MessageInfo,
     from_asset: AssetInfo,
+    recipient: Option<Addr>,
 ) -> StdResult<Response> {
+    assert_not_paused(deps.storage)?;
+    let recipient = read_recipient(deps.api, recipient, info.sender.as_str())?;
+
     let asset_key = from_asset.to_vec(deps.api)?;
-    let token_ratio = read_token_ratio(deps.storage, &asset_key)?;
+    // the sent denom selects which native target of from_asset is converted back
+    let token_ratio = info
+        .funds
+        .iter()
+        .find_map(|coin| read_token_ratio_to(deps.storage, &asset_key, coin.denom.as_bytes()).ok())
+        .map_or_else(|| read_token_ratio(deps.storage, &asset_key), Ok)?;
 
     if let AssetInfo::NativeToken { denom } = token_ratio.info {
         //check funds includes To token
         if let Some(native_coin) = info.funds.iter().find(|a| a.denom.eq(&denom)) {
+            // reject any other coins sent alongside, otherwise they would be stranded in the contract
+            if info.funds.len() > 1 {
+                return Err(StdError::generic_err(format!(
+                    "convert_reverse() only accepts {} funds",
+                    denom
+                )));
+            }
+
             let amount = native_coin.amount.checked_div_decimal(token_ratio.ratio)?;
             let message = Asset {
                 info: from_asset,
                 amount: amount.clone(),
             }
-            .into_msg(None, &deps.querier, info.sender.clone())?;
+            .into_msg(None, &deps.querier, recipient)?;
 
             return Ok(Response::new().add_message(message).add_attributes(vec![
                 ("action", "convert_token_reverse"),
@@ -223,11 +388,99 @@ pub fn convert_reverse(
     }
 }
 
+pub fn convert_route(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    route: Vec<AssetInfo>,
+) -> StdResult<Response> {
+    assert_not_paused(deps.storage)?;
+
+    if route.len() < 2 || route.len() > MAX_ROUTE_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "route must have between 2 and {} assets",
+            MAX_ROUTE_LENGTH
+        )));
+    }
+
+    // an asset showing up twice means the route loops
+    for (index, asset_info) in route.iter().enumerate() {
+        if route[..index].contains(asset_info) {
+            return Err(StdError::generic_err(format!(
+                "route visits {} more than once",
+                asset_info
+            )));
+        }
+    }
+
+    let denom = match &route[0] {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => {
+            return Err(StdError::generic_err(
+                "route must start from a native token",
+            ))
+        }
+    };
+    let native_coin = match info.funds.as_slice() {
+        [native_coin] if native_coin.denom.eq(denom) => native_coin,
+        _ => {
+            return Err(StdError::generic_err(format!(
+                "convert_route() only accepts {} funds",
+                denom
+            )))
+        }
+    };
+
+    // compose the ratios so the amount is rounded only once
+    let mut ratio = Decimal::one();
+    for hop in route.windows(2) {
+        let token_ratio = read_token_ratio_to(
+            deps.storage,
+            &hop[0].to_vec(deps.api)?,
+            &hop[1].to_vec(deps.api)?,
+        )
+        .map_err(|_| {
+            StdError::generic_err(format!("no conversion from {} to {}", hop[0], hop[1]))
+        })?;
+        ratio = ratio * token_ratio.ratio;
+    }
+
+    let to_amount = native_coin.amount * ratio;
+    let to_asset = route[route.len() - 1].clone();
+    let message = Asset {
+        info: to_asset,
+        amount: to_amount,
+    }
+    .into_msg(None, &deps.querier, info.sender.clone())?;
+
+    Ok(Response::new().add_message(message).add_attributes(vec![
+        ("action", "convert_route"),
+        (
+            "route",
+            &route
+                .iter()
+                .map(|asset_info| asset_info.to_string())
+                .collect::<Vec<String>>()
+                .join(" -> "),
+        ),
+        ("from_amount", &native_coin.amount.to_string()),
+        ("to_amount", &to_amount.to_string()),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::ConvertInfo { asset_info } => to_binary(&query_convert_info(deps, asset_info)?),
+        QueryMsg::ConvertInfo {
+            asset_info,
+            to_asset,
+        } => to_binary(&query_convert_info(deps, asset_info, to_asset)?),
+        QueryMsg::ConvertSources { to_asset } => to_binary(&query_convert_sources(deps, to_asset)?),
+        QueryMsg::ConvertPairs { start_after, limit } => {
+            to_binary(&query_convert_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::Balances { asset_infos } => to_binary(&query_balances(deps, env, asset_infos)?),
     }
 }
 
@@ -235,22 +488,91 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?,
+        paused: state.paused,
     };
 
     Ok(resp)
 }
 
-pub fn query_convert_info(deps: Deps, asset_info: AssetInfo) -> StdResult<ConvertInfoResponse> {
+pub fn query_convert_info(
+    deps: Deps,
+    asset_info: AssetInfo,
+    to_asset: Option<AssetInfo>,
+) -> StdResult<ConvertInfoResponse> {
     let asset_key = asset_info.to_vec(deps.api)?;
-    let token_ratio = read_token_ratio(deps.storage, &asset_key)?;
+    let token_ratio = read_convert_ratio(deps.storage, deps.api, &asset_key, to_asset.as_ref())?;
     Ok(ConvertInfoResponse { token_ratio })
 }
 
+pub fn query_convert_sources(deps: Deps, to_asset: AssetInfo) -> StdResult<ConvertSourcesResponse> {
+    let to_key = to_asset.to_vec(deps.api)?;
+    let mut from_assets = vec![];
+    // every from asset with a target also has a default ratio
+    for (asset_key, _) in read_token_ratios(deps.storage)? {
+        if read_token_ratio_to(deps.storage, &asset_key, &to_key).is_err() {
+            continue;
+        }
+        from_assets.push(read_from_asset(deps, asset_key)?);
+    }
+
+    Ok(ConvertSourcesResponse { from_assets })
+}
+
+pub fn query_convert_pairs(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<ConvertPairsResponse> {
+    let start_after = start_after
+        .map(|asset_info| asset_info.to_vec(deps.api))
+        .transpose()?;
+    let pairs = read_token_ratios_paged(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(asset_key, token_ratio)| {
+            Ok(ConvertPairResponse {
+                from: read_from_asset(deps, asset_key)?,
+                token_ratio,
+            })
+        })
+        .collect::<StdResult<Vec<ConvertPairResponse>>>()?;
+
+    Ok(ConvertPairsResponse { pairs })
+}
+
+fn read_from_asset(deps: Deps, asset_key: Vec<u8>) -> StdResult<AssetInfo> {
+    Ok(match read_token_from(deps.storage, &asset_key)? {
+        Some(from_asset) => from_asset,
+        // pairs registered before the from asset was stored, recover it from the raw key
+        None => match String::from_utf8(asset_key) {
+            Ok(denom) => AssetInfo::NativeToken { denom },
+            Err(err) => AssetInfo::Token {
+                contract_addr: deps.api.addr_humanize(&err.into_bytes().into())?,
+            },
+        },
+    })
+}
+
+pub fn query_balances(
+    deps: Deps,
+    env: Env,
+    asset_infos: Vec<AssetInfo>,
+) -> StdResult<BalancesResponse> {
+    let balances = asset_infos
+        .into_iter()
+        .map(|info| {
+            let amount = info.query_pool(&deps.querier, env.contract.address.clone())?;
+            Ok(Asset { info, amount })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    Ok(BalancesResponse { balances })
+}
+
 pub fn withdraw_tokens(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    asset_infos: Vec<AssetInfo>,
+    assets: Vec<WithdrawAsset>,
 ) -> StdResult<Response> {
     let config = read_config(deps.storage)?;
     let owner = deps.api.addr_humanize(&config.owner)?;
@@ -260,15 +582,18 @@ pub fn withdraw_tokens(
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut attributes: Vec<Attribute> = vec![("action", "withdraw_tokens").into()];
 
-    for asset in asset_infos {
-        let balance = asset.query_pool(&deps.querier, env.contract.address.clone())?;
+    for asset in assets {
+        let balance = asset
+            .info
+            .query_pool(&deps.querier, env.contract.address.clone())?;
+        let amount = asset.amount.map_or(balance, |amount| amount.min(balance));
         let message = Asset {
-            info: asset,
-            amount: balance.clone(),
+            info: asset.info,
+            amount,
         }
         .into_msg(None, &deps.querier, owner.clone())?;
         messages.push(message);
-        attributes.push(("amount", balance.to_string()).into())
+        attributes.push(("amount", amount.to_string()).into())
     }
 
     Ok(Response::new()
@@ -277,6 +602,12 @@ pub fn withdraw_tokens(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // ratios used to be keyed by from only, register each of them as a (from, to) target
+    for (asset_key, token_ratio) in read_token_ratios(deps.storage)? {
+        let to_key = token_ratio.info.to_vec(deps.api)?;
+        store_token_ratio_to(deps.storage, &asset_key, &to_key, &token_ratio)?;
+    }
+
     Ok(Response::default())
 }
@@ -1,16 +1,21 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 
 static KEY_CONFIG: &[u8] = b"config";
 static KEY_TOKEN_RATIO: &[u8] = b"token_ratio";
+static KEY_TOKEN_FROM: &[u8] = b"token_from";
+static KEY_TOKEN_RATIO_TO: &[u8] = b"token_ratio_to";
 
-use oraiswap::converter::TokenRatio;
+use oraiswap::{asset::AssetInfo, converter::TokenRatio};
 
 #[cw_serde]
 pub struct Config {
     pub owner: CanonicalAddr,
+    /// halts every conversion, configs stored before the switch existed are not paused
+    #[serde(default)]
+    pub paused: bool,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -33,10 +38,89 @@ pub fn read_token_ratio(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<To
     ReadonlyBucket::new(storage, KEY_TOKEN_RATIO).load(asset_key)
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// a page of the registered pairs, keyed by the from asset key
+pub fn read_token_ratios_paged(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Vec<u8>, TokenRatio)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // keys have no fixed length, so the first key after start_after is start_after with a 0 byte appended
+    let start = start_after.map(|mut key| {
+        key.push(0);
+        key
+    });
+
+    ReadonlyBucket::new(storage, KEY_TOKEN_RATIO)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+/// full scan over all registered pairs, keyed by the from asset key
+pub fn read_token_ratios(storage: &dyn Storage) -> StdResult<Vec<(Vec<u8>, TokenRatio)>> {
+    ReadonlyBucket::new(storage, KEY_TOKEN_RATIO)
+        .range(None, None, Order::Ascending)
+        .collect()
+}
+
+// every registered target of a from asset, the ratio under KEY_TOKEN_RATIO is the default one
+pub fn store_token_ratio_to(
+    storage: &mut dyn Storage,
+    from_key: &[u8],
+    to_key: &[u8],
+    token_ratio: &TokenRatio,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[KEY_TOKEN_RATIO_TO, from_key]).save(to_key, token_ratio)
+}
+
+pub fn read_token_ratio_to(
+    storage: &dyn Storage,
+    from_key: &[u8],
+    to_key: &[u8],
+) -> StdResult<TokenRatio> {
+    ReadonlyBucket::multilevel(storage, &[KEY_TOKEN_RATIO_TO, from_key]).load(to_key)
+}
+
+pub fn read_token_ratios_to(
+    storage: &dyn Storage,
+    from_key: &[u8],
+) -> StdResult<Vec<(Vec<u8>, TokenRatio)>> {
+    ReadonlyBucket::multilevel(storage, &[KEY_TOKEN_RATIO_TO, from_key])
+        .range(None, None, Order::Ascending)
+        .collect()
+}
+
+pub fn token_ratio_to_remove(storage: &mut dyn Storage, from_key: &[u8], to_key: &[u8]) {
+    Bucket::<TokenRatio>::multilevel(storage, &[KEY_TOKEN_RATIO_TO, from_key]).remove(to_key)
+}
+
+// token ratios are keyed by raw asset key, so the from asset info is kept to be returned in queries
+pub fn store_token_from(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    asset_info: &AssetInfo,
+) -> StdResult<()> {
+    Bucket::new(storage, KEY_TOKEN_FROM).save(asset_key, asset_info)
+}
+
+pub fn read_token_from(storage: &dyn Storage, asset_key: &[u8]) -> StdResult<Option<AssetInfo>> {
+    ReadonlyBucket::new(storage, KEY_TOKEN_FROM).may_load(asset_key)
+}
+
 // pub fn token_ratio_store<'a>(storage: &'a mut dyn Storage) -> Bucket<'a, TokenRatio> {
 //     Bucket::new(storage, KEY_TOKEN_RATIO)
 // }
 
-pub fn token_ratio_remove<'a>(storage: &'a mut dyn Storage, asset_key: &[u8]) {
-    Bucket::<'a, TokenRatio>::new(storage, KEY_TOKEN_RATIO).remove(asset_key)
+pub fn token_ratio_remove(storage: &mut dyn Storage, asset_key: &[u8]) -> StdResult<()> {
+    for (to_key, _) in read_token_ratios_to(storage, asset_key)? {
+        token_ratio_to_remove(storage, asset_key, &to_key);
+    }
+    Bucket::<TokenRatio>::new(storage, KEY_TOKEN_RATIO).remove(asset_key);
+    Bucket::<AssetInfo>::new(storage, KEY_TOKEN_FROM).remove(asset_key);
+    Ok(())
 }
@@ -1,19 +1,26 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, coin,
-    testing::{mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info},
-    to_binary, Addr, BankMsg, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+    attr, coin, from_binary,
+    testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    },
+    to_binary, Addr, BankMsg, CosmosMsg, Decimal, OwnedDeps, StdError, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use oraiswap::{
-    asset::{AssetInfo, ORAI_DENOM},
-    converter::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, TokenInfo},
+    asset::{Asset, AssetInfo, ORAI_DENOM},
+    converter::{
+        BalancesResponse, ConfigResponse, ConvertPairsResponse, ConvertSourcesResponse,
+        Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, TokenInfo, TokenRatio,
+        WithdrawAsset,
+    },
     math::Converter128,
     testing::ATOM_DENOM,
 };
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::state::store_token_ratio;
 
 #[test]
 fn test_decimal_valid_same_decimal() {
@@ -76,6 +83,8 @@ fn test_convert_reverse() {
             },
             decimals: 6,
         },
+        ratio: None,
+        set_default: None,
     };
 
     //register pair1
@@ -88,6 +97,7 @@ fn test_convert_reverse() {
         from: AssetInfo::Token {
             contract_addr: Addr::unchecked("asset1"),
         },
+        recipient: None,
     };
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         amount: Uint128::from(1u64),
@@ -124,6 +134,7 @@ fn test_convert_reverse() {
         from: AssetInfo::Token {
             contract_addr: Addr::unchecked("asset1"),
         },
+        recipient: None,
     };
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         amount: Uint128::from(1u64),
@@ -151,6 +162,8 @@ fn test_convert_reverse() {
             },
             decimals: 18,
         },
+        ratio: None,
+        set_default: None,
     };
     let info = mock_info("addr", &[]);
     execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
@@ -160,6 +173,7 @@ fn test_convert_reverse() {
         from_asset: AssetInfo::Token {
             contract_addr: Addr::unchecked("asset1"),
         },
+        recipient: None,
     };
 
     //convert 10^12 ORAI to asset1
@@ -194,6 +208,7 @@ fn test_convert_reverse() {
         from_asset: AssetInfo::Token {
             contract_addr: Addr::unchecked("asset1"),
         },
+        recipient: None,
     };
 
     //convert 10^12 ORAI to asset1
@@ -207,6 +222,24 @@ fn test_convert_reverse() {
         ),
         _ => panic!("Must return invalid cw20 hook message"),
     };
+
+    //check if send Orai together with another denom
+    let info = mock_info(
+        "addr",
+        &[
+            coin(1000000000000u128, ORAI_DENOM),
+            coin(1000000000000u128, ATOM_DENOM),
+        ],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+
+    match res {
+        Err(StdError::GenericErr { msg }) => assert_eq!(
+            msg,
+            format!("convert_reverse() only accepts {} funds", ORAI_DENOM)
+        ),
+        _ => panic!("Must reject unexpected funds"),
+    };
 }
 
 #[test]
@@ -233,6 +266,8 @@ fn test_remove_pair() {
             },
             decimals: 16,
         },
+        ratio: None,
+        set_default: None,
     };
     let info = mock_info("addr", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
@@ -244,6 +279,7 @@ fn test_remove_pair() {
             asset_info: AssetInfo::Token {
                 contract_addr: Addr::unchecked("asset1"),
             },
+            to_asset: None,
         },
     )
     .unwrap();
@@ -255,6 +291,7 @@ fn test_remove_pair() {
             },
             decimals: 16,
         },
+        to: None,
     };
     let info = mock_info("addr", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
@@ -265,6 +302,7 @@ fn test_remove_pair() {
             asset_info: AssetInfo::Token {
                 contract_addr: Addr::unchecked("asset1"),
             },
+            to_asset: None,
         },
     );
 
@@ -295,12 +333,18 @@ fn test_withdraw_tokens() {
 
     //test proper withdraw tokens
     let msg = ExecuteMsg::WithdrawTokens {
-        asset_infos: vec![
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.into(),
+        assets: vec![
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.into(),
+                },
+                amount: None,
             },
-            AssetInfo::NativeToken {
-                denom: ATOM_DENOM.into(),
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.into(),
+                },
+                amount: None,
             },
         ],
     };
@@ -332,12 +376,18 @@ fn test_withdraw_tokens() {
 
     //test unauthorized withdraw tokens
     let msg = ExecuteMsg::WithdrawTokens {
-        asset_infos: vec![
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.into(),
+        assets: vec![
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.into(),
+                },
+                amount: None,
             },
-            AssetInfo::NativeToken {
-                denom: ATOM_DENOM.into(),
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.into(),
+                },
+                amount: None,
             },
         ],
     };
@@ -350,3 +400,944 @@ fn test_withdraw_tokens() {
         _ => panic!("Must return unauthorized"),
     };
 }
+
+#[test]
+fn test_update_pair_with_explicit_ratio() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let from = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let to = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+
+    // zero ratio is rejected
+    let msg = ExecuteMsg::UpdatePair {
+        from: from.clone(),
+        to: to.clone(),
+        ratio: Some(Decimal::zero()),
+        set_default: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg }) => assert_eq!(msg, "ratio must be greater than zero"),
+        _ => panic!("Must return generic error"),
+    };
+
+    // 1.05:1 premium, while the decimals formula would give 1:1
+    let msg = ExecuteMsg::UpdatePair {
+        from,
+        to,
+        ratio: Some(Decimal::from_str("1.05").unwrap()),
+        set_default: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let info = mock_info("addr", &[coin(1000000u128, ATOM_DENOM)]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: None,
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(1050000u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_query_convert_sources() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.into(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.into(),
+    };
+    let asset1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked("asset1"),
+    };
+    let asset2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked("asset2"),
+    };
+
+    // atom -> orai, asset1 -> orai, asset2 -> atom
+    for (from, to) in [
+        (atom.clone(), orai.clone()),
+        (asset1.clone(), orai.clone()),
+        (asset2.clone(), atom.clone()),
+    ] {
+        let msg = ExecuteMsg::UpdatePair {
+            from: TokenInfo {
+                info: from,
+                decimals: 6,
+            },
+            to: TokenInfo {
+                info: to,
+                decimals: 6,
+            },
+            ratio: None,
+            set_default: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+
+    let res: ConvertSourcesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertSources {
+                to_asset: orai.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.from_assets.len(), 2);
+    assert!(res.from_assets.contains(&atom));
+    assert!(res.from_assets.contains(&asset1));
+
+    let res: ConvertSourcesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertSources { to_asset: atom },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.from_assets, vec![asset2]);
+
+    // unregistered pairs are no longer listed
+    let msg = ExecuteMsg::UnregisterPair {
+        from: TokenInfo {
+            info: asset1,
+            decimals: 6,
+        },
+        to: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let res: ConvertSourcesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertSources { to_asset: orai },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.from_assets,
+        vec![AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        }]
+    );
+}
+
+#[test]
+fn test_convert_to_selected_target() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let atom = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let orai = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let asset1 = TokenInfo {
+        info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset1"),
+        },
+        decimals: 6,
+    };
+
+    // atom -> orai at 2:1 and atom -> asset1 at 3:1
+    for (to, ratio) in [(orai.clone(), 2u128), (asset1.clone(), 3u128)] {
+        let msg = ExecuteMsg::UpdatePair {
+            from: atom.clone(),
+            to,
+            ratio: Some(Decimal::from_ratio(ratio, 1u128)),
+            set_default: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+
+    let info = mock_info("addr", &[coin(100u128, ATOM_DENOM)]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: Some(orai.info.clone()),
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(200u128, ORAI_DENOM)],
+        }))]
+    );
+
+    let asset1_transfer = SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "asset1".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount: Uint128::from(300u128),
+        })
+        .unwrap(),
+        funds: vec![],
+    }));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: Some(asset1.info.clone()),
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![asset1_transfer.clone()]);
+
+    // without a target the last registered one is used
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: None,
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![asset1_transfer.clone()]);
+
+    // re-pricing another target with set_default off leaves the default one unchanged
+    let msg = ExecuteMsg::UpdatePair {
+        from: atom.clone(),
+        to: orai.clone(),
+        ratio: Some(Decimal::from_ratio(4u128, 1u128)),
+        set_default: Some(false),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: None,
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![asset1_transfer]);
+
+    // an unregistered target is rejected
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: Some(AssetInfo::NativeToken {
+                denom: "usdt".into(),
+            }),
+            recipient: None,
+            minimum_receive: None,
+        },
+    );
+    match res {
+        Err(StdError::NotFound { kind }) => assert_eq!(kind, "oraiswap::converter::TokenRatio"),
+        _ => panic!("Must return not found"),
+    };
+
+    // removing the default target falls back to the remaining one
+    let msg = ExecuteMsg::UnregisterPair {
+        from: atom.clone(),
+        to: Some(asset1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Convert {
+            to_asset: None,
+            recipient: None,
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(400u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_migrate_single_target_ratios() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // a ratio stored before targets were keyed by (from, to)
+    store_token_ratio(
+        deps.as_mut().storage,
+        ATOM_DENOM.as_bytes(),
+        &TokenRatio {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.into(),
+            },
+            ratio: Decimal::one(),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Convert {
+        to_asset: Some(AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        }),
+        recipient: None,
+        minimum_receive: None,
+    };
+    let info = mock_info("addr", &[coin(100u128, ATOM_DENOM)]);
+    assert!(execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).is_err());
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_query_balances() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.into(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.into(),
+    };
+
+    let res: BalancesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balances {
+                asset_infos: vec![orai.clone(), atom.clone()],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.balances,
+        vec![
+            Asset {
+                info: orai,
+                amount: Uint128::from(10000000000u128),
+            },
+            Asset {
+                info: atom,
+                amount: Uint128::zero(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_convert_route() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let atom = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let orai = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let asset1 = TokenInfo {
+        info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset1"),
+        },
+        decimals: 6,
+    };
+
+    // atom -> orai at 2:1 and orai -> asset1 at 3:1, nothing registered from atom to asset1
+    for (from, to, ratio) in [
+        (atom.clone(), orai.clone(), 2u128),
+        (orai.clone(), asset1.clone(), 3u128),
+    ] {
+        let msg = ExecuteMsg::UpdatePair {
+            from,
+            to,
+            ratio: Some(Decimal::from_ratio(ratio, 1u128)),
+            set_default: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+
+    let info = mock_info("addr", &[coin(100u128, ATOM_DENOM)]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::ConvertRoute {
+            route: vec![atom.info.clone(), orai.info.clone(), asset1.info.clone()],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset1".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: Uint128::from(600u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "convert_route"),
+            attr(
+                "route",
+                format!("{} -> {} -> asset1", ATOM_DENOM, ORAI_DENOM)
+            ),
+            attr("from_amount", "100"),
+            attr("to_amount", "600"),
+        ]
+    );
+
+    // no ratio back from asset1
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::ConvertRoute {
+            route: vec![atom.info.clone(), asset1.info.clone()],
+        },
+    );
+    assert!(res.is_err());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::ConvertRoute {
+            route: vec![atom.info.clone(), orai.info.clone(), atom.info.clone()],
+        },
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        StdError::generic_err(format!("route visits {} more than once", ATOM_DENOM))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ConvertRoute {
+            route: vec![atom.info.clone(); 6],
+        },
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        StdError::generic_err("route must have between 2 and 5 assets")
+    );
+}
+
+#[test]
+fn test_set_paused() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let atom = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let orai = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let msg = ExecuteMsg::UpdatePair {
+        from: atom.clone(),
+        to: orai.clone(),
+        ratio: None,
+        set_default: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    // only the owner can pause
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("unauthorized"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert!(config.paused);
+
+    let convert_msg = ExecuteMsg::Convert {
+        to_asset: None,
+        recipient: None,
+        minimum_receive: None,
+    };
+    let convert_info = mock_info("addr", &[coin(100u128, ATOM_DENOM)]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        convert_info.clone(),
+        convert_msg.clone(),
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("paused"));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[coin(100u128, ORAI_DENOM)]),
+        ExecuteMsg::ConvertReverse {
+            from_asset: atom.info.clone(),
+            recipient: None,
+        },
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("paused"));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset1", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Convert {
+                to_asset: None,
+                recipient: None,
+                minimum_receive: None,
+            })
+            .unwrap(),
+        }),
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("paused"));
+
+    // registered pairs survive the pause
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), mock_env(), convert_info.clone(), convert_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: convert_info.sender.to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_convert_to_recipient() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let orai = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    // atom -> orai at 2:1 and asset1 -> orai at 1:1
+    for (from, ratio) in [
+        (
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.into(),
+            },
+            2u128,
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset1"),
+            },
+            1u128,
+        ),
+    ] {
+        let msg = ExecuteMsg::UpdatePair {
+            from: TokenInfo {
+                info: from,
+                decimals: 6,
+            },
+            to: orai.clone(),
+            ratio: Some(Decimal::from_ratio(ratio, 1u128)),
+            set_default: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[coin(100u128, ATOM_DENOM)]),
+        ExecuteMsg::Convert {
+            to_asset: None,
+            recipient: Some(Addr::unchecked("vault")),
+            minimum_receive: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "vault".to_string(),
+            amount: vec![coin(200u128, ORAI_DENOM)],
+        }))]
+    );
+
+    // the cw20 hook pays the recipient rather than the cw20 sender
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset1", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Convert {
+                to_asset: None,
+                recipient: Some(Addr::unchecked("vault")),
+                minimum_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "vault".to_string(),
+            amount: vec![coin(100u128, ORAI_DENOM)],
+        }))]
+    );
+}
+
+#[test]
+fn test_query_convert_pairs() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let token_info = |denom: &str| TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: denom.into(),
+        },
+        decimals: 6,
+    };
+    for (from, to) in [
+        (ORAI_DENOM, "usdt"),
+        ("usdt", ORAI_DENOM),
+        (ATOM_DENOM, ORAI_DENOM),
+    ] {
+        let msg = ExecuteMsg::UpdatePair {
+            from: token_info(from),
+            to: token_info(to),
+            ratio: None,
+            set_default: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    }
+
+    // pages follow the raw from key, so the ibc denom comes first
+    let res: ConvertPairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertPairs {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pairs
+            .iter()
+            .map(|pair| (pair.from.clone(), pair.token_ratio.info.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (token_info(ATOM_DENOM).info, token_info(ORAI_DENOM).info),
+            (token_info(ORAI_DENOM).info, token_info("usdt").info),
+        ]
+    );
+    assert_eq!(res.pairs[0].token_ratio.ratio, Decimal::one());
+
+    let res: ConvertPairsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertPairs {
+                start_after: Some(res.pairs[1].from.clone()),
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.pairs.len(), 1);
+    assert_eq!(res.pairs[0].from, token_info("usdt").info);
+    assert_eq!(res.pairs[0].token_ratio.info, token_info(ORAI_DENOM).info);
+}
+
+#[test]
+fn test_convert_minimum_receive() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let orai = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let atom = TokenInfo {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.into(),
+        },
+        decimals: 6,
+    };
+    let asset1 = TokenInfo {
+        info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset1"),
+        },
+        decimals: 6,
+    };
+    let update_ratios = |deps: &mut OwnedDeps<_, _, _>, ratio: u128| {
+        for from in [atom.clone(), asset1.clone()] {
+            let msg = ExecuteMsg::UpdatePair {
+                from,
+                to: orai.clone(),
+                ratio: Some(Decimal::from_ratio(ratio, 1u128)),
+                set_default: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+        }
+    };
+
+    // simulated at 2:1, then the owner lowers the ratio before the conversions land
+    update_ratios(&mut deps, 2);
+    let convert_info = mock_info("addr", &[coin(100u128, ATOM_DENOM)]);
+    let convert_msg = ExecuteMsg::Convert {
+        to_asset: None,
+        recipient: None,
+        minimum_receive: Some(Uint128::from(200u128)),
+    };
+    let hook_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Convert {
+            to_asset: None,
+            recipient: None,
+            minimum_receive: Some(Uint128::from(200u128)),
+        })
+        .unwrap(),
+    });
+    update_ratios(&mut deps, 1);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        convert_info.clone(),
+        convert_msg.clone(),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        StdError::generic_err("Assertion failed; minimum receive amount: 200, convert amount: 100")
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset1", &[]),
+        hook_msg.clone(),
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        StdError::generic_err("Assertion failed; minimum receive amount: 200, convert amount: 100")
+    );
+
+    // back at the simulated ratio the guard passes
+    update_ratios(&mut deps, 2);
+    let res = execute(deps.as_mut(), mock_env(), convert_info, convert_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(200u128, ORAI_DENOM)],
+        }))]
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset1", &[]),
+        hook_msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_withdraw_partial_amount() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // a requested amount above the balance is clamped to it
+    let msg = ExecuteMsg::WithdrawTokens {
+        assets: vec![
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.into(),
+                },
+                amount: Some(Uint128::from(4000000000u128)),
+            },
+            WithdrawAsset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.into(),
+                },
+                amount: Some(Uint128::from(30000000000u128)),
+            },
+        ],
+    };
+    let info = mock_info("addr", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(4000000000u128, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(20000000000u128, ATOM_DENOM)],
+            }))
+        ]
+    );
+
+    // the bank module would leave the rest of the orai with the converter
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(6000000000u128, ORAI_DENOM)]);
+    let res: BalancesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balances {
+                asset_infos: vec![AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.into(),
+                }],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.balances[0].amount, Uint128::from(6000000000u128));
+}
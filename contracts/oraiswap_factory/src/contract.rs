@@ -1,11 +1,12 @@
 use std::convert::TryFrom;
+use std::str::FromStr;
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    StdError, StdResult, SubMsg, WasmMsg,
+    to_binary, Addr, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdError, StdResult, SubMsg, WasmMsg,
 };
 use oraiswap::error::ContractError;
 use oraiswap::querier::query_pair_info_from_pair;
@@ -36,6 +37,8 @@ pub fn instantiate(
         commission_rate: msg
             .commission_rate
             .unwrap_or(DEFAULT_COMMISSION_RATE.to_string()),
+        min_commission_rate: None,
+        max_commission_rate: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -55,12 +58,30 @@ pub fn execute(
             owner,
             token_code_id,
             pair_code_id,
-        } => execute_update_config(deps, env, info, owner, token_code_id, pair_code_id),
+            commission_rate,
+            min_commission_rate,
+            max_commission_rate,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            owner,
+            token_code_id,
+            pair_code_id,
+            commission_rate,
+            min_commission_rate,
+            max_commission_rate,
+        ),
         ExecuteMsg::CreatePair {
             asset_infos,
             pair_admin,
-        } => execute_create_pair(deps, env, info, asset_infos, pair_admin),
+            commission_rate,
+        } => execute_create_pair(deps, env, info, asset_infos, pair_admin, commission_rate),
         ExecuteMsg::AddPair { pair_info } => execute_add_pair_manually(deps, env, info, pair_info),
+        ExecuteMsg::UpdatePairStatus {
+            asset_infos,
+            enabled,
+        } => execute_update_pair_status(deps, info, asset_infos, enabled),
         ExecuteMsg::MigrateContract {
             contract_addr,
             new_code_id,
@@ -95,6 +116,7 @@ pub fn migrate_pair(
 }
 
 // Only owner can execute it
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
     _env: Env,
@@ -102,6 +124,9 @@ pub fn execute_update_config(
     owner: Option<String>,
     token_code_id: Option<u64>,
     pair_code_id: Option<u64>,
+    commission_rate: Option<String>,
+    min_commission_rate: Option<Decimal>,
+    max_commission_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -122,6 +147,32 @@ pub fn execute_update_config(
         config.pair_code_id = pair_code_id;
     }
 
+    if let Some(commission_rate) = commission_rate {
+        config.commission_rate = commission_rate;
+    }
+
+    if let Some(min_commission_rate) = min_commission_rate {
+        config.min_commission_rate = Some(min_commission_rate);
+    }
+
+    if let Some(max_commission_rate) = max_commission_rate {
+        config.max_commission_rate = Some(max_commission_rate);
+    }
+
+    // an empty range would reject every pair, a bound above one would allow a confiscatory rate
+    if config
+        .max_commission_rate
+        .map_or(false, |max| max > Decimal::one())
+        || config.min_commission_rate.map_or(false, |min| {
+            min > config.max_commission_rate.unwrap_or(Decimal::one())
+        })
+    {
+        return Err(ContractError::InvalidCommissionRateBounds {});
+    }
+
+    // the default rate must stay usable for new pairs
+    assert_commission_rate(&config, &config.commission_rate)?;
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
@@ -134,8 +185,17 @@ pub fn execute_create_pair(
     _info: MessageInfo,
     asset_infos: [AssetInfo; 2],
     pair_admin: Option<String>,
+    commission_rate: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
+    // anyone can create a pair, so a chosen rate is only accepted within the bounds set by the owner
+    if commission_rate.is_some()
+        && (config.min_commission_rate.is_none() || config.max_commission_rate.is_none())
+    {
+        return Err(ContractError::CommissionRateNotBounded {});
+    }
+    let commission_rate = commission_rate.unwrap_or(config.commission_rate.clone());
+    assert_commission_rate(&config, &commission_rate)?;
     let raw_infos = [
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
@@ -156,7 +216,8 @@ pub fn execute_create_pair(
             liquidity_token: CanonicalAddr::from(vec![]),
             contract_addr: CanonicalAddr::from(vec![]),
             asset_infos: raw_infos,
-            commission_rate: config.commission_rate.clone(),
+            commission_rate: commission_rate.clone(),
+            enabled: true,
         },
     )?;
 
@@ -171,7 +232,11 @@ pub fn execute_create_pair(
                     oracle_addr: deps.api.addr_humanize(&config.oracle_addr)?,
                     asset_infos: asset_infos.clone(),
                     token_code_id: config.token_code_id,
-                    commission_rate: Some(config.commission_rate),
+                    commission_rate: Some(commission_rate),
+                    swap_volume_limit: None,
+                    spread_policy: None,
+                    curve: None,
+                    protocol_fee: None,
                 })?,
             },
             INSTANTIATE_REPLY_ID,
@@ -195,6 +260,8 @@ pub fn execute_add_pair_manually(
         return Err(ContractError::Unauthorized {});
     }
 
+    assert_commission_rate(&config, &pair_info.commission_rate)?;
+
     let raw_infos = [
         pair_info.asset_infos[0].to_raw(deps.api)?,
         pair_info.asset_infos[1].to_raw(deps.api)?,
@@ -220,6 +287,7 @@ pub fn execute_add_pair_manually(
                 .addr_canonicalize(pair_info.contract_addr.as_str())?,
             asset_infos: raw_infos,
             commission_rate: pair_info.commission_rate.clone(),
+            enabled: pair_info.enabled,
         },
     )?;
 
@@ -232,6 +300,48 @@ pub fn execute_add_pair_manually(
     ]))
 }
 
+// Only owner can execute it
+pub fn execute_update_pair_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let mut pair_info: PairInfoRaw = PAIRS.load(deps.storage, &pair_key)?;
+    pair_info.enabled = enabled;
+    PAIRS.save(deps.storage, &pair_key, &pair_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_pair_status"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        ("enabled", &enabled.to_string()),
+    ]))
+}
+
+fn assert_commission_rate(config: &Config, commission_rate: &str) -> Result<(), ContractError> {
+    let rate = Decimal::from_str(commission_rate)?;
+    if rate > Decimal::one()
+        || config.min_commission_rate.map_or(false, |min| rate < min)
+        || config.max_commission_rate.map_or(false, |max| rate > max)
+    {
+        return Err(ContractError::CommissionRateOutOfBounds {
+            commission_rate: commission_rate.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 /// This just stores the result for future query
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
@@ -271,9 +381,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Pair { asset_infos } => to_binary(&query_pair(deps, asset_infos)?),
-        QueryMsg::Pairs { start_after, limit } => {
-            to_binary(&query_pairs(deps, start_after, limit)?)
-        }
+        QueryMsg::Pairs {
+            start_after,
+            limit,
+            include_disabled,
+        } => to_binary(&query_pairs(deps, start_after, limit, include_disabled)?),
     }
 }
 
@@ -284,6 +396,9 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: deps.api.addr_humanize(&state.owner)?,
         token_code_id: state.token_code_id,
         pair_code_id: state.pair_code_id,
+        commission_rate: state.commission_rate,
+        min_commission_rate: state.min_commission_rate,
+        max_commission_rate: state.max_commission_rate,
     };
 
     Ok(resp)
@@ -302,6 +417,7 @@ pub fn query_pairs(
     deps: Deps,
     start_after: Option<[AssetInfo; 2]>,
     limit: Option<u32>,
+    include_disabled: Option<bool>,
 ) -> StdResult<PairsResponse> {
     let start_after = if let Some(start_after) = start_after {
         Some([
@@ -312,7 +428,13 @@ pub fn query_pairs(
         None
     };
 
-    let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
+    let pairs: Vec<PairInfo> = read_pairs(
+        deps.storage,
+        deps.api,
+        start_after,
+        limit,
+        include_disabled.unwrap_or(false),
+    )?;
     let resp = PairsResponse { pairs };
 
     Ok(resp)
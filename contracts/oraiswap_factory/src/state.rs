@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Api, CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_std::{Api, CanonicalAddr, Decimal, Order, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 use oraiswap::asset::{AssetInfoRaw, PairInfo, PairInfoRaw};
 
@@ -11,6 +11,8 @@ pub struct Config {
     pub pair_code_id: u64,
     pub token_code_id: u64,
     pub commission_rate: String,
+    pub min_commission_rate: Option<Decimal>,
+    pub max_commission_rate: Option<Decimal>,
 }
 
 // put the length bytes at the first for compatibility with legacy singleton store
@@ -27,12 +29,14 @@ pub fn read_pairs(
     api: &dyn Api,
     start_after: Option<[AssetInfoRaw; 2]>,
     limit: Option<u32>,
+    include_disabled: bool,
 ) -> StdResult<Vec<PairInfo>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = calc_range_start(start_after).map(Bound::ExclusiveRaw);
 
     PAIRS
         .range(storage, start, None, Order::Ascending)
+        .filter(|item| include_disabled || item.as_ref().map_or(true, |(_, v)| v.enabled))
         .take(limit)
         .map(|item| {
             let (_, v) = item?;
@@ -87,6 +91,8 @@ mod test {
                 pair_code_id: 1,
                 token_code_id: 1,
                 commission_rate: DEFAULT_COMMISSION_RATE.to_string(),
+                min_commission_rate: None,
+                max_commission_rate: None,
             },
         )
         .unwrap();
@@ -154,6 +160,7 @@ mod test {
             contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
             liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
             commission_rate: DEFAULT_COMMISSION_RATE.to_string(),
+            enabled: true,
         };
 
         let pair_info2 = PairInfoRaw {
@@ -169,6 +176,7 @@ mod test {
             contract_addr: deps.api.addr_canonicalize("pair0001").unwrap(),
             liquidity_token: deps.api.addr_canonicalize("liquidity0001").unwrap(),
             commission_rate: DEFAULT_COMMISSION_RATE.to_string(),
+            enabled: true,
         };
 
         store_pair(&mut deps.storage, &pair_info).unwrap();
@@ -189,7 +197,7 @@ mod test {
         );
 
         assert_eq!(
-            read_pairs(&deps.storage, &deps.api, None, None),
+            read_pairs(&deps.storage, &deps.api, None, None, true),
             legacy_read_pairs(&deps.storage, &deps.api, None, None),
         );
     }
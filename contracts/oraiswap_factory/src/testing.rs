@@ -1,10 +1,13 @@
-use cosmwasm_std::Addr;
-use oraiswap::asset::{AssetInfo, PairInfo};
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Decimal};
+use oraiswap::asset::{AssetInfo, PairInfo, ORAI_DENOM};
 
 use oraiswap::create_entry_points_testing;
+use oraiswap::factory::{ConfigResponse, ExecuteMsg, PairsResponse, QueryMsg};
 use oraiswap::pair::DEFAULT_COMMISSION_RATE;
 use oraiswap::querier::query_pair_info_from_pair;
-use oraiswap::testing::MockApp;
+use oraiswap::testing::{MockApp, APP_OWNER};
 
 #[test]
 fn create_pair() {
@@ -46,7 +49,8 @@ fn create_pair() {
             liquidity_token: pair_info.liquidity_token,
             contract_addr,
             asset_infos,
-            commission_rate: DEFAULT_COMMISSION_RATE.into()
+            commission_rate: DEFAULT_COMMISSION_RATE.into(),
+            enabled: true,
         }
     );
 }
@@ -82,6 +86,7 @@ fn add_pair() {
         contract_addr: Addr::unchecked("contract_addr"),
         asset_infos: asset_infos.clone(),
         commission_rate: DEFAULT_COMMISSION_RATE.into(),
+        enabled: true,
     };
 
     // add pair
@@ -90,3 +95,241 @@ fn add_pair() {
     let pair_res = app.query_pair(asset_infos.clone()).unwrap();
     assert_eq!(pair_res, pair_info);
 }
+
+#[test]
+fn pair_commission_rate_bounds() {
+    let mut app = MockApp::new(&[]);
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_factory_and_pair_contract(
+        Box::new(create_entry_points_testing!(crate).with_reply(crate::contract::reply)),
+        Box::new(
+            create_entry_points_testing!(oraiswap_pair).with_reply(oraiswap_pair::contract::reply),
+        ),
+    );
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: app.create_token("assetA"),
+        },
+        AssetInfo::Token {
+            contract_addr: app.create_token("assetB"),
+        },
+    ];
+
+    let update_bounds = |app: &mut MockApp, min: &str, max: &str| {
+        app.execute(
+            Addr::unchecked(APP_OWNER),
+            app.factory_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                owner: None,
+                token_code_id: None,
+                pair_code_id: None,
+                commission_rate: None,
+                min_commission_rate: Some(Decimal::from_str(min).unwrap()),
+                max_commission_rate: Some(Decimal::from_str(max).unwrap()),
+            },
+            &[],
+        )
+    };
+
+    let create_pair = |app: &mut MockApp, commission_rate: &str| {
+        app.execute(
+            Addr::unchecked(APP_OWNER),
+            app.factory_addr.clone(),
+            &ExecuteMsg::CreatePair {
+                asset_infos: asset_infos.clone(),
+                pair_admin: None,
+                commission_rate: Some(commission_rate.to_string()),
+            },
+            &[],
+        )
+    };
+
+    // without bounds the rate of a new pair can not be chosen
+    let res = create_pair(&mut app, "0.005");
+    app.assert_fail(res);
+
+    // an empty range and a ceiling above one are rejected
+    let res = update_bounds(&mut app, "0.01", "0.001");
+    app.assert_fail(res);
+    let res = update_bounds(&mut app, "0.001", "1.5");
+    app.assert_fail(res);
+
+    // the default rate 0.003 would fall below the floor
+    let res = update_bounds(&mut app, "0.005", "0.01");
+    app.assert_fail(res);
+
+    update_bounds(&mut app, "0.001", "0.01").unwrap();
+    let config: ConfigResponse = app
+        .query(app.factory_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        config.min_commission_rate,
+        Some(Decimal::from_str("0.001").unwrap())
+    );
+    assert_eq!(
+        config.max_commission_rate,
+        Some(Decimal::from_str("0.01").unwrap())
+    );
+
+    // below the floor and above the ceiling are rejected
+    let res = create_pair(&mut app, "0.0001");
+    app.assert_fail(res);
+    let res = create_pair(&mut app, "0.5");
+    app.assert_fail(res);
+
+    // a manually added pair is checked as well
+    let res = app.execute(
+        Addr::unchecked(APP_OWNER),
+        app.factory_addr.clone(),
+        &ExecuteMsg::AddPair {
+            pair_info: PairInfo {
+                oracle_addr: app.oracle_addr.clone(),
+                liquidity_token: Addr::unchecked("liquidity_token"),
+                contract_addr: Addr::unchecked("contract_addr"),
+                asset_infos: asset_infos.clone(),
+                commission_rate: "0.5".into(),
+                enabled: true,
+            },
+        },
+        &[],
+    );
+    app.assert_fail(res);
+
+    create_pair(&mut app, "0.005").unwrap();
+    let pair_res = app.query_pair(asset_infos.clone()).unwrap();
+    assert_eq!(pair_res.commission_rate, "0.005");
+}
+
+#[test]
+fn query_pairs_pagination() {
+    let mut app = MockApp::new(&[]);
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_factory_and_pair_contract(
+        Box::new(create_entry_points_testing!(crate).with_reply(crate::contract::reply)),
+        Box::new(
+            create_entry_points_testing!(oraiswap_pair).with_reply(oraiswap_pair::contract::reply),
+        ),
+    );
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let mut created_pairs = vec![];
+    for token in ["assetA", "assetB", "assetC", "assetD", "assetE"] {
+        let asset_infos = [
+            orai.clone(),
+            AssetInfo::Token {
+                contract_addr: app.create_token(token),
+            },
+        ];
+        created_pairs.push(app.create_pair(asset_infos).unwrap());
+    }
+
+    let query_pairs = |start_after: Option<[AssetInfo; 2]>, limit: Option<u32>| {
+        app.query::<PairsResponse, _>(
+            app.factory_addr.clone(),
+            &QueryMsg::Pairs {
+                start_after,
+                limit,
+                include_disabled: None,
+            },
+        )
+        .unwrap()
+        .pairs
+    };
+
+    let all_pairs = query_pairs(None, None);
+    assert_eq!(all_pairs.len(), created_pairs.len());
+    for contract_addr in created_pairs {
+        assert!(all_pairs
+            .iter()
+            .any(|pair| pair.contract_addr == contract_addr));
+    }
+
+    // paging by the last asset infos of each page walks the same order without gaps
+    let mut paged_pairs: Vec<PairInfo> = vec![];
+    let mut start_after = None;
+    loop {
+        let page = query_pairs(start_after, Some(2));
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 2);
+        start_after = page.last().map(|pair| pair.asset_infos.clone());
+        paged_pairs.extend(page);
+    }
+    assert_eq!(paged_pairs, all_pairs);
+
+    // the asset infos of a page key are order independent
+    let [first, second] = all_pairs[0].asset_infos.clone();
+    assert_eq!(query_pairs(Some([second, first]), None), all_pairs[1..]);
+}
+
+#[test]
+fn update_pair_status() {
+    let mut app = MockApp::new(&[]);
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_factory_and_pair_contract(
+        Box::new(create_entry_points_testing!(crate).with_reply(crate::contract::reply)),
+        Box::new(
+            create_entry_points_testing!(oraiswap_pair).with_reply(oraiswap_pair::contract::reply),
+        ),
+    );
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: app.create_token("assetA"),
+        },
+        AssetInfo::Token {
+            contract_addr: app.create_token("assetB"),
+        },
+    ];
+    let contract_addr = app.create_pair(asset_infos.clone()).unwrap();
+
+    let update_status = |app: &mut MockApp, sender: &str, enabled: bool| {
+        app.execute(
+            Addr::unchecked(sender),
+            app.factory_addr.clone(),
+            &ExecuteMsg::UpdatePairStatus {
+                asset_infos: asset_infos.clone(),
+                enabled,
+            },
+            &[],
+        )
+    };
+    let query_pairs = |app: &MockApp, include_disabled: Option<bool>| {
+        app.query::<PairsResponse, _>(
+            app.factory_addr.clone(),
+            &QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+                include_disabled,
+            },
+        )
+        .unwrap()
+        .pairs
+    };
+
+    // only the owner can change the status
+    let res = update_status(&mut app, "addr0000", false);
+    app.assert_fail(res);
+
+    update_status(&mut app, APP_OWNER, false).unwrap();
+    let pair_res = app.query_pair(asset_infos.clone()).unwrap();
+    assert!(!pair_res.enabled);
+    assert_eq!(pair_res.contract_addr, contract_addr);
+    assert_eq!(query_pairs(&app, None), vec![]);
+    assert_eq!(query_pairs(&app, Some(true)), vec![pair_res]);
+
+    update_status(&mut app, APP_OWNER, true).unwrap();
+    let pair_res = app.query_pair(asset_infos).unwrap();
+    assert!(pair_res.enabled);
+    assert_eq!(query_pairs(&app, None), vec![pair_res]);
+}
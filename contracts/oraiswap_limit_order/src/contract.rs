@@ -1,27 +1,37 @@
+use std::str::FromStr;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128,
+    attr, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order as OrderBy, Response, StdError, StdResult, Uint128,
 };
 use oraiswap::error::ContractError;
 
 use crate::order::{
-    cancel_order, execute_matching_orders, query_last_order_id, query_order, query_orderbook,
-    query_orderbook_is_matchable, query_orderbooks, query_orders, remove_pair, submit_order,
+    activate_triggers, admin_cancel_order, amend_order, assert_max_mid_price_deviation,
+    cancel_all_orders, cancel_order, cancel_order_partial, claim_executor_reward,
+    distribute_executor_bonus, execute_matching_orders, query_best_prices, query_book_snapshot,
+    query_executor_reward, query_executors, query_expired_orders, query_last_order_id,
+    query_last_stored_order_id, query_matching_worthwhile, query_mid_price, query_order,
+    query_orderbook, query_orderbook_depth, query_orderbook_is_matchable, query_orderbooks,
+    query_orders, query_orders_by_bidder, query_pair_limits, query_pending_orders, query_spread,
+    query_total_escrow, query_trade_participants, remove_pair, submit_order, submit_order_by_quote,
 };
 use crate::orderbook::OrderBook;
 use crate::state::{
-    init_last_order_id, read_config, read_orderbook, store_config, store_orderbook,
+    init_last_order_id, read_config, read_last_order_id, read_last_stored_order_id, read_orderbook,
+    read_orderbooks, recount_ticks, remove_allowed_executor, store_allowed_executor, store_config,
+    store_orderbook,
 };
 use crate::tick::{query_tick, query_ticks_with_end};
 
 use cw20::Cw20ReceiveMsg;
 use oraiswap::asset::{pair_key, Asset, AssetInfo};
 use oraiswap::limit_order::{
-    ContractInfo, ContractInfoResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
-    OrderDirection, QueryMsg,
+    ContractInfo, ContractInfoResponse, CreatePairParams, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, MinCommission, OrderDirection, OrderType, QueryMsg, SubmitOrderItem,
 };
 
 // version info for migration info
@@ -59,6 +69,16 @@ pub fn instantiate(
         } else {
             default_reward_address
         },
+        min_commission: None,
+        auto_distribute_threshold: None,
+        max_orders_per_match: None,
+        oracle_addr: None,
+        dust_threshold: None,
+        stp_cancel_newest: None,
+        maker_commission_rate: msg.maker_commission_rate,
+        taker_commission_rate: msg.taker_commission_rate,
+        min_commission_rate: None,
+        max_commission_rate: None,
     };
 
     store_config(deps.storage, &config)?;
@@ -71,22 +91,48 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateAdmin { admin } => execute_update_admin(deps, info, admin),
         ExecuteMsg::UpdateConfig {
             reward_address,
             commission_rate,
-        } => execute_update_config(deps, info, reward_address, commission_rate),
+            min_commission,
+            auto_distribute_threshold,
+            max_orders_per_match,
+            oracle_addr,
+            dust_threshold,
+            stp_cancel_newest,
+            min_commission_rate,
+            max_commission_rate,
+        } => execute_update_config(
+            deps,
+            info,
+            reward_address,
+            commission_rate,
+            min_commission,
+            auto_distribute_threshold,
+            max_orders_per_match,
+            oracle_addr,
+            dust_threshold,
+            stp_cancel_newest,
+            min_commission_rate,
+            max_commission_rate,
+        ),
+        ExecuteMsg::UpdateExecutors { add, remove } => {
+            execute_update_executors(deps, info, add, remove)
+        }
         ExecuteMsg::CreateOrderBookPair {
             base_coin_info,
             quote_coin_info,
             spread,
             min_quote_coin_amount,
+            commission_rate,
+            precision,
         } => execute_create_pair(
             deps,
             info,
@@ -94,94 +140,157 @@ pub fn execute(
             quote_coin_info,
             spread,
             min_quote_coin_amount,
+            commission_rate,
+            precision,
         ),
-        ExecuteMsg::SubmitOrder { direction, assets } => {
-            let pair_key = pair_key(&[
-                assets[0].to_raw(deps.api)?.info,
-                assets[1].to_raw(deps.api)?.info,
-            ]);
-            let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
-
-            // if sell then paid asset must be ask asset, this way we've just assumed that we offer usdt and ask for orai
-            // for execute order, it is direct match(user has known it is buy or sell) so no order is needed
-            // Buy: wanting ask asset(orai) => paid offer asset(usdt)
-            // Sell: paid ask asset(orai) => wating offer asset(usdt)
-            let paid_asset: &Asset;
-            let quote_asset: &Asset;
-
-            if orderbook_pair.base_coin_info.to_normal(deps.api)? == assets[0].info {
-                paid_asset = match direction {
-                    OrderDirection::Buy => &assets[1],
-                    OrderDirection::Sell => &assets[0],
-                };
-                quote_asset = &assets[1];
-            } else {
-                paid_asset = match direction {
-                    OrderDirection::Buy => &assets[0],
-                    OrderDirection::Sell => &assets[1],
-                };
-                quote_asset = &assets[0];
-            }
+        ExecuteMsg::CreateOrderBookPairs { pairs } => execute_create_pairs(deps, info, pairs),
+        ExecuteMsg::CreateOrderBookPairAndSeed {
+            base_coin_info,
+            quote_coin_info,
+            spread,
+            min_quote_coin_amount,
+            commission_rate,
+            precision,
+            seed_orders,
+        } => execute_create_pair_and_seed(
+            deps,
+            env,
+            info,
+            base_coin_info,
+            quote_coin_info,
+            spread,
+            min_quote_coin_amount,
+            commission_rate,
+            precision,
+            seed_orders,
+        ),
+        ExecuteMsg::SubmitOrder {
+            direction,
+            assets,
+            post_only,
+            client_order_id,
+            fill_callback,
+            expires_at,
+            order_type,
+            min_fill_amount,
+            trigger_price,
+        } => {
+            let (pair_key, assets) = order_assets(deps.as_ref(), direction, &assets)?;
 
             // if paid asset is cw20, we check it in Cw20HookMessage
-            if !paid_asset.is_native_token() {
+            if !assets[0].is_native_token() {
                 return Err(ContractError::MustProvideNativeToken {});
             }
+            assets[0].assert_sent_native_token_balance(&info)?;
 
-            paid_asset.assert_sent_native_token_balance(&info)?;
-
-            // require minimum amount for quote asset
-            if quote_asset.amount.lt(&orderbook_pair.min_quote_coin_amount) {
-                return Err(ContractError::TooSmallQuoteAsset {
-                    quote_coin: quote_asset.info.to_string(),
-                    min_quote_amount: orderbook_pair.min_quote_coin_amount,
-                });
-            }
-
-            // then submit order
-            if orderbook_pair.base_coin_info.to_normal(deps.api)? == assets[0].info {
-                match direction {
-                    OrderDirection::Buy => submit_order(
-                        deps,
-                        info.sender,
-                        &pair_key,
-                        direction,
-                        [assets[1].clone(), assets[0].clone()],
-                    ),
-                    OrderDirection::Sell => submit_order(
-                        deps,
-                        info.sender,
-                        &pair_key,
-                        direction,
-                        [assets[0].clone(), assets[1].clone()],
-                    ),
-                }
-            } else {
-                match direction {
-                    OrderDirection::Buy => submit_order(
-                        deps,
-                        info.sender,
-                        &pair_key,
-                        direction,
-                        [assets[0].clone(), assets[1].clone()],
-                    ),
-                    OrderDirection::Sell => submit_order(
-                        deps,
-                        info.sender,
-                        &pair_key,
-                        direction,
-                        [assets[1].clone(), assets[0].clone()],
-                    ),
-                }
-            }
+            submit_order(
+                deps,
+                &env,
+                info.sender,
+                &pair_key,
+                direction,
+                assets,
+                post_only.unwrap_or(false),
+                client_order_id,
+                fill_callback,
+                expires_at,
+                order_type.unwrap_or_default(),
+                min_fill_amount,
+                trigger_price,
+            )
         }
+        ExecuteMsg::SubmitOrderByQuote {
+            direction,
+            base_coin_info,
+            quote_asset,
+            price,
+        } => submit_order_by_quote(
+            deps,
+            &env,
+            info.sender,
+            direction,
+            base_coin_info,
+            quote_asset,
+            price,
+            info.funds
+                .iter()
+                .map(|coin| Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: coin.denom.clone(),
+                    },
+                    amount: coin.amount,
+                })
+                .collect(),
+        ),
+        ExecuteMsg::SubmitOrders { orders } => execute_submit_orders(deps, env, info, orders),
         ExecuteMsg::CancelOrder {
             order_id,
             asset_infos,
-        } => cancel_order(deps, info, order_id, asset_infos),
-        ExecuteMsg::ExecuteOrderBookPair { asset_infos, limit } => {
-            execute_matching_orders(deps, info, asset_infos, limit)
+            refund_to,
+        } => cancel_order(deps, info, order_id, asset_infos, refund_to),
+        ExecuteMsg::CancelAllOrders {
+            asset_infos,
+            direction,
+        } => cancel_all_orders(deps, info, asset_infos, direction),
+        ExecuteMsg::CancelOrderPartial {
+            order_id,
+            asset_infos,
+            amount,
+        } => cancel_order_partial(deps, info, order_id, asset_infos, amount),
+        ExecuteMsg::AmendOrder {
+            order_id,
+            asset_infos,
+            new_price,
+            new_ask_amount,
+        } => amend_order(
+            deps,
+            info.sender,
+            order_id,
+            asset_infos,
+            new_price,
+            new_ask_amount,
+            info.funds
+                .iter()
+                .map(|coin| Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: coin.denom.clone(),
+                    },
+                    amount: coin.amount,
+                })
+                .collect(),
+        ),
+        ExecuteMsg::AdminCancelOrder {
+            asset_infos,
+            order_id,
+        } => admin_cancel_order(deps, info, order_id, asset_infos),
+        ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos,
+            limit,
+            best_effort,
+        } => execute_matching_orders(
+            deps,
+            env,
+            info,
+            asset_infos,
+            limit,
+            best_effort.unwrap_or(false),
+        ),
+        ExecuteMsg::ActivateTriggers { asset_infos } => activate_triggers(deps, asset_infos),
+        ExecuteMsg::ClaimExecutorReward { asset_infos } => {
+            claim_executor_reward(deps, info, asset_infos)
         }
+        ExecuteMsg::DistributeExecutorBonus { asset_infos, bonus } => {
+            // cw20 bonus is sent with Cw20HookMsg
+            if !bonus.is_native_token() {
+                return Err(ContractError::MustProvideNativeToken {});
+            }
+            Asset::assert_sent_native_tokens(&[bonus.clone()], &info)?;
+            distribute_executor_bonus(deps, info.sender, asset_infos, bonus)
+        }
+        ExecuteMsg::UpdatePairCommission {
+            asset_infos,
+            commission_rate,
+        } => execute_update_pair_commission(deps, info, asset_infos, commission_rate),
         ExecuteMsg::RemoveOrderBookPair { asset_infos } => remove_pair(deps, info, asset_infos),
     }
 }
@@ -206,11 +315,20 @@ pub fn execute_update_admin(
     Ok(Response::new().add_attributes(vec![("action", "execute_update_admin")]))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
     reward_address: Option<Addr>,
     commission_rate: Option<String>,
+    min_commission: Option<MinCommission>,
+    auto_distribute_threshold: Option<Uint128>,
+    max_orders_per_match: Option<u64>,
+    oracle_addr: Option<Addr>,
+    dust_threshold: Option<Uint128>,
+    stp_cancel_newest: Option<bool>,
+    min_commission_rate: Option<Decimal>,
+    max_commission_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let mut contract_info = read_config(deps.storage)?;
     let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -230,10 +348,89 @@ pub fn execute_update_config(
         contract_info.commission_rate = commission_rate;
     }
 
+    // update minimum commission policy
+    if let Some(min_commission) = min_commission {
+        contract_info.min_commission = Some(min_commission);
+    }
+
+    // update reward auto distribution threshold
+    if let Some(auto_distribute_threshold) = auto_distribute_threshold {
+        contract_info.auto_distribute_threshold = Some(auto_distribute_threshold);
+    }
+
+    // update matching cap
+    if let Some(max_orders_per_match) = max_orders_per_match {
+        contract_info.max_orders_per_match = Some(max_orders_per_match);
+    }
+
+    // update oracle
+    if let Some(oracle_addr) = oracle_addr {
+        contract_info.oracle_addr = Some(deps.api.addr_canonicalize(oracle_addr.as_str())?);
+    }
+
+    // update dust threshold
+    if let Some(dust_threshold) = dust_threshold {
+        contract_info.dust_threshold = Some(dust_threshold);
+    }
+
+    // update self-trade prevention
+    if let Some(stp_cancel_newest) = stp_cancel_newest {
+        contract_info.stp_cancel_newest = Some(stp_cancel_newest);
+    }
+
+    // update pair commission bounds
+    if let Some(min_commission_rate) = min_commission_rate {
+        contract_info.min_commission_rate = Some(min_commission_rate);
+    }
+    if let Some(max_commission_rate) = max_commission_rate {
+        contract_info.max_commission_rate = Some(max_commission_rate);
+    }
+    // an empty range would reject every pair rate, a bound above one would allow a confiscatory rate
+    if contract_info
+        .max_commission_rate
+        .map_or(false, |max| max > Decimal::one())
+        || contract_info.min_commission_rate.map_or(false, |min| {
+            min > contract_info.max_commission_rate.unwrap_or(Decimal::one())
+        })
+    {
+        return Err(ContractError::InvalidCommissionRateBounds {});
+    }
+
     store_config(deps.storage, &contract_info)?;
     Ok(Response::new().add_attributes(vec![("action", "execute_update_config")]))
 }
 
+pub fn execute_update_executors(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<Addr>,
+    remove: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    let contract_info = read_config(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check authorized
+    if contract_info.admin.ne(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for executor in add.iter() {
+        store_allowed_executor(
+            deps.storage,
+            &deps.api.addr_canonicalize(executor.as_str())?,
+        )?;
+    }
+    for executor in remove.iter() {
+        remove_allowed_executor(
+            deps.storage,
+            &deps.api.addr_canonicalize(executor.as_str())?,
+        );
+    }
+
+    Ok(Response::new().add_attributes(vec![("action", "execute_update_executors")]))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn execute_create_pair(
     deps: DepsMut,
     info: MessageInfo,
@@ -241,6 +438,8 @@ pub fn execute_create_pair(
     quote_coin_info: AssetInfo,
     spread: Option<Decimal>,
     min_quote_coin_amount: Uint128,
+    commission_rate: Option<String>,
+    precision: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let contract_info = read_config(deps.storage)?;
     let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -262,12 +461,26 @@ pub fn execute_create_pair(
         return Err(ContractError::OrderBookAlreadyExists {});
     }
 
+    // a rate that can not be parsed would make every matching of the pair fail,
+    // and a rate outside of the bounds would overcharge or not reward executors
+    if let Some(commission_rate) = commission_rate.as_ref() {
+        assert_pair_commission_rate(&contract_info, commission_rate)?;
+    }
+
     let order_book = OrderBook {
         base_coin_info: base_coin_info.to_raw(deps.api)?,
         quote_coin_info: quote_coin_info.to_raw(deps.api)?,
         spread,
         min_quote_coin_amount,
+        commission_rate,
+        precision,
     };
+
+    // the sorted pair key must map back to this orderbook whichever asset order is given
+    if base_coin_info == quote_coin_info || order_book.get_pair_key() != pair_key {
+        return Err(ContractError::InvalidOrderBookPair {});
+    }
+
     store_orderbook(deps.storage, &pair_key, &order_book)?;
 
     Ok(Response::new().add_attributes(vec![
@@ -278,8 +491,243 @@ pub fn execute_create_pair(
     ]))
 }
 
+/// the rate of a pair must parse and stay within the bounds of the contract, never above one
+fn assert_pair_commission_rate(
+    contract_info: &ContractInfo,
+    commission_rate: &str,
+) -> Result<(), ContractError> {
+    let rate = Decimal::from_str(commission_rate)?;
+    if rate > Decimal::one()
+        || contract_info
+            .min_commission_rate
+            .map_or(false, |min| rate < min)
+        || contract_info
+            .max_commission_rate
+            .map_or(false, |max| rate > max)
+    {
+        return Err(ContractError::CommissionRateOutOfBounds {
+            commission_rate: commission_rate.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn execute_update_pair_commission(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    commission_rate: Option<String>,
+) -> Result<Response, ContractError> {
+    let contract_info = read_config(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check authorized
+    if contract_info.admin.ne(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let mut orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+
+    // a rate that can not be parsed would make every matching of the pair fail,
+    // and a rate outside of the bounds would overcharge or not reward executors
+    if let Some(commission_rate) = commission_rate.as_ref() {
+        assert_pair_commission_rate(&contract_info, commission_rate)?;
+    }
+    orderbook_pair.commission_rate = commission_rate;
+    store_orderbook(deps.storage, &pair_key, &orderbook_pair)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_pair_commission"),
+        ("pair", &format!("{} - {}", asset_infos[0], asset_infos[1])),
+        (
+            "commission_rate",
+            orderbook_pair
+                .commission_rate
+                .as_deref()
+                .unwrap_or("default"),
+        ),
+    ]))
+}
+
+pub fn execute_create_pairs(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    pairs: Vec<CreatePairParams>,
+) -> Result<Response, ContractError> {
+    let mut attributes = vec![attr("action", "create_orderbook_pairs")];
+
+    // any failure reverts the whole batch, so no order book is created partially
+    for pair in pairs {
+        let res = execute_create_pair(
+            deps.branch(),
+            info.clone(),
+            pair.base_coin_info,
+            pair.quote_coin_info,
+            pair.spread,
+            pair.min_quote_coin_amount,
+            pair.commission_rate,
+            pair.precision,
+        )?;
+        attributes.extend(
+            res.attributes
+                .into_iter()
+                .filter(|attr| attr.key != "action"),
+        );
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_pair_and_seed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    base_coin_info: AssetInfo,
+    quote_coin_info: AssetInfo,
+    spread: Option<Decimal>,
+    min_quote_coin_amount: Uint128,
+    commission_rate: Option<String>,
+    precision: Option<Decimal>,
+    seed_orders: Vec<SubmitOrderItem>,
+) -> Result<Response, ContractError> {
+    let new_pair_key = pair_key(&[
+        base_coin_info.to_raw(deps.api)?,
+        quote_coin_info.to_raw(deps.api)?,
+    ]);
+
+    // only the admin can create the pair, so the seed orders are always placed by the admin
+    let create_res = execute_create_pair(
+        deps.branch(),
+        info.clone(),
+        base_coin_info,
+        quote_coin_info,
+        spread,
+        min_quote_coin_amount,
+        commission_rate,
+        precision,
+    )?;
+
+    // seeding another book would bypass the funds of its own pair creation
+    for seed_order in seed_orders.iter() {
+        let seed_pair_key = pair_key(&[
+            seed_order.assets[0].to_raw(deps.api)?.info,
+            seed_order.assets[1].to_raw(deps.api)?.info,
+        ]);
+        if seed_pair_key != new_pair_key {
+            return Err(ContractError::InvalidOrderBookPair {});
+        }
+    }
+
+    // the whole batch is checked against the funds, any failure reverts the pair creation too
+    let seed_res = execute_submit_orders(deps, env, info, seed_orders)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_orderbook_pair_and_seed")
+        .add_attributes(
+            create_res
+                .attributes
+                .into_iter()
+                .chain(seed_res.attributes)
+                .filter(|attr| attr.key != "action"),
+        ))
+}
+
+/// orients the submitted assets as [offer, ask] of the pair and checks the minimum quote amount
+fn order_assets(
+    deps: Deps,
+    direction: OrderDirection,
+    assets: &[Asset; 2],
+) -> Result<(Vec<u8>, [Asset; 2]), ContractError> {
+    let pair_key = pair_key(&[
+        assets[0].to_raw(deps.api)?.info,
+        assets[1].to_raw(deps.api)?.info,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+
+    // if sell then paid asset must be ask asset, this way we've just assumed that we offer usdt and ask for orai
+    // for execute order, it is direct match(user has known it is buy or sell) so no order is needed
+    // Buy: wanting ask asset(orai) => paid offer asset(usdt)
+    // Sell: paid ask asset(orai) => wating offer asset(usdt)
+    let (base_asset, quote_asset) =
+        if orderbook_pair.base_coin_info.to_normal(deps.api)? == assets[0].info {
+            (&assets[0], &assets[1])
+        } else {
+            (&assets[1], &assets[0])
+        };
+
+    // require minimum amount for quote asset
+    if quote_asset.amount.lt(&orderbook_pair.min_quote_coin_amount) {
+        return Err(ContractError::TooSmallQuoteAsset {
+            quote_coin: quote_asset.info.to_string(),
+            min_quote_amount: orderbook_pair.min_quote_coin_amount,
+        });
+    }
+
+    let assets = match direction {
+        OrderDirection::Buy => [quote_asset.clone(), base_asset.clone()],
+        OrderDirection::Sell => [base_asset.clone(), quote_asset.clone()],
+    };
+    Ok((pair_key, assets))
+}
+
+pub fn execute_submit_orders(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    orders: Vec<SubmitOrderItem>,
+) -> Result<Response, ContractError> {
+    let mut prepared_orders = vec![];
+    let mut paid_assets: Vec<Asset> = vec![];
+    for order in orders {
+        let (pair_key, assets) = order_assets(deps.as_ref(), order.direction, &order.assets)?;
+        if !assets[0].is_native_token() {
+            return Err(ContractError::MustProvideNativeToken {});
+        }
+        paid_assets.push(assets[0].clone());
+        prepared_orders.push((pair_key, order.direction, assets));
+    }
+
+    // the funds sent must cover the whole batch exactly
+    Asset::assert_sent_native_tokens(&paid_assets, &info)?;
+
+    let mut attributes = vec![attr("action", "submit_orders")];
+
+    // any failure reverts the whole batch, so no order is placed partially
+    for (pair_key, direction, assets) in prepared_orders {
+        let res = submit_order(
+            deps.branch(),
+            &env,
+            info.sender.clone(),
+            &pair_key,
+            direction,
+            assets,
+            false,
+            None,
+            None,
+            None,
+            OrderType::Gtc,
+            None,
+            None,
+        )?;
+        attributes.extend(
+            res.attributes
+                .into_iter()
+                .filter(|attr| attr.key != "action"),
+        );
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
 pub fn receive_cw20(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
@@ -293,7 +741,19 @@ pub fn receive_cw20(
     };
 
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::SubmitOrder { direction, assets }) => {
+        Ok(Cw20HookMsg::SubmitOrder {
+            direction,
+            assets,
+            post_only,
+            client_order_id,
+            fill_callback,
+            expires_at,
+            order_type,
+            min_fill_amount,
+            trigger_price,
+            belief_price,
+            max_spread,
+        }) => {
             let pair_key = pair_key(&[
                 assets[0].to_raw(deps.api)?.info,
                 assets[1].to_raw(deps.api)?.info,
@@ -329,48 +789,119 @@ pub fn receive_cw20(
                 });
             }
 
+            assert_max_mid_price_deviation(
+                deps.as_ref(),
+                [assets[0].info.clone(), assets[1].info.clone()],
+                belief_price,
+                max_spread,
+            )?;
+
             if orderbook_pair.base_coin_info.to_normal(deps.api)? == assets[0].info {
                 match direction {
                     OrderDirection::Buy => submit_order(
                         deps,
+                        &env,
                         sender,
                         &pair_key,
                         direction,
                         [assets[1].clone(), assets[0].clone()],
+                        post_only.unwrap_or(false),
+                        client_order_id,
+                        fill_callback,
+                        expires_at,
+                        order_type.unwrap_or_default(),
+                        min_fill_amount,
+                        trigger_price,
                     ),
                     OrderDirection::Sell => submit_order(
                         deps,
+                        &env,
                         sender,
                         &pair_key,
                         direction,
                         [assets[0].clone(), assets[1].clone()],
+                        post_only.unwrap_or(false),
+                        client_order_id,
+                        fill_callback,
+                        expires_at,
+                        order_type.unwrap_or_default(),
+                        min_fill_amount,
+                        trigger_price,
                     ),
                 }
             } else {
                 match direction {
                     OrderDirection::Buy => submit_order(
                         deps,
+                        &env,
                         sender,
                         &pair_key,
                         direction,
                         [assets[0].clone(), assets[1].clone()],
+                        post_only.unwrap_or(false),
+                        client_order_id,
+                        fill_callback,
+                        expires_at,
+                        order_type.unwrap_or_default(),
+                        min_fill_amount,
+                        trigger_price,
                     ),
                     OrderDirection::Sell => submit_order(
                         deps,
+                        &env,
                         sender,
                         &pair_key,
                         direction,
                         [assets[1].clone(), assets[0].clone()],
+                        post_only.unwrap_or(false),
+                        client_order_id,
+                        fill_callback,
+                        expires_at,
+                        order_type.unwrap_or_default(),
+                        min_fill_amount,
+                        trigger_price,
                     ),
                 }
             }
         }
+        Ok(Cw20HookMsg::SubmitOrderByQuote {
+            direction,
+            base_coin_info,
+            quote_asset,
+            price,
+        }) => submit_order_by_quote(
+            deps,
+            &env,
+            sender,
+            direction,
+            base_coin_info,
+            quote_asset,
+            price,
+            vec![provided_asset],
+        ),
+        Ok(Cw20HookMsg::AmendOrder {
+            order_id,
+            asset_infos,
+            new_price,
+            new_ask_amount,
+        }) => amend_order(
+            deps,
+            sender,
+            order_id,
+            asset_infos,
+            new_price,
+            new_ask_amount,
+            vec![provided_asset],
+        ),
+        Ok(Cw20HookMsg::DistributeExecutorBonus { asset_infos }) => {
+            distribute_executor_bonus(deps, sender, asset_infos, provided_asset)
+        }
         Err(_) => Err(ContractError::InvalidCw20HookMessage {}),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ContractInfo {} => to_binary(&query_contract_info(deps)?),
         QueryMsg::Order {
@@ -400,6 +931,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             order_by,
         )?),
         QueryMsg::LastOrderId {} => to_binary(&query_last_order_id(deps)?),
+        QueryMsg::TradeParticipants {
+            asset_infos,
+            trade_id,
+        } => to_binary(&query_trade_participants(deps, asset_infos, trade_id)?),
+        QueryMsg::LastStoredOrderId { asset_infos } => {
+            to_binary(&query_last_stored_order_id(deps, asset_infos)?)
+        }
         QueryMsg::Tick {
             price,
             asset_infos,
@@ -435,47 +973,74 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::OrderBookMatchable { asset_infos } => {
             to_binary(&query_orderbook_is_matchable(deps, asset_infos)?)
         }
+        QueryMsg::MatchingWorthwhile {
+            asset_infos,
+            gas_price,
+            estimated_gas,
+        } => to_binary(&query_matching_worthwhile(
+            deps,
+            env,
+            asset_infos,
+            gas_price,
+            estimated_gas,
+        )?),
         // TODO: add test cases
-        QueryMsg::MidPrice { asset_infos } => {
-            let pair_key = pair_key(&[
-                asset_infos[0].to_raw(deps.api)?,
-                asset_infos[1].to_raw(deps.api)?,
-            ]);
-            let best_buy = query_ticks_with_end(
-                deps.storage,
-                &pair_key,
-                OrderDirection::Buy,
-                None,
-                None,
-                Some(1),
-                Some(2),
-            )?;
-            let best_sell = query_ticks_with_end(
-                deps.storage,
-                &pair_key,
-                OrderDirection::Sell,
-                None,
-                None,
-                Some(1),
-                Some(1),
-            )?;
-            let best_buy_price = if best_buy.ticks.len() == 0 {
-                Decimal::zero()
-            } else {
-                best_buy.ticks[0].price
-            };
-            let best_sell_price = if best_sell.ticks.len() == 0 {
-                Decimal::zero()
-            } else {
-                best_sell.ticks[0].price
-            };
-            let mid_price = best_buy_price
-                .checked_add(best_sell_price)
-                .unwrap_or_default()
-                .checked_div(Decimal::from_ratio(2u128, 1u128))
-                .unwrap_or_default();
-            to_binary(&mid_price)
-        }
+        QueryMsg::MidPrice { asset_infos } => to_binary(&query_mid_price(deps, asset_infos)?),
+        QueryMsg::TotalEscrow { asset_info } => to_binary(&query_total_escrow(deps, asset_info)?),
+        QueryMsg::Spread { asset_infos } => to_binary(&query_spread(deps, asset_infos)?),
+        QueryMsg::BestPrices { asset_infos } => to_binary(&query_best_prices(deps, asset_infos)?),
+        QueryMsg::BookSnapshot {
+            asset_infos,
+            max_ticks,
+        } => to_binary(&query_book_snapshot(deps, asset_infos, max_ticks)?),
+        QueryMsg::PairLimits { asset_infos } => to_binary(&query_pair_limits(deps, asset_infos)?),
+        QueryMsg::OrderBookDepth {
+            asset_infos,
+            direction,
+            limit,
+        } => to_binary(&query_orderbook_depth(deps, asset_infos, direction, limit)?),
+        QueryMsg::OrdersByBidder {
+            bidder,
+            start_after,
+            limit,
+            order_by,
+        } => to_binary(&query_orders_by_bidder(
+            deps,
+            bidder,
+            start_after,
+            limit,
+            order_by,
+        )?),
+        QueryMsg::ExecutorReward {
+            asset_infos,
+            executor,
+        } => to_binary(&query_executor_reward(deps, asset_infos, executor)?),
+        QueryMsg::Executors {
+            asset_infos,
+            start_after,
+            limit,
+        } => to_binary(&query_executors(deps, asset_infos, start_after, limit)?),
+        QueryMsg::ExpiredOrders {
+            asset_infos,
+            start_after,
+            limit,
+        } => to_binary(&query_expired_orders(
+            deps,
+            env,
+            asset_infos,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PendingOrders {
+            asset_infos,
+            start_after,
+            limit,
+        } => to_binary(&query_pending_orders(
+            deps,
+            asset_infos,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -487,10 +1052,54 @@ pub fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
         admin: deps.api.addr_humanize(&info.admin)?,
         commission_rate: info.commission_rate,
         reward_address: deps.api.addr_humanize(&info.reward_address)?,
+        min_commission: info.min_commission,
+        auto_distribute_threshold: info.auto_distribute_threshold,
+        max_orders_per_match: info.max_orders_per_match,
+        oracle_addr: info
+            .oracle_addr
+            .map(|oracle_addr| deps.api.addr_humanize(&oracle_addr))
+            .transpose()?,
+        dust_threshold: info.dust_threshold,
+        stp_cancel_newest: info.stp_cancel_newest,
+        maker_commission_rate: info.maker_commission_rate,
+        taker_commission_rate: info.taker_commission_rate,
+        min_commission_rate: info.min_commission_rate,
+        max_commission_rate: info.max_commission_rate,
     })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let last_order_id = read_last_order_id(deps.storage)?;
+    // books are migrated a page at a time, the next page starts after the reported pair key
+    let orderbooks = read_orderbooks(
+        deps.storage,
+        msg.start_after.map(|pair_key| pair_key.to_vec()),
+        msg.limit,
+        Some(OrderBy::Ascending),
+    )?;
+    for ob in orderbooks.iter() {
+        let pair_key = ob.get_pair_key();
+        // tick totals drive best price lookups, rebuild them from the orders actually stored
+        recount_ticks(deps.storage, &pair_key)?;
+
+        // new orders would reuse the id of a stored order if the counter fell behind
+        if let Some(order_id) = read_last_stored_order_id(deps.storage, &pair_key)? {
+            if order_id > last_order_id {
+                return Err(StdError::generic_err(format!(
+                    "last order id {} is behind stored order {}",
+                    last_order_id, order_id
+                )));
+            }
+        }
+    }
+
+    let mut attributes = vec![attr("action", "migrate")];
+    if let Some(ob) = orderbooks.last() {
+        attributes.push(attr(
+            "last_pair_key",
+            Binary::from(ob.get_pair_key()).to_base64(),
+        ));
+    }
+    Ok(Response::new().add_attributes(attributes))
 }
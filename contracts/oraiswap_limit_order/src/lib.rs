@@ -5,5 +5,7 @@ pub mod state;
 mod order;
 mod tick;
 
+pub use crate::order::match_orders;
+
 #[cfg(test)]
 mod testing;
@@ -1,89 +1,391 @@
 use std::convert::TryFrom;
 use std::str::FromStr;
 
-use crate::orderbook::{BulkOrders, Executor, Order, OrderBook, OrderWithFee};
+use crate::orderbook::{
+    BulkOrders, Executor, Fill, MatchResult, Order, OrderBook, TickFill, Trade,
+};
 use crate::state::{
-    increase_last_order_id, read_config, read_last_order_id, read_order, read_orderbook,
-    read_orderbooks, read_orders, read_orders_with_indexer, read_reward, remove_order,
-    remove_orderbook, store_order, store_reward, DEFAULT_LIMIT, MAX_LIMIT, PREFIX_ORDER_BY_BIDDER,
-    PREFIX_ORDER_BY_DIRECTION, PREFIX_ORDER_BY_PRICE, PREFIX_TICK,
+    increase_last_order_id, increase_last_trade_id, is_allowed_executor, read_all_executors,
+    read_all_orderbooks, read_all_orders, read_all_pending_orders, read_bidder_pending_orders,
+    read_client_order_id, read_config, read_executors, read_expired_orders, read_last_order_id,
+    read_last_stored_order_id, read_order, read_orderbook, read_orderbooks, read_orders,
+    read_orders_with_indexer, read_pending_order, read_pending_orders, read_reward, read_trade,
+    read_triggered_orders, remove_order, remove_orderbook, remove_pending_order,
+    store_client_order_id, store_order, store_pending_order, store_reward, store_trade,
+    DEFAULT_LIMIT, MAX_LIMIT, PREFIX_ORDER_BY_BIDDER, PREFIX_ORDER_BY_DIRECTION,
+    PREFIX_ORDER_BY_PRICE, PREFIX_TICK,
 };
+use crate::tick::query_ticks_with_end;
 use cosmwasm_std::{
-    attr, Addr, Attribute, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Event, MessageInfo,
-    Order as OrderBy, Response, StdError, StdResult, Storage, Uint128,
+    attr, Addr, Api, Attribute, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env, Event,
+    MessageInfo, Order as OrderBy, Response, StdError, StdResult, Storage, Uint128,
 };
 
 use cosmwasm_storage::ReadonlyBucket;
-use oraiswap::asset::{pair_key, Asset, AssetInfo};
+use oraiswap::asset::{pair_key, Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::error::ContractError;
 use oraiswap::limit_order::{
-    LastOrderIdResponse, OrderBookMatchableResponse, OrderBookResponse, OrderBooksResponse,
-    OrderDirection, OrderFilter, OrderResponse, OrderStatus, OrdersResponse,
+    BestPricesResponse, BidderOrderResponse, BidderOrdersResponse, BookSnapshotResponse,
+    ContractInfo, ExecutorResponse, ExecutorsResponse, FillHookMsg, Hook, LastOrderIdResponse,
+    LastStoredOrderIdResponse, MatchingWorthwhileResponse, MinCommissionPolicy,
+    OrderBookMatchableResponse, OrderBookResponse, OrderBooksResponse, OrderDirection, OrderFilter,
+    OrderResponse, OrderStatus, OrderType, OrdersResponse, PairLimitsResponse,
+    SnapshotTickResponse, SpreadResponse, TickDepth, TradeParticipantsResponse,
 };
+use oraiswap::oracle::OracleContract;
 
 const RELAY_FEE: u128 = 300u128;
+const DEFAULT_AUTO_DISTRIBUTE_THRESHOLD: u128 = 1000000u128;
+// a client order id can not be reused by the same bidder within a day
+const CLIENT_ORDER_ID_WINDOW: u64 = 86400;
 
 struct Payment {
     address: Addr,
     asset: Asset,
 }
 
+/// checks an order about to rest on the book, every path placing or resizing an order goes through it
+fn validate_order(orderbook_pair: &OrderBook, order: &Order) -> Result<(), ContractError> {
+    if order.offer_amount.is_zero() || order.ask_amount.is_zero() {
+        return Err(ContractError::AssetMustNotBeZero {});
+    }
+
+    // amounts this far apart floor the price to zero, a tick no order of the other side can cross
+    let price = order.get_price();
+    if price.is_zero() {
+        return Err(ContractError::AssetMustNotBeZero {});
+    }
+
+    // a price off the precision would never line up with the ticks of the book
+    orderbook_pair.assert_price_precision(price)?;
+
+    if let Some(min_fill_amount) = order.min_fill_amount {
+        if min_fill_amount > order.offer_amount {
+            return Err(ContractError::InvalidMinFillAmount {
+                min_fill_amount,
+                offer_amount: order.offer_amount,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn submit_order(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: &Env,
     sender: Addr,
     pair_key: &[u8],
     direction: OrderDirection,
     assets: [Asset; 2],
+    post_only: bool,
+    client_order_id: Option<String>,
+    fill_callback: Option<Hook>,
+    expires_at: Option<u64>,
+    order_type: OrderType,
+    min_fill_amount: Option<Uint128>,
+    trigger_price: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     if assets[0].amount.is_zero() || assets[1].amount.is_zero() {
         return Err(ContractError::AssetMustNotBeZero {});
     }
+    if let Some(trigger_price) = trigger_price {
+        if trigger_price.is_zero() {
+            return Err(ContractError::AssetMustNotBeZero {});
+        }
+        // an immediate order has nothing to wait for
+        if order_type != OrderType::Gtc {
+            return Err(ContractError::InvalidTriggerOrderType {});
+        }
+    }
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.block.time.seconds() {
+            return Err(ContractError::InvalidOrderExpiry { expires_at });
+        }
+    }
+
+    let bidder_addr = deps.api.addr_canonicalize(sender.as_str())?;
+    if let Some(hook) = fill_callback.as_ref() {
+        deps.api.addr_validate(hook.contract_addr.as_str())?;
+    }
+
+    // a retried submission carries the same client order id, only the first one is placed
+    let now = env.block.time.seconds();
+    if let Some(client_order_id) = client_order_id.as_ref() {
+        if let Some((order_id, used_at)) =
+            read_client_order_id(deps.storage, &bidder_addr, client_order_id)?
+        {
+            if now < used_at + CLIENT_ORDER_ID_WINDOW {
+                return Err(ContractError::DuplicateClientOrderId {
+                    client_order_id: client_order_id.clone(),
+                    order_id,
+                });
+            }
+        }
+    }
+
+    // the order is numbered once it passed every check
+    let mut order = Order {
+        order_id: 0,
+        direction,
+        bidder_addr,
+        offer_amount: assets[0].to_raw(deps.api)?.amount,
+        ask_amount: assets[1].to_raw(deps.api)?.amount,
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        status: if trigger_price.is_some() {
+            OrderStatus::Pending
+        } else {
+            OrderStatus::Open
+        },
+        fill_callback,
+        expires_at,
+        min_fill_amount,
+        trigger_price,
+    };
+
+    let orderbook_pair = read_orderbook(deps.storage, pair_key)?;
+    validate_order(&orderbook_pair, &order)?;
+
+    // post-only order must rest on the book, so it can not cross the best opposite price.
+    // a trigger order is checked against the book only once it is activated
+    if post_only
+        && trigger_price.is_none()
+        && orderbook_pair.is_crossing(deps.storage, direction, order.get_price())
+    {
+        return Err(ContractError::WouldTakeLiquidity {});
+    }
 
     let order_id = increase_last_order_id(deps.storage)?;
+    order.order_id = order_id;
+
+    if trigger_price.is_some() {
+        store_pending_order(deps.storage, pair_key, &order)?;
+    } else {
+        store_order(deps.storage, &pair_key, &order, true)?;
+    }
 
-    store_order(
+    let mut attributes = vec![
+        attr("action", "submit_order"),
+        attr("pair", format!("{} - {}", &assets[0].info, &assets[1].info)),
+        attr("order_id", order_id.to_string()),
+        attr("status", format!("{:?}", order.status)),
+        attr("direction", format!("{:?}", direction)),
+        attr("bidder_addr", sender.as_str()),
+        attr(
+            "offer_asset",
+            format!("{} {}", &assets[0].amount, &assets[0].info),
+        ),
+        attr(
+            "ask_asset",
+            format!("{} {}", &assets[1].amount, &assets[1].info),
+        ),
+    ];
+    if let Some(client_order_id) = client_order_id {
+        store_client_order_id(
+            deps.storage,
+            &order.bidder_addr,
+            &client_order_id,
+            order_id,
+            now,
+        )?;
+        attributes.push(attr("client_order_id", client_order_id));
+    }
+    if let Some(trigger_price) = trigger_price {
+        attributes.push(attr("trigger_price", trigger_price.to_string()));
+    }
+
+    let mut response = Response::new().add_attributes(attributes);
+    if order_type == OrderType::Gtc {
+        return Ok(response);
+    }
+
+    // immediate orders are matched right away and alone, no relayer fee is charged
+    // since the bidder matches its own order
+    let result = match_pair(
+        deps.branch(),
+        env,
+        None,
+        pair_key,
+        None,
+        false,
+        Some(&order),
+    )?;
+    response = response
+        .add_attribute("order_type", format!("{:?}", order_type))
+        .add_messages(result.messages)
+        .add_events(result.fills.iter().flat_map(to_events))
+        .add_events(result.trades.iter().map(to_trade_event));
+
+    // a fulfilled order has already left the book
+    if let Ok(order) = read_order(deps.storage, pair_key, order_id) {
+        if order_type == OrderType::Fok {
+            return Err(ContractError::FillOrKillUnfulfilled {});
+        }
+        let left_offer_amount = order.offer_amount.checked_sub(order.filled_offer_amount)?;
+        remove_order(deps.storage, pair_key, &order)?;
+        if !left_offer_amount.is_zero() {
+            response = response.add_message(
+                Asset {
+                    info: assets[0].info.clone(),
+                    amount: left_offer_amount,
+                }
+                .into_msg(None, &deps.querier, sender)?,
+            );
+        }
+        response = response.add_attribute("canceled_offer_amount", left_offer_amount);
+    }
+
+    Ok(response)
+}
+
+pub fn cancel_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+    asset_infos: [AssetInfo; 2],
+    refund_to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let order = read_order_or_pending(deps.storage, &pair_key, order_id)?;
+
+    // only the bidder can cancel the order, and so choose where the refund goes
+    if order.bidder_addr != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let refund_addr = match &refund_to {
+        Some(refund_to) => deps.api.addr_validate(refund_to.as_str())?,
+        None => deps.api.addr_humanize(&order.bidder_addr)?,
+    };
+
+    let mut response = refund_and_remove_order(
+        deps,
+        &pair_key,
+        &orderbook_pair,
+        &order,
+        refund_addr.clone(),
+        "cancel_order",
+    )?;
+
+    if refund_to.is_some() {
+        response = response.add_attribute("refund_to", refund_addr);
+    }
+
+    Ok(response)
+}
+
+/// cancels the unfilled orders of the sender on a pair, fulfilled orders are skipped
+/// and the refunds are batched into one message per asset
+pub fn cancel_all_orders(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    direction: Option<OrderDirection>,
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let bidder_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // trigger orders waiting for activation belong to the bidder too
+    let pending_orders = read_bidder_pending_orders(
         deps.storage,
         &pair_key,
-        &Order {
-            order_id,
-            direction,
-            bidder_addr: deps.api.addr_canonicalize(sender.as_str())?,
-            offer_amount: assets[0].to_raw(deps.api)?.amount,
-            ask_amount: assets[1].to_raw(deps.api)?.amount,
-            filled_offer_amount: Uint128::zero(),
-            filled_ask_amount: Uint128::zero(),
-            status: OrderStatus::Open,
-        },
-        true,
+        &bidder_addr_raw,
+        direction,
+        Some(MAX_LIMIT),
     )?;
+    let direction_filter: Box<dyn Fn(&OrderDirection) -> bool> = match direction {
+        Some(d) => Box::new(move |x| d.eq(x)),
+        None => Box::new(|_| true),
+    };
+    let orders = read_orders_with_indexer::<OrderDirection>(
+        deps.storage,
+        &[
+            PREFIX_ORDER_BY_BIDDER,
+            &pair_key,
+            bidder_addr_raw.as_slice(),
+        ],
+        direction_filter,
+        None,
+        Some(MAX_LIMIT),
+        Some(OrderBy::Ascending),
+    )?
+    .unwrap_or_default();
+
+    let mut base_refund = Asset {
+        info: orderbook_pair.base_coin_info.to_normal(deps.api)?,
+        amount: Uint128::zero(),
+    };
+    let mut quote_refund = Asset {
+        info: orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+        amount: Uint128::zero(),
+    };
+    let mut cancelled_orders: Vec<String> = vec![];
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "submit_order"),
-        (
+    for order in orders.iter().chain(pending_orders.iter()) {
+        if order.status == OrderStatus::Fulfilled {
+            continue;
+        }
+
+        let left_offer_amount = order.offer_amount.checked_sub(order.filled_offer_amount)?;
+        match order.direction {
+            OrderDirection::Buy => quote_refund.amount += left_offer_amount,
+            OrderDirection::Sell => base_refund.amount += left_offer_amount,
+        }
+
+        if order.status == OrderStatus::Pending {
+            remove_pending_order(deps.storage, &pair_key, order);
+        } else {
+            remove_order(deps.storage, &pair_key, order)?;
+        }
+        cancelled_orders.push(order.order_id.to_string());
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for refund in [&base_refund, &quote_refund] {
+        if !refund.amount.is_zero() {
+            messages.push(
+                refund
+                    .clone()
+                    .into_msg(None, &deps.querier, info.sender.clone())?,
+            );
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "cancel_all_orders"),
+        attr(
             "pair",
-            &format!("{} - {}", &assets[0].info, &assets[1].info),
-        ),
-        ("order_id", &order_id.to_string()),
-        ("status", &format!("{:?}", OrderStatus::Open)),
-        ("direction", &format!("{:?}", direction)),
-        ("bidder_addr", sender.as_str()),
-        (
-            "offer_asset",
-            &format!("{} {}", &assets[0].amount, &assets[0].info),
-        ),
-        (
-            "ask_asset",
-            &format!("{} {}", &assets[1].amount, &assets[1].info),
+            format!("{} - {}", base_refund.info, quote_refund.info),
         ),
+        attr("bidder_addr", info.sender),
+        attr("cancelled_count", cancelled_orders.len().to_string()),
+        attr("cancelled_orders", cancelled_orders.join(",")),
+        attr("base_refund", base_refund.to_string()),
+        attr("quote_refund", quote_refund.to_string()),
     ]))
 }
 
-pub fn cancel_order(
+/// cancels a slice of the unfilled offer, the ask amount is reduced proportionally so the order keeps its price
+pub fn cancel_order_partial(
     deps: DepsMut,
     info: MessageInfo,
     order_id: u64,
     asset_infos: [AssetInfo; 2],
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::AssetMustNotBeZero {});
+    }
+
     let pair_key = pair_key(&[
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
@@ -95,68 +397,481 @@ pub fn cancel_order(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Compute refund asset
     let left_offer_amount = order.offer_amount.checked_sub(order.filled_offer_amount)?;
+    if amount > left_offer_amount {
+        return Err(ContractError::CancelAmountExceeded {
+            amount,
+            remaining: left_offer_amount,
+        });
+    }
+
+    let bidder_addr = deps.api.addr_humanize(&order.bidder_addr)?;
+
+    // nothing would be left resting, so this is a full cancel
+    if amount == left_offer_amount {
+        return refund_and_remove_order(
+            deps,
+            &pair_key,
+            &orderbook_pair,
+            &order,
+            bidder_addr,
+            "cancel_order_partial",
+        );
+    }
+
+    let mut updated_order = order.clone();
+    updated_order.offer_amount = order.offer_amount.checked_sub(amount)?;
+    updated_order.ask_amount = order
+        .ask_amount
+        .multiply_ratio(updated_order.offer_amount, order.offer_amount);
+    validate_order(&orderbook_pair, &updated_order)?;
+
+    // re-index the order since rounding may move it to another tick
+    remove_order(deps.storage, &pair_key, &order)?;
+    store_order(deps.storage, &pair_key, &updated_order, true)?;
 
     let bidder_refund = Asset {
         info: match order.direction {
             OrderDirection::Buy => orderbook_pair.quote_coin_info.to_normal(deps.api)?,
             OrderDirection::Sell => orderbook_pair.base_coin_info.to_normal(deps.api)?,
         },
-        amount: left_offer_amount,
+        amount,
     };
 
-    // Build refund msg
-    let messages = if left_offer_amount > Uint128::zero() {
-        vec![bidder_refund.clone().into_msg(
-            None,
-            &deps.querier,
-            deps.api.addr_humanize(&order.bidder_addr)?,
-        )?]
-    } else {
-        vec![]
+    Ok(Response::new()
+        .add_message(
+            bidder_refund
+                .clone()
+                .into_msg(None, &deps.querier, bidder_addr)?,
+        )
+        .add_attribute("action", "cancel_order_partial")
+        .add_attributes(order_attributes(
+            deps.api,
+            &updated_order,
+            &format!("{:?}", updated_order.status),
+        )?)
+        .add_attribute("bidder_refund", bidder_refund.to_string()))
+}
+
+/// amends the price and size of an open order, the order is re-indexed under its new tick.
+/// sent_assets pays the offer increase, a decrease is refunded to the bidder
+#[allow(clippy::too_many_arguments)]
+pub fn amend_order(
+    deps: DepsMut,
+    sender: Addr,
+    order_id: u64,
+    asset_infos: [AssetInfo; 2],
+    new_price: Decimal,
+    new_ask_amount: Uint128,
+    sent_assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let order = read_order(deps.storage, &pair_key, order_id)?;
+
+    if order.bidder_addr != deps.api.addr_canonicalize(sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    match order.status {
+        OrderStatus::Open => {}
+        OrderStatus::Fulfilled => return Err(ContractError::OrderFulfilled { order_id }),
+        _ => return Err(ContractError::AmendFilledOrder { order_id }),
+    }
+    if new_price.is_zero() {
+        return Err(ContractError::AssetMustNotBeZero {});
+    }
+
+    let mut amended_order = Order::new(
+        order_id,
+        order.bidder_addr.clone(),
+        order.direction,
+        new_price,
+        new_ask_amount,
+    )?;
+    amended_order.fill_callback = order.fill_callback.clone();
+    amended_order.expires_at = order.expires_at;
+    amended_order.min_fill_amount = order.min_fill_amount;
+    // the order rests at the price of its rounded amounts, which is the one the book must accept
+    validate_order(&orderbook_pair, &amended_order)?;
+    let amended_price = amended_order.get_price();
+
+    // require minimum amount for quote asset
+    let quote_amount = match order.direction {
+        OrderDirection::Buy => amended_order.offer_amount,
+        OrderDirection::Sell => amended_order.ask_amount,
+    };
+    if quote_amount.lt(&orderbook_pair.min_quote_coin_amount) {
+        return Err(ContractError::TooSmallQuoteAsset {
+            quote_coin: orderbook_pair
+                .quote_coin_info
+                .to_normal(deps.api)?
+                .to_string(),
+            min_quote_amount: orderbook_pair.min_quote_coin_amount,
+        });
+    }
+
+    // the sent funds must pay exactly the offer increase
+    let paid_info = match order.direction {
+        OrderDirection::Buy => orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+        OrderDirection::Sell => orderbook_pair.base_coin_info.to_normal(deps.api)?,
     };
+    let increased_amount = amended_order
+        .offer_amount
+        .checked_sub(order.offer_amount)
+        .unwrap_or_default();
+    let decreased_amount = order
+        .offer_amount
+        .checked_sub(amended_order.offer_amount)
+        .unwrap_or_default();
+    let mut sent_amount = Uint128::zero();
+    for sent_asset in sent_assets.iter() {
+        if sent_asset.info == paid_info {
+            sent_amount += sent_asset.amount;
+        } else if !sent_asset.amount.is_zero() {
+            return Err(ContractError::AssetMismatch {});
+        }
+    }
+    if sent_amount != increased_amount {
+        return Err(ContractError::AssetMismatch {});
+    }
 
     remove_order(deps.storage, &pair_key, &order)?;
+    store_order(deps.storage, &pair_key, &amended_order, true)?;
+
+    let mut messages = vec![];
+    if !decreased_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: paid_info,
+                amount: decreased_amount,
+            }
+            .into_msg(None, &deps.querier, sender.clone())?,
+        );
+    }
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "cancel_order"),
-        (
+        attr("action", "amend_order"),
+        attr("order_id", order_id.to_string()),
+        attr("direction", format!("{:?}", order.direction)),
+        attr("bidder_addr", sender),
+        attr("price", amended_price.to_string()),
+        attr("offer_amount", amended_order.offer_amount.to_string()),
+        attr("ask_amount", amended_order.ask_amount.to_string()),
+        attr("refund_amount", decreased_amount.to_string()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn submit_order_by_quote(
+    deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    direction: OrderDirection,
+    base_coin_info: AssetInfo,
+    quote_asset: Asset,
+    price: Decimal,
+    sent_assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        base_coin_info.to_raw(deps.api)?,
+        quote_asset.info.to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    if orderbook_pair.quote_coin_info.to_normal(deps.api)? != quote_asset.info {
+        return Err(ContractError::AssetMismatch {});
+    }
+    if price.is_zero() || quote_asset.amount.is_zero() {
+        return Err(ContractError::AssetMustNotBeZero {});
+    }
+
+    // with the price in lowest terms, whole multiples of its numerator and denominator keep the
+    // order exactly at the limit price. A buy offers the quote amount and a sell asks for it,
+    // the part of it that is not a whole multiple is left out
+    let divisor = gcd(price.atomics(), Decimal::one().atomics());
+    let (numerator, denominator) = (
+        price.atomics() / divisor,
+        Decimal::one().atomics() / divisor,
+    );
+    let units = quote_asset.amount / numerator;
+    let quote_amount = units * numerator;
+    let base_amount = units.checked_mul(denominator)?;
+    let (offer_amount, ask_amount) = match direction {
+        OrderDirection::Buy => (quote_amount, base_amount),
+        OrderDirection::Sell => (base_amount, quote_amount),
+    };
+
+    // require minimum amount for quote asset
+    if quote_amount.lt(&orderbook_pair.min_quote_coin_amount) {
+        return Err(ContractError::TooSmallQuoteAsset {
+            quote_coin: quote_asset.info.to_string(),
+            min_quote_amount: orderbook_pair.min_quote_coin_amount,
+        });
+    }
+
+    let (paid_info, asked_info) = match direction {
+        OrderDirection::Buy => (quote_asset.info, base_coin_info),
+        OrderDirection::Sell => (base_coin_info, quote_asset.info),
+    };
+    let mut sent_amount = Uint128::zero();
+    for sent_asset in sent_assets.iter() {
+        if sent_asset.info == paid_info {
+            sent_amount += sent_asset.amount;
+        } else if !sent_asset.amount.is_zero() {
+            return Err(ContractError::AssetMismatch {});
+        }
+    }
+    let refund_amount = sent_amount
+        .checked_sub(offer_amount)
+        .map_err(|_| ContractError::AssetMismatch {})?;
+
+    let mut messages = vec![];
+    if !refund_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: paid_info.clone(),
+                amount: refund_amount,
+            }
+            .into_msg(None, &deps.querier, sender.clone())?,
+        );
+    }
+
+    let res = submit_order(
+        deps,
+        env,
+        sender,
+        &pair_key,
+        direction,
+        [
+            Asset {
+                info: paid_info,
+                amount: offer_amount,
+            },
+            Asset {
+                info: asked_info,
+                amount: ask_amount,
+            },
+        ],
+        false,
+        None,
+        None,
+        None,
+        OrderType::Gtc,
+        None,
+        None,
+    )?;
+
+    Ok(res
+        .add_messages(messages)
+        .add_attribute("refund_amount", refund_amount.to_string()))
+}
+
+/// greatest common divisor, puts a price in lowest terms
+fn gcd(mut a: Uint128, mut b: Uint128) -> Uint128 {
+    while !b.is_zero() {
+        let rest = a % b;
+        a = b;
+        b = rest;
+    }
+    a
+}
+
+/// admin can force-cancel any order, the unfilled offer is always refunded to the bidder
+pub fn admin_cancel_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+    asset_infos: [AssetInfo; 2],
+) -> Result<Response, ContractError> {
+    let contract_info = read_config(deps.storage)?;
+    if contract_info.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let order = read_order_or_pending(deps.storage, &pair_key, order_id)?;
+    let bidder_addr = deps.api.addr_humanize(&order.bidder_addr)?;
+
+    let response = refund_and_remove_order(
+        deps,
+        &pair_key,
+        &orderbook_pair,
+        &order,
+        bidder_addr,
+        "admin_cancel_order",
+    )?;
+
+    Ok(response.add_attribute("admin", info.sender))
+}
+
+/// a trigger order is not in the order index until it is activated, but can still be canceled or queried
+fn read_order_or_pending(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    order_id: u64,
+) -> StdResult<Order> {
+    read_order(storage, pair_key, order_id)
+        .or_else(|err| read_pending_order(storage, pair_key, order_id).map_err(|_| err))
+}
+
+/// opens the pending trigger orders of the pair crossed by the mid price, at most MAX_LIMIT per call
+pub fn activate_triggers(
+    deps: DepsMut,
+    asset_infos: [AssetInfo; 2],
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+
+    // with an empty side the mid price is half of the other side, which must not trigger anything
+    let (_, found_buy, _) = orderbook_pair.highest_price(deps.storage, OrderDirection::Buy);
+    let (_, found_sell, _) = orderbook_pair.lowest_price(deps.storage, OrderDirection::Sell);
+    let mid_price = query_mid_price(deps.as_ref(), asset_infos)?;
+
+    let mut activated_ids = vec![];
+    if found_buy && found_sell {
+        for mut order in read_triggered_orders(deps.storage, &pair_key, mid_price, Some(MAX_LIMIT))?
+        {
+            remove_pending_order(deps.storage, &pair_key, &order);
+            order.status = OrderStatus::Open;
+            order.trigger_price = None;
+            store_order(deps.storage, &pair_key, &order, true)?;
+            activated_ids.push(order.order_id.to_string());
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "activate_triggers"),
+        attr(
             "pair",
-            &format!(
+            format!(
                 "{} - {}",
                 &orderbook_pair.base_coin_info.to_normal(deps.api)?,
                 &orderbook_pair.quote_coin_info.to_normal(deps.api)?
             ),
-        ),
-        ("order_id", &order_id.to_string()),
-        ("direction", &format!("{:?}", order.direction)),
-        ("status", "Cancel"),
-        (
-            "bidder_addr",
-            &deps.api.addr_humanize(&order.bidder_addr)?.to_string(),
-        ),
-        ("offer_amount", &order.offer_amount.to_string()),
-        ("ask_amount", &order.ask_amount.to_string()),
-        ("bidder_refund", &bidder_refund.to_string()),
-    ]))
+        ),
+        attr("mid_price", mid_price.to_string()),
+        attr("activated_orders", activated_ids.join(",")),
+    ]))
+}
+
+fn refund_and_remove_order(
+    deps: DepsMut,
+    pair_key: &[u8],
+    orderbook_pair: &OrderBook,
+    order: &Order,
+    refund_addr: Addr,
+    action: &str,
+) -> Result<Response, ContractError> {
+    // Compute refund asset
+    let left_offer_amount = order.offer_amount.checked_sub(order.filled_offer_amount)?;
+
+    let bidder_refund = Asset {
+        info: match order.direction {
+            OrderDirection::Buy => orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+            OrderDirection::Sell => orderbook_pair.base_coin_info.to_normal(deps.api)?,
+        },
+        amount: left_offer_amount,
+    };
+
+    // Build refund msg
+    let messages = if left_offer_amount > Uint128::zero() {
+        vec![bidder_refund
+            .clone()
+            .into_msg(None, &deps.querier, refund_addr)?]
+    } else {
+        vec![]
+    };
+
+    if order.status == OrderStatus::Pending {
+        remove_pending_order(deps.storage, pair_key, order);
+    } else {
+        remove_order(deps.storage, pair_key, order)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", action),
+            (
+                "pair",
+                &format!(
+                    "{} - {}",
+                    &orderbook_pair.base_coin_info.to_normal(deps.api)?,
+                    &orderbook_pair.quote_coin_info.to_normal(deps.api)?
+                ),
+            ),
+        ])
+        .add_attributes(order_attributes(deps.api, order, "Cancel")?)
+        .add_attribute("bidder_refund", bidder_refund.to_string()))
 }
 
-fn to_events(order: &OrderWithFee, human_bidder: String) -> Event {
-    let attrs: Vec<Attribute> = [
-        attr("status", format!("{:?}", order.status)),
-        attr("bidder_addr", human_bidder),
+/// the attributes describing an order as it leaves the book, filled amounts included so partial fills are visible
+fn order_attributes(api: &dyn Api, order: &Order, status: &str) -> StdResult<Vec<Attribute>> {
+    Ok(vec![
         attr("order_id", order.order_id.to_string()),
         attr("direction", format!("{:?}", order.direction)),
+        attr("status", status),
+        attr("bidder_addr", api.addr_humanize(&order.bidder_addr)?),
         attr("offer_amount", order.offer_amount.to_string()),
-        attr("filled_offer_amount", order.filled_offer_amount.to_string()),
         attr("ask_amount", order.ask_amount.to_string()),
+        attr("filled_offer_amount", order.filled_offer_amount.to_string()),
         attr("filled_ask_amount", order.filled_ask_amount.to_string()),
-        attr("reward_fee", order.reward_fee),
-        attr("relayer_fee", order.relayer_fee),
+    ])
+}
+
+/// the matched_order summary of the fill, plus an order_filled event with the trade of this round
+fn to_events(fill: &Fill) -> Vec<Event> {
+    let attrs: Vec<Attribute> = [
+        attr("status", format!("{:?}", fill.status)),
+        attr("bidder_addr", fill.bidder_addr.to_string()),
+        attr("order_id", fill.order_id.to_string()),
+        attr("direction", format!("{:?}", fill.direction)),
+        attr("offer_amount", fill.offer_amount.to_string()),
+        attr("filled_offer_amount", fill.filled_offer_amount.to_string()),
+        attr("ask_amount", fill.ask_amount.to_string()),
+        attr("filled_ask_amount", fill.filled_ask_amount.to_string()),
+        attr("reward_fee", fill.reward_fee),
+        attr("relayer_fee", fill.relayer_fee),
     ]
     .to_vec();
-    Event::new("matched_order").add_attributes(attrs)
+    let mut events = vec![Event::new("matched_order").add_attributes(attrs)];
+
+    // an order left over from a previous round is listed without trading in this one
+    if !fill.round_filled_offer_amount.is_zero() {
+        let mut order_filled = Event::new("order_filled").add_attributes(vec![
+            attr("order_id", fill.order_id.to_string()),
+            attr("match_price", fill.match_price.to_string()),
+            attr("filled_offer", fill.round_filled_offer_amount),
+            attr("filled_ask", fill.round_filled_ask_amount),
+        ]);
+        if let Some(counterparty_order_id) = fill.counterparty_order_id {
+            order_filled = order_filled
+                .add_attribute("counterparty_order_id", counterparty_order_id.to_string());
+        }
+        events.push(order_filled);
+    }
+    events
+}
+
+/// a trade event per pair of orders, its trade_id looks up the participants later
+fn to_trade_event(trade: &Trade) -> Event {
+    Event::new("trade").add_attributes(vec![
+        attr("trade_id", trade.trade_id.to_string()),
+        attr("maker_order_id", trade.maker_order_id.to_string()),
+        attr("taker_order_id", trade.taker_order_id.to_string()),
+        attr("price", trade.price.to_string()),
+        attr("base_amount", trade.base_amount),
+        attr("quote_amount", trade.quote_amount),
+    ])
 }
 
 fn process_reward(
@@ -175,17 +890,16 @@ fn process_reward(
 fn transfer_reward(
     deps: &DepsMut,
     executor: &mut Executor,
-    total_reward: &mut Vec<String>,
+    threshold: Uint128,
     messages: &mut Vec<CosmosMsg>,
 ) -> StdResult<()> {
     for reward_asset in executor.reward_assets.iter_mut() {
-        if Uint128::from(reward_asset.amount) >= Uint128::from(1000000u128) {
+        if !reward_asset.amount.is_zero() && reward_asset.amount >= threshold {
             messages.push(reward_asset.into_msg(
                 None,
                 &deps.querier,
                 deps.api.addr_humanize(&executor.address)?,
             )?);
-            total_reward.push(reward_asset.to_string());
             reward_asset.amount = Uint128::zero();
         }
     }
@@ -222,11 +936,59 @@ fn process_list_trader(
     Ok(())
 }
 
+/// runs the per-order steps of settling an order that can fail: paying out its bidder
+/// and computing what is left of its offer and ask
+fn check_settleable(api: &dyn Api, order: &Order) -> StdResult<()> {
+    api.addr_humanize(&order.bidder_addr)?;
+    order.offer_amount.checked_sub(order.filled_offer_amount)?;
+    order.ask_amount.checked_sub(order.filled_ask_amount)?;
+    Ok(())
+}
+
+/// in best effort mode the orders that could not be settled are dropped before any volume is matched,
+/// so they stay on the book untouched and the counter orders are never filled against their escrow.
+/// otherwise the first of them fails the matching before anything is written
+fn settleable_orders(
+    api: &dyn Api,
+    orders: Vec<Order>,
+    best_effort: bool,
+) -> StdResult<Vec<Order>> {
+    if !best_effort {
+        for order in orders.iter() {
+            check_settleable(api, order)?;
+        }
+        return Ok(orders);
+    }
+    Ok(orders
+        .into_iter()
+        .filter(|order| check_settleable(api, order).is_ok())
+        .collect())
+}
+
+/// moves the orders expired at now out of a loaded tick, they are canceled instead of matched
+fn split_expired_orders(orders: Vec<Order>, now: u64, expired: &mut Vec<Order>) -> Vec<Order> {
+    let (expired_orders, orders): (Vec<Order>, Vec<Order>) =
+        orders.into_iter().partition(|order| order.is_expired(now));
+    expired.extend(expired_orders);
+    orders
+}
+
+#[allow(clippy::type_complexity)]
 fn execute_bulk_orders(
-    deps: &DepsMut,
+    deps: Deps,
     orderbook_pair: OrderBook,
     limit: Option<u32>,
-) -> StdResult<(Vec<BulkOrders>, Vec<BulkOrders>)> {
+    best_effort: bool,
+    now: u64,
+    taker_order: Option<&Order>,
+) -> StdResult<(
+    Vec<BulkOrders>,
+    Vec<BulkOrders>,
+    Vec<TickFill>,
+    Vec<Order>,
+    Vec<(u64, u64)>,
+    bool,
+)> {
     let pair_key = &orderbook_pair.get_pair_key();
     let buy_position_bucket: ReadonlyBucket<u64> = ReadonlyBucket::multilevel(
         deps.storage,
@@ -240,6 +1002,27 @@ fn execute_bulk_orders(
     );
     let mut sell_cursor = sell_position_bucket.range(None, None, OrderBy::Ascending);
 
+    let contract_info = read_config(deps.storage)?;
+    let maker_commission_rate = side_commission_rate(&contract_info, &orderbook_pair, false)?;
+    let taker_commission_rate = side_commission_rate(&contract_info, &orderbook_pair, true)?;
+    let skip_unit = match contract_info.min_commission {
+        Some(min_commission) if min_commission.policy == MinCommissionPolicy::Skip => {
+            min_commission.unit
+        }
+        _ => Uint128::zero(),
+    };
+
+    // budget of orders loaded in this call, a stuffed tick is only read up to the budget.
+    // the caller's limit bounds the orders as well as the ticks, so keepers can chunk the matching
+    let mut remaining_orders = match (
+        contract_info.max_orders_per_match,
+        limit.map(|limit| limit.min(MAX_LIMIT) as u64),
+    ) {
+        (Some(max_orders), Some(limit)) => Some(max_orders.min(limit)),
+        (max_orders, limit) => max_orders.or(limit),
+    };
+    let mut has_more = false;
+
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let mut i = 0;
     let mut j = 0;
@@ -249,65 +1032,136 @@ fn execute_bulk_orders(
     let mut best_sell_price_list = vec![];
     let mut buy_bulk_orders_list = vec![];
     let mut sell_bulk_orders_list = vec![];
+    let mut tick_fills = vec![];
+    let mut expired_orders = vec![];
+    let mut self_trades = vec![];
 
     while i < limit && j < limit {
         if best_sell_price_list.len() <= j {
-            if let Some(Ok((k, _))) = sell_cursor.next() {
-                let price =
-                    Decimal::raw(u128::from_be_bytes(k.try_into().map_err(|_| {
-                        StdError::generic_err("Error converting bytes to u128")
-                    })?));
-                best_sell_price_list.push(price);
-            } else {
-                break;
+            // an immediate order is matched alone, on its own side it is the only tick
+            let next_tick = match taker_order {
+                Some(order) if order.direction == OrderDirection::Sell => {
+                    Some((order.get_price(), 1)).filter(|_| best_sell_price_list.is_empty())
+                }
+                _ => match sell_cursor.next() {
+                    Some(Ok((k, total_orders))) => Some((
+                        Decimal::raw(u128::from_be_bytes(k.try_into().map_err(|_| {
+                            StdError::generic_err("Error converting bytes to u128")
+                        })?)),
+                        total_orders,
+                    )),
+                    _ => None,
+                },
+            };
+            match next_tick {
+                Some(tick) => best_sell_price_list.push(tick),
+                None => break,
             }
         }
-        let sell_price = best_sell_price_list[j];
+        let (sell_price, sell_tick_orders) = best_sell_price_list[j];
 
         if best_buy_price_list.len() <= i {
-            if let Some(Ok((k, _))) = buy_cursor.next() {
-                let price =
-                    Decimal::raw(u128::from_be_bytes(k.try_into().map_err(|_| {
-                        StdError::generic_err("Error converting bytes to u128")
-                    })?));
-                best_buy_price_list.push(price);
-            } else {
-                break;
+            // an immediate order is matched alone, on its own side it is the only tick
+            let next_tick = match taker_order {
+                Some(order) if order.direction == OrderDirection::Buy => {
+                    Some((order.get_price(), 1)).filter(|_| best_buy_price_list.is_empty())
+                }
+                _ => match buy_cursor.next() {
+                    Some(Ok((k, total_orders))) => Some((
+                        Decimal::raw(u128::from_be_bytes(k.try_into().map_err(|_| {
+                            StdError::generic_err("Error converting bytes to u128")
+                        })?)),
+                        total_orders,
+                    )),
+                    _ => None,
+                },
+            };
+            match next_tick {
+                Some(tick) => best_buy_price_list.push(tick),
+                None => break,
             }
         }
-        let buy_price = best_buy_price_list[i];
+        let (buy_price, buy_tick_orders) = best_buy_price_list[i];
         if buy_price < sell_price {
             break;
         }
         if buy_bulk_orders_list.len() <= i {
-            if let Some(orders) = orderbook_pair.query_orders_by_price_and_direction(
-                deps.as_ref().storage,
-                buy_price,
-                OrderDirection::Buy,
-                None,
-            ) {
+            // keep an order of the budget for the sell tick, or a stuffed buy tick would stall the matching
+            let buy_budget = if sell_bulk_orders_list.len() <= j {
+                remaining_orders.map(|remaining| remaining.saturating_sub(1))
+            } else {
+                remaining_orders
+            };
+            if buy_budget == Some(0) {
+                has_more = true;
+                break;
+            }
+            let orders = match taker_order {
+                Some(order) if order.direction == OrderDirection::Buy => Some(vec![order.clone()]),
+                _ => orderbook_pair.query_orders_by_price_and_direction(
+                    deps.storage,
+                    buy_price,
+                    OrderDirection::Buy,
+                    buy_budget.map(|remaining| remaining.min(u32::MAX as u64) as u32),
+                ),
+            };
+            if let Some(orders) = orders {
                 if orders.len() == 0 {
                     continue;
                 }
+                if let Some(remaining) = remaining_orders.as_mut() {
+                    *remaining -= orders.len() as u64;
+                    if (orders.len() as u64) < buy_tick_orders {
+                        has_more = true;
+                    }
+                }
+                let orders = settleable_orders(deps.api, orders, best_effort)?;
+                let orders = split_expired_orders(orders, now, &mut expired_orders);
                 let bulk = BulkOrders::from_orders(&orders, buy_price, OrderDirection::Buy);
                 buy_bulk_orders_list.push(bulk);
+                // every order of the tick was dropped, keep the empty bulk and move to the next tick
+                if orders.is_empty() {
+                    i += 1;
+                    continue;
+                }
             } else {
                 break;
             }
         };
 
         if sell_bulk_orders_list.len() <= j {
-            if let Some(orders) = orderbook_pair.query_orders_by_price_and_direction(
-                deps.as_ref().storage,
-                sell_price,
-                OrderDirection::Sell,
-                None,
-            ) {
+            if remaining_orders == Some(0) {
+                has_more = true;
+                break;
+            }
+            let orders = match taker_order {
+                Some(order) if order.direction == OrderDirection::Sell => Some(vec![order.clone()]),
+                _ => orderbook_pair.query_orders_by_price_and_direction(
+                    deps.storage,
+                    sell_price,
+                    OrderDirection::Sell,
+                    remaining_orders.map(|remaining| remaining.min(u32::MAX as u64) as u32),
+                ),
+            };
+            if let Some(orders) = orders {
                 if orders.len() == 0 {
                     continue;
                 }
+                if let Some(remaining) = remaining_orders.as_mut() {
+                    *remaining -= orders.len() as u64;
+                    if (orders.len() as u64) < sell_tick_orders {
+                        has_more = true;
+                    }
+                }
+                let orders = settleable_orders(deps.api, orders, best_effort)?;
+                let orders = split_expired_orders(orders, now, &mut expired_orders);
                 let bulk = BulkOrders::from_orders(&orders, sell_price, OrderDirection::Sell);
                 sell_bulk_orders_list.push(bulk);
+                // every order of the tick was dropped, keep the empty bulk and move to the next tick
+                if orders.is_empty() {
+                    j += 1;
+                    continue;
+                }
             } else {
                 break;
             }
@@ -317,12 +1171,14 @@ fn execute_bulk_orders(
         let buy_bulk_orders = &mut buy_bulk_orders_list[i];
         let sell_bulk_orders = &mut sell_bulk_orders_list[j];
 
-        // match price
-        let match_price = if buy_bulk_orders.average_order_id >= sell_bulk_orders.average_order_id {
-            buy_price
-        } else {
-            sell_price
-        };
+        // match price, the later order is the taker
+        let (match_price, taker) =
+            if buy_bulk_orders.average_order_id >= sell_bulk_orders.average_order_id {
+                (buy_price, OrderDirection::Buy)
+            } else {
+                (sell_price, OrderDirection::Sell)
+            };
+        let match_price = improve_match_price(match_price, buy_price, sell_price, taker);
 
         // remaining_sell_ask_volume = remaining_sell_volume * match_price
         let remaining_sell_volume = sell_bulk_orders.remaining_volume;
@@ -347,13 +1203,96 @@ fn execute_bulk_orders(
 
         // fill_base_volume = min(remaining_sell_volume, remaining_buy_ask_volume)
         // fill_quote_volume = fill_base_volume * match_price
-        let fill_base_volume = Uint128::min(remaining_sell_volume, remaining_buy_ask_volume);
+        // the fill stops before the first pair of orders of the same bidder it would match
+        let (fill_base_volume, self_trade) = volume_before_self_trade(
+            buy_bulk_orders,
+            sell_bulk_orders,
+            Uint128::min(remaining_sell_volume, remaining_buy_ask_volume),
+        );
         let fill_quote_volume = Uint128::from(fill_base_volume * match_price);
 
+        // nothing trades before the self-trade, so the newer order of the pair leaves the round,
+        // or its whole tick does when the order already took volume in this round
+        if let Some([(buy_position, buy_allocated), (sell_position, sell_allocated)]) =
+            self_trade.filter(|_| fill_quote_volume.is_zero())
+        {
+            let buy_order_id = buy_bulk_orders.orders[buy_position].order_id;
+            let sell_order_id = sell_bulk_orders.orders[sell_position].order_id;
+            if !self_trades.contains(&(buy_order_id, sell_order_id)) {
+                self_trades.push((buy_order_id, sell_order_id));
+            }
+            match (
+                buy_order_id > sell_order_id,
+                buy_allocated.is_zero(),
+                sell_allocated.is_zero(),
+            ) {
+                (true, true, _) => {
+                    buy_bulk_orders.take_order(buy_position);
+                }
+                (true, false, _) => i += 1,
+                (false, _, true) => {
+                    sell_bulk_orders.take_order(sell_position);
+                }
+                (false, _, false) => j += 1,
+            }
+            continue;
+        }
+
         if fill_base_volume.is_zero() || fill_quote_volume.is_zero() {
             continue;
         }
 
+        // skip the fill when either side can not pay the minimum commission at the rate calculate_fee charges it,
+        // the smaller side stays on the book and matching moves to its next tick
+        let (buy_commission_rate, sell_commission_rate) = match taker {
+            OrderDirection::Buy => (taker_commission_rate, maker_commission_rate),
+            OrderDirection::Sell => (maker_commission_rate, taker_commission_rate),
+        };
+        if (!buy_commission_rate.is_zero() && fill_base_volume * buy_commission_rate < skip_unit)
+            || (!sell_commission_rate.is_zero()
+                && fill_quote_volume * sell_commission_rate < skip_unit)
+        {
+            if fill_base_volume == remaining_sell_volume {
+                j += 1;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        // skip a fill that would leave an order with less than its minimum fill,
+        // like the commission skip the smaller side moves to its next tick
+        if is_below_min_fill(sell_bulk_orders, fill_base_volume)
+            || is_below_min_fill(buy_bulk_orders, fill_quote_volume)
+        {
+            if fill_base_volume == remaining_sell_volume {
+                j += 1;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        match taker {
+            OrderDirection::Buy => buy_bulk_orders.is_taker = true,
+            OrderDirection::Sell => sell_bulk_orders.is_taker = true,
+        }
+        if buy_bulk_orders.counterparty_order_id.is_none() {
+            buy_bulk_orders.counterparty_order_id =
+                sell_bulk_orders.orders.first().map(|order| order.order_id);
+        }
+        if sell_bulk_orders.counterparty_order_id.is_none() {
+            sell_bulk_orders.counterparty_order_id =
+                buy_bulk_orders.orders.first().map(|order| order.order_id);
+        }
+        tick_fills.push(TickFill {
+            buy_index: i,
+            sell_index: j,
+            base_volume: fill_base_volume,
+            price: match_price,
+            taker,
+        });
+
         // In sell side
         // filled_volume = filled_volume + fill_base_volume
         // filled_ask_volume = filled_ask_volume + fill_quote_volume
@@ -402,55 +1341,200 @@ fn execute_bulk_orders(
         }
     }
 
-    return Ok((buy_bulk_orders_list, sell_bulk_orders_list));
+    return Ok((
+        buy_bulk_orders_list,
+        sell_bulk_orders_list,
+        tick_fills,
+        expired_orders,
+        self_trades,
+        has_more,
+    ));
+}
+
+/// the orders of the bulk still able to take base volume in the order process_orders distributes it,
+/// with their position, the base volume they already received and the base volume left to them
+fn base_volume_queue(bulk: &BulkOrders) -> Vec<(usize, Uint128, Uint128)> {
+    // buy orders receive the base asset as ask, sell orders give it as offer
+    let mut allocated_volume = match bulk.direction {
+        OrderDirection::Buy => bulk.filled_ask_volume,
+        OrderDirection::Sell => bulk.filled_volume,
+    };
+    let mut queue = vec![];
+    for (position, order) in bulk.orders.iter().enumerate() {
+        let remaining_volume = match bulk.direction {
+            OrderDirection::Buy => order.ask_amount.checked_sub(order.filled_ask_amount),
+            OrderDirection::Sell => order.offer_amount.checked_sub(order.filled_offer_amount),
+        }
+        .unwrap_or_default();
+        let allocated = Uint128::min(allocated_volume, remaining_volume);
+        allocated_volume -= allocated;
+        if allocated < remaining_volume {
+            queue.push((position, allocated, remaining_volume - allocated));
+        }
+    }
+    queue
+}
+
+/// walks the orders of both ticks pair by pair for up to base_volume, and returns the volume traded before
+/// the first pair of orders of the same bidder with the position and received base volume of both orders.
+/// the whole base_volume is returned when no such pair is reached
+fn volume_before_self_trade(
+    buy_bulk: &BulkOrders,
+    sell_bulk: &BulkOrders,
+    base_volume: Uint128,
+) -> (Uint128, Option<[(usize, Uint128); 2]>) {
+    let mut buy_queue = base_volume_queue(buy_bulk);
+    let mut sell_queue = base_volume_queue(sell_bulk);
+    let (mut buy_index, mut sell_index) = (0, 0);
+    let mut traded_volume = Uint128::zero();
+    while traded_volume < base_volume
+        && buy_index < buy_queue.len()
+        && sell_index < sell_queue.len()
+    {
+        let (buy_position, buy_allocated, buy_left) = buy_queue[buy_index];
+        let (sell_position, sell_allocated, sell_left) = sell_queue[sell_index];
+        if buy_bulk.orders[buy_position].bidder_addr == sell_bulk.orders[sell_position].bidder_addr
+        {
+            return (
+                traded_volume,
+                Some([
+                    (buy_position, buy_allocated),
+                    (sell_position, sell_allocated),
+                ]),
+            );
+        }
+
+        let volume = buy_left.min(sell_left).min(base_volume - traded_volume);
+        traded_volume += volume;
+        buy_queue[buy_index] = (buy_position, buy_allocated + volume, buy_left - volume);
+        sell_queue[sell_index] = (sell_position, sell_allocated + volume, sell_left - volume);
+        if buy_queue[buy_index].2.is_zero() {
+            buy_index += 1;
+        }
+        if sell_queue[sell_index].2.is_zero() {
+            sell_index += 1;
+        }
+    }
+    (base_volume, None)
+}
+
+/// whether adding fill_volume to the bulk gives the order receiving it less than its minimum fill,
+/// orders receive the filled volume in the same order as process_orders distributes it
+fn is_below_min_fill(bulk: &BulkOrders, fill_volume: Uint128) -> bool {
+    let mut allocated_volume = bulk.filled_volume;
+    for order in bulk.orders.iter() {
+        let remaining_offer = order
+            .offer_amount
+            .checked_sub(order.filled_offer_amount)
+            .unwrap_or_default();
+        if allocated_volume >= remaining_offer {
+            allocated_volume -= remaining_offer;
+            continue;
+        }
+
+        let round_fill = Uint128::min(remaining_offer, allocated_volume + fill_volume);
+        return match order.min_fill_amount {
+            Some(min_fill_amount) => round_fill < min_fill_amount && round_fill < remaining_offer,
+            None => false,
+        };
+    }
+    false
+}
+
+/// the taker must never get a worse price than its limit, otherwise fall back to the limit price
+pub fn improve_match_price(
+    match_price: Decimal,
+    buy_price: Decimal,
+    sell_price: Decimal,
+    taker: OrderDirection,
+) -> Decimal {
+    match taker {
+        OrderDirection::Buy if match_price > buy_price => buy_price,
+        OrderDirection::Sell if match_price < sell_price => sell_price,
+        _ => match_price,
+    }
+}
+
+/// the rate of the pair applies to both makers and takers,
+/// otherwise the side pays the maker or taker rate of the contract, or its default rate when not set
+fn side_commission_rate(
+    contract_info: &ContractInfo,
+    orderbook_pair: &OrderBook,
+    is_taker: bool,
+) -> StdResult<Decimal> {
+    let commission_rate = match (
+        orderbook_pair.commission_rate.as_ref(),
+        is_taker,
+        contract_info.maker_commission_rate.as_ref(),
+        contract_info.taker_commission_rate.as_ref(),
+    ) {
+        (Some(pair_commission_rate), ..) => pair_commission_rate,
+        (None, false, Some(maker_commission_rate), _) => maker_commission_rate,
+        (None, true, _, Some(taker_commission_rate)) => taker_commission_rate,
+        _ => &contract_info.commission_rate,
+    };
+    Decimal::from_str(commission_rate)
 }
 
-// TODO: write test cases for this function
+#[allow(clippy::too_many_arguments)]
 fn calculate_fee(
-    deps: &DepsMut,
+    deps: Deps,
+    orderbook_pair: &OrderBook,
     amount: Uint128,
     relayer_quote_fee: Uint128,
     direction: OrderDirection,
+    is_taker: bool,
+    charge_relayer_fee: bool,
     trader_ask_asset: &mut Asset,
     reward: &mut Executor,
     relayer: &mut Executor,
 ) -> StdResult<(Uint128, Uint128)> {
-    let reward_fee: Uint128;
-    let relayer_fee: Uint128;
     let contract_info = read_config(deps.storage)?;
-    let commission_rate = Decimal::from_str(&contract_info.commission_rate)?;
+    let commission_rate = side_commission_rate(&contract_info, orderbook_pair, is_taker)?;
 
-    reward_fee = amount * commission_rate;
-
-    match direction {
-        OrderDirection::Buy => {
-            relayer_fee = Uint128::min(Uint128::from(RELAY_FEE), amount);
+    // buy orders pay in base asset, sell orders in quote asset
+    let (relayer_fee, fee_index) = match direction {
+        OrderDirection::Buy => (Uint128::min(Uint128::from(RELAY_FEE), amount), 0),
+        OrderDirection::Sell => (Uint128::min(relayer_quote_fee, amount), 1),
+    };
+    // a match nobody relayed pays no relayer fee
+    let relayer_fee = if charge_relayer_fee {
+        relayer_fee
+    } else {
+        Uint128::zero()
+    };
 
-            reward.reward_assets[0].amount += reward_fee;
-            relayer.reward_assets[0].amount += relayer_fee;
-        }
-        OrderDirection::Sell => {
-            relayer_fee = Uint128::min(relayer_quote_fee, amount);
+    // the relayer fee is taken first, so both fees never credit more than the filled amount
+    let reward_fee = Uint128::min(
+        match contract_info.min_commission {
+            Some(min_commission)
+                if min_commission.policy == MinCommissionPolicy::RoundUp
+                    && !commission_rate.is_zero() =>
+            {
+                Uint128::max(amount * commission_rate, min_commission.unit)
+            }
+            _ => amount * commission_rate,
+        },
+        amount.checked_sub(relayer_fee)?,
+    );
 
-            reward.reward_assets[1].amount += reward_fee;
-            relayer.reward_assets[1].amount += relayer_fee;
-        }
-    }
+    reward.reward_assets[fee_index].amount += reward_fee;
+    relayer.reward_assets[fee_index].amount += relayer_fee;
 
     trader_ask_asset.amount = trader_ask_asset
         .amount
-        .checked_sub(reward_fee + relayer_fee)
-        .unwrap_or_default();
-    return Ok((reward_fee, relayer_fee));
+        .checked_sub(reward_fee + relayer_fee)?;
+    Ok((reward_fee, relayer_fee))
 }
 
 fn process_orders(
-    deps: &DepsMut,
+    deps: Deps,
     orderbook_pair: &OrderBook,
     bulk_orders: &mut Vec<BulkOrders>,
     bulk_traders: &mut Vec<Payment>,
     reward: &mut Executor,
     relayer: &mut Executor,
+    charge_relayer_fee: bool,
 ) -> StdResult<()> {
     for bulk in bulk_orders.iter_mut() {
         let mut trader_ask_asset = Asset {
@@ -499,15 +1583,20 @@ fn process_orders(
 
             // fill order
             order.fill_order(filled_ask, filled_offer);
+            order.round_filled_offer_amount = filled_offer;
+            order.round_filled_ask_amount = filled_ask;
 
             // calculate fee
             if !filled_ask.is_zero() {
                 trader_ask_asset.amount = filled_ask;
                 let (reward_fee, relayer_fee) = calculate_fee(
                     deps,
+                    orderbook_pair,
                     filled_ask,
                     relayer_quote_fee,
                     bulk.direction,
+                    bulk.is_taker,
+                    charge_relayer_fee,
                     &mut trader_ask_asset,
                     reward,
                     relayer,
@@ -530,19 +1619,121 @@ fn process_orders(
     Ok(())
 }
 
-pub fn execute_matching_orders(
+/// pairs the orders of the two ticks of every tick fill in the order process_orders gave them the volume,
+/// and stores a trade for each pair in the trade log
+fn record_trades(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    buy_list: &[BulkOrders],
+    sell_list: &[BulkOrders],
+    tick_fills: &[TickFill],
+) -> StdResult<Vec<Trade>> {
+    // base amount of every order left to pair, buy orders receive it and sell orders give it
+    let mut buy_left: Vec<Vec<Uint128>> = buy_list
+        .iter()
+        .map(|bulk| {
+            bulk.orders
+                .iter()
+                .map(|order| order.round_filled_ask_amount)
+                .collect()
+        })
+        .collect();
+    let mut sell_left: Vec<Vec<Uint128>> = sell_list
+        .iter()
+        .map(|bulk| {
+            bulk.orders
+                .iter()
+                .map(|order| order.round_filled_offer_amount)
+                .collect()
+        })
+        .collect();
+
+    let mut trades = vec![];
+    for tick_fill in tick_fills {
+        let buy_left = &mut buy_left[tick_fill.buy_index];
+        let sell_left = &mut sell_left[tick_fill.sell_index];
+        let mut base_left = tick_fill.base_volume;
+        while !base_left.is_zero() {
+            let (buy_position, sell_position) = match (
+                buy_left.iter().position(|amount| !amount.is_zero()),
+                sell_left.iter().position(|amount| !amount.is_zero()),
+            ) {
+                (Some(buy_position), Some(sell_position)) => (buy_position, sell_position),
+                _ => break,
+            };
+            let base_amount = base_left
+                .min(buy_left[buy_position])
+                .min(sell_left[sell_position]);
+            base_left -= base_amount;
+            buy_left[buy_position] -= base_amount;
+            sell_left[sell_position] -= base_amount;
+
+            let buy_order = &buy_list[tick_fill.buy_index].orders[buy_position];
+            let sell_order = &sell_list[tick_fill.sell_index].orders[sell_position];
+            let (maker, taker) = match tick_fill.taker {
+                OrderDirection::Buy => (sell_order, buy_order),
+                OrderDirection::Sell => (buy_order, sell_order),
+            };
+            let trade = Trade {
+                trade_id: increase_last_trade_id(storage)?,
+                maker_order_id: maker.order_id,
+                maker_addr: maker.bidder_addr.clone(),
+                taker_order_id: taker.order_id,
+                taker_addr: taker.bidder_addr.clone(),
+                taker_direction: tick_fill.taker,
+                price: tick_fill.price,
+                base_amount,
+                quote_amount: base_amount * tick_fill.price,
+            };
+            store_trade(storage, pair_key, &trade)?;
+            trades.push(trade);
+        }
+    }
+    Ok(trades)
+}
+
+/// matches the order book of a pair and returns the structured result without building a response,
+/// so other contracts can run the matching core and inspect fills directly
+pub fn match_orders(
     deps: DepsMut,
-    info: MessageInfo,
+    env: &Env,
+    relayer: Addr,
     asset_infos: [AssetInfo; 2],
     limit: Option<u32>,
-) -> Result<Response, ContractError> {
-    let contract_info = read_config(deps.storage)?;
-    let relayer_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    best_effort: bool,
+) -> Result<MatchResult, ContractError> {
+    let relayer_addr = deps.api.addr_canonicalize(relayer.as_str())?;
+    if !is_allowed_executor(deps.storage, &relayer_addr)? {
+        return Err(ContractError::Unauthorized {});
+    }
     let pair_key = pair_key(&[
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ]);
-    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    match_pair(
+        deps,
+        env,
+        Some(relayer_addr),
+        &pair_key,
+        limit,
+        best_effort,
+        None,
+    )
+}
+
+/// matches the book of a pair, or only the given taker order against the book when it is set.
+/// the relayer earns the relayer fees, a match without relayer charges none
+fn match_pair(
+    deps: DepsMut,
+    env: &Env,
+    relayer_addr: Option<CanonicalAddr>,
+    pair_key: &[u8],
+    limit: Option<u32>,
+    best_effort: bool,
+    taker_order: Option<&Order>,
+) -> Result<MatchResult, ContractError> {
+    let contract_info = read_config(deps.storage)?;
+    let orderbook_pair = read_orderbook(deps.storage, pair_key)?;
     let reward_assets = [
         Asset {
             info: orderbook_pair.base_coin_info.to_normal(deps.api)?,
@@ -555,85 +1746,444 @@ pub fn execute_matching_orders(
     ];
     let mut reward = process_reward(
         deps.storage,
-        &pair_key,
+        pair_key,
         contract_info.reward_address,
         reward_assets.clone(),
     );
 
-    let mut relayer = process_reward(deps.storage, &pair_key, relayer_addr, reward_assets);
+    let mut relayer = match relayer_addr.clone() {
+        Some(relayer_addr) => {
+            process_reward(deps.storage, pair_key, relayer_addr, reward_assets.clone())
+        }
+        None => Executor::new(CanonicalAddr::from(vec![]), reward_assets.clone()),
+    };
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut list_bidder: Vec<Payment> = vec![];
     let mut list_asker: Vec<Payment> = vec![];
-    let mut ret_events: Vec<Event> = vec![];
-    let mut total_reward: Vec<String> = Vec::new();
-    let mut total_orders: u64 = 0;
-
-    let (mut buy_list, mut sell_list) = execute_bulk_orders(&deps, orderbook_pair.clone(), limit)?;
+    let mut list_refund: Vec<Payment> = vec![];
+    let mut fills: Vec<Fill> = vec![];
+    let mut callback_messages: Vec<CosmosMsg> = vec![];
+    let mut executor_reward = reward_assets;
+
+    let (mut buy_list, mut sell_list, tick_fills, expired, self_trades, has_more) =
+        execute_bulk_orders(
+            deps.as_ref(),
+            orderbook_pair.clone(),
+            limit,
+            best_effort,
+            env.block.time.seconds(),
+            taker_order,
+        )?;
 
     process_orders(
-        &deps,
+        deps.as_ref(),
         &orderbook_pair,
         &mut buy_list,
         &mut list_bidder,
         &mut reward,
         &mut relayer,
+        relayer_addr.is_some(),
     )?;
     process_orders(
-        &deps,
+        deps.as_ref(),
         &orderbook_pair,
         &mut sell_list,
         &mut list_asker,
         &mut reward,
         &mut relayer,
+        relayer_addr.is_some(),
+    )?;
+    let trades = record_trades(deps.storage, pair_key, &buy_list, &sell_list, &tick_fills)?;
+
+    for bulk in buy_list.iter_mut().chain(sell_list.iter_mut()) {
+        for order in bulk.orders.iter_mut() {
+            if order.status != OrderStatus::Open {
+                // a remainder below the dust threshold would never match, refund it instead of resting it
+                if let Some(dust_threshold) = contract_info.dust_threshold {
+                    let left_offer_amount =
+                        order.offer_amount.checked_sub(order.filled_offer_amount)?;
+                    if order.status == OrderStatus::PartialFilled
+                        && left_offer_amount < dust_threshold
+                    {
+                        order.status = OrderStatus::Cancel;
+                        list_refund.push(Payment {
+                            address: deps.api.addr_humanize(&order.bidder_addr)?,
+                            asset: Asset {
+                                info: match order.direction {
+                                    OrderDirection::Buy => {
+                                        orderbook_pair.quote_coin_info.to_normal(deps.api)?
+                                    }
+                                    OrderDirection::Sell => {
+                                        orderbook_pair.base_coin_info.to_normal(deps.api)?
+                                    }
+                                },
+                                amount: left_offer_amount,
+                            },
+                        });
+                    }
+                }
+                order.match_order(deps.storage, pair_key)?;
+                // buy orders pay commission in base asset, sell orders in quote asset.
+                // the base sold by the sell orders is the volume matched by the relayer
+                match order.direction {
+                    OrderDirection::Buy => executor_reward[0].amount += order.reward_fee,
+                    OrderDirection::Sell => {
+                        executor_reward[1].amount += order.reward_fee;
+                        relayer.matched_volume += order.round_filled_offer_amount;
+                    }
+                }
+                if let Some(hook) = order.fill_callback.as_ref() {
+                    callback_messages.push(
+                        FillHookMsg::OrderFilled {
+                            order_id: order.order_id,
+                            status: order.status,
+                            direction: order.direction,
+                            offer_amount: order.offer_amount,
+                            ask_amount: order.ask_amount,
+                            filled_offer_amount: order.filled_offer_amount,
+                            filled_ask_amount: order.filled_ask_amount,
+                            msg: hook.msg.clone(),
+                        }
+                        .into_cosmos_msg(&hook.contract_addr)?,
+                    );
+                }
+                fills.push(Fill {
+                    order_id: order.order_id,
+                    status: order.status,
+                    direction: order.direction,
+                    bidder_addr: deps.api.addr_humanize(&order.bidder_addr)?,
+                    offer_amount: order.offer_amount,
+                    ask_amount: order.ask_amount,
+                    filled_offer_amount: order.filled_offer_amount,
+                    filled_ask_amount: order.filled_ask_amount,
+                    reward_fee: order.reward_fee,
+                    relayer_fee: order.relayer_fee,
+                    round_filled_offer_amount: order.round_filled_offer_amount,
+                    round_filled_ask_amount: order.round_filled_ask_amount,
+                    // both round amounts are zero when the order did not trade in this round
+                    match_price: match order.direction {
+                        _ if order.round_filled_offer_amount.is_zero() => Decimal::zero(),
+                        OrderDirection::Buy => Decimal::from_ratio(
+                            order.round_filled_offer_amount,
+                            order.round_filled_ask_amount,
+                        ),
+                        OrderDirection::Sell => Decimal::from_ratio(
+                            order.round_filled_ask_amount,
+                            order.round_filled_offer_amount,
+                        ),
+                    },
+                    counterparty_order_id: bulk.counterparty_order_id,
+                });
+            }
+        }
+    }
+
+    process_list_trader(&deps, list_bidder, &mut messages)?;
+    process_list_trader(&deps, list_asker, &mut messages)?;
+
+    // expired orders are refunded like cancel_order and leave the book
+    let mut expired_orders = vec![];
+    for order in expired.iter() {
+        let left_offer_amount = order.offer_amount.checked_sub(order.filled_offer_amount)?;
+        list_refund.push(Payment {
+            address: deps.api.addr_humanize(&order.bidder_addr)?,
+            asset: Asset {
+                info: match order.direction {
+                    OrderDirection::Buy => orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+                    OrderDirection::Sell => orderbook_pair.base_coin_info.to_normal(deps.api)?,
+                },
+                amount: left_offer_amount,
+            },
+        });
+        remove_order(deps.storage, pair_key, order)?;
+        expired_orders.push(order.order_id);
+    }
+
+    // the newer order of a self-trade is canceled so the book no longer crosses itself
+    if contract_info.stp_cancel_newest.unwrap_or(false) {
+        let mut canceled_order_ids: Vec<u64> = vec![];
+        for (buy_order_id, sell_order_id) in self_trades.iter() {
+            let order_id = u64::max(*buy_order_id, *sell_order_id);
+            if canceled_order_ids.contains(&order_id) {
+                continue;
+            }
+            // already gone when it also expired in this round
+            if let Ok(order) = read_order(deps.storage, pair_key, order_id) {
+                let left_offer_amount =
+                    order.offer_amount.checked_sub(order.filled_offer_amount)?;
+                list_refund.push(Payment {
+                    address: deps.api.addr_humanize(&order.bidder_addr)?,
+                    asset: Asset {
+                        info: match order.direction {
+                            OrderDirection::Buy => {
+                                orderbook_pair.quote_coin_info.to_normal(deps.api)?
+                            }
+                            OrderDirection::Sell => {
+                                orderbook_pair.base_coin_info.to_normal(deps.api)?
+                            }
+                        },
+                        amount: left_offer_amount,
+                    },
+                });
+                remove_order(deps.storage, pair_key, &order)?;
+            }
+            canceled_order_ids.push(order_id);
+        }
+    }
+    process_list_trader(&deps, list_refund, &mut messages)?;
+    // callbacks run after the payouts so receivers already hold the filled assets
+    messages.extend(callback_messages);
+
+    // pay out executors whose accrued reward crossed the threshold
+    let threshold = contract_info
+        .auto_distribute_threshold
+        .unwrap_or(Uint128::from(DEFAULT_AUTO_DISTRIBUTE_THRESHOLD));
+    transfer_reward(&deps, &mut reward, threshold, &mut messages)?;
+    store_reward(deps.storage, pair_key, &reward)?;
+    if relayer_addr.is_some() {
+        transfer_reward(&deps, &mut relayer, threshold, &mut messages)?;
+        store_reward(deps.storage, pair_key, &relayer)?;
+    }
+
+    Ok(MatchResult {
+        fills,
+        messages,
+        executor_reward,
+        has_more,
+        expired_orders,
+        self_trades,
+        trades,
+    })
+}
+
+pub fn execute_matching_orders(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    limit: Option<u32>,
+    best_effort: bool,
+) -> Result<Response, ContractError> {
+    let result = match_orders(
+        deps.branch(),
+        &env,
+        info.sender,
+        asset_infos.clone(),
+        limit,
+        best_effort,
     )?;
 
-    for bulk in buy_list.iter_mut() {
-        for buy_order in bulk.orders.iter_mut() {
-            if buy_order.status != OrderStatus::Open {
-                total_orders += 1;
-                buy_order.match_order(deps.storage, &pair_key)?;
-                ret_events.push(to_events(
-                    &buy_order,
-                    deps.api.addr_humanize(&buy_order.bidder_addr)?.to_string(),
-                ));
-            }
-        }
-    }
+    // a keeper calls again while orders are left on crossing ticks
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orders_remaining =
+        read_orderbook(deps.storage, &pair_key)?.crossing_order_count(deps.storage);
+
+    let mut response = Response::new()
+        .add_messages(result.messages)
+        .add_attributes(vec![
+            ("action", "execute_orderbook_pair"),
+            (
+                "pair",
+                &format!("{} - {}", &asset_infos[0], &asset_infos[1]),
+            ),
+            ("total_matched_orders", &result.fills.len().to_string()),
+            (
+                "executor_reward",
+                &result
+                    .executor_reward
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            ("has_more", &result.has_more.to_string()),
+            ("expired_orders", &result.expired_orders.len().to_string()),
+            ("orders_remaining", &orders_remaining.to_string()),
+        ]);
+    if !result.self_trades.is_empty() {
+        response = response.add_attribute(
+            "self_trade_skipped",
+            result
+                .self_trades
+                .iter()
+                .map(|(buy_order_id, sell_order_id)| format!("{}-{}", buy_order_id, sell_order_id))
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+    }
+
+    Ok(response
+        .add_events(result.fills.iter().flat_map(to_events))
+        .add_events(result.trades.iter().map(to_trade_event)))
+}
+
+pub fn claim_executor_reward(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+) -> Result<Response, ContractError> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let executor_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut executor = read_reward(deps.storage, &pair_key, &executor_addr)?;
+    let claimed = executor
+        .reward_assets
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<String>>();
+
+    // threshold of 1 pays out every non-zero reward asset
+    let mut messages: Vec<CosmosMsg> = vec![];
+    transfer_reward(&deps, &mut executor, Uint128::one(), &mut messages)?;
+    store_reward(deps.storage, &pair_key, &executor)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "claim_executor_reward"),
+        ("executor", info.sender.as_str()),
+        ("reward", &format!("{:?}", claimed)),
+    ]))
+}
+
+/// shares the bonus between the executors of the pair by the volume they matched, the rounding
+/// remainder goes back to the admin. volumes restart from zero for the next distribution
+pub fn distribute_executor_bonus(
+    deps: DepsMut,
+    sender: Addr,
+    asset_infos: [AssetInfo; 2],
+    bonus: Asset,
+) -> Result<Response, ContractError> {
+    let contract_info = read_config(deps.storage)?;
+    if contract_info
+        .admin
+        .ne(&deps.api.addr_canonicalize(sender.as_str())?)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if bonus.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let executors: Vec<Executor> = read_all_executors(deps.storage, &pair_key)?
+        .into_iter()
+        .filter(|executor| !executor.matched_volume.is_zero())
+        .collect();
+    let total_volume = executors.iter().fold(Uint128::zero(), |total, executor| {
+        total + executor.matched_volume
+    });
+    if total_volume.is_zero() {
+        return Err(ContractError::NoMatchedVolume {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "distribute_executor_bonus"),
+        attr("bonus", bonus.to_string()),
+    ];
+    let mut remaining_amount = bonus.amount;
+    for mut executor in executors {
+        let amount = bonus
+            .amount
+            .multiply_ratio(executor.matched_volume, total_volume);
+        let executor_addr = deps.api.addr_humanize(&executor.address)?;
+        if !amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: bonus.info.clone(),
+                    amount,
+                }
+                .into_msg(None, &deps.querier, executor_addr.clone())?,
+            );
+            remaining_amount -= amount;
+        }
+        attributes.push(attr(executor_addr.as_str(), amount));
+
+        executor.matched_volume = Uint128::zero();
+        store_reward(deps.storage, &pair_key, &executor)?;
+    }
+    if !remaining_amount.is_zero() {
+        messages.push(
+            Asset {
+                info: bonus.info,
+                amount: remaining_amount,
+            }
+            .into_msg(None, &deps.querier, sender)?,
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+pub fn query_executor_reward(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    executor: Addr,
+) -> StdResult<ExecutorResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+
+    // an executor that never matched has nothing accrued yet
+    let reward_assets = [
+        Asset {
+            info: orderbook_pair.base_coin_info.to_normal(deps.api)?,
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+            amount: Uint128::zero(),
+        },
+    ];
+    let executor_addr = deps.api.addr_canonicalize(executor.as_str())?;
+    let executor_reward = process_reward(deps.storage, &pair_key, executor_addr, reward_assets);
 
-    for bulk in sell_list.iter_mut() {
-        for sell_order in bulk.orders.iter_mut() {
-            if sell_order.status != OrderStatus::Open {
-                total_orders += 1;
-                sell_order.match_order(deps.storage, &pair_key)?;
-                ret_events.push(to_events(
-                    &sell_order,
-                    deps.api.addr_humanize(&sell_order.bidder_addr)?.to_string(),
-                ));
-            }
-        }
-    }
+    Ok(ExecutorResponse {
+        executor,
+        reward_assets: executor_reward.reward_assets,
+        matched_volume: executor_reward.matched_volume,
+    })
+}
 
-    process_list_trader(&deps, list_bidder, &mut messages)?;
-    process_list_trader(&deps, list_asker, &mut messages)?;
+pub fn query_executors(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<ExecutorsResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(start_after.as_str())?),
+        None => None,
+    };
 
-    transfer_reward(&deps, &mut reward, &mut total_reward, &mut messages)?;
-    transfer_reward(&deps, &mut relayer, &mut total_reward, &mut messages)?;
+    let executors = read_executors(deps.storage, &pair_key, start_after, limit)?
+        .into_iter()
+        .map(|executor| {
+            Ok(ExecutorResponse {
+                executor: deps.api.addr_humanize(&executor.address)?,
+                reward_assets: executor.reward_assets,
+                matched_volume: executor.matched_volume,
+            })
+        })
+        .collect::<StdResult<Vec<ExecutorResponse>>>()?;
 
-    store_reward(deps.storage, &pair_key, &reward)?;
-    store_reward(deps.storage, &pair_key, &relayer)?;
-    Ok(Response::new()
-        .add_messages(messages)
-        .add_attributes(vec![
-            ("action", "execute_orderbook_pair"),
-            (
-                "pair",
-                &format!("{} - {}", &asset_infos[0], &asset_infos[1]),
-            ),
-            ("total_matched_orders", &total_orders.to_string()),
-            ("executor_reward", &format!("{:?}", &total_reward)),
-        ])
-        .add_events(ret_events))
+    Ok(ExecutorsResponse { executors })
 }
 
 pub fn remove_pair(
@@ -674,7 +2224,7 @@ pub fn query_order(
         asset_infos[1].to_raw(deps.api)?,
     ]);
     let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
-    let order = read_order(deps.storage, &pair_key, order_id)?;
+    let order = read_order_or_pending(deps.storage, &pair_key, order_id)?;
 
     order.to_response(
         deps.api,
@@ -683,6 +2233,57 @@ pub fn query_order(
     )
 }
 
+pub fn query_orders_by_bidder(
+    deps: Deps,
+    bidder: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order_by: Option<i32>,
+) -> StdResult<BidderOrdersResponse> {
+    let order_by = order_by.map_or(None, |val| OrderBy::try_from(val).ok());
+    let bidder_addr_raw = deps.api.addr_canonicalize(&bidder)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // order ids are unique across books, so the first page of every book is enough to build the page
+    let mut orders: Vec<BidderOrderResponse> = vec![];
+    for orderbook_pair in read_all_orderbooks(deps.storage)? {
+        let pair_key = orderbook_pair.get_pair_key();
+        let base_coin_info = orderbook_pair.base_coin_info.to_normal(deps.api)?;
+        let quote_coin_info = orderbook_pair.quote_coin_info.to_normal(deps.api)?;
+        let bidder_orders = read_orders_with_indexer::<OrderDirection>(
+            deps.storage,
+            &[
+                PREFIX_ORDER_BY_BIDDER,
+                &pair_key,
+                bidder_addr_raw.as_slice(),
+            ],
+            Box::new(|_| true),
+            start_after,
+            Some(limit as u32),
+            order_by,
+        )?
+        .unwrap_or_default();
+        for order in bidder_orders.iter() {
+            orders.push(BidderOrderResponse {
+                asset_infos: [base_coin_info.clone(), quote_coin_info.clone()],
+                order: order.to_response(
+                    deps.api,
+                    base_coin_info.clone(),
+                    quote_coin_info.clone(),
+                )?,
+            });
+        }
+    }
+
+    match order_by {
+        Some(OrderBy::Ascending) => orders.sort_by_key(|item| item.order.order_id),
+        _ => orders.sort_by_key(|item| std::cmp::Reverse(item.order.order_id)),
+    }
+    orders.truncate(limit);
+
+    Ok(BidderOrdersResponse { orders })
+}
+
 pub fn query_orders(
     deps: Deps,
     asset_infos: [AssetInfo; 2],
@@ -784,6 +2385,44 @@ pub fn query_last_order_id(deps: Deps) -> StdResult<LastOrderIdResponse> {
     Ok(resp)
 }
 
+/// the counter next to the highest order id stored for a pair, migrate refuses a counter behind it
+pub fn query_last_stored_order_id(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+) -> StdResult<LastStoredOrderIdResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    Ok(LastStoredOrderIdResponse {
+        last_order_id: read_last_order_id(deps.storage)?,
+        last_stored_order_id: read_last_stored_order_id(deps.storage, &pair_key)?,
+    })
+}
+
+pub fn query_trade_participants(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    trade_id: u64,
+) -> StdResult<TradeParticipantsResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let trade = read_trade(deps.storage, &pair_key, trade_id)?;
+    Ok(TradeParticipantsResponse {
+        trade_id: trade.trade_id,
+        maker_order_id: trade.maker_order_id,
+        maker_addr: deps.api.addr_humanize(&trade.maker_addr)?,
+        taker_order_id: trade.taker_order_id,
+        taker_addr: deps.api.addr_humanize(&trade.taker_addr)?,
+        taker_direction: trade.taker_direction,
+        price: trade.price,
+        base_amount: trade.base_amount,
+        quote_amount: trade.quote_amount,
+    })
+}
+
 pub fn query_orderbooks(
     deps: Deps,
     start_after: Option<Vec<u8>>,
@@ -821,7 +2460,428 @@ pub fn query_orderbook_is_matchable(
         .find_list_match_price(deps.storage, Some(30))
         .unwrap_or_default();
 
+    let (best_buy_price, best_sell_price, matchable_volume) = match ob
+        .find_match_price(deps.storage)
+    {
+        Some((best_buy_price, best_sell_price)) => {
+            // buy orders ask for the base asset, sell orders ask for the quote asset
+            let buy_volume =
+                ob.find_match_amount_at_price(deps.storage, best_buy_price, OrderDirection::Buy);
+            let sell_volume = ob
+                .find_match_amount_at_price(deps.storage, best_sell_price, OrderDirection::Sell)
+                .multiply_ratio(Decimal::one().atomics(), best_sell_price.atomics());
+            (
+                Some(best_buy_price),
+                Some(best_sell_price),
+                Uint128::min(buy_volume, sell_volume),
+            )
+        }
+        None => (None, None, Uint128::zero()),
+    };
+
     Ok(OrderBookMatchableResponse {
         is_matchable: best_buy_price_list.len() != 0 && best_sell_price_list.len() != 0,
+        best_buy_price,
+        best_sell_price,
+        matchable_volume,
+    })
+}
+
+pub fn query_matching_worthwhile(
+    deps: Deps,
+    env: Env,
+    asset_infos: [AssetInfo; 2],
+    gas_price: Decimal,
+    estimated_gas: u64,
+) -> StdResult<MatchingWorthwhileResponse> {
+    let contract_info = read_config(deps.storage)?;
+    let oracle = match contract_info.oracle_addr {
+        Some(oracle_addr) => OracleContract(deps.api.addr_humanize(&oracle_addr)?),
+        None => return Err(StdError::generic_err("Oracle is not configured")),
+    };
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let reward_assets = [
+        Asset {
+            info: orderbook_pair.base_coin_info.to_normal(deps.api)?,
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: orderbook_pair.quote_coin_info.to_normal(deps.api)?,
+            amount: Uint128::zero(),
+        },
+    ];
+
+    // dry run of the matching core, the caller only earns the relayer fees
+    let mut reward = Executor::new(contract_info.reward_address, reward_assets.clone());
+    let mut relayer = Executor::new(CanonicalAddr::from(vec![]), reward_assets);
+    let mut traders: Vec<Payment> = vec![];
+    let (mut buy_list, mut sell_list, _, _, _, _) = execute_bulk_orders(
+        deps,
+        orderbook_pair.clone(),
+        None,
+        false,
+        env.block.time.seconds(),
+        None,
+    )?;
+    for bulk_orders in [&mut buy_list, &mut sell_list] {
+        process_orders(
+            deps,
+            &orderbook_pair,
+            bulk_orders,
+            &mut traders,
+            &mut reward,
+            &mut relayer,
+            true,
+        )?;
+    }
+
+    // value of one unit in orai is the orai -> denom rate
+    let mut expected_reward = Uint128::zero();
+    for asset in relayer.reward_assets.iter().filter(|a| !a.amount.is_zero()) {
+        let rate = match &asset.info {
+            AssetInfo::NativeToken { denom } => {
+                oracle
+                    .query_exchange_rate(&deps.querier, ORAI_DENOM.to_string(), denom.to_string())?
+                    .item
+                    .exchange_rate
+            }
+            AssetInfo::Token { contract_addr } => {
+                return Err(StdError::generic_err(format!(
+                    "No oracle exchange rate found for {}",
+                    contract_addr
+                )))
+            }
+        };
+        expected_reward += asset.amount * rate;
+    }
+
+    let gas_cost = Uint128::from(estimated_gas) * gas_price;
+    Ok(MatchingWorthwhileResponse {
+        worthwhile: expected_reward > gas_cost,
+        expected_reward,
+        gas_cost,
+        net_profit: expected_reward.saturating_sub(gas_cost),
+    })
+}
+
+pub fn query_expired_orders(
+    deps: Deps,
+    env: Env,
+    asset_infos: [AssetInfo; 2],
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let base_coin_info = orderbook_pair.base_coin_info.to_normal(deps.api)?;
+    let quote_coin_info = orderbook_pair.quote_coin_info.to_normal(deps.api)?;
+
+    Ok(OrdersResponse {
+        orders: read_expired_orders(
+            deps.storage,
+            &pair_key,
+            env.block.time.seconds(),
+            start_after,
+            limit,
+        )?
+        .iter()
+        .map(|order| order.to_response(deps.api, base_coin_info.clone(), quote_coin_info.clone()))
+        .collect::<StdResult<Vec<OrderResponse>>>()?,
+    })
+}
+
+pub fn query_pending_orders(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let orderbook_pair = read_orderbook(deps.storage, &pair_key)?;
+    let base_coin_info = orderbook_pair.base_coin_info.to_normal(deps.api)?;
+    let quote_coin_info = orderbook_pair.quote_coin_info.to_normal(deps.api)?;
+
+    Ok(OrdersResponse {
+        orders: read_pending_orders(deps.storage, &pair_key, start_after, limit)?
+            .iter()
+            .map(|order| {
+                order.to_response(deps.api, base_coin_info.clone(), quote_coin_info.clone())
+            })
+            .collect::<StdResult<Vec<OrderResponse>>>()?,
+    })
+}
+
+/// average of the best buy and best sell tick, an empty side counts as zero
+pub fn query_mid_price(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<Decimal> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let best_buy = query_ticks_with_end(
+        deps.storage,
+        &pair_key,
+        OrderDirection::Buy,
+        None,
+        None,
+        Some(1),
+        Some(2),
+    )?;
+    let best_sell = query_ticks_with_end(
+        deps.storage,
+        &pair_key,
+        OrderDirection::Sell,
+        None,
+        None,
+        Some(1),
+        Some(1),
+    )?;
+    let best_buy_price = if best_buy.ticks.len() == 0 {
+        Decimal::zero()
+    } else {
+        best_buy.ticks[0].price
+    };
+    let best_sell_price = if best_sell.ticks.len() == 0 {
+        Decimal::zero()
+    } else {
+        best_sell.ticks[0].price
+    };
+    Ok(best_buy_price
+        .checked_add(best_sell_price)
+        .unwrap_or_default()
+        .checked_div(Decimal::from_ratio(2u128, 1u128))
+        .unwrap_or_default())
+}
+
+/// an empty book has no mid price to move away from, so it never trips the guard
+pub fn assert_max_mid_price_deviation(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+) -> Result<(), ContractError> {
+    if let (Some(belief_price), Some(max_spread)) = (belief_price, max_spread) {
+        if belief_price.is_zero() {
+            return Err(ContractError::AssetMustNotBeZero {});
+        }
+        let mid_price = query_mid_price(deps, asset_infos)?;
+        if mid_price.is_zero() {
+            return Ok(());
+        }
+
+        let deviation = if mid_price > belief_price {
+            mid_price - belief_price
+        } else {
+            belief_price - mid_price
+        };
+        if Decimal::from_ratio(deviation.atomics(), belief_price.atomics()) > max_spread {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    }
+
+    Ok(())
+}
+
+pub fn query_pair_limits(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairLimitsResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let ob = read_orderbook(deps.storage, &pair_key)?;
+    let contract_info = read_config(deps.storage)?;
+
+    Ok(PairLimitsResponse {
+        base_coin_info: ob.base_coin_info.to_normal(deps.api)?,
+        quote_coin_info: ob.quote_coin_info.to_normal(deps.api)?,
+        min_quote_coin_amount: ob.min_quote_coin_amount,
+        spread: ob.spread,
+        commission_rate: ob.commission_rate.unwrap_or(contract_info.commission_rate),
+        relayer_fee: Uint128::from(RELAY_FEE),
+    })
+}
+
+fn snapshot_ticks(
+    storage: &dyn Storage,
+    ob: &OrderBook,
+    direction: OrderDirection,
+    max_ticks: Option<u32>,
+    order_by: OrderBy,
+) -> StdResult<Vec<SnapshotTickResponse>> {
+    let ticks = query_ticks_with_end(
+        storage,
+        &ob.get_pair_key(),
+        direction,
+        None,
+        None,
+        max_ticks,
+        Some(order_by as i32),
+    )?;
+
+    ticks
+        .ticks
+        .into_iter()
+        .map(|tick| {
+            let mut volume = Uint128::zero();
+            let mut start_after = None;
+            while let Some(orders) =
+                ob.orders_at(storage, tick.price, direction, start_after, Some(MAX_LIMIT))
+            {
+                if orders.is_empty() {
+                    break;
+                }
+                for order in orders.iter() {
+                    volume += order.offer_amount.checked_sub(order.filled_offer_amount)?;
+                }
+                start_after = orders.last().map(|order| order.order_id);
+            }
+            Ok(SnapshotTickResponse {
+                price: tick.price,
+                total_orders: tick.total_orders,
+                volume,
+            })
+        })
+        .collect()
+}
+
+pub fn query_book_snapshot(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    max_ticks: Option<u32>,
+) -> StdResult<BookSnapshotResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let ob = read_orderbook(deps.storage, &pair_key)?;
+
+    // best price first: highest buy and lowest sell
+    let buy_ticks = snapshot_ticks(
+        deps.storage,
+        &ob,
+        OrderDirection::Buy,
+        max_ticks,
+        OrderBy::Descending,
+    )?;
+    let sell_ticks = snapshot_ticks(
+        deps.storage,
+        &ob,
+        OrderDirection::Sell,
+        max_ticks,
+        OrderBy::Ascending,
+    )?;
+    let total_orders = buy_ticks
+        .iter()
+        .chain(sell_ticks.iter())
+        .map(|tick| tick.total_orders)
+        .sum();
+
+    Ok(BookSnapshotResponse {
+        buy_ticks,
+        sell_ticks,
+        total_orders,
+    })
+}
+
+pub fn query_orderbook_depth(
+    deps: Deps,
+    asset_infos: [AssetInfo; 2],
+    direction: OrderDirection,
+    limit: Option<u32>,
+) -> StdResult<Vec<TickDepth>> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let ob = read_orderbook(deps.storage, &pair_key)?;
+    ob.depth(deps.storage, direction, limit)
+}
+
+pub fn query_spread(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<Option<SpreadResponse>> {
+    let best_prices = query_best_prices(deps, asset_infos)?;
+    Ok(
+        match (
+            best_prices.best_buy,
+            best_prices.best_sell,
+            best_prices.spread_pct,
+        ) {
+            (Some(best_buy), Some(best_sell), Some(spread_pct)) => Some(SpreadResponse {
+                best_buy,
+                best_sell,
+                spread_pct,
+            }),
+            _ => None,
+        },
+    )
+}
+
+pub fn query_best_prices(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<BestPricesResponse> {
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let ob = read_orderbook(deps.storage, &pair_key)?;
+    let (best_buy, found_buy, _) = ob.highest_price(deps.storage, OrderDirection::Buy);
+    let (best_sell, found_sell, _) = ob.lowest_price(deps.storage, OrderDirection::Sell);
+    let best_buy = if found_buy { Some(best_buy) } else { None };
+    let best_sell = if found_sell { Some(best_sell) } else { None };
+
+    let (spread, spread_pct) = match (best_buy, best_sell) {
+        (Some(best_buy), Some(best_sell)) => {
+            // spread_pct = (best_sell - best_buy) / ((best_sell + best_buy) / 2)
+            let spread = best_sell.checked_sub(best_buy).unwrap_or_default();
+            let spread_pct = Decimal::from_ratio(
+                spread.atomics() * Uint128::from(2u128),
+                (best_buy + best_sell).atomics(),
+            );
+            (Some(spread), Some(spread_pct))
+        }
+        _ => (None, None),
+    };
+
+    Ok(BestPricesResponse {
+        best_buy,
+        best_sell,
+        spread,
+        spread_pct,
+    })
+}
+
+pub fn query_total_escrow(deps: Deps, asset_info: AssetInfo) -> StdResult<Asset> {
+    let asset_info_raw = asset_info.to_raw(deps.api)?;
+    let mut total_escrow = Uint128::zero();
+
+    for orderbook_pair in read_all_orderbooks(deps.storage)? {
+        // buy orders pay quote asset, sell orders pay base asset
+        let direction = if orderbook_pair.quote_coin_info.eq(&asset_info_raw) {
+            OrderDirection::Buy
+        } else if orderbook_pair.base_coin_info.eq(&asset_info_raw) {
+            OrderDirection::Sell
+        } else {
+            continue;
+        };
+
+        let pair_key = orderbook_pair.get_pair_key();
+        for order in read_all_orders(deps.storage, &pair_key)?
+            .into_iter()
+            .chain(read_all_pending_orders(deps.storage, &pair_key)?)
+        {
+            if order.direction == direction {
+                total_escrow += order.offer_amount.checked_sub(order.filled_offer_amount)?;
+            }
+        }
+    }
+
+    Ok(Asset {
+        info: asset_info,
+        amount: total_escrow,
     })
 }
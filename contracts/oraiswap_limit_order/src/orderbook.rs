@@ -4,15 +4,19 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_storage::ReadonlyBucket;
 use oraiswap::{
     asset::{pair_key_from_asset_keys, Asset, AssetInfo, AssetInfoRaw},
-    limit_order::{OrderBookResponse, OrderDirection, OrderResponse, OrderStatus},
+    error::ContractError,
+    limit_order::{Hook, OrderBookResponse, OrderDirection, OrderResponse, OrderStatus, TickDepth},
 };
 
-use cosmwasm_std::{Api, CanonicalAddr, Decimal, Order as OrderBy, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    Addr, Api, CanonicalAddr, CosmosMsg, Decimal, Order as OrderBy, StdError, StdResult, Storage,
+    Uint128, Uint256,
+};
 
 use crate::{
     state::{
-        read_orders, read_orders_with_indexer, remove_order, store_order, PREFIX_ORDER_BY_PRICE,
-        PREFIX_TICK,
+        read_orders, read_orders_with_indexer, remove_order, store_order, DEFAULT_LIMIT, MAX_LIMIT,
+        PREFIX_ORDER_BY_PRICE, PREFIX_TICK,
     },
     tick::{query_ticks_prices, query_ticks_prices_with_end},
 };
@@ -27,6 +31,14 @@ pub struct Order {
     pub ask_amount: Uint128,
     pub filled_offer_amount: Uint128,
     pub filled_ask_amount: Uint128,
+    // notified on every fill, none for most orders
+    pub fill_callback: Option<Hook>,
+    // block time in seconds, orders stored before expiry was added never expire
+    pub expires_at: Option<u64>,
+    // smallest fill in offer asset, a fill completing the order is always allowed
+    pub min_fill_amount: Option<Uint128>,
+    // mid price activating a pending order, none once the order rests in the ticks
+    pub trigger_price: Option<Decimal>,
 }
 
 #[cw_serde]
@@ -41,12 +53,82 @@ pub struct OrderWithFee {
     pub filled_ask_amount: Uint128,
     pub reward_fee: Uint128,
     pub relayer_fee: Uint128,
+    pub round_filled_offer_amount: Uint128,
+    pub round_filled_ask_amount: Uint128,
+    pub fill_callback: Option<Hook>,
+    pub expires_at: Option<u64>,
+    pub min_fill_amount: Option<Uint128>,
+    pub trigger_price: Option<Decimal>,
 }
 
 #[cw_serde]
 pub struct Executor {
     pub address: CanonicalAddr,
     pub reward_assets: [Asset; 2],
+    // base amount matched as relayer since the last bonus distribution
+    #[serde(default)]
+    pub matched_volume: Uint128,
+}
+
+/// an order touched by a matching round, amounts are cumulative over the order lifetime
+#[cw_serde]
+pub struct Fill {
+    pub order_id: u64,
+    pub status: OrderStatus,
+    pub direction: OrderDirection,
+    pub bidder_addr: Addr,
+    pub offer_amount: Uint128,
+    pub ask_amount: Uint128,
+    pub filled_offer_amount: Uint128,
+    pub filled_ask_amount: Uint128,
+    pub reward_fee: Uint128,
+    pub relayer_fee: Uint128,
+    // amounts filled in this round only and their average price in quote per base
+    pub round_filled_offer_amount: Uint128,
+    pub round_filled_ask_amount: Uint128,
+    pub match_price: Decimal,
+    // first order of the first opposite tick traded with in this round
+    pub counterparty_order_id: Option<u64>,
+}
+
+/// a trade between two orders, the maker rested on the book and the taker crossed it
+#[cw_serde]
+pub struct Trade {
+    pub trade_id: u64,
+    pub maker_order_id: u64,
+    pub maker_addr: CanonicalAddr,
+    pub taker_order_id: u64,
+    pub taker_addr: CanonicalAddr,
+    pub taker_direction: OrderDirection,
+    // quote per base
+    pub price: Decimal,
+    pub base_amount: Uint128,
+    pub quote_amount: Uint128,
+}
+
+/// a fill between a buy tick and a sell tick of a matching round, in the order they were matched
+pub struct TickFill {
+    pub buy_index: usize,
+    pub sell_index: usize,
+    pub base_volume: Uint128,
+    pub price: Decimal,
+    pub taker: OrderDirection,
+}
+
+#[cw_serde]
+pub struct MatchResult {
+    pub fills: Vec<Fill>,
+    pub messages: Vec<CosmosMsg>,
+    // commission collected by the reward executor in this round, [base, quote]
+    pub executor_reward: [Asset; 2],
+    // the matching cap was reached while crossing orders remain on the book
+    pub has_more: bool,
+    // orders canceled and refunded because they expired
+    pub expired_orders: Vec<u64>,
+    // (buy order id, sell order id) of the same bidder left out of matching
+    pub self_trades: Vec<(u64, u64)>,
+    // trades recorded in the trade log
+    pub trades: Vec<Trade>,
 }
 
 impl Order {
@@ -57,15 +139,18 @@ impl Order {
         direction: OrderDirection,
         price: Decimal,
         ask_amount: Uint128,
-    ) -> Self {
+    ) -> Result<Self, ContractError> {
         let offer_amount = match direction {
-            OrderDirection::Buy => ask_amount * price,
-            OrderDirection::Sell => Uint128::from(ask_amount * Uint128::from(1000000u128))
+            OrderDirection::Buy => Uint128::try_from(
+                ask_amount.full_mul(price.atomics()) / Uint256::from(Decimal::one().atomics()),
+            )?,
+            OrderDirection::Sell => ask_amount
+                .checked_mul(Uint128::from(1000000u128))?
                 .checked_div(price * Uint128::from(1000000u128))
-                .unwrap(),
+                .map_err(StdError::from)?,
         };
 
-        Order {
+        Ok(Order {
             direction,
             order_id,
             bidder_addr,
@@ -74,7 +159,25 @@ impl Order {
             filled_offer_amount: Uint128::zero(),
             filled_ask_amount: Uint128::zero(),
             status: OrderStatus::Open,
-        }
+            fill_callback: None,
+            expires_at: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        })
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+
+    /// a buy stop triggers once the mid price rises to its trigger price, a sell stop once it falls to it
+    pub fn is_triggered(&self, mid_price: Decimal) -> bool {
+        self.trigger_price
+            .map_or(true, |trigger_price| match self.direction {
+                OrderDirection::Buy => mid_price >= trigger_price,
+                OrderDirection::Sell => mid_price <= trigger_price,
+            })
     }
 
     pub fn fill_order(&mut self, ask_amount: Uint128, offer_amount: Uint128) {
@@ -164,9 +267,13 @@ impl OrderWithFee {
             ask_amount: self.ask_amount,
             filled_offer_amount: self.filled_offer_amount,
             filled_ask_amount: self.filled_ask_amount,
+            fill_callback: self.fill_callback.clone(),
+            expires_at: self.expires_at,
+            min_fill_amount: self.min_fill_amount,
+            trigger_price: self.trigger_price,
         };
-        if self.status == OrderStatus::Fulfilled {
-            // When status is Fulfilled, remove order
+        if self.status == OrderStatus::Fulfilled || self.status == OrderStatus::Cancel {
+            // When status is Fulfilled or the dust was canceled, remove order
             remove_order(storage, pair_key, &order)
         } else {
             // update order
@@ -182,6 +289,10 @@ pub struct OrderBook {
     pub quote_coin_info: AssetInfoRaw,
     pub spread: Option<Decimal>,
     pub min_quote_coin_amount: Uint128,
+    // takes precedence over the commission rates of the contract, books stored before it use those
+    pub commission_rate: Option<String>,
+    // price step of the book, books stored before it accept any price
+    pub precision: Option<Decimal>,
 }
 
 impl OrderBook {
@@ -195,15 +306,40 @@ impl OrderBook {
             quote_coin_info,
             spread,
             min_quote_coin_amount: Uint128::zero(),
+            commission_rate: None,
+            precision: None,
+        }
+    }
+
+    /// rounds the price down to a multiple of the precision, unchanged if the book has none
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        match self.precision {
+            Some(precision) if !precision.is_zero() => Decimal::raw(
+                price.atomics().u128() / precision.atomics().u128() * precision.atomics().u128(),
+            ),
+            _ => price,
+        }
+    }
+
+    pub fn assert_price_precision(&self, price: Decimal) -> Result<(), ContractError> {
+        match self.precision {
+            Some(precision) if self.round_price(price) != price => {
+                Err(ContractError::InvalidPricePrecision { price, precision })
+            }
+            _ => Ok(()),
         }
     }
 
     pub fn to_response(&self, api: &dyn Api) -> StdResult<OrderBookResponse> {
+        let quote_coin_info = self.quote_coin_info.to_normal(api)?;
         Ok(OrderBookResponse {
             base_coin_info: self.base_coin_info.to_normal(api)?,
-            quote_coin_info: self.quote_coin_info.to_normal(api)?,
+            price_denominated_in: quote_coin_info.clone(),
+            quote_coin_info,
             spread: self.spread,
             min_quote_coin_amount: self.min_quote_coin_amount,
+            commission_rate: self.commission_rate.clone(),
+            precision: self.precision,
         })
     }
 
@@ -266,6 +402,27 @@ impl OrderBook {
         self.best_price(storage, direction, OrderBy::Ascending)
     }
 
+    /// check if an order at the given price would be matched right away with the best opposite order
+    pub fn is_crossing(
+        &self,
+        storage: &dyn Storage,
+        direction: OrderDirection,
+        price: Decimal,
+    ) -> bool {
+        match direction {
+            OrderDirection::Buy => {
+                let (lowest_sell_price, found, _) =
+                    self.lowest_price(storage, OrderDirection::Sell);
+                found && price.ge(&lowest_sell_price)
+            }
+            OrderDirection::Sell => {
+                let (highest_buy_price, found, _) =
+                    self.highest_price(storage, OrderDirection::Buy);
+                found && price.le(&highest_buy_price)
+            }
+        }
+    }
+
     pub fn orders_at(
         &self,
         storage: &dyn Storage,
@@ -290,6 +447,56 @@ impl OrderBook {
         .unwrap()
     }
 
+    /// depth returns the unfilled amounts per tick, buy side from high to low and sell side from low to high
+    pub fn depth(
+        &self,
+        storage: &dyn Storage,
+        direction: OrderDirection,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<TickDepth>> {
+        let pair_key = &self.get_pair_key();
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let order_by = match direction {
+            OrderDirection::Buy => OrderBy::Descending,
+            OrderDirection::Sell => OrderBy::Ascending,
+        };
+        let tick_namespaces = &[PREFIX_TICK, pair_key, direction.as_bytes()];
+        let position_bucket: ReadonlyBucket<u64> =
+            ReadonlyBucket::multilevel(storage, tick_namespaces);
+
+        position_bucket
+            .range(None, None, order_by)
+            .take(limit)
+            .map(|item| {
+                let (price_key, _) = item?;
+                let price = Decimal::raw(u128::from_be_bytes(price_key.try_into().unwrap()));
+                let mut tick_depth = TickDepth {
+                    price,
+                    total_offer_amount: Uint128::zero(),
+                    total_ask_amount: Uint128::zero(),
+                    order_count: 0,
+                };
+                let mut start_after = None;
+                while let Some(orders) =
+                    self.orders_at(storage, price, direction, start_after, Some(MAX_LIMIT))
+                {
+                    if orders.is_empty() {
+                        break;
+                    }
+                    for order in orders.iter() {
+                        tick_depth.total_offer_amount +=
+                            order.offer_amount.checked_sub(order.filled_offer_amount)?;
+                        tick_depth.total_ask_amount +=
+                            order.ask_amount.checked_sub(order.filled_ask_amount)?;
+                        tick_depth.order_count += 1;
+                    }
+                    start_after = orders.last().map(|order| order.order_id);
+                }
+                Ok(tick_depth)
+            })
+            .collect()
+    }
+
     // get_orders returns all orders in the order book, with pagination
     pub fn get_orders(
         &self,
@@ -363,6 +570,43 @@ impl OrderBook {
         None
     }
 
+    /// number of orders resting on ticks that cross the other side, at most MAX_LIMIT ticks per side are counted
+    pub fn crossing_order_count(&self, storage: &dyn Storage) -> u64 {
+        let pair_key = &self.get_pair_key();
+        let (best_buy_price, found_buy, _) = self.highest_price(storage, OrderDirection::Buy);
+        let (best_sell_price, found_sell, _) = self.lowest_price(storage, OrderDirection::Sell);
+        if !found_buy || !found_sell || best_buy_price < best_sell_price {
+            return 0;
+        }
+
+        [
+            (OrderDirection::Buy, OrderBy::Descending),
+            (OrderDirection::Sell, OrderBy::Ascending),
+        ]
+        .into_iter()
+        .map(|(direction, order_by)| {
+            ReadonlyBucket::<u64>::multilevel(
+                storage,
+                &[PREFIX_TICK, pair_key, direction.as_bytes()],
+            )
+            .range(None, None, order_by)
+            .take(MAX_LIMIT as usize)
+            .filter_map(|item| item.ok())
+            .take_while(|(price_key, _)| {
+                let price = Decimal::raw(u128::from_be_bytes(
+                    price_key.as_slice().try_into().unwrap(),
+                ));
+                match direction {
+                    OrderDirection::Buy => price >= best_sell_price,
+                    OrderDirection::Sell => price <= best_buy_price,
+                }
+            })
+            .map(|(_, total_orders)| total_orders)
+            .sum::<u64>()
+        })
+        .sum()
+    }
+
     /// find list best buy / sell prices
     pub fn find_list_match_price(
         &self,
@@ -469,6 +713,7 @@ impl Executor {
         Executor {
             address,
             reward_assets,
+            matched_volume: Uint128::zero(),
         }
     }
 }
@@ -480,13 +725,17 @@ pub struct BulkOrders {
     pub price: Decimal,
     // offer volume
     pub volume: Uint128,
+    // the tick crossed an order that rested before it, so it pays the taker commission
+    pub is_taker: bool,
+    // first order of the first opposite tick the tick traded with
+    pub counterparty_order_id: Option<u64>,
     // remaining volume
     pub remaining_volume: Uint128,
-    // filled volume
+    // volume filled in this round
     pub filled_volume: Uint128,
-    // ask volume
+    // ask volume left to the orders
     pub ask_volume: Uint128,
-    // filled ask volume
+    // ask volume filled in this round
     pub filled_ask_volume: Uint128,
     pub spread_volume: Uint128,
 }
@@ -497,8 +746,6 @@ impl BulkOrders {
         let mut volume = Uint128::zero();
         let mut remaining_volume = Uint128::zero();
         let mut ask_volume = Uint128::zero();
-        let mut filled_volume = Uint128::zero();
-        let mut filled_ask_volume = Uint128::zero();
         let mut sum_order_id = Uint128::zero();
         let mut average_order_id = Uint128::zero();
         let spread_volume = Uint128::zero();
@@ -506,15 +753,16 @@ impl BulkOrders {
         for order in orders {
             sum_order_id += Uint128::from(order.order_id);
             volume += order.offer_amount;
-            ask_volume += order.ask_amount;
 
+            // the fills of earlier rounds are already paid out, the bulk only tracks what is left
             remaining_volume += order
                 .offer_amount
                 .checked_sub(order.filled_offer_amount)
                 .unwrap_or_default();
-
-            filled_volume += order.filled_offer_amount;
-            filled_ask_volume += order.filled_ask_amount;
+            ask_volume += order
+                .ask_amount
+                .checked_sub(order.filled_ask_amount)
+                .unwrap_or_default();
         }
 
         if orders.len() > 0 {
@@ -539,15 +787,51 @@ impl BulkOrders {
                     filled_ask_amount: order.filled_ask_amount,
                     relayer_fee: Uint128::zero(),
                     reward_fee: Uint128::zero(),
+                    round_filled_offer_amount: Uint128::zero(),
+                    round_filled_ask_amount: Uint128::zero(),
+                    fill_callback: order.fill_callback,
+                    expires_at: order.expires_at,
+                    min_fill_amount: order.min_fill_amount,
+                    trigger_price: order.trigger_price,
                 })
                 .collect(),
             remaining_volume,
-            filled_volume,
-            filled_ask_volume,
+            filled_volume: Uint128::zero(),
+            filled_ask_volume: Uint128::zero(),
             spread_volume,
             volume,
             ask_volume,
             average_order_id,
+            is_taker: false,
+            counterparty_order_id: None,
         };
     }
+
+    /// takes an order out of the bulk, the bulk goes on as if its tick was loaded without it
+    pub fn take_order(&mut self, position: usize) -> OrderWithFee {
+        let order = self.orders.remove(position);
+        self.volume = self
+            .volume
+            .checked_sub(order.offer_amount)
+            .unwrap_or_default();
+        self.ask_volume = self
+            .ask_volume
+            .checked_sub(
+                order
+                    .ask_amount
+                    .checked_sub(order.filled_ask_amount)
+                    .unwrap_or_default(),
+            )
+            .unwrap_or_default();
+        self.remaining_volume = self
+            .remaining_volume
+            .checked_sub(
+                order
+                    .offer_amount
+                    .checked_sub(order.filled_offer_amount)
+                    .unwrap_or_default(),
+            )
+            .unwrap_or_default();
+        order
+    }
 }
@@ -1,4 +1,6 @@
-use cosmwasm_std::{CanonicalAddr, Order as OrderBy, StdResult, Storage};
+use cosmwasm_std::{
+    CanonicalAddr, Decimal, Order as OrderBy, StdError, StdResult, Storage, Uint128,
+};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 use oraiswap::{
     limit_order::{ContractInfo, OrderDirection},
@@ -6,13 +8,22 @@ use oraiswap::{
 };
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::orderbook::{Executor, Order, OrderBook};
+use crate::orderbook::{Executor, Order, OrderBook, Trade};
 
 // settings for pagination
 pub const MAX_LIMIT: u32 = 100;
 pub const DEFAULT_LIMIT: u32 = 10;
 
 pub fn init_last_order_id(storage: &mut dyn Storage) -> StdResult<()> {
+    // resetting a counter that already issued ids would collide new orders with existing ones
+    if let Some(last_order_id) = singleton_read::<u64>(storage, KEY_LAST_ORDER_ID).may_load()? {
+        if last_order_id > 0 {
+            return Err(StdError::generic_err(format!(
+                "last order id is already {}, can not reset it",
+                last_order_id
+            )));
+        }
+    }
     singleton(storage, KEY_LAST_ORDER_ID).save(&0u64)
 }
 
@@ -49,6 +60,71 @@ pub fn read_reward(
     ReadonlyBucket::multilevel(storage, &[PREFIX_REWARD, pair_key]).load(address)
 }
 
+pub fn read_executors(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Executor>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after.map(|addr| addr.to_vec()));
+
+    ReadonlyBucket::multilevel(storage, &[PREFIX_REWARD, pair_key])
+        .range(start.as_deref(), None, OrderBy::Ascending)
+        .take(limit)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
+pub fn read_all_executors(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<Vec<Executor>> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_REWARD, pair_key])
+        .range(None, None, OrderBy::Ascending)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
+pub fn store_allowed_executor(storage: &mut dyn Storage, address: &CanonicalAddr) -> StdResult<()> {
+    Bucket::new(storage, PREFIX_EXECUTOR_ALLOWLIST).save(address, &true)
+}
+
+pub fn remove_allowed_executor(storage: &mut dyn Storage, address: &CanonicalAddr) {
+    Bucket::<bool>::new(storage, PREFIX_EXECUTOR_ALLOWLIST).remove(address)
+}
+
+// matching is open to anyone until at least one executor is allowed
+pub fn is_allowed_executor(storage: &dyn Storage, address: &CanonicalAddr) -> StdResult<bool> {
+    let allowlist: ReadonlyBucket<bool> = ReadonlyBucket::new(storage, PREFIX_EXECUTOR_ALLOWLIST);
+    if allowlist
+        .range(None, None, OrderBy::Ascending)
+        .next()
+        .is_none()
+    {
+        return Ok(true);
+    }
+    Ok(allowlist.may_load(address)?.is_some())
+}
+
+/// client order ids are kept per bidder with the order id and the time they were used
+pub fn read_client_order_id(
+    storage: &dyn Storage,
+    bidder_addr: &CanonicalAddr,
+    client_order_id: &str,
+) -> StdResult<Option<(u64, u64)>> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_CLIENT_ORDER_ID, bidder_addr.as_slice()])
+        .may_load(client_order_id.as_bytes())
+}
+
+pub fn store_client_order_id(
+    storage: &mut dyn Storage,
+    bidder_addr: &CanonicalAddr,
+    client_order_id: &str,
+    order_id: u64,
+    used_at: u64,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_CLIENT_ORDER_ID, bidder_addr.as_slice()])
+        .save(client_order_id.as_bytes(), &(order_id, used_at))
+}
+
 pub fn store_orderbook(
     storage: &mut dyn Storage,
     pair_key: &[u8],
@@ -59,7 +135,13 @@ pub fn store_orderbook(
 
 // do not return error, by default it return no precision and zero min offer amount
 pub fn read_orderbook(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<OrderBook> {
-    ReadonlyBucket::new(storage, PREFIX_ORDER_BOOK).load(pair_key)
+    let ob: OrderBook = ReadonlyBucket::new(storage, PREFIX_ORDER_BOOK).load(pair_key)?;
+    debug_assert_eq!(
+        ob.get_pair_key(),
+        pair_key,
+        "orderbook orientation does not match its pair key"
+    );
+    Ok(ob)
 }
 
 pub fn read_orderbooks(
@@ -81,6 +163,14 @@ pub fn read_orderbooks(
         .collect()
 }
 
+/// read_all_orderbooks: iterates every order book without pagination, only for aggregated queries
+pub fn read_all_orderbooks(storage: &dyn Storage) -> StdResult<Vec<OrderBook>> {
+    ReadonlyBucket::new(storage, PREFIX_ORDER_BOOK)
+        .range(None, None, OrderBy::Ascending)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
 pub fn remove_orderbook<'a>(storage: &'a mut dyn Storage, pair_key: &[u8]) {
     Bucket::<'a, OrderBook>::new(storage, PREFIX_ORDER_BOOK).remove(pair_key)
 }
@@ -103,10 +193,13 @@ pub fn store_order(
         .load(&price_key)
         .unwrap_or_default();
 
-    if inserted {
-        total_tick_orders += 1;
+    // price, bidder and direction never change for an existing order, so an update only needs to rewrite the order itself
+    if !inserted {
+        return Ok(total_tick_orders);
     }
 
+    total_tick_orders += 1;
+
     // save total orders for a tick
     Bucket::multilevel(storage, tick_namespaces).save(&price_key, &total_tick_orders)?;
 
@@ -114,28 +207,13 @@ pub fn store_order(
     // => query tick price from pair key => each price query order belong to price => order list
     // insert tick => insert price entry for pair_key of prefix tick
     // insert order to tick => update index for [pair key, price]
-    Bucket::multilevel(storage, &[PREFIX_ORDER_BY_PRICE, pair_key, &price_key])
-        .save(order_id_key, &order.direction)?;
-
-    Bucket::multilevel(
-        storage,
-        &[
-            PREFIX_ORDER_BY_BIDDER,
-            pair_key,
-            order.bidder_addr.as_slice(),
-        ],
-    )
-    .save(order_id_key, &order.direction)?;
-
-    Bucket::multilevel(
-        storage,
-        &[
-            PREFIX_ORDER_BY_DIRECTION,
-            pair_key,
-            &order.direction.as_bytes(),
-        ],
-    )
-    .save(order_id_key, &order.direction)?;
+    for namespaces in order_index_namespaces(pair_key, order, &price_key) {
+        Bucket::multilevel(storage, &namespaces).save(order_id_key, &order.direction)?;
+    }
+    if let Some(expires_at) = order.expires_at {
+        Bucket::multilevel(storage, &[PREFIX_ORDER_BY_EXPIRY, pair_key])
+            .save(&expiry_key(expires_at, order.order_id), &order.order_id)?;
+    }
 
     Ok(total_tick_orders)
 }
@@ -157,46 +235,133 @@ pub fn remove_order(storage: &mut dyn Storage, pair_key: &[u8], order: &Order) -
         total_tick_orders -= 1;
         if total_tick_orders > 0 {
             // save total orders for a tick
-            Bucket::multilevel(storage, tick_namespaces)
-                .save(&price_key, &total_tick_orders)
-                .unwrap();
+            Bucket::multilevel(storage, tick_namespaces).save(&price_key, &total_tick_orders)?;
         } else {
             Bucket::<u64>::multilevel(storage, tick_namespaces).remove(&price_key);
         }
     }
 
-    // value is just bool to represent indexer
-    Bucket::<OrderDirection>::multilevel(storage, &[PREFIX_ORDER_BY_PRICE, pair_key, &price_key])
-        .remove(order_id_key);
+    // value is just the direction to represent indexer
+    for namespaces in order_index_namespaces(pair_key, order, &price_key) {
+        Bucket::<OrderDirection>::multilevel(storage, &namespaces).remove(order_id_key);
+    }
+    if let Some(expires_at) = order.expires_at {
+        Bucket::<u64>::multilevel(storage, &[PREFIX_ORDER_BY_EXPIRY, pair_key])
+            .remove(&expiry_key(expires_at, order.order_id));
+    }
 
-    Bucket::<OrderDirection>::multilevel(
-        storage,
-        &[
+    // return total orders belong to the tick
+    Ok(total_tick_orders)
+}
+
+/// recount_ticks rewrites the tick totals of a pair from the orders indexed by price,
+/// ticks without any order left are removed
+pub fn recount_ticks(storage: &mut dyn Storage, pair_key: &[u8]) -> StdResult<()> {
+    // prices of orders whose tick total was lost must be counted too, the orders are scanned once
+    let order_prices = read_all_orders(storage, pair_key)?
+        .into_iter()
+        .map(|order| {
+            (
+                order.direction,
+                order.get_price().atomics().to_be_bytes().to_vec(),
+            )
+        })
+        .collect::<Vec<(OrderDirection, Vec<u8>)>>();
+
+    for direction in [OrderDirection::Buy, OrderDirection::Sell] {
+        let tick_namespaces = &[PREFIX_TICK, pair_key, direction.as_bytes()];
+        let mut price_keys = ReadonlyBucket::<u64>::multilevel(storage, tick_namespaces)
+            .range(None, None, OrderBy::Ascending)
+            .map(|item| item.map(|item| item.0))
+            .collect::<StdResult<Vec<Vec<u8>>>>()?;
+        price_keys.extend(
+            order_prices
+                .iter()
+                .filter(|(order_direction, _)| *order_direction == direction)
+                .map(|(_, price_key)| price_key.clone()),
+        );
+        price_keys.sort();
+        price_keys.dedup();
+
+        for price_key in price_keys {
+            let total_tick_orders = ReadonlyBucket::<OrderDirection>::multilevel(
+                storage,
+                &[PREFIX_ORDER_BY_PRICE, pair_key, &price_key],
+            )
+            .range(None, None, OrderBy::Ascending)
+            .filter(|item| item.as_ref().map_or(false, |item| item.1 == direction))
+            .count() as u64;
+
+            if total_tick_orders > 0 {
+                Bucket::multilevel(storage, tick_namespaces)
+                    .save(&price_key, &total_tick_orders)?;
+            } else {
+                Bucket::<u64>::multilevel(storage, tick_namespaces).remove(&price_key);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// orders are indexed by expiry then id, so the expired ones are a prefix of the index
+fn expiry_key(expires_at: u64, order_id: u64) -> Vec<u8> {
+    [expires_at.to_be_bytes(), order_id.to_be_bytes()].concat()
+}
+
+/// pending orders are indexed by trigger price then id, per direction
+fn trigger_key(trigger_price: Decimal, order_id: u64) -> Vec<u8> {
+    [
+        trigger_price.atomics().to_be_bytes().as_slice(),
+        order_id.to_be_bytes().as_slice(),
+    ]
+    .concat()
+}
+
+/// order_index_namespaces returns the namespaces of every secondary indexer of an order,
+/// all of them share the pair key prefix and use the order id as key
+fn order_index_namespaces<'a>(
+    pair_key: &'a [u8],
+    order: &'a Order,
+    price_key: &'a [u8],
+) -> [[&'a [u8]; 3]; 3] {
+    [
+        [PREFIX_ORDER_BY_PRICE, pair_key, price_key],
+        [
             PREFIX_ORDER_BY_BIDDER,
             pair_key,
             order.bidder_addr.as_slice(),
         ],
-    )
-    .remove(order_id_key);
-
-    Bucket::<OrderDirection>::multilevel(
-        storage,
-        &[
+        [
             PREFIX_ORDER_BY_DIRECTION,
             pair_key,
-            &order.direction.as_bytes(),
+            order.direction.as_bytes(),
         ],
-    )
-    .remove(order_id_key);
-
-    // return total orders belong to the tick
-    Ok(total_tick_orders)
+    ]
 }
 
 pub fn read_order(storage: &dyn Storage, pair_key: &[u8], order_id: u64) -> StdResult<Order> {
     ReadonlyBucket::multilevel(storage, &[PREFIX_ORDER, pair_key]).load(&order_id.to_be_bytes())
 }
 
+/// read_all_orders: iterates every order of a pair without pagination, only for aggregated queries
+pub fn read_all_orders(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<Vec<Order>> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_ORDER, pair_key])
+        .range(None, None, OrderBy::Ascending)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
+/// read_last_stored_order_id returns the highest order id stored for a pair
+pub fn read_last_stored_order_id(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<Option<u64>> {
+    ReadonlyBucket::<Order>::multilevel(storage, &[PREFIX_ORDER, pair_key])
+        .range(None, None, OrderBy::Descending)
+        .take(1)
+        .map(|item| item.map(|item| item.1.order_id))
+        .next()
+        .transpose()
+}
+
 /// read_orders_with_indexer: namespace is PREFIX + PAIR_KEY + INDEXER
 pub fn read_orders_with_indexer<T: Serialize + DeserializeOwned>(
     storage: &dyn Storage,
@@ -249,11 +414,234 @@ pub fn read_orders(
         .collect()
 }
 
+/// orders of a pair that expired at or before now, the earliest expiry first.
+/// paging resumes after the start_after order, or from the start once that order left the book
+pub fn read_expired_orders(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    now: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Order>> {
+    let expiry_bucket: ReadonlyBucket<u64> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_ORDER_BY_EXPIRY, pair_key]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after.map(|order_id| read_order(storage, pair_key, order_id)) {
+        Some(Ok(order)) => calc_range_start(
+            order
+                .expires_at
+                .map(|expires_at| expiry_key(expires_at, order.order_id)),
+        ),
+        _ => None,
+    };
+    // every key expiring at or before now is below the first key of the next second
+    let end = now.checked_add(1).map(|end| end.to_be_bytes().to_vec());
+
+    expiry_bucket
+        .range(start.as_deref(), end.as_deref(), OrderBy::Ascending)
+        .take(limit)
+        .map(|item| read_order(storage, pair_key, item?.1))
+        .collect()
+}
+
+/// pending trigger orders are kept apart from PREFIX_ORDER so they are neither indexed by tick nor matched
+pub fn store_pending_order(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    order: &Order,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key])
+        .save(&order.order_id.to_be_bytes(), order)?;
+    if let Some(trigger_price) = order.trigger_price {
+        Bucket::multilevel(
+            storage,
+            &[
+                PREFIX_PENDING_ORDER_BY_TRIGGER,
+                pair_key,
+                order.direction.as_bytes(),
+            ],
+        )
+        .save(&trigger_key(trigger_price, order.order_id), &order.order_id)?;
+    }
+    Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_PENDING_ORDER_BY_BIDDER,
+            pair_key,
+            order.bidder_addr.as_slice(),
+        ],
+    )
+    .save(&order.order_id.to_be_bytes(), &order.direction)
+}
+
+pub fn read_pending_order(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    order_id: u64,
+) -> StdResult<Order> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key])
+        .load(&order_id.to_be_bytes())
+}
+
+pub fn remove_pending_order(storage: &mut dyn Storage, pair_key: &[u8], order: &Order) {
+    Bucket::<Order>::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key])
+        .remove(&order.order_id.to_be_bytes());
+    if let Some(trigger_price) = order.trigger_price {
+        Bucket::<u64>::multilevel(
+            storage,
+            &[
+                PREFIX_PENDING_ORDER_BY_TRIGGER,
+                pair_key,
+                order.direction.as_bytes(),
+            ],
+        )
+        .remove(&trigger_key(trigger_price, order.order_id));
+    }
+    Bucket::<OrderDirection>::multilevel(
+        storage,
+        &[
+            PREFIX_PENDING_ORDER_BY_BIDDER,
+            pair_key,
+            order.bidder_addr.as_slice(),
+        ],
+    )
+    .remove(&order.order_id.to_be_bytes());
+}
+
+/// pending orders of a bidder on a pair in ascending id, only the ones of direction when it is set
+pub fn read_bidder_pending_orders(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    bidder_addr: &CanonicalAddr,
+    direction: Option<OrderDirection>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Order>> {
+    let position_indexer: ReadonlyBucket<OrderDirection> = ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PREFIX_PENDING_ORDER_BY_BIDDER,
+            pair_key,
+            bidder_addr.as_slice(),
+        ],
+    );
+    let order_bucket = ReadonlyBucket::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    position_indexer
+        .range(None, None, OrderBy::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, order_direction)| {
+                direction.map_or(true, |direction| direction.eq(order_direction))
+            })
+        })
+        .take(limit)
+        .map(|item| order_bucket.load(&item?.0))
+        .collect()
+}
+
+pub fn read_pending_orders(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Order>> {
+    let position_bucket: ReadonlyBucket<Order> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after.map(|id| id.to_be_bytes().to_vec()));
+
+    position_bucket
+        .range(start.as_deref(), None, OrderBy::Ascending)
+        .take(limit)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
+/// read_all_pending_orders: iterates every pending order of a pair without pagination, only for aggregated queries
+pub fn read_all_pending_orders(storage: &dyn Storage, pair_key: &[u8]) -> StdResult<Vec<Order>> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_PENDING_ORDER, pair_key])
+        .range(None, None, OrderBy::Ascending)
+        .map(|item| item.map(|item| item.1))
+        .collect()
+}
+
+/// increase_last_trade_id starts from 1, contracts instantiated before the trade log have no counter yet
+pub fn increase_last_trade_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let last_trade_id = singleton_read::<u64>(storage, KEY_LAST_TRADE_ID)
+        .may_load()?
+        .unwrap_or_default()
+        + 1;
+    singleton(storage, KEY_LAST_TRADE_ID).save(&last_trade_id)?;
+    Ok(last_trade_id)
+}
+
+pub fn store_trade(storage: &mut dyn Storage, pair_key: &[u8], trade: &Trade) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_TRADE, pair_key])
+        .save(&trade.trade_id.to_be_bytes(), trade)
+}
+
+pub fn read_trade(storage: &dyn Storage, pair_key: &[u8], trade_id: u64) -> StdResult<Trade> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_TRADE, pair_key]).load(&trade_id.to_be_bytes())
+}
+
+/// pending orders of a pair whose trigger price the mid price has crossed, buy stops first.
+/// only the triggered range of the trigger price index is read
+pub fn read_triggered_orders(
+    storage: &dyn Storage,
+    pair_key: &[u8],
+    mid_price: Decimal,
+    limit: Option<u32>,
+) -> StdResult<Vec<Order>> {
+    let buy_bucket: ReadonlyBucket<u64> = ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PREFIX_PENDING_ORDER_BY_TRIGGER,
+            pair_key,
+            OrderDirection::Buy.as_bytes(),
+        ],
+    );
+    let sell_bucket: ReadonlyBucket<u64> = ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PREFIX_PENDING_ORDER_BY_TRIGGER,
+            pair_key,
+            OrderDirection::Sell.as_bytes(),
+        ],
+    );
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // a buy stop triggers at or below the mid price, a sell stop at or above it
+    let mid_price_key = mid_price.atomics().to_be_bytes().to_vec();
+    let buy_end = mid_price
+        .atomics()
+        .checked_add(Uint128::one())
+        .ok()
+        .map(|end| end.to_be_bytes().to_vec());
+
+    buy_bucket
+        .range(None, buy_end.as_deref(), OrderBy::Ascending)
+        .chain(sell_bucket.range(Some(&mid_price_key), None, OrderBy::Ascending))
+        .take(limit)
+        .map(|item| read_pending_order(storage, pair_key, item?.1))
+        .collect()
+}
+
 static KEY_LAST_ORDER_ID: &[u8] = b"last_order_id"; // should use big int? guess no need
 static CONTRACT_INFO: &[u8] = b"contract_info"; // contract info
 static PREFIX_ORDER_BOOK: &[u8] = b"order_book"; // store config for an order book like min ask amount and min sell amount
 static PREFIX_ORDER: &[u8] = b"order"; // this is orderbook
 static PREFIX_REWARD: &[u8] = b"reward_wallet"; // executor that running matching engine for orderbook pair
+static PREFIX_EXECUTOR_ALLOWLIST: &[u8] = b"executor_allowlist"; // executors allowed to run matching engine
+static PREFIX_CLIENT_ORDER_ID: &[u8] = b"client_order_id"; // client order ids recently used by a bidder
+static PREFIX_PENDING_ORDER: &[u8] = b"pending_order"; // trigger orders waiting for activation
+static PREFIX_PENDING_ORDER_BY_TRIGGER: &[u8] = b"pending_order_by_trigger"; // pending orders by trigger price
+static PREFIX_PENDING_ORDER_BY_BIDDER: &[u8] = b"pending_order_by_bidder"; // pending orders of a bidder
+static PREFIX_ORDER_BY_EXPIRY: &[u8] = b"order_by_expiry"; // orders with an expiry by expiry time
+static KEY_LAST_TRADE_ID: &[u8] = b"last_trade_id"; // trades are numbered across every pair
+static PREFIX_TRADE: &[u8] = b"trade"; // trade log of matched order pairs
 
 pub static PREFIX_ORDER_BY_BIDDER: &[u8] = b"order_by_bidder"; // order from a bidder
 pub static PREFIX_ORDER_BY_PRICE: &[u8] = b"order_by_price"; // this where orders belong to tick
@@ -1,17 +1,29 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{to_binary, Addr, Coin, Decimal, StdError, Uint128};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, Addr, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal,
+    Deps, DepsMut, StdError, SubMsg, Uint128,
+};
+use cosmwasm_storage::singleton;
 use oraiswap::create_entry_points_testing;
-use oraiswap::testing::{AttributeUtil, MockApp, ATOM_DENOM};
+use oraiswap::testing::{AttributeUtil, MockApp, APP_OWNER, ATOM_DENOM};
 
-use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
+use oraiswap::asset::{pair_key, Asset, AssetInfo, ORAI_DENOM};
+use oraiswap::error::ContractError;
 use oraiswap::limit_order::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LastOrderIdResponse, OrderBookMatchableResponse,
-    OrderBookResponse, OrderBooksResponse, OrderDirection, OrderFilter, OrderResponse, OrderStatus,
-    OrdersResponse, QueryMsg, TicksResponse,
+    BestPricesResponse, BidderOrdersResponse, BookSnapshotResponse, ContractInfoResponse,
+    CreatePairParams, Cw20HookMsg, ExecuteMsg, ExecutorResponse, ExecutorsResponse, FillHookMsg,
+    Hook, InstantiateMsg, LastOrderIdResponse, LastStoredOrderIdResponse,
+    MatchingWorthwhileResponse, MigrateMsg, MinCommission, MinCommissionPolicy,
+    OrderBookMatchableResponse, OrderBookResponse, OrderBooksResponse, OrderDirection, OrderFilter,
+    OrderResponse, OrderStatus, OrderType, OrdersResponse, PairLimitsResponse, QueryMsg,
+    SpreadResponse, SubmitOrderItem, TickDepth, TicksResponse, TradeParticipantsResponse,
 };
 
-use crate::jsonstr;
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::state::{init_last_order_id, read_last_order_id, read_order, store_order};
+use crate::{jsonstr, match_orders};
 const USDT_DENOM: &str = "usdt";
 
 fn basic_fixture() -> (MockApp, Addr) {
@@ -57,6 +69,8 @@ fn basic_fixture() -> (MockApp, Addr) {
         admin: None,
         commission_rate: None,
         reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
     };
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
     let limit_order_addr = app
@@ -79,6 +93,8 @@ fn basic_fixture() -> (MockApp, Addr) {
         },
         spread: None,
         min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
     };
     let _res = app
         .execute(
@@ -127,6 +143,13 @@ fn test_query_mid_price() {
                 amount: Uint128::from(300u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _ = app
@@ -174,6 +197,13 @@ fn test_query_mid_price() {
                 amount: Uint128::from(1500u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _ = app
@@ -207,568 +237,679 @@ fn test_query_mid_price() {
 }
 
 #[test]
-fn submit_order() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-            ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-            ],
-        ),
-    ]);
-
-    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
-
-    app.set_token_balances(&[(
-        &"asset".to_string(),
-        &[(&"addr0000".to_string(), &Uint128::from(1000000000u128))],
-    )]);
-
-    let token_addr = app.get_token_addr("asset").unwrap();
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
-        )
-        .unwrap();
-
-    // create order book for pair [orai, usdt]
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
-            denom: USDT_DENOM.to_string(),
-        },
-        spread: None,
-        min_quote_coin_amount: Uint128::from(10u128),
-    };
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-
-    // Create an existed order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
+fn test_query_spread() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
         },
-        quote_coin_info: AssetInfo::NativeToken {
+        AssetInfo::NativeToken {
             denom: USDT_DENOM.to_string(),
         },
-        spread: None,
-        min_quote_coin_amount: Uint128::zero(),
+    ];
+    let query_msg = QueryMsg::Spread {
+        asset_infos: asset_infos.clone(),
     };
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(_res);
 
+    // paid 300 usdt to get 150 orai -> best buy 2
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Buy,
         assets: [
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000000u128),
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(150u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000000u128),
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(300u128),
             },
         ],
-    };
-
-    // offer asset is null
-    let res = app.execute(
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+    app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
         &msg,
-        &[],
-    );
-    app.assert_fail(res);
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(300u128),
+        }],
+    )
+    .unwrap();
+
+    // sell side is still empty
+    let spread = app
+        .query::<Option<SpreadResponse>, _>(limit_order_addr.clone(), &query_msg)
+        .unwrap();
+    assert_eq!(spread, None);
 
+    // sell 100 orai to get 220 usdt -> best sell 2.2
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(5u128),
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(100u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(50u128),
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(220u128),
             },
         ],
-    };
-
-    // Offer ammount 5 usdt (min 10 usdt) is too low
-    let res = app.execute(
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+    app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
         &msg,
         &[Coin {
             denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(50u128),
+            amount: Uint128::from(100u128),
         }],
+    )
+    .unwrap();
+
+    // spread = (2.2 - 2) / 2.1
+    let spread = app
+        .query::<Option<SpreadResponse>, _>(limit_order_addr.clone(), &query_msg)
+        .unwrap();
+    assert_eq!(
+        spread,
+        Some(SpreadResponse {
+            best_buy: Decimal::from_ratio(2u128, 1u128),
+            best_sell: Decimal::from_str("2.2").unwrap(),
+            spread_pct: Decimal::from_str("0.095238095238095238").unwrap(),
+        })
     );
-    app.assert_fail(res);
+}
 
-    // paid 150 usdt to get 150 orai
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(150u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(150u128),
-            },
-        ],
+#[test]
+fn test_query_best_prices() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let query_msg = QueryMsg::BestPrices {
+        asset_infos: asset_infos.clone(),
     };
 
-    let _ = app
-        .execute(
+    // an empty book is not an error
+    let best_prices = app
+        .query::<BestPricesResponse, _>(limit_order_addr.clone(), &query_msg)
+        .unwrap();
+    assert_eq!(
+        best_prices,
+        BestPricesResponse {
+            best_buy: None,
+            best_sell: None,
+            spread: None,
+            spread_pct: None,
+        }
+    );
+
+    // (direction, orai amount, usdt amount)
+    let orders = [
+        (OrderDirection::Sell, 100u128, 500u128),
+        (OrderDirection::Sell, 100, 450),
+        (OrderDirection::Buy, 100, 350),
+        (OrderDirection::Buy, 100, 300),
+    ];
+    for (direction, orai_amount, usdt_amount) in orders {
+        let msg = ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(orai_amount),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(usdt_amount),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        };
+        let funds = match direction {
+            OrderDirection::Buy => coins(usdt_amount, USDT_DENOM),
+            OrderDirection::Sell => coins(orai_amount, ORAI_DENOM),
+        };
+        app.execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(150u128),
-            }],
+            &funds,
         )
         .unwrap();
 
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(150u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(0u128),
-            },
-        ],
-    };
+        // only the sell side is seeded so far
+        if direction == OrderDirection::Sell {
+            let best_prices = app
+                .query::<BestPricesResponse, _>(limit_order_addr.clone(), &query_msg)
+                .unwrap();
+            assert_eq!(best_prices.best_buy, None);
+            assert_eq!(
+                best_prices.best_sell,
+                Some(Decimal::from_ratio(usdt_amount, orai_amount))
+            );
+            assert_eq!(best_prices.spread_pct, None);
+        }
+    }
 
-    // Asset must not be zero
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(0u128),
-        }],
+    // spread = 4.5 - 3.5, spread_pct = 1 / 4
+    let best_prices = app
+        .query::<BestPricesResponse, _>(limit_order_addr.clone(), &query_msg)
+        .unwrap();
+    assert_eq!(
+        best_prices,
+        BestPricesResponse {
+            best_buy: Some(Decimal::from_str("3.5").unwrap()),
+            best_sell: Some(Decimal::from_str("4.5").unwrap()),
+            spread: Some(Decimal::one()),
+            spread_pct: Some(Decimal::from_str("0.25").unwrap()),
+        }
     );
-    app.assert_fail(res);
+}
 
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+#[test]
+fn test_query_book_snapshot() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // (direction, orai amount, usdt amount)
+    let orders = [
+        (OrderDirection::Buy, 100u128, 200u128),
+        (OrderDirection::Buy, 50, 100),
+        (OrderDirection::Buy, 100, 150),
+        (OrderDirection::Sell, 100, 300),
+        (OrderDirection::Sell, 100, 400),
+        (OrderDirection::Sell, 50, 200),
+    ];
+    for (direction, orai_amount, usdt_amount) in orders {
+        let msg = ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(orai_amount),
                 },
-                amount: Uint128::from(11111111u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(usdt_amount),
                 },
-                amount: Uint128::from(12345678u128),
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        };
+        let funds = match direction {
+            OrderDirection::Buy => Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(usdt_amount),
             },
-        ],
-    };
-
-    // paid 11111111 usdt to get 12345678 orai
-    let res = app
-        .execute(
+            OrderDirection::Sell => Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(orai_amount),
+            },
+        };
+        app.execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(11111111u128),
-            }],
+            &[funds],
         )
         .unwrap();
-    println!("submit 2 {:?}", res);
+    }
 
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(20000u128),
+    let snapshot = app
+        .query::<BookSnapshotResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::BookSnapshot {
+                asset_infos: asset_infos.clone(),
+                max_ticks: None,
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+        )
+        .unwrap();
+
+    // same ticks as the per side queries, best price first
+    for (direction, order_by, snapshot_ticks) in [
+        (OrderDirection::Buy, 2, &snapshot.buy_ticks),
+        (OrderDirection::Sell, 1, &snapshot.sell_ticks),
+    ] {
+        let ticks = app
+            .query::<TicksResponse, _>(
+                limit_order_addr.clone(),
+                &QueryMsg::Ticks {
+                    asset_infos: asset_infos.clone(),
+                    direction,
+                    start_after: None,
+                    end: None,
+                    limit: None,
+                    order_by: Some(order_by),
                 },
-                amount: Uint128::from(70000u128),
-            },
-        ],
-    };
+            )
+            .unwrap();
+        assert_eq!(ticks.ticks.len(), snapshot_ticks.len());
+        for (tick, snapshot_tick) in ticks.ticks.iter().zip(snapshot_ticks.iter()) {
+            assert_eq!(tick.price, snapshot_tick.price);
+            assert_eq!(tick.total_orders, snapshot_tick.total_orders);
+        }
+    }
 
-    // paid 70000 orai to get 20000 usdt
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    // buy volume is in usdt, sell volume is in orai
+    assert_eq!(
+        snapshot
+            .buy_ticks
+            .iter()
+            .map(|tick| (tick.price, tick.volume))
+            .collect::<Vec<_>>(),
+        vec![
+            (Decimal::from_ratio(2u128, 1u128), Uint128::from(300u128)),
+            (Decimal::from_ratio(3u128, 2u128), Uint128::from(150u128)),
+        ]
+    );
+    assert_eq!(
+        snapshot
+            .sell_ticks
+            .iter()
+            .map(|tick| (tick.price, tick.volume))
+            .collect::<Vec<_>>(),
+        vec![
+            (Decimal::from_ratio(3u128, 1u128), Uint128::from(100u128)),
+            (Decimal::from_ratio(4u128, 1u128), Uint128::from(150u128)),
+        ]
+    );
+    assert_eq!(snapshot.total_orders, 6);
+
+    // max_ticks is applied per side
+    let snapshot = app
+        .query::<BookSnapshotResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(70000u128),
-            }],
+            &QueryMsg::BookSnapshot {
+                asset_infos,
+                max_ticks: Some(1),
+            },
         )
         .unwrap();
-    println!("submit 3 {:?}", res);
+    assert_eq!(snapshot.buy_ticks.len(), 1);
+    assert_eq!(snapshot.sell_ticks.len(), 1);
+    assert_eq!(snapshot.total_orders, 3);
+}
 
-    let order_1 = OrderResponse {
-        order_id: 1u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(150u128),
-            info: AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
-            },
-        },
-        ask_asset: Asset {
-            amount: Uint128::from(150u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
+#[test]
+fn test_query_pair_limits() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let usdt = AssetInfo::NativeToken {
+        denom: USDT_DENOM.to_string(),
     };
 
-    let order_2 = OrderResponse {
-        order_id: 2u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(11111111u128),
-            info: AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
-            },
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: atom.clone(),
+            quote_coin_info: usdt.clone(),
+            spread: Some(Decimal::percent(10)),
+            min_quote_coin_amount: Uint128::from(10000u128),
+            commission_rate: None,
+            precision: None,
         },
-        ask_asset: Asset {
-            amount: Uint128::from(12345678u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+        &[],
+    )
+    .unwrap();
+
+    let res = app
+        .query::<PairLimitsResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::PairLimits {
+                asset_infos: [usdt.clone(), atom.clone()],
             },
-        },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
-    };
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        PairLimitsResponse {
+            base_coin_info: atom,
+            quote_coin_info: usdt,
+            min_quote_coin_amount: Uint128::from(10000u128),
+            spread: Some(Decimal::percent(10)),
+            commission_rate: "0.001".to_string(),
+            relayer_fee: Uint128::from(300u128),
+        }
+    );
+}
 
-    let order_3 = OrderResponse {
-        order_id: 3u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(70000u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+#[test]
+fn test_query_total_escrow() {
+    let (mut app, limit_order_addr) = basic_fixture();
+
+    // second pair [atom, usdt] sharing the same quote asset
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
             },
-        },
-        ask_asset: Asset {
-            amount: Uint128::from(20000u128),
-            info: AssetInfo::NativeToken {
+            quote_coin_info: AssetInfo::NativeToken {
                 denom: USDT_DENOM.to_string(),
             },
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Sell,
-        status: OrderStatus::Open,
-    };
+        &[],
+    )
+    .unwrap();
 
-    assert_eq!(
-        order_3.clone(),
-        app.query::<OrderResponse, _>(
+    let submit_orders = [
+        (
+            OrderDirection::Buy,
+            ORAI_DENOM,
+            1000u128,
+            2000u128,
+            USDT_DENOM,
+        ),
+        (
+            OrderDirection::Buy,
+            ATOM_DENOM,
+            500u128,
+            3000u128,
+            USDT_DENOM,
+        ),
+        (
+            OrderDirection::Sell,
+            ORAI_DENOM,
+            700u128,
+            3000u128,
+            ORAI_DENOM,
+        ),
+    ];
+    for (direction, base_denom, base_amount, quote_amount, paid_denom) in submit_orders {
+        let paid_amount = if paid_denom == USDT_DENOM {
+            quote_amount
+        } else {
+            base_amount
+        };
+        app.execute(
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 3,
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+            &ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: base_denom.to_string(),
+                        },
+                        amount: Uint128::from(base_amount),
                     },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: USDT_DENOM.to_string(),
+                        },
+                        amount: Uint128::from(quote_amount),
                     },
                 ],
-            }
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &[Coin {
+                denom: paid_denom.to_string(),
+                amount: Uint128::from(paid_amount),
+            }],
         )
-        .unwrap()
-    );
+        .unwrap();
+    }
 
-    assert_eq!(
-        order_2.clone(),
-        app.query::<OrderResponse, _>(
+    let query_escrow = |denom: &str| {
+        app.query::<Asset, _>(
             limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 2,
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-            }
+            &QueryMsg::TotalEscrow {
+                asset_info: AssetInfo::NativeToken {
+                    denom: denom.to_string(),
+                },
+            },
         )
         .unwrap()
-    );
+        .amount
+    };
 
+    // usdt is escrowed by buy orders of both pairs
+    assert_eq!(query_escrow(USDT_DENOM), Uint128::from(5000u128));
+    assert_eq!(query_escrow(ORAI_DENOM), Uint128::from(700u128));
+    assert_eq!(query_escrow(ATOM_DENOM), Uint128::zero());
+
+    // escrow matches the contract balances
     assert_eq!(
-        order_1.clone(),
-        app.query::<OrderResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 1,
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-            }
-        )
-        .unwrap()
+        app.query_balance(limit_order_addr.clone(), USDT_DENOM.to_string())
+            .unwrap(),
+        query_escrow(USDT_DENOM)
     );
+}
 
-    // create order book for pair [orai, token_addr]
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
+#[test]
+fn create_orderbook_pairs() {
+    let (mut app, limit_order_addr) = basic_fixture();
+
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let new_pairs = vec![
+        CreatePairParams {
+            base_coin_info: native(ATOM_DENOM),
+            quote_coin_info: native(USDT_DENOM),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
         },
-        quote_coin_info: AssetInfo::Token {
-            contract_addr: token_addr.clone(),
+        CreatePairParams {
+            base_coin_info: native(ORAI_DENOM),
+            quote_coin_info: native(ATOM_DENOM),
+            spread: Some(Decimal::percent(1)),
+            min_quote_coin_amount: Uint128::zero(),
+            commission_rate: None,
+            precision: None,
         },
+        CreatePairParams {
+            base_coin_info: native("milky"),
+            quote_coin_info: native(USDT_DENOM),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(100u128),
+            commission_rate: None,
+            precision: None,
+        },
+    ];
+
+    // only admin can create order books
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPairs {
+            pairs: new_pairs.clone(),
+        },
+        &[],
+    );
+    app.assert_fail(res);
+
+    // a duplicated pair aborts the whole batch
+    let mut duplicated_pairs = new_pairs.clone();
+    duplicated_pairs.push(CreatePairParams {
+        base_coin_info: native(ORAI_DENOM),
+        quote_coin_info: native(USDT_DENOM),
         spread: None,
         min_quote_coin_amount: Uint128::zero(),
-    };
-    let _res = app.execute(
+        commission_rate: None,
+        precision: None,
+    });
+    let res = app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
-        &msg,
+        &ExecuteMsg::CreateOrderBookPairs {
+            pairs: duplicated_pairs,
+        },
         &[],
     );
+    app.assert_fail(res);
+    let res = app
+        .query::<OrderBooksResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBooks {
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.order_books.len(), 1);
 
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1212121u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(2121212u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addr.clone(),
-                    },
-                    amount: Uint128::from(1212121u128),
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPairs {
+            pairs: new_pairs.clone(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    for pair in new_pairs {
+        let res = app
+            .query::<OrderBookResponse, _>(
+                limit_order_addr.clone(),
+                &QueryMsg::OrderBook {
+                    asset_infos: [pair.base_coin_info.clone(), pair.quote_coin_info.clone()],
                 },
-            ],
-        })
-        .unwrap(),
-    };
+            )
+            .unwrap();
+        assert_eq!(res.base_coin_info, pair.base_coin_info);
+        assert_eq!(res.quote_coin_info, pair.quote_coin_info);
+        assert_eq!(res.spread, pair.spread);
+        assert_eq!(res.min_quote_coin_amount, pair.min_quote_coin_amount);
+    }
+}
 
-    let _ = app
-        .execute(Addr::unchecked("addr0000"), token_addr.clone(), &msg, &[])
-        .unwrap();
+#[test]
+fn create_orderbook_pair_orientation() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let usdt = AssetInfo::NativeToken {
+        denom: USDT_DENOM.to_string(),
+    };
 
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addr.clone(),
-                },
-                amount: Uint128::from(1111111u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1234567u128),
-            },
-        ],
-    };
-
-    // paid 1234567 orai to get 1111111 token
-    let _ = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1234567u128),
-            }],
-        )
-        .unwrap();
-
-    let order_4 = OrderResponse {
-        order_id: 4u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(1212121u128),
-            info: AssetInfo::Token {
-                contract_addr: token_addr.clone(),
-            },
-        },
-        ask_asset: Asset {
-            amount: Uint128::from(2121212u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
+    // [orai, usdt] already exists, the reversed order maps to the same pair key
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: usdt.clone(),
+            quote_coin_info: orai.clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
-    };
+        &[],
+    );
+    app.assert_fail(res);
 
-    let order_5 = OrderResponse {
-        order_id: 5u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(1234567u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        },
-        ask_asset: Asset {
-            amount: Uint128::from(1111111u128),
-            info: AssetInfo::Token {
-                contract_addr: token_addr.clone(),
-            },
+    // same asset on both sides can not be oriented
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: orai.clone(),
+            quote_coin_info: orai.clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Sell,
-        status: OrderStatus::Open,
-    };
-
-    assert_eq!(
-        order_4.clone(),
-        app.query::<OrderResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 4,
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::Token {
-                        contract_addr: token_addr.clone(),
-                    },
-                ],
-            }
-        )
-        .unwrap()
+        &[],
     );
+    app.assert_fail(res);
 
-    assert_eq!(
-        order_5.clone(),
-        app.query::<OrderResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 5,
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addr.clone(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                ],
-            }
-        )
-        .unwrap()
-    );
-    assert_eq!(
-        app.query::<LastOrderIdResponse, _>(limit_order_addr.clone(), &QueryMsg::LastOrderId {})
-            .unwrap(),
-        LastOrderIdResponse { last_order_id: 5 }
-    );
+    // create [usdt, atom] with the base sorted after the quote
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: usdt.clone(),
+            quote_coin_info: atom.clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // orientation is the stored one, whichever order the pair is queried with
+    for (base, quote) in [(orai.clone(), usdt.clone()), (usdt, atom)] {
+        for asset_infos in [[base.clone(), quote.clone()], [quote.clone(), base.clone()]] {
+            let res = app
+                .query::<OrderBookResponse, _>(
+                    limit_order_addr.clone(),
+                    &QueryMsg::OrderBook { asset_infos },
+                )
+                .unwrap();
+            assert_eq!(res.base_coin_info, base);
+            assert_eq!(res.quote_coin_info, quote);
+            assert_eq!(res.price_denominated_in, quote);
+        }
+    }
 }
 
 #[test]
-fn cancel_order_native_token() {
+fn submit_order() {
     let mut app = MockApp::new(&[
         (
             &"addr0000".to_string(),
@@ -798,12 +939,23 @@ fn cancel_order_native_token() {
         ),
     ]);
 
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    app.set_token_balances(&[(
+        &"asset".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000000u128))],
+    )]);
+
+    let token_addr = app.get_token_addr("asset").unwrap();
+
     let msg = InstantiateMsg {
         name: None,
         version: None,
         admin: None,
         commission_rate: None,
         reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
     };
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
     let limit_order_addr = app
@@ -816,7 +968,29 @@ fn cancel_order_native_token() {
         )
         .unwrap();
 
-    // create order book for pair [orai, atom]
+    // create order book for pair [orai, usdt]
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    // Create an existed order book
     let msg = ExecuteMsg::CreateOrderBookPair {
         base_coin_info: AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
@@ -826,6 +1000,8 @@ fn cancel_order_native_token() {
         },
         spread: None,
         min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
     };
     let _res = app.execute(
         Addr::unchecked("addr0000"),
@@ -833,355 +1009,364 @@ fn cancel_order_native_token() {
         &msg,
         &[],
     );
+    app.assert_fail(_res);
 
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Buy,
         assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(500000u128),
+                amount: Uint128::from(1000000u128),
             },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer asset is null
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(6666666u128),
+                amount: Uint128::from(5u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(50u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(6666666u128),
-            }],
-        )
-        .unwrap();
+    // Offer ammount 5 usdt (min 10 usdt) is too low
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(50u128),
+        }],
+    );
+    app.assert_fail(res);
 
+    // paid 150 usdt to get 150 orai
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(456789u128),
+                amount: Uint128::from(150u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(6666666u128),
+                amount: Uint128::from(150u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    let _res = app
+    let _ = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(456789u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(150u128),
             }],
         )
         .unwrap();
 
-    let msg = ExecuteMsg::CancelOrder {
-        order_id: 1,
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(150u128),
             },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(0u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // verfication failed
+    // Asset must not be zero
     let res = app.execute(
-        Addr::unchecked("addr0001"),
+        Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
         &msg,
-        &[],
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(0u128),
+        }],
     );
     app.assert_fail(res);
 
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-    assert_eq!(
-        res.get_attributes(1),
-        vec![
-            ("action", "cancel_order"),
-            ("pair", "orai - usdt"),
-            ("order_id", "1"),
-            ("direction", "Buy"),
-            ("status", "Cancel"),
-            ("bidder_addr", "addr0000"),
-            ("offer_amount", "6666666"),
-            ("ask_amount", "500000"),
-            ("bidder_refund", &format!("6666666{}", USDT_DENOM)),
-        ]
-    );
-
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    println!("round 1 - address0_balances: {:?}", address0_balances);
-    println!("round 1 - address1_balances: {:?}", address1_balances);
-
-    let mut expected_balances: Vec<Coin> = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(999543211u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-
-    // failed no order exists
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
-
-    let msg = ExecuteMsg::CancelOrder {
-        order_id: 2,
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(11111111u128),
             },
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(12345678u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    let _res = app
+    // paid 11111111 usdt to get 12345678 orai
+    let res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(11111111u128),
+            }],
         )
         .unwrap();
-
-    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    println!("round 2 - address1_balances: {:?}", address1_balances);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
+    println!("submit 2 {:?}", res);
 
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1234560u128),
+                amount: Uint128::from(20000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000000u128),
+                amount: Uint128::from(70000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    let _res = app
+    // paid 70000 orai to get 20000 usdt
+    let res = app
         .execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1234560u128),
+                amount: Uint128::from(70000u128),
             }],
         )
         .unwrap();
+    println!("submit 3 {:?}", res);
 
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    println!("round 3 - address0_balances: {:?}", address0_balances);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(998765440u128),
+    let order_1 = OrderResponse {
+        order_id: 1u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(150u128),
+            info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
         },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
+        ask_asset: Asset {
+            amount: Uint128::from(150u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
         },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
+    };
 
-    let msg = ExecuteMsg::CancelOrder {
-        order_id: 3,
-        asset_infos: [
-            AssetInfo::NativeToken {
+    let order_2 = OrderResponse {
+        order_id: 2u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(11111111u128),
+            info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(12345678u128),
+            info: AssetInfo::NativeToken {
                 denom: ORAI_DENOM.to_string(),
             },
-            AssetInfo::NativeToken {
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
+    };
+
+    let order_3 = OrderResponse {
+        order_id: 3u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(70000u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(20000u128),
+            info: AssetInfo::NativeToken {
                 denom: USDT_DENOM.to_string(),
             },
-        ],
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Sell,
+        status: OrderStatus::Open,
     };
 
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    assert_eq!(
+        order_3.clone(),
+        app.query::<OrderResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[],
+            &QueryMsg::Order {
+                order_id: 3,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            }
         )
-        .unwrap();
-    assert_eq!(
-        res.get_attributes(1),
-        vec![
-            ("action", "cancel_order"),
-            ("pair", "orai - usdt"),
-            ("order_id", "3"),
-            ("direction", "Sell"),
-            ("status", "Cancel"),
-            ("bidder_addr", "addr0000"),
-            ("offer_amount", "1234560"),
-            ("ask_amount", "1000000"),
-            ("bidder_refund", &format!("1234560{}", ORAI_DENOM)),
-        ]
+        .unwrap()
     );
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    println!("round 4 - address0_balances: {:?}", address0_balances);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-}
-
-#[test]
-fn cancel_order_token() {
-    let mut app = MockApp::new(&[(
-        &"addr0000".to_string(),
-        &[Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        }],
-    )]);
-    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
 
-    let token_addrs = app.set_token_balances(&[
-        (
-            &"assetA".to_string(),
-            &[
-                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
-                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
-            ],
-        ),
-        (
-            &"assetB".to_string(),
-            &[
-                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
-                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
-            ],
-        ),
-    ]);
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
+    assert_eq!(
+        order_2.clone(),
+        app.query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 2,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            }
         )
-        .unwrap();
+        .unwrap()
+    );
 
-    // create order book for pair [token_addrs[1], token_addrs[0]]
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[1].clone(),
-        },
-        quote_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[0].clone(),
-        },
-        spread: None,
-        min_quote_coin_amount: Uint128::zero(),
-    };
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
+    assert_eq!(
+        order_1.clone(),
+        app.query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 1,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            }
+        )
+        .unwrap()
     );
 
-    // create order book for pair [orai, token_addrs[1]]
+    // create order book for pair [orai, token_addr]
     let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[1].clone(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
+        base_coin_info: AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
         },
+        quote_coin_info: AssetInfo::Token {
+            contract_addr: token_addr.clone(),
+        },
         spread: None,
         min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
     };
     let _res = app.execute(
         Addr::unchecked("addr0000"),
@@ -1192,236 +1377,289 @@ fn cancel_order_token() {
 
     let msg = cw20::Cw20ExecuteMsg::Send {
         contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1234567u128), // Fund must be equal to offer amount
+        amount: Uint128::new(1212121u128),
         msg: to_binary(&Cw20HookMsg::SubmitOrder {
             direction: OrderDirection::Buy,
             assets: [
                 Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1234567u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(4567890u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let msg2 = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(3333335u128), // Fund must be equal to offer amount
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Sell,
-            assets: [
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1212121u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
                     },
-                    amount: Uint128::from(3333335u128),
+                    amount: Uint128::from(2121212u128),
                 },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let msg3 = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(3333336u128), // Fund must be equal to offer amount
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Sell,
-            assets: [
                 Asset {
                     info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
+                        contract_addr: token_addr.clone(),
                     },
                     amount: Uint128::from(1212121u128),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(3333335u128),
-                },
             ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
         })
         .unwrap(),
     };
 
     let _ = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-
-    let _ = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[1].clone(),
-            &msg2,
-            &[],
-        )
+        .execute(Addr::unchecked("addr0000"), token_addr.clone(), &msg, &[])
         .unwrap();
 
-    // provided and paid asset are different
-    let res = app.execute(
-        Addr::unchecked("addr0001"),
-        token_addrs[1].clone(),
-        &msg3,
-        &[],
-    );
-    app.assert_fail(res);
-
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1223344u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Sell,
-            assets: [
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(1223344u128),
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_addr.clone(),
                 },
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(2334455u128),
+                amount: Uint128::from(1111111u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1234567u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
+    // paid 1234567 orai to get 1111111 token
     let _ = app
         .execute(
             Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1234567u128),
+            }],
         )
         .unwrap();
 
-    let msg = ExecuteMsg::CancelOrder {
-        order_id: 1,
-        asset_infos: [
-            AssetInfo::Token {
-                contract_addr: token_addrs[0].clone(),
+    let order_4 = OrderResponse {
+        order_id: 4u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(1212121u128),
+            info: AssetInfo::Token {
+                contract_addr: token_addr.clone(),
             },
-            AssetInfo::Token {
-                contract_addr: token_addrs[1].clone(),
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(2121212u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
-        ],
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
     };
 
-    // failed verfication failed
-    let res = app.execute(
-        Addr::unchecked("addr0001"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
-
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-
-    assert_eq!(
-        res.get_attributes(1),
-        vec![
-            ("action", "cancel_order"),
-            ("pair", "contract1 - contract0"),
-            ("order_id", "1"),
-            ("direction", "Buy"),
-            ("status", "Cancel"),
-            ("bidder_addr", "addr0000"),
-            ("offer_amount", "1234567"),
-            ("ask_amount", "4567890"),
-            ("bidder_refund", &format!("1234567{}", token_addrs[0])),
-        ]
-    );
-
-    let msg = ExecuteMsg::CancelOrder {
-        order_id: 2,
-        asset_infos: [
-            AssetInfo::Token {
-                contract_addr: token_addrs[1].clone(),
+    let order_5 = OrderResponse {
+        order_id: 5u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(1234567u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
-            AssetInfo::Token {
-                contract_addr: token_addrs[0].clone(),
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(1111111u128),
+            info: AssetInfo::Token {
+                contract_addr: token_addr.clone(),
             },
-        ],
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Sell,
+        status: OrderStatus::Open,
     };
 
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    assert_eq!(
+        order_4.clone(),
+        app.query::<OrderResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[],
+            &QueryMsg::Order {
+                order_id: 4,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addr.clone(),
+                    },
+                ],
+            }
         )
-        .unwrap();
+        .unwrap()
+    );
 
     assert_eq!(
-        res.get_attributes(1),
-        vec![
-            ("action", "cancel_order"),
-            ("pair", "contract1 - contract0"),
-            ("order_id", "2"),
-            ("direction", "Sell"),
-            ("status", "Cancel"),
-            ("bidder_addr", "addr0000"),
-            ("offer_amount", "3333335"),
-            ("ask_amount", "1212121"),
-            ("bidder_refund", &format!("3333335{}", token_addrs[1])),
-        ]
+        order_5.clone(),
+        app.query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 5,
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addr.clone(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                ],
+            }
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        app.query::<LastOrderIdResponse, _>(limit_order_addr.clone(), &QueryMsg::LastOrderId {})
+            .unwrap(),
+        LastOrderIdResponse { last_order_id: 5 }
     );
+}
 
-    // failed no order exists
+#[test]
+fn submit_post_only_order() {
+    let (mut app, limit_order_addr) = basic_fixture();
+
+    let orai_usdt_assets = |orai_amount: u128, usdt_amount: u128| {
+        [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(orai_amount),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(usdt_amount),
+            },
+        ]
+    };
+
+    // resting sell order at price 2
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: orai_usdt_assets(100, 200),
+            post_only: Some(true),
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    )
+    .unwrap();
+
+    // post-only buy order at price 2 would cross the sell order
     let res = app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
-        &msg,
-        &[],
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: orai_usdt_assets(100, 200),
+            post_only: Some(true),
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(200u128),
+        }],
     );
     app.assert_fail(res);
+
+    // post-only buy order at price 1 rests on the book
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: orai_usdt_assets(100, 100),
+            post_only: Some(true),
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    )
+    .unwrap();
+
+    let res = app
+        .query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+                filter: OrderFilter::Bidder("addr0000".to_string()),
+                direction: None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.orders.len(), 1);
+    assert_eq!(res.orders[0].direction, OrderDirection::Buy);
+    assert_eq!(res.orders[0].offer_asset.amount, Uint128::from(100u128));
 }
 
 #[test]
-fn execute_pair_native_token() {
+fn cancel_order_native_token() {
     let mut app = MockApp::new(&[
         (
             &"addr0000".to_string(),
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                    amount: Uint128::from(1000000000u128),
                 },
                 Coin {
                     denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                    amount: Uint128::from(1000000000u128),
                 },
             ],
         ),
@@ -1430,24 +1668,11 @@ fn execute_pair_native_token() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-            ],
-        ),
-        (
-            &"addr0002".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                    amount: Uint128::from(1000000000u128),
                 },
                 Coin {
                     denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                    amount: Uint128::from(1000000000u128),
                 },
             ],
         ),
@@ -1459,6 +1684,8 @@ fn execute_pair_native_token() {
         admin: None,
         commission_rate: None,
         reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
     };
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
     let limit_order_addr = app
@@ -1471,7 +1698,7 @@ fn execute_pair_native_token() {
         )
         .unwrap();
 
-    // Create pair [orai, usdt] for order book
+    // create order book for pair [orai, atom]
     let msg = ExecuteMsg::CreateOrderBookPair {
         base_coin_info: AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
@@ -1480,9 +1707,10 @@ fn execute_pair_native_token() {
             denom: USDT_DENOM.to_string(),
         },
         spread: None,
-        min_quote_coin_amount: Uint128::from(10u128),
+        min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
     };
-
     let _res = app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
@@ -1490,23 +1718,29 @@ fn execute_pair_native_token() {
         &[],
     );
 
-    /* <----------------------------------- order 1 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(500000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(6666666u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -1515,29 +1749,35 @@ fn execute_pair_native_token() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(6666666u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(9700u128),
+                amount: Uint128::from(456789u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(6666666u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -1547,185 +1787,157 @@ fn execute_pair_native_token() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(456789u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 3 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(13000u128),
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 1,
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(14000u128),
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
         ],
+        refund_to: None,
     };
 
-    let _res = app
+    // verfication failed
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(13000u128),
-            }],
+            &[],
         )
         .unwrap();
+    assert_eq!(
+        res.get_attributes(1),
+        vec![
+            ("action", "cancel_order"),
+            ("pair", "orai - usdt"),
+            ("order_id", "1"),
+            ("direction", "Buy"),
+            ("status", "Cancel"),
+            ("bidder_addr", "addr0000"),
+            ("offer_amount", "6666666"),
+            ("ask_amount", "500000"),
+            ("filled_offer_amount", "0"),
+            ("filled_ask_amount", "0"),
+            ("bidder_refund", &format!("6666666{}", USDT_DENOM)),
+        ]
+    );
 
-    /* <----------------------------------- order 4 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(5000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-        ],
-    };
-
-    // offer usdt, ask for orai
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(5000u128),
-            }],
-        )
-        .unwrap();
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    println!("round 1 - address0_balances: {:?}", address0_balances);
+    println!("round 1 - address1_balances: {:?}", address1_balances);
 
-    /* <----------------------------------- order 5 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(4400u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(8800u128),
-            },
-        ],
-    };
+    let mut expected_balances: Vec<Coin> = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(999543211u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(4400u128),
-            }],
-        )
-        .unwrap();
+    // failed no order exists
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
 
-    /* <----------------------------------- order 6 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(7000u128),
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 2,
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(14000u128),
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
             },
         ],
+        refund_to: None,
     };
 
-    // offer orai, ask for atom
     let _res = app
         .execute(
             Addr::unchecked("addr0001"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 7 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(2000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(2000u128),
-            },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(2000u128),
-            }],
-        )
-        .unwrap();
+    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    println!("round 2 - address1_balances: {:?}", address1_balances);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
 
-    /* <----------------------------------- order 8 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(1234560u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1200u128),
+                amount: Uint128::from(1000000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -1734,620 +1946,1118 @@ fn execute_pair_native_token() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1200u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1234560u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 9 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(5000u128),
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    println!("round 3 - address0_balances: {:?}", address0_balances);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(998765440u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 3,
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
             },
         ],
+        refund_to: None,
     };
 
-    let _res = app
+    let res = app
         .execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
-        )
-        .unwrap();
-
-    /* <----------------------------------- order 10 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(7000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(6789u128),
-            },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
-            }],
+            &[],
         )
         .unwrap();
+    assert_eq!(
+        res.get_attributes(1),
+        vec![
+            ("action", "cancel_order"),
+            ("pair", "orai - usdt"),
+            ("order_id", "3"),
+            ("direction", "Sell"),
+            ("status", "Cancel"),
+            ("bidder_addr", "addr0000"),
+            ("offer_amount", "1234560"),
+            ("ask_amount", "1000000"),
+            ("filled_offer_amount", "0"),
+            ("filled_ask_amount", "0"),
+            ("bidder_refund", &format!("1234560{}", ORAI_DENOM)),
+        ]
+    );
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    println!("round 4 - address0_balances: {:?}", address0_balances);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+}
 
-    /* <----------------------------------- order 11 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1500u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
-    };
+#[test]
+fn submit_order_token_with_max_spread() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        }],
+    )]);
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+    let token_addrs = app.set_token_balances(&[(
+        &"assetA".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000000u128))],
+    )]);
+    app.set_limit_order_contract(Box::new(create_entry_points_testing!(crate)));
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: token_addrs[0].clone(),
+        },
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+    ];
+    app.create_orderbook_pair(asset_infos.clone(), Uint128::zero());
+    let limit_order_addr = app.limit_order_addr.clone();
 
-    let _res = app
-        .execute(
+    let buy = |app: &mut MockApp, token_amount: u128, orai_amount: u128| {
+        app.execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
-            }],
+            &ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Buy,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(token_amount),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(orai_amount),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &coins(orai_amount, ORAI_DENOM),
         )
         .unwrap();
-
-    /* <----------------------------------- order 12 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1600u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
+    };
+    let sell_msg = |token_amount: u128, orai_amount: u128, max_spread: Option<Decimal>| {
+        cw20::Cw20ExecuteMsg::Send {
+            contract: limit_order_addr.to_string(),
+            amount: Uint128::from(token_amount),
+            msg: to_binary(&Cw20HookMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(token_amount),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(orai_amount),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+                belief_price: Some(Decimal::from_str("2.1").unwrap()),
+                max_spread,
+            })
+            .unwrap(),
+        }
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
-            }],
-        )
-        .unwrap();
+    // best buy 2, best sell 2.2 -> mid price 2.1
+    buy(&mut app, 100, 200);
+    app.execute(
+        Addr::unchecked("addr0000"),
+        token_addrs[0].clone(),
+        &sell_msg(100, 220, None),
+        &[],
+    )
+    .unwrap();
 
-    /* <----------------------------------- order 13 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1500u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
-    };
+    // the mid price is the belief price
+    app.execute(
+        Addr::unchecked("addr0000"),
+        token_addrs[0].clone(),
+        &sell_msg(100, 300, Some(Decimal::percent(1))),
+        &[],
+    )
+    .unwrap();
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
+    // best buy moves to 2.18 -> mid price 2.19, about 4.3% away from the belief price
+    buy(&mut app, 100, 218);
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        token_addrs[0].clone(),
+        &sell_msg(100, 300, Some(Decimal::percent(1))),
+        &[],
+    );
+    app.assert_fail(res);
+    let mid_price = app
+        .query::<Decimal, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1500u128),
-            }],
+            &QueryMsg::MidPrice {
+                asset_infos: asset_infos.clone(),
+            },
         )
         .unwrap();
+    assert_eq!(mid_price, Decimal::from_str("2.19").unwrap());
 
-    /* <----------------------------------- order 14 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1600u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
+    // a wider tolerance still accepts the order
+    app.execute(
+        Addr::unchecked("addr0000"),
+        token_addrs[0].clone(),
+        &sell_msg(100, 300, Some(Decimal::percent(5))),
+        &[],
+    )
+    .unwrap();
+    let orders = app
+        .query::<OrdersResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1600u128),
-            }],
+            &QueryMsg::Orders {
+                asset_infos: asset_infos.clone(),
+                order_by: None,
+                limit: None,
+                filter: OrderFilter::None,
+                start_after: None,
+                direction: Some(OrderDirection::Sell),
+            },
         )
         .unwrap();
+    assert_eq!(orders.orders.len(), 3);
+}
 
-    /* <----------------------------------- order 15 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-        ],
-    };
+#[test]
+fn cancel_order_token() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        }],
+    )]);
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
 
-    let _res = app
-        .execute(
+    let token_addrs = app.set_token_balances(&[
+        (
+            &"assetA".to_string(),
+            &[
+                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
+                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
+            ],
+        ),
+        (
+            &"assetB".to_string(),
+            &[
+                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
+                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
             Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+            &[],
+            "limit order",
         )
         .unwrap();
 
-    /* <----------------------------------- order 16 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    // create order book for pair [token_addrs[1], token_addrs[0]]
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[1].clone(),
+        },
+        quote_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[0].clone(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
+    };
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    // create order book for pair [orai, token_addrs[1]]
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[1].clone(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
+    };
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1234567u128), // Fund must be equal to offer amount
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1234567u128),
                 },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(4567890u128),
                 },
-                amount: Uint128::from(9700u128),
-            },
-        ],
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
-        )
-        .unwrap();
+    let msg2 = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(3333335u128), // Fund must be equal to offer amount
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1212121u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(3333335u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
 
-    /* <----------------------------------- order 17 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    let msg3 = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(3333336u128), // Fund must be equal to offer amount
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1212121u128),
                 },
-                amount: Uint128::from(14000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(3333335u128),
                 },
-                amount: Uint128::from(13000u128),
-            },
-        ],
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
-    let _res = app
+    let _ = app
         .execute(
-            Addr::unchecked("addr0001"),
-            limit_order_addr.clone(),
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(13000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 18 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    let _ = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[1].clone(),
+            &msg2,
+            &[],
+        )
+        .unwrap();
+
+    // provided and paid asset are different
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        token_addrs[1].clone(),
+        &msg3,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1223344u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(1223344u128),
                 },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(2334455u128),
                 },
-                amount: Uint128::from(5000u128),
-            },
-        ],
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
-    // offer usdt, ask for orai
-    let _res = app
+    let _ = app
         .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(5000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 19 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(8800u128),
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 1,
+        asset_infos: [
+            AssetInfo::Token {
+                contract_addr: token_addrs[0].clone(),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(4400u128),
+            AssetInfo::Token {
+                contract_addr: token_addrs[1].clone(),
             },
         ],
+        refund_to: None,
     };
 
-    let _res = app
+    // failed verfication failed
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let res = app
         .execute(
-            Addr::unchecked("addr0002"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(4400u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 20 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(14000u128),
+    assert_eq!(
+        res.get_attributes(1),
+        vec![
+            ("action", "cancel_order"),
+            ("pair", "contract1 - contract0"),
+            ("order_id", "1"),
+            ("direction", "Buy"),
+            ("status", "Cancel"),
+            ("bidder_addr", "addr0000"),
+            ("offer_amount", "1234567"),
+            ("ask_amount", "4567890"),
+            ("filled_offer_amount", "0"),
+            ("filled_ask_amount", "0"),
+            ("bidder_refund", &format!("1234567{}", token_addrs[0])),
+        ]
+    );
+
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 2,
+        asset_infos: [
+            AssetInfo::Token {
+                contract_addr: token_addrs[1].clone(),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(7000u128),
+            AssetInfo::Token {
+                contract_addr: token_addrs[0].clone(),
             },
         ],
+        refund_to: None,
     };
 
-    // offer orai, ask for atom
-    let _res = app
+    let res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 21 -----------------------------------> */
+    assert_eq!(
+        res.get_attributes(1),
+        vec![
+            ("action", "cancel_order"),
+            ("pair", "contract1 - contract0"),
+            ("order_id", "2"),
+            ("direction", "Sell"),
+            ("status", "Cancel"),
+            ("bidder_addr", "addr0000"),
+            ("offer_amount", "3333335"),
+            ("ask_amount", "1212121"),
+            ("filled_offer_amount", "0"),
+            ("filled_ask_amount", "0"),
+            ("bidder_refund", &format!("3333335{}", token_addrs[1])),
+        ]
+    );
+
+    // failed no order exists
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+}
+
+#[test]
+fn cancel_order_with_refund_recipient() {
+    let (mut app, limit_order_addr) = basic_fixture();
+
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(500000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(1000000u128),
             },
         ],
-    };
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    )
+    .unwrap();
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
+    let msg = ExecuteMsg::CancelOrder {
+        order_id: 1,
+        asset_infos: [
+            AssetInfo::NativeToken {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(2000u128),
-            }],
-        )
-        .unwrap();
-
-    /* <----------------------------------- order 22 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1500u128),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1200u128),
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
             },
         ],
+        refund_to: Some(Addr::unchecked("addr0002")),
     };
 
-    let _res = app
+    // only the bidder can redirect the refund
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let res = app
         .execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1200u128),
-            }],
+            &[],
         )
         .unwrap();
+    assert_eq!(res.get_attributes(1).last().unwrap().value, "addr0002");
 
-    /* <----------------------------------- order 23 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    // the unfilled escrow lands on the refund recipient, not the bidder
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0002"), USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(1000000u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(999000000u128)
+    );
+}
+
+#[test]
+fn cancel_all_orders_by_direction() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // 3 buys at 1 usdt and 2 sells at 2 usdt, nothing crosses
+    let orders = [
+        (OrderDirection::Buy, 1000u128, 1000u128),
+        (OrderDirection::Buy, 2000u128, 2000u128),
+        (OrderDirection::Sell, 1000u128, 2000u128),
+        (OrderDirection::Buy, 3000u128, 3000u128),
+        (OrderDirection::Sell, 2000u128, 4000u128),
+    ];
+    for (direction, base_amount, quote_amount) in orders {
+        let (denom, amount) = match direction {
+            OrderDirection::Buy => (USDT_DENOM, quote_amount),
+            OrderDirection::Sell => (ORAI_DENOM, base_amount),
+        };
+        let msg = ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(base_amount),
                 },
-                amount: Uint128::from(5000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(quote_amount),
                 },
-                amount: Uint128::from(10000u128),
-            },
-        ],
-    };
-
-    let _res = app
-        .execute(
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        };
+        app.execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+            &coins(amount, denom),
         )
         .unwrap();
+    }
 
-    /* <----------------------------------- order 24 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(7000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(6789u128),
-            },
-        ],
-    };
-
-    let _res = app
+    let res = app
         .execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
-            }],
+            &ExecuteMsg::CancelAllOrders {
+                asset_infos: asset_infos.clone(),
+                direction: Some(OrderDirection::Buy),
+            },
+            &[],
         )
         .unwrap();
-
-    /* <----------------------------------- order 25 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1500u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
+    let attrs = res.get_attributes(1);
+    let attr_value = |key: &str| {
+        attrs
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap()
     };
+    assert_eq!(attr_value("cancelled_count"), "3");
+    assert_eq!(attr_value("cancelled_orders"), "1,2,4");
+    assert_eq!(attr_value("base_refund"), format!("0{}", ORAI_DENOM));
+    assert_eq!(attr_value("quote_refund"), format!("6000{}", USDT_DENOM));
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    // the buy escrow is back in one refund, the sells stay in escrow
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(1000000000u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(999997000u128)
+    );
+
+    let res = app
+        .query::<OrdersResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
-            }],
+            &QueryMsg::Orders {
+                asset_infos,
+                filter: OrderFilter::Bidder("addr0000".to_string()),
+                direction: None,
+                start_after: None,
+                limit: None,
+                order_by: Some(1),
+            },
         )
         .unwrap();
+    assert_eq!(res.orders.len(), 2);
+    assert!(res
+        .orders
+        .iter()
+        .all(|order| order.direction == OrderDirection::Sell));
+}
 
-    /* <----------------------------------- order 26 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+#[test]
+fn cancel_partially_filled_order_logs_filled_amounts() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // buy 1000 orai, only 400 get sold into it
+    let orders = [
+        ("addr0000", OrderDirection::Buy, 1000u128, 1000u128),
+        ("addr0001", OrderDirection::Sell, 400u128, 400u128),
+    ];
+    for (sender, direction, base_amount, quote_amount) in orders {
+        let (denom, amount) = match direction {
+            OrderDirection::Buy => (USDT_DENOM, quote_amount),
+            OrderDirection::Sell => (ORAI_DENOM, base_amount),
+        };
+        let msg = ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(base_amount),
                 },
-                amount: Uint128::from(1600u128),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(quote_amount),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        };
+        app.execute(
+            Addr::unchecked(sender),
+            limit_order_addr.clone(),
+            &msg,
+            &coins(amount, denom),
+        )
+        .unwrap();
+    }
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let order = app
+        .query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 1,
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::PartialFilled);
+    assert!(order.filled_ask_amount < order.ask_asset.amount);
+
+    let res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &ExecuteMsg::CancelOrder {
+                order_id: 1,
+                asset_infos,
+                refund_to: None,
             },
+            &[],
+        )
+        .unwrap();
+    let attrs = res.get_attributes(1);
+    let attr_value = |key: &str| {
+        attrs
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap()
+    };
+
+    // the log reports what was really filled, not the full ask
+    assert_eq!(attr_value("ask_amount"), "1000");
+    assert_eq!(
+        attr_value("filled_ask_amount"),
+        order.filled_ask_amount.to_string()
+    );
+    assert_eq!(
+        attr_value("filled_offer_amount"),
+        order.filled_offer_amount.to_string()
+    );
+    assert_eq!(
+        attr_value("bidder_refund"),
+        format!(
+            "{}{}",
+            order.offer_asset.amount - order.filled_offer_amount,
+            USDT_DENOM
+        )
+    );
+}
+
+#[test]
+fn admin_cancel_order() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000u128),
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(2000000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &msg,
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::AdminCancelOrder {
+        asset_infos: asset_infos.clone(),
+        order_id: 1,
     };
 
-    let _res = app
+    // even the bidder can not use the admin path
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let res = app
         .execute(
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
-            }],
+            &[],
         )
         .unwrap();
+    let attrs = res.get_attributes(1);
+    assert_eq!(attrs[0].value, "admin_cancel_order");
+    assert_eq!(attrs.last().unwrap().value, "addr0000");
 
-    /* <----------------------------------- order 27 -----------------------------------> */
+    // the bidder gets the whole escrow back
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0001"), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(1000000000u128)
+    );
+
+    let res = app.query::<OrderResponse, _>(
+        limit_order_addr.clone(),
+        &QueryMsg::Order {
+            order_id: 1,
+            asset_infos,
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn execute_pair_native_token() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0002".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, usdt] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 1 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(10000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 28 -----------------------------------> */
+    /* <----------------------------------- order 2 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1600u128),
+                amount: Uint128::from(9700u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(10000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -2357,191 +3067,189 @@ fn execute_pair_native_token() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1600u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 29 -----------------------------------> */
+    /* <----------------------------------- order 3 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(13000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(14000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0001"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(2000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(13000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 30 -----------------------------------> */
+    /* <----------------------------------- order 4 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(5000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1200u128),
+                amount: Uint128::from(10000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
+    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0002"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1200u128),
+                amount: Uint128::from(5000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 31 -----------------------------------> */
+    /* <----------------------------------- order 5 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(4400u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1200u128),
+                amount: Uint128::from(8800u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0002"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(1200u128),
+                amount: Uint128::from(4400u128),
             }],
         )
         .unwrap();
 
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    let mut reward_balances = app
-        .query_all_balances(Addr::unchecked(
-            "orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en",
-        ))
-        .unwrap();
-
-    println!("round 0 - address0's balances: {:?}", address0_balances);
-    println!("round 0 - address1's balances: {:?}", address1_balances);
-    println!("round 0 - address2's balances: {:?}", address2_balances);
-    println!(
-        "round 0 - reward_balances's balances: {:?}",
-        reward_balances
-    );
-
-    let mut expected_balances: Vec<Coin> = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(960000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(971200u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(973800u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(960000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(981200u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances,);
-
-    // assertion; native asset balance
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 6 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(7000u128),
             },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(14000u128),
             },
         ],
-        limit: None,
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // Native token balance mismatch between the argument and the transferred
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
+    // offer orai, ask for atom
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(7000u128),
+            }],
+        )
+        .unwrap();
 
-    // Excecute all orders
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 7 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(2000u128),
             },
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(2000u128),
             },
         ],
-        limit: Some(10),
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -2549,159 +3257,90 @@ fn execute_pair_native_token() {
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(2000u128),
+            }],
         )
         .unwrap();
-    println!("[LOG] attribute - round 1 - {:?}", _res);
-
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    reward_balances = app
-        .query_all_balances(Addr::unchecked(
-            "orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en",
-        ))
-        .unwrap();
-
-    println!("round 1 - address0's balances: {:?}", address0_balances);
-    println!("round 1 - address1's balances: {:?}", address1_balances);
-    println!("round 1 - address2's balances: {:?}", address2_balances);
-    println!(
-        "round 1 - reward_balances's balances: {:?}",
-        reward_balances
-    );
 
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(969390u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(984184u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(973800u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(965356u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(981200u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances);
+    /* <----------------------------------- order 8 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1500u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(1200u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
 
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-            },
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1200u128),
+            }],
         )
         .unwrap();
 
-    println!("[LOG] orderbook matchable: {}", jsonstr!(res));
-}
+    /* <----------------------------------- order 9 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(5000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
 
-#[test]
-fn execute_pair_cw20_token() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000000u128),
-            }],
-        ),
-        (
-            &"addr0001".to_string(),
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
             }],
-        ),
-        (
-            &"addr0002".to_string(),
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000000u128),
-            }],
-        ),
-    ]);
-
-    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
-
-    let token_addrs = app.set_token_balances(&[(
-        &"usdt".to_string(),
-        &[
-            (&"addr0000".to_string(), &Uint128::from(1000000u128)),
-            (&"addr0001".to_string(), &Uint128::from(1000000u128)),
-            (&"addr0002".to_string(), &Uint128::from(1000000u128)),
-        ],
-    )]);
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
         )
         .unwrap();
 
-    // Create pair [orai, token_addrs[0]] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[0].clone(),
-        },
-        spread: None,
-        min_quote_coin_amount: Uint128::from(10u128),
-    };
-
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    /* <----------------------------------- order 1 -----------------------------------> */
+    /* <----------------------------------- order 10 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -2709,15 +3348,22 @@ fn execute_pair_cw20_token() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(7000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(6789u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -2727,175 +3373,126 @@ fn execute_pair_cw20_token() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(7000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
+    /* <----------------------------------- order 11 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1500u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(9700u128),
+                amount: Uint128::from(1000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 3 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(13000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(13000u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(13000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let _res = app.execute(
-        Addr::unchecked("addr0001"),
-        token_addrs[0].clone(),
-        &msg,
-        &[],
-    );
-
-    /* <----------------------------------- order 4 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(5000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(10000u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(5000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    // offer usdt, ask for orai
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-
-    /* <----------------------------------- order 5 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(4400u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(8800u128),
+    /* <----------------------------------- order 12 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(4400u128),
+                amount: Uint128::from(1600u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 6 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(7000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(14000u128),
+    /* <----------------------------------- order 13 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(7000u128),
+                amount: Uint128::from(1500u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer orai, ask for usdt
     let _res = app
         .execute(
             Addr::unchecked("addr0001"),
-            token_addrs[0].clone(),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1500u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 7 -----------------------------------> */
+    /* <----------------------------------- order 14 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -2903,224 +3500,267 @@ fn execute_pair_cw20_token() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(1600u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(1000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0001"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(2000u128),
+                amount: Uint128::from(1600u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 8 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1200u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1500u128),
+    /* <----------------------------------- order 15 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1200u128),
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(10000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
             Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 9 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(10000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(5000u128),
+    /* <----------------------------------- order 16 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(10000u128),
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(9700u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 10 -----------------------------------> */
+    /* <----------------------------------- order 17 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(7000u128),
+                amount: Uint128::from(14000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(6789u128),
+                amount: Uint128::from(13000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0001"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(13000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 11 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1500u128),
+    /* <----------------------------------- order 18 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1000u128),
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(5000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
+    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(5000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 12 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1600u128),
+    /* <----------------------------------- order 19 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1000u128),
+                amount: Uint128::from(8800u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(4400u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(4400u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 13 -----------------------------------> */
+    /* <----------------------------------- order 20 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1500u128),
+                amount: Uint128::from(14000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(7000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
+    // offer orai, ask for atom
     let _res = app
         .execute(
             Addr::unchecked("addr0001"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1500u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(7000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 14 -----------------------------------> */
+    /* <----------------------------------- order 21 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -3128,46 +3768,60 @@ fn execute_pair_cw20_token() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1600u128),
+                amount: Uint128::from(2000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(2000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1600u128),
+                amount: Uint128::from(2000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 15 -----------------------------------> */
+    /* <----------------------------------- order 22 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1500u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1200u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -3176,178 +3830,242 @@ fn execute_pair_cw20_token() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1200u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 16 -----------------------------------> */
+    /* <----------------------------------- order 23 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(5000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(9700u128),
+                amount: Uint128::from(10000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
+    // placed by addr0002, from addr0000 it would be a skipped self-trade with sell order 10
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0002"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
+                denom: USDT_DENOM.to_string(),
                 amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 17 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(13000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(14000u128),
+    /* <----------------------------------- order 24 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(13000u128),
+                amount: Uint128::from(7000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(6789u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(7000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 18 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(5000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(10000u128),
+    /* <----------------------------------- order 25 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(5000u128),
+                amount: Uint128::from(1500u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 19 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(4400u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(8800u128),
+    /* <----------------------------------- order 26 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(4400u128),
+                amount: Uint128::from(1600u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
-            token_addrs[0].clone(),
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 20 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(7000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(14000u128),
+    /* <----------------------------------- order 27 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(7000u128),
+                amount: Uint128::from(1500u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer cw20 usdt, ask for orai
     let _res = app
         .execute(
             Addr::unchecked("addr0001"),
-            token_addrs[0].clone(),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1500u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 21 -----------------------------------> */
+    /* <----------------------------------- order 28 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1600u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1600u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 29 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -3358,12 +4076,19 @@ fn execute_pair_cw20_token() {
                 amount: Uint128::from(2000u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
                 amount: Uint128::from(2000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -3378,89 +4103,68 @@ fn execute_pair_cw20_token() {
         )
         .unwrap();
 
-    /* <----------------------------------- order 22 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1200u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1500u128),
+    /* <----------------------------------- order 30 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
                 },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1200u128),
+                amount: Uint128::from(1500u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-            ],
-        })
-        .unwrap(),
+                amount: Uint128::from(1200u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
             Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
+            limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1200u128),
+            }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 23 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(10000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(5000u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(10000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
-
-    /* <----------------------------------- order 24 -----------------------------------> */
+    /* <----------------------------------- order 31 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(7000u128),
+                amount: Uint128::from(1500u128),
             },
             Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(6789u128),
+                amount: Uint128::from(1200u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -3469,157 +4173,101 @@ fn execute_pair_cw20_token() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(7000u128),
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1200u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 25 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1500u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
-        )
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    let mut reward_balances = app
+        .query_all_balances(Addr::unchecked(
+            "orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en",
+        ))
         .unwrap();
 
-    /* <----------------------------------- order 26 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    amount: Uint128::from(1600u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(1000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
-        )
-        .unwrap();
+    println!("round 0 - address0's balances: {:?}", address0_balances);
+    println!("round 0 - address1's balances: {:?}", address1_balances);
+    println!("round 0 - address2's balances: {:?}", address2_balances);
+    println!(
+        "round 0 - reward_balances's balances: {:?}",
+        reward_balances
+    );
 
-    /* <----------------------------------- order 27 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1500u128),
-            },
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
-                },
-                amount: Uint128::from(1000u128),
-            },
-        ],
-    };
+    let mut expected_balances: Vec<Coin> = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(960000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(981200u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(973800u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(960000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(971200u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances,);
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
+    // assertion; native asset balance
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1500u128),
-            }],
-        )
-        .unwrap();
-
-    /* <----------------------------------- order 28 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1600u128),
             },
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
-                },
-                amount: Uint128::from(1000u128),
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
         ],
+        limit: None,
+        best_effort: None,
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1600u128),
-            }],
-        )
-        .unwrap();
+    // Native token balance mismatch between the argument and the transferred
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
 
-    /* <----------------------------------- order 29 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(2000u128),
+    // Excecute all orders
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
             },
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_addrs[0].clone(),
-                },
-                amount: Uint128::from(2000u128),
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
             },
         ],
+        limit: None,
+        best_effort: None,
     };
 
     let _res = app
@@ -3627,17 +4275,242 @@ fn execute_pair_cw20_token() {
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(2000u128),
-            }],
+            &[],
         )
         .unwrap();
+    println!("[LOG] attribute - round 1 - {:?}", _res);
 
-    /* <----------------------------------- order 30 -----------------------------------> */
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1200u128),
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    reward_balances = app
+        .query_all_balances(Addr::unchecked(
+            "orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en",
+        ))
+        .unwrap();
+
+    println!("round 1 - address0's balances: {:?}", address0_balances);
+    println!("round 1 - address1's balances: {:?}", address1_balances);
+    println!("round 1 - address2's balances: {:?}", address2_balances);
+    println!(
+        "round 1 - reward_balances's balances: {:?}",
+        reward_balances
+    );
+
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(964695u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(994184u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(973800u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(965356u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1004695u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(971200u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances);
+
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    println!("[LOG] orderbook matchable: {}", jsonstr!(res));
+}
+
+#[test]
+fn execute_pair_cw20_token() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+        (
+            &"addr0002".to_string(),
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+    ]);
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let token_addrs = app.set_token_balances(&[(
+        &"usdt".to_string(),
+        &[
+            (&"addr0000".to_string(), &Uint128::from(1000000u128)),
+            (&"addr0001".to_string(), &Uint128::from(1000000u128)),
+            (&"addr0002".to_string(), &Uint128::from(1000000u128)),
+        ],
+    )]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, token_addrs[0]] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[0].clone(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
+                },
+                amount: Uint128::from(9700u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 3 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(13000u128),
         msg: to_binary(&Cw20HookMsg::SubmitOrder {
             direction: OrderDirection::Buy,
             assets: [
@@ -3645,32 +4518,83 @@ fn execute_pair_cw20_token() {
                     info: AssetInfo::NativeToken {
                         denom: ORAI_DENOM.to_string(),
                     },
-                    amount: Uint128::from(1500u128),
+                    amount: Uint128::from(13000u128),
                 },
                 Asset {
                     info: AssetInfo::Token {
                         contract_addr: token_addrs[0].clone(),
                     },
-                    amount: Uint128::from(1200u128),
+                    amount: Uint128::from(13000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    // placed by addr0002, from addr0001 it would be a skipped self-trade with sell order 2
+    let _res = app.execute(
+        Addr::unchecked("addr0002"),
+        token_addrs[0].clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 4 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(5000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(10000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(5000u128),
                 },
             ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
         })
         .unwrap(),
     };
 
+    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0002"),
             token_addrs[0].clone(),
             &msg,
             &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 31 -----------------------------------> */
+    /* <----------------------------------- order 5 -----------------------------------> */
     let msg = cw20::Cw20ExecuteMsg::Send {
         contract: limit_order_addr.to_string(),
-        amount: Uint128::new(1200u128),
+        amount: Uint128::new(4400u128),
         msg: to_binary(&Cw20HookMsg::SubmitOrder {
             direction: OrderDirection::Buy,
             assets: [
@@ -3678,210 +4602,81 @@ fn execute_pair_cw20_token() {
                     info: AssetInfo::NativeToken {
                         denom: ORAI_DENOM.to_string(),
                     },
-                    amount: Uint128::from(1500u128),
+                    amount: Uint128::from(8800u128),
                 },
                 Asset {
                     info: AssetInfo::Token {
                         contract_addr: token_addrs[0].clone(),
                     },
-                    amount: Uint128::from(1200u128),
+                    amount: Uint128::from(4400u128),
                 },
             ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
         })
         .unwrap(),
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
+            Addr::unchecked("addr0002"),
             token_addrs[0].clone(),
             &msg,
             &[],
         )
         .unwrap();
 
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 0 - address0's balances: {:?}", address0_balances);
-    println!("round 0 - address1's balances: {:?}", address1_balances);
-    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
-
-    let mut expected_balances: Vec<Coin> = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(960000u128),
-    }]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(973800u128),
-    }]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(1000000u128),
-    }]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances,);
-
-    // assertion; native asset balance
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        ],
-        limit: None,
-    };
-
-    // Native token balance mismatch between the argument and the transferred
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
-
-    // Excecute all orders
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-            AssetInfo::Token {
-                contract_addr: token_addrs[0].clone(),
-            },
-        ],
-        limit: None,
-    };
-
-    let _ = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 1 - address0's balances: {:?}", address0_balances);
-    println!("round 1 - address1's balances: {:?}", address1_balances);
-    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
-
-    expected_balances = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(969390u128),
-    }]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(986487u128),
-    }]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [Coin {
-        denom: ORAI_DENOM.to_string(),
-        amount: Uint128::from(1000000u128),
-    }]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances,);
-}
-
-/// Test for spread parameter of orderbook pair
-/// Example: If pair ORAI/USDT has spread = 10%,
-/// it mean matching engine will not match orders if buy_price <= (sell_price*(1 + 10%))
-/// Therefore, we need to find the highest suitable buy price and lowest suitable sell price
-/// Not the Highest and Lowest price in orderbook
-#[test]
-fn spread_test() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-            ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-            ],
-        ),
-        (
-            &"addr0002".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+    /* <----------------------------------- order 6 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(7000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(14000u128),
                 },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(7000u128),
                 },
             ],
-        ),
-    ]);
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
+
+    // offer orai, ask for usdt
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            token_addrs[0].clone(),
             &msg,
             &[],
-            "limit order",
         )
         .unwrap();
 
-    // Create pair [orai, usdt] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
-            denom: USDT_DENOM.to_string(),
-        },
-        spread: Some(Decimal::percent(10)),
-        min_quote_coin_amount: Uint128::from(10u128),
-    };
-
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    /* <----------------------------------- order 1 -----------------------------------> */
+    /* <----------------------------------- order 7 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -3889,15 +4684,22 @@ fn spread_test() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(2000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(20000u128),
+                amount: Uint128::from(2000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -3907,74 +4709,218 @@ fn spread_test() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(2000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 8 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1200u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
                 },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1200u128),
                 },
-                amount: Uint128::from(30000u128),
-            },
-        ],
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
     let _res = app
         .execute(
             Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
+            token_addrs[0].clone(),
             &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 3 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
+    /* <----------------------------------- order 9 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(10000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(5000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(10000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 10 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(7000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(15000u128),
+                amount: Uint128::from(6789u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(7000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 4 -----------------------------------> */
+    /* <----------------------------------- order 11 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 12 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1600u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 13 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -3982,15 +4928,22 @@ fn spread_test() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1500u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(41000u128),
+                amount: Uint128::from(1000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4000,12 +4953,12 @@ fn spread_test() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1500u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 5 -----------------------------------> */
+    /* <----------------------------------- order 14 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -4013,15 +4966,22 @@ fn spread_test() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1600u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(19000u128),
+                amount: Uint128::from(1000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4031,14 +4991,14 @@ fn spread_test() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1600u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 6 -----------------------------------> */
+    /* <----------------------------------- order 15 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
@@ -4047,44 +5007,57 @@ fn spread_test() {
                 amount: Uint128::from(10000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(44800u128),
+                amount: Uint128::from(10000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(44800u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 7 -----------------------------------> */
+    /* <----------------------------------- order 16 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(14000u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(28100u128),
+                amount: Uint128::from(9700u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4093,263 +5066,206 @@ fn spread_test() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(28100u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 8 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 17 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(13000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(14000u128),
                 },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(13000u128),
                 },
-                amount: Uint128::from(50000u128),
-            },
-        ],
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 18 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(5000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(10000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(5000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
     // offer usdt, ask for orai
     let _res = app
         .execute(
             Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
+            token_addrs[0].clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(50000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 0 - address0's balances: {:?}", address0_balances);
-    println!("round 0 - address1's balances: {:?}", address1_balances);
-    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
-
-    let mut expected_balances: Vec<Coin> = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(980000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(970000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(971900u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(905200u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances,);
-
-    // assertion; native asset balance
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        ],
-        limit: None,
-    };
-
-    // Native token balance mismatch between the argument and the transferred
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
-
-    // Excecute all orders
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
-            },
-        ],
-        limit: None,
+    /* <----------------------------------- order 19 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(4400u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(8800u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(4400u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
+            Addr::unchecked("addr0002"),
+            token_addrs[0].clone(),
             &msg,
             &[],
         )
         .unwrap();
-    println!("[LOG] attribute - round 1 - {:?}", _res);
-
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 1 - address0's balances: {:?}", address0_balances);
-    println!("round 1 - address1's balances: {:?}", address1_balances);
-    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
-
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(980000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1019380u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(979690u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1004846u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1019380u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(905200u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances,);
-}
 
-#[test]
-fn reward_to_executor_test() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-            ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
+    /* <----------------------------------- order 20 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(7000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(14000u128),
                 },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(7000u128),
                 },
             ],
-        ),
-    ]);
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
+
+    // offer cw20 usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            token_addrs[0].clone(),
             &msg,
             &[],
-            "limit order",
         )
         .unwrap();
 
-    // Create pair [orai, usdt] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
-            denom: USDT_DENOM.to_string(),
-        },
-        spread: Some(Decimal::percent(10)),
-        min_quote_coin_amount: Uint128::from(10000u128),
-    };
-
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    /* <----------------------------------- order 1 -----------------------------------> */
+    /* <----------------------------------- order 21 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(103000u128),
+                amount: Uint128::from(2000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(618000u128),
+                amount: Uint128::from(2000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4358,75 +5274,220 @@ fn reward_to_executor_test() {
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(103000u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(2000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(610000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+    /* <----------------------------------- order 22 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1200u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
                 },
-                amount: Uint128::from(100000u128),
-            },
-        ],
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1200u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
     };
 
     let _res = app
         .execute(
             Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
+            token_addrs[0].clone(),
             &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(100000u128),
-            }],
+            &[],
         )
         .unwrap();
 
-    /* <----------------------------------- order 3 -----------------------------------> */
+    /* <----------------------------------- order 23 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(10000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(5000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(10000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    // placed by addr0002, from addr0000 it would be a skipped self-trade with sell order 10
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 24 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100000u128),
+                amount: Uint128::from(7000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(600000u128),
+                amount: Uint128::from(6789u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(600000u128),
+                amount: Uint128::from(7000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 4 -----------------------------------> */
+    /* <----------------------------------- order 25 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 26 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1600u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 27 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -4434,15 +5495,22 @@ fn reward_to_executor_test() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(610000u128),
+                amount: Uint128::from(1500u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
                 },
-                amount: Uint128::from(100000u128),
+                amount: Uint128::from(1000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4452,74 +5520,73 @@ fn reward_to_executor_test() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(610000u128),
+                amount: Uint128::from(1500u128),
             }],
         )
         .unwrap();
 
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    println!("round 0 - address0's balances: {:?}", address0_balances);
-    println!("round 0 - address1's balances: {:?}\n\n", address1_balances);
-
-    let mut expected_balances: Vec<Coin> = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(999797000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(998790000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-
-    // assertion; native asset balance
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 28 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1600u128),
             },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
+                },
+                amount: Uint128::from(1000u128),
             },
         ],
-        limit: None,
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // Native token balance mismatch between the argument and the transferred
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
-
-    // Excecute all orders
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
                 denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1600u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 29 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(2000u128),
             },
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_addrs[0].clone(),
+                },
+                amount: Uint128::from(2000u128),
             },
         ],
-        limit: None,
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4527,55 +5594,212 @@ fn reward_to_executor_test() {
             Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
-            &[],
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(2000u128),
+            }],
         )
         .unwrap();
-    println!("[LOG] attribute - round 1 - {:?}", _res);
+
+    /* <----------------------------------- order 30 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1200u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1200u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 31 -----------------------------------> */
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::new(1200u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1500u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1200u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    println!("round 0 - address0's balances: {:?}", address0_balances);
+    println!("round 0 - address1's balances: {:?}", address1_balances);
+    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
+
+    let mut expected_balances: Vec<Coin> = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(960000u128),
+    }]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(973800u128),
+    }]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(1000000u128),
+    }]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances,);
+
+    // assertion; native asset balance
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    // Native token balance mismatch between the argument and the transferred
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    // Excecute all orders
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: token_addrs[0].clone(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    let _ = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
 
     address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
     address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
     println!("round 1 - address0's balances: {:?}", address0_balances);
-    println!("round 1 - address1's balances: {:?}\n\n", address1_balances);
+    println!("round 1 - address1's balances: {:?}", address1_balances);
+    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
 
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000617082u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(999797000u128),
-        },
-    ]
+    expected_balances = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(964695u128),
+    }]
     .to_vec();
     assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(998790000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(1000102799u128),
-        },
-    ]
+    expected_balances = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(973800u128),
+    }]
     .to_vec();
     assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [Coin {
+        denom: ORAI_DENOM.to_string(),
+        amount: Uint128::from(1017382u128),
+    }]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances,);
 }
 
+/// Test for spread parameter of orderbook pair
+/// Example: If pair ORAI/USDT has spread = 10%,
+/// it mean matching engine will not match orders if buy_price <= (sell_price*(1 + 10%))
+/// Therefore, we need to find the highest suitable buy price and lowest suitable sell price
+/// Not the Highest and Lowest price in orderbook
 #[test]
-fn simple_matching_test() {
+fn spread_test() {
     let mut app = MockApp::new(&[
         (
             &"addr0000".to_string(),
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
                 Coin {
                     denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
             ],
         ),
@@ -4584,11 +5808,11 @@ fn simple_matching_test() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
                 Coin {
                     denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
             ],
         ),
@@ -4597,86 +5821,3233 @@ fn simple_matching_test() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
                 Coin {
                     denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(10000000000u128),
+                    amount: Uint128::from(1000000u128),
                 },
             ],
         ),
     ]);
 
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, usdt] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: Some(Decimal::percent(10)),
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(20000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(30000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 3 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(15000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 4 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(41000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 5 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(19000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 6 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(44800u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(44800u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 7 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(14000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(28100u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(28100u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 8 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(50000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(50000u128),
+            }],
+        )
+        .unwrap();
+
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    println!("round 0 - address0's balances: {:?}", address0_balances);
+    println!("round 0 - address1's balances: {:?}", address1_balances);
+    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
+
+    let mut expected_balances: Vec<Coin> = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(980000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(970000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(971900u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(905200u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances,);
+
+    // assertion; native asset balance
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    // Native token balance mismatch between the argument and the transferred
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    // Excecute all orders
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    println!("[LOG] attribute - round 1 - {:?}", _res);
+
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    println!("round 1 - address0's balances: {:?}", address0_balances);
+    println!("round 1 - address1's balances: {:?}", address1_balances);
+    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
+
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(980000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1019380u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(979690u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1004846u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1019380u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(905200u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances,);
+}
+
+#[test]
+fn reward_to_executor_test() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, usdt] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: Some(Decimal::percent(10)),
+        min_quote_coin_amount: Uint128::from(10000u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(103000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(618000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(103000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(610000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(100000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(100000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 3 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(100000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(600000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(600000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 4 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(610000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(100000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(610000u128),
+            }],
+        )
+        .unwrap();
+
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    println!("round 0 - address0's balances: {:?}", address0_balances);
+    println!("round 0 - address1's balances: {:?}\n\n", address1_balances);
+
+    let mut expected_balances: Vec<Coin> = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(999797000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(998790000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+
+    // assertion; native asset balance
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    // Native token balance mismatch between the argument and the transferred
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    // Excecute all orders
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    println!("[LOG] attribute - round 1 - {:?}", _res);
+
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    println!("round 1 - address0's balances: {:?}", address0_balances);
+    println!("round 1 - address1's balances: {:?}\n\n", address1_balances);
+
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000617082u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(999797000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(998790000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(1000102799u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+}
+
+#[test]
+fn min_commission_policy() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // 500 * 0.001 rounds down to zero commission on both sides
+    let assets = [
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(500u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(500u128),
+        },
+    ];
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: assets.clone(),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(500u128),
+        }],
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(500u128),
+        }],
+    )
+    .unwrap();
+
+    // only admin can change the policy
+    let update_msg = ExecuteMsg::UpdateConfig {
+        reward_address: None,
+        commission_rate: None,
+        min_commission: Some(MinCommission {
+            unit: Uint128::one(),
+            policy: MinCommissionPolicy::Skip,
+        }),
+        auto_distribute_threshold: None,
+        max_orders_per_match: None,
+        oracle_addr: None,
+        dust_threshold: None,
+        stp_cancel_newest: None,
+        min_commission_rate: None,
+        max_commission_rate: None,
+    };
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &update_msg,
+        &[],
+    );
+    app.assert_fail(res);
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &update_msg,
+        &[],
+    )
+    .unwrap();
+
+    let execute_msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: asset_infos.clone(),
+        limit: None,
+        best_effort: None,
+    };
+
+    // skip policy leaves both orders untouched
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &execute_msg,
+        &[],
+    )
+    .unwrap();
+    for order_id in [1u64, 2u64] {
+        let order = app
+            .query::<OrderResponse, _>(
+                limit_order_addr.clone(),
+                &QueryMsg::Order {
+                    order_id,
+                    asset_infos: asset_infos.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(order.status, OrderStatus::Open);
+        assert_eq!(order.filled_offer_amount, Uint128::zero());
+    }
+
+    // round up policy charges one unit on each side
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: Some(MinCommission {
+                unit: Uint128::one(),
+                policy: MinCommissionPolicy::RoundUp,
+            }),
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: None,
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &execute_msg,
+            &[],
+        )
+        .unwrap();
+    let reward_fees: Vec<String> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-matched_order")
+        .flat_map(|event| event.attributes.iter())
+        .filter(|attr| attr.key == "reward_fee")
+        .map(|attr| attr.value.clone())
+        .collect();
+    assert_eq!(reward_fees, vec!["1".to_string(), "1".to_string()]);
+}
+
+#[test]
+fn round_up_commission_capped_by_fill() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let assets = [
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(500u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(500u128),
+        },
+    ];
+    for (bidder, direction, denom) in [
+        ("addr0000", OrderDirection::Sell, ORAI_DENOM),
+        ("addr0001", OrderDirection::Buy, USDT_DENOM),
+    ] {
+        app.execute(
+            Addr::unchecked(bidder),
+            limit_order_addr.clone(),
+            &ExecuteMsg::SubmitOrder {
+                direction,
+                assets: assets.clone(),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &[Coin {
+                denom: denom.to_string(),
+                amount: Uint128::from(500u128),
+            }],
+        )
+        .unwrap();
+    }
+
+    // a unit of 400 plus the relayer fee of 300 would be more than the 500 filled
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: Some(MinCommission {
+                unit: Uint128::from(400u128),
+                policy: MinCommissionPolicy::RoundUp,
+            }),
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: None,
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &ExecuteMsg::ExecuteOrderBookPair {
+                asset_infos: asset_infos.clone(),
+                limit: None,
+                best_effort: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // the reward fee only gets what the relayer fee leaves of the fill
+    let fees: Vec<(String, String)> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-matched_order")
+        .map(|event| {
+            let fee = |key: &str| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            (fee("reward_fee"), fee("relayer_fee"))
+        })
+        .collect();
+    assert_eq!(
+        fees,
+        vec![
+            ("200".to_string(), "300".to_string()),
+            ("200".to_string(), "300".to_string())
+        ]
+    );
+
+    // both sides are credited exactly the 500 they filled
+    let executor_reward = |executor: &str| {
+        app.query::<ExecutorResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::ExecutorReward {
+                asset_infos: asset_infos.clone(),
+                executor: Addr::unchecked(executor),
+            },
+        )
+        .unwrap()
+        .reward_assets
+    };
+    let reward = executor_reward("orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en");
+    let relayer = executor_reward("addr0000");
+    for i in 0..2 {
+        assert_eq!(reward[i].amount + relayer[i].amount, Uint128::from(500u128));
+    }
+}
+
+#[test]
+fn auto_distribute_executor_reward() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let reward_wallet = Addr::unchecked("orai16stq6f4pnrfpz75n9ujv6qg3czcfa4qyjux5en");
+
+    // each round accrues 1000 orai and 2000 usdt of commission for the reward wallet
+    let match_round = |app: &mut MockApp| {
+        let assets = [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(2000000u128),
+            },
+        ];
+        app.execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: assets.clone(),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        )
+        .unwrap();
+        app.execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Buy,
+                assets,
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(2000000u128),
+            }],
+        )
+        .unwrap();
+        app.execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &ExecuteMsg::ExecuteOrderBookPair {
+                asset_infos: asset_infos.clone(),
+                limit: None,
+                best_effort: None,
+            },
+            &[],
+        )
+        .unwrap()
+    };
+
+    // below the default threshold the reward stays accrued
+    match_round(&mut app);
+    assert_eq!(
+        app.query_balance(reward_wallet.clone(), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::zero()
+    );
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: Some(Uint128::from(1500u128)),
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: None,
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // crossing the threshold pays the whole accrued reward within the match transaction
+    match_round(&mut app);
+    assert_eq!(
+        app.query_balance(reward_wallet.clone(), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(2000u128)
+    );
+    assert_eq!(
+        app.query_balance(reward_wallet, USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(4000u128)
+    );
+
+    // the relayer accrued 600 orai and 1200 usdt, still below the threshold
+    assert_eq!(
+        app.query_all_balances(Addr::unchecked("addr0002")).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn executor_allowlist() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let execute_msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+    let update_msg = ExecuteMsg::UpdateExecutors {
+        add: vec![Addr::unchecked("addr0002")],
+        remove: vec![],
+    };
+
+    // only admin can update the allowlist
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &update_msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    // default open
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &execute_msg,
+        &[],
+    )
+    .unwrap();
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &update_msg,
+        &[],
+    )
+    .unwrap();
+
+    // non listed executor is rejected, listed one succeeds
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &execute_msg,
+        &[],
+    );
+    app.assert_fail(res);
+    app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &execute_msg,
+        &[],
+    )
+    .unwrap();
+
+    // emptying the allowlist opens matching again
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateExecutors {
+            add: vec![],
+            remove: vec![Addr::unchecked("addr0002")],
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &execute_msg,
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn match_orders_result_matches_response() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // build the same order book twice, one for the library call and one for the entry point
+    let setup = || {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            InstantiateMsg {
+                name: None,
+                version: None,
+                admin: None,
+                commission_rate: None,
+                reward_address: None,
+                maker_commission_rate: None,
+                taker_commission_rate: None,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::CreateOrderBookPair {
+                base_coin_info: asset_infos[0].clone(),
+                quote_coin_info: asset_infos[1].clone(),
+                spread: None,
+                min_quote_coin_amount: Uint128::from(10u128),
+                commission_rate: None,
+                precision: None,
+            },
+        )
+        .unwrap();
+        let assets = [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(2000000u128),
+            },
+        ];
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+            ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: assets.clone(),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0001", &coins(2000000u128, USDT_DENOM)),
+            ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Buy,
+                assets,
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+        )
+        .unwrap();
+        deps
+    };
+
+    let mut deps = setup();
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(result.fills.len(), 2);
+    assert_eq!(
+        result.executor_reward,
+        [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(2000u128),
+            },
+        ]
+    );
+
+    let mut deps = setup();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos,
+            limit: None,
+            best_effort: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages
+            .iter()
+            .map(|sub_msg| sub_msg.msg.clone())
+            .collect::<Vec<_>>(),
+        result.messages
+    );
+    assert_eq!(
+        res.attributes[2],
+        attr("total_matched_orders", result.fills.len().to_string())
+    );
+    assert_eq!(
+        res.attributes[3],
+        attr(
+            "executor_reward",
+            result
+                .executor_reward
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    );
+    let matched_events: Vec<_> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "matched_order")
+        .collect();
+    assert_eq!(matched_events.len(), result.fills.len());
+    for (event, fill) in matched_events.into_iter().zip(result.fills.iter()) {
+        assert!(event
+            .attributes
+            .contains(&attr("order_id", fill.order_id.to_string())));
+        assert!(event
+            .attributes
+            .contains(&attr("bidder_addr", fill.bidder_addr.to_string())));
+        assert!(event
+            .attributes
+            .contains(&attr("filled_ask_amount", fill.filled_ask_amount)));
+        assert!(event
+            .attributes
+            .contains(&attr("reward_fee", fill.reward_fee)));
+    }
+}
+
+#[test]
+fn simple_matching_test() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0002".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(10000000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, usdt] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: Some(Decimal::percent(1)),
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 0 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(261500000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(261500000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(75000000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(261500000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(261500000u128),
+            }],
+        )
+        .unwrap();
+
+    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    println!("round 0 - address0's balances: {:?}", address0_balances);
+    println!("round 0 - address1's balances: {:?}", address1_balances);
+    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
+
+    let mut expected_balances: Vec<Coin> = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(9990000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(10000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(10000000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address1_balances, expected_balances,);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10000000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(9477000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances);
+
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    // Native token balance mismatch between the argument and the transferred
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert!(res.is_matchable);
+
+    // Excecute all orders
+    let msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        ],
+        limit: None,
+        best_effort: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    println!("[LOG] attribute - round 1 - {:?}", _res);
+
+    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
+    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
+    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
+    println!("round 1 - address0's balances: {:?}", address0_balances);
+    println!("round 1 - address1's balances: {:?}", address1_balances);
+    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
+
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(9990000000u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(10074922750u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address0_balances, expected_balances);
+    expected_balances = [
+        Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10001997400u128),
+        },
+        Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(9477000000u128),
+        },
+    ]
+    .to_vec();
+    assert_eq!(address2_balances, expected_balances);
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert!(!res.is_matchable);
+}
+
+fn mock_basic_query_data() -> (MockApp, Addr) {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0002".to_string(),
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: USDT_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, usdt] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+        spread: Some(Decimal::percent(10)),
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+    (app, limit_order_addr)
+}
+
+#[test]
+fn query_matchable() {
+    let (mut app, limit_order_addr) = mock_basic_query_data();
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(20000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(30000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
+
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert!(!res.is_matchable);
+    println!("[LOG] [1] orderbook matchable: {}", jsonstr!(res));
+
+    /* <----------------------------------- order 3 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(44800u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(44800u128),
+            }],
+        )
+        .unwrap();
+
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert!(res.is_matchable);
+    println!("[LOG] [2] orderbook matchable: {}", jsonstr!(res));
+
+    /* <----------------------------------- order 4 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(22000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer usdt, ask for orai
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(22000u128),
+            }],
+        )
+        .unwrap();
+
+    let res = app
+        .query::<OrderBookMatchableResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert!(res.is_matchable);
+    println!("[LOG] [3] orderbook matchable: {}", jsonstr!(res));
+}
+
+#[test]
+fn remove_orderbook_pair() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0002".to_string(),
+            &[
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // Create pair [orai, atom] for order book
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
+    };
+
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    /* <----------------------------------- order 1 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(11111u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(12345u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(11111u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(12222u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(9700u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(12222u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 3 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(14000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(13000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(13000u128),
+            }],
+        )
+        .unwrap();
+
+    /* <----------------------------------- order 4 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(1900u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1499u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // offer orai, ask for atom
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0002"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1499u128),
+            }],
+        )
+        .unwrap();
+
+    let order_3 = OrderResponse {
+        order_id: 3u64,
+        bidder_addr: "addr0001".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(13000u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(14000u128),
+            info: AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
+    };
+
+    assert_eq!(
+        order_3,
+        app.query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 3,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                ],
+            }
+        )
+        .unwrap()
+    );
+
+    // remove order book for pair [orai, atom]
+    let msg = ExecuteMsg::RemoveOrderBookPair {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        ],
+    };
+
+    let res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    println!("remove order book pair res: {:?}", res);
+
+    let res = app
+        .query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::GenericErr {
+            msg: "Querier contract error: oraiswap_limit_order::orderbook::OrderBook not found"
+                .to_string()
+        }
+    );
+    let res = app
+        .query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 3,
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        res,
+        StdError::GenericErr {
+            msg: "Querier contract error: oraiswap_limit_order::orderbook::OrderBook not found"
+                .to_string()
+        }
+    );
+}
+
+#[test]
+fn orders_querier() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+            ],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000000u128),
+                },
+            ],
+        ),
+    ]);
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let token_addrs = app.set_token_balances(&[
+        (
+            &"assetA".to_string(),
+            &[
+                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
+                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
+            ],
+        ),
+        (
+            &"assetB".to_string(),
+            &[
+                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
+                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
+            ],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &msg,
+            &[],
+            "limit order",
+        )
+        .unwrap();
+
+    // create order book for pair [orai, atom]
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        quote_coin_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        spread: Some(Decimal::percent(1)),
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: None,
+        precision: None,
+    };
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    // create order book for pair [token_addrs[1], token_addrs[0]]
+    let msg = ExecuteMsg::CreateOrderBookPair {
+        base_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[1].clone(),
+        },
+        quote_coin_info: AssetInfo::Token {
+            contract_addr: token_addrs[0].clone(),
+        },
+        spread: None,
+        min_quote_coin_amount: Uint128::zero(),
+        commission_rate: None,
+        precision: None,
+    };
+    let _res = app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &msg,
+        &[],
+    );
+
+    // query orderbooks
+    let res = app
+        .query::<OrderBookResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBook {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+    println!("[LOG] 1st orderbooks :{}", jsonstr!(res));
+
+    // query all orderbooks
+    let res = app
+        .query::<OrderBooksResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBooks {
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+
+    println!("orderbooks :{}", jsonstr!(res));
+
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        )
+        .unwrap();
+
+    // user sends token therefore no need to set allowance for limit order contract
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::from(12345678u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(11223344u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(12345678u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    let msg = cw20::Cw20ExecuteMsg::Send {
+        contract: limit_order_addr.to_string(),
+        amount: Uint128::from(22334455u128),
+        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(22334455u128),
+                },
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(22000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+            belief_price: None,
+            max_spread: None,
+        })
+        .unwrap(),
+    };
+
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0001"),
+            token_addrs[0].clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    let order_1 = OrderResponse {
+        order_id: 1u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(1000000u128),
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(1000000u128),
+            info: AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
+    };
+
+    let order_2 = OrderResponse {
+        order_id: 2u64,
+        bidder_addr: "addr0000".to_string(),
+        offer_asset: Asset {
+            amount: Uint128::from(1000000u128),
+            info: AssetInfo::Token {
+                contract_addr: token_addrs[0].clone(),
+            },
+        },
+        ask_asset: Asset {
+            amount: Uint128::from(1000000u128),
+            info: AssetInfo::Token {
+                contract_addr: token_addrs[1].clone(),
+            },
+        },
+        filled_offer_amount: Uint128::zero(),
+        filled_ask_amount: Uint128::zero(),
+        direction: OrderDirection::Buy,
+        status: OrderStatus::Open,
+    };
+
+    let all_order = OrdersResponse {
+        orders: [
+            OrderResponse {
+                order_id: 4u64,
+                direction: OrderDirection::Sell,
+                bidder_addr: "addr0001".to_string(),
+                offer_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(22334455u128),
+                },
+                ask_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(22000000u128),
+                },
+                filled_offer_amount: Uint128::zero(),
+                filled_ask_amount: Uint128::zero(),
+                status: OrderStatus::Open,
+            },
+            OrderResponse {
+                order_id: 3u64,
+                direction: OrderDirection::Sell,
+                bidder_addr: "addr0001".to_string(),
+                offer_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    amount: Uint128::from(12345678u128),
+                },
+                ask_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    amount: Uint128::from(11223344u128),
+                },
+                filled_offer_amount: Uint128::zero(),
+                filled_ask_amount: Uint128::zero(),
+                status: OrderStatus::Open,
+            },
+            OrderResponse {
+                order_id: 2u64,
+                direction: OrderDirection::Buy,
+                bidder_addr: "addr0000".to_string(),
+                offer_asset: Asset {
+                    amount: Uint128::from(1000000u128),
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                },
+                ask_asset: Asset {
+                    amount: Uint128::from(1000000u128),
+                    info: AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                },
+                filled_offer_amount: Uint128::zero(),
+                filled_ask_amount: Uint128::zero(),
+                status: OrderStatus::Open,
+            },
+        ]
+        .to_vec(),
+    };
+
+    assert_eq!(
+        OrdersResponse {
+            orders: vec![order_2.clone(),],
+        },
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::Bidder("addr0000".to_string()),
+                start_after: None,
+                limit: None,
+                order_by: Some(1),
+            }
+        )
+        .unwrap()
+    );
+
+    let test = app
+        .query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                ],
+                direction: Some(OrderDirection::Buy),
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+    println!("[LOG] [1] - query all buy order: {}", jsonstr!(test));
+
+    let test = app
+        .query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                ],
+                direction: Some(OrderDirection::Sell), //None
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+    println!("[LOG] [2] - query all sell order: {}", jsonstr!(test));
+
+    let test = app
+        .query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+            },
+        )
+        .unwrap();
+    println!("[LOG] [3] - query all order: {}", jsonstr!(test));
+
+    assert_eq!(
+        OrdersResponse {
+            orders: vec![order_1.clone()],
+        },
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: Some(1),
+            }
+        )
+        .unwrap()
+    );
+
+    // DESC test
+    assert_eq!(
+        all_order.clone(),
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[1].clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token_addrs[0].clone(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: None,
+                limit: None,
+                order_by: Some(2),
+            }
+        )
+        .unwrap()
+    );
+
+    // different bidder
+    assert_eq!(
+        OrdersResponse { orders: vec![] },
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::Bidder("addr0001".to_string()),
+                start_after: None,
+                limit: None,
+                order_by: None,
+            }
+        )
+        .unwrap()
+    );
+
+    // start after DESC
+    assert_eq!(
+        OrdersResponse {
+            orders: vec![order_1],
+        },
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: Some(2u64),
+                limit: None,
+                order_by: Some(2),
+            }
+        )
+        .unwrap()
+    );
+
+    // start after ASC
+    assert_eq!(
+        OrdersResponse { orders: vec![] },
+        app.query::<OrdersResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Orders {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                ],
+                direction: None,
+                filter: OrderFilter::None,
+                start_after: Some(1u64),
+                limit: None,
+                order_by: Some(1),
+            }
+        )
+        .unwrap()
+    );
+
+    // query all ticks
+    let res = app
+        .query::<TicksResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Ticks {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                ],
+                direction: OrderDirection::Buy,
+                start_after: None,
+                end: None,
+                limit: None,
+                order_by: Some(1),
+            },
         )
         .unwrap();
 
-    // Create pair [orai, usdt] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
-            denom: USDT_DENOM.to_string(),
-        },
-        spread: Some(Decimal::percent(1)),
-        min_quote_coin_amount: Uint128::from(10u128),
-    };
+    for tick in res.ticks {
+        let res = app
+            .query::<OrdersResponse, _>(
+                limit_order_addr.clone(),
+                &QueryMsg::Orders {
+                    asset_infos: [
+                        AssetInfo::NativeToken {
+                            denom: ORAI_DENOM.to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: ATOM_DENOM.to_string(),
+                        },
+                    ],
+                    direction: None,
+                    filter: OrderFilter::Price(tick.price),
+                    start_after: None,
+                    limit: None,
+                    order_by: Some(1),
+                },
+            )
+            .unwrap();
+        println!("{:?}", res);
+    }
+}
 
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
+#[test]
+fn test_query_ticks_start_after() {
+    let (mut app, limit_order_addr) = mock_basic_query_data();
 
-    /* <----------------------------------- order 0 -----------------------------------> */
+    /* <----------------------------------- order 1 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000000u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(261500000u128),
+                amount: Uint128::from(20000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(261500000u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 1 -----------------------------------> */
+    /* <----------------------------------- order 2 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
         direction: OrderDirection::Sell,
         assets: [
@@ -4684,15 +9055,22 @@ fn simple_matching_test() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000000u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(75000000u128),
+                amount: Uint128::from(30000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
     let _res = app
@@ -4702,112 +9080,148 @@ fn simple_matching_test() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000000u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
+    let result = app
+        .query::<TicksResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Ticks {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+                direction: OrderDirection::Sell,
+                start_after: Some(Decimal::from_str("3").unwrap()),
+                end: None,
+                limit: None,
+                order_by: Some(2),
+            },
+        )
+        .unwrap();
+    assert_eq!(result.ticks.len(), 1);
+
+    let result = app
+        .query::<TicksResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::Ticks {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+                direction: OrderDirection::Sell,
+                start_after: Some(Decimal::from_str("2").unwrap()),
+                end: None,
+                limit: None,
+                order_by: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(result.ticks.len(), 1);
+}
+
+#[test]
+fn test_unwrap_default_check_sub_uint128() {
+    let result = Uint128::from(0u64)
+        .checked_sub(Uint128::from(1u64))
+        .unwrap_or_default();
+    assert_eq!(result, Uint128::from(0u64));
+}
+
+#[test]
+fn test_query_ticks_with_end() {
+    let (mut app, limit_order_addr) = mock_basic_query_data();
+
+    /* <----------------------------------- order 1 -----------------------------------> */
     let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1000000u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(261500000u128),
+                amount: Uint128::from(20000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // offer usdt, ask for orai
     let _res = app
         .execute(
-            Addr::unchecked("addr0002"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
             &msg,
             &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(261500000u128),
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    let mut address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    let mut address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    let mut address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 0 - address0's balances: {:?}", address0_balances);
-    println!("round 0 - address1's balances: {:?}", address1_balances);
-    println!("round 0 - address2's balances: {:?}\n\n", address2_balances);
-
-    let mut expected_balances: Vec<Coin> = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(9990000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(10000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(10000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(10000000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address1_balances, expected_balances,);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(10000000000u128),
-        },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(9477000000u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances);
-
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+    /* <----------------------------------- order 2 -----------------------------------> */
+    let msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
             },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: USDT_DENOM.to_string(),
+                },
+                amount: Uint128::from(30000u128),
             },
         ],
-        limit: None,
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    // Native token balance mismatch between the argument and the transferred
-    let res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-    app.assert_fail(res);
+    let _res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &msg,
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
+        )
+        .unwrap();
 
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
+    let result = app
+        .query::<TicksResponse, _>(
             limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
+            &QueryMsg::Ticks {
                 asset_infos: [
                     AssetInfo::NativeToken {
                         denom: ORAI_DENOM.to_string(),
@@ -4816,1546 +9230,5027 @@ fn simple_matching_test() {
                         denom: USDT_DENOM.to_string(),
                     },
                 ],
+                direction: OrderDirection::Sell,
+                start_after: Some(Decimal::from_str("3").unwrap()),
+                end: Some(Decimal::from_str("2").unwrap()),
+                limit: None,
+                order_by: Some(2),
             },
         )
         .unwrap();
+    assert_eq!(result.ticks.len(), 1);
+    assert_eq!(result.ticks[0].price, Decimal::from_str("2").unwrap());
 
-    let expected_res = OrderBookMatchableResponse { is_matchable: true };
-    assert_eq!(res, expected_res);
-
-    // Excecute all orders
-    let msg = ExecuteMsg::ExecuteOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: USDT_DENOM.to_string(),
-            },
-        ],
-        limit: None,
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    let result = app
+        .query::<TicksResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[],
+            &QueryMsg::Ticks {
+                asset_infos: [
+                    AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                ],
+                direction: OrderDirection::Sell,
+                start_after: Some(Decimal::from_str("2").unwrap()),
+                end: Some(Decimal::from_str("3").unwrap()),
+                limit: None,
+                order_by: Some(1),
+            },
         )
         .unwrap();
-    println!("[LOG] attribute - round 1 - {:?}", _res);
-
-    address0_balances = app.query_all_balances(Addr::unchecked("addr0000")).unwrap();
-    address1_balances = app.query_all_balances(Addr::unchecked("addr0001")).unwrap();
-    address2_balances = app.query_all_balances(Addr::unchecked("addr0002")).unwrap();
-    println!("round 1 - address0's balances: {:?}", address0_balances);
-    println!("round 1 - address1's balances: {:?}", address1_balances);
-    println!("round 1 - address2's balances: {:?}\n\n", address2_balances);
+    assert_eq!(result.ticks.len(), 1);
+    assert_eq!(result.ticks[0].price, Decimal::from_str("3").unwrap());
+}
 
-    expected_balances = [
-        Coin {
+#[test]
+fn max_orders_per_match() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(9990000000u128),
         },
-        Coin {
+        AssetInfo::NativeToken {
             denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(10074922750u128),
-        },
-    ]
-    .to_vec();
-    assert_eq!(address0_balances, expected_balances);
-    expected_balances = [
-        Coin {
-            denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(10001997400u128),
         },
-        Coin {
-            denom: USDT_DENOM.to_string(),
-            amount: Uint128::from(9477000000u128),
+    ];
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: None,
+            max_orders_per_match: Some(3),
+            oracle_addr: None,
+            dust_threshold: None,
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
         },
-    ]
-    .to_vec();
-    assert_eq!(address2_balances, expected_balances);
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
+        &[],
+    )
+    .unwrap();
+
+    // stuff the sell tick at price 2 with 5 orders
+    for _ in 0..5 {
+        app.execute(
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+            &ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(10000u128),
                     },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(20000u128),
                     },
                 ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            }],
         )
         .unwrap();
+    }
 
-    let expected_res = OrderBookMatchableResponse {
-        is_matchable: false,
-    };
-    assert_eq!(res, expected_res);
-}
-
-fn mock_basic_query_data() -> (MockApp, Addr) {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+    // a single buy order at price 2 covering the whole tick
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(50000u128),
                 },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(100000u128),
                 },
             ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
+            denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(100000u128),
+        }],
+    )
+    .unwrap();
+
+    let mut execute_match = || {
+        let res = app
+            .execute(
+                Addr::unchecked("addr0002"),
+                limit_order_addr.clone(),
+                &ExecuteMsg::ExecuteOrderBookPair {
+                    asset_infos: asset_infos.clone(),
+                    limit: None,
+                    best_effort: None,
                 },
-            ],
-        ),
+                &[],
+            )
+            .unwrap();
+        let attrs = res.get_attributes(1);
+        let find_attr = |key: &str| attrs.iter().find(|attr| attr.key == key).unwrap().clone();
         (
-            &"addr0002".to_string(),
-            &[
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-                Coin {
-                    denom: USDT_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
-                },
-            ],
-        ),
-    ]);
+            find_attr("total_matched_orders").value,
+            find_attr("has_more"),
+        )
+    };
 
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
+    // each call loads the buy order plus 2 sell orders, so the tick is drained in 3 calls
+    for (total_matched_orders, has_more) in [("3", "true"), ("3", "true"), ("2", "false")] {
+        assert_eq!(
+            execute_match(),
+            (total_matched_orders.to_string(), attr("has_more", has_more))
+        );
+    }
+}
+
+#[test]
+fn query_matching_worthwhile() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+    app.execute(
+        Addr::unchecked(APP_OWNER),
+        app.oracle_addr.clone(),
+        &oraiswap::oracle::ExecuteMsg::UpdateExchangeRate {
+            denom: USDT_DENOM.to_string(),
+            exchange_rate: Decimal::one(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let query_msg = QueryMsg::MatchingWorthwhile {
+        asset_infos: asset_infos.clone(),
+        gas_price: Decimal::from_str("0.001").unwrap(),
+        estimated_gas: 1000000,
     };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
-        )
-        .unwrap();
 
-    // Create pair [orai, usdt] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
+    // can not value the reward without an oracle
+    assert!(app
+        .query::<MatchingWorthwhileResponse, _>(limit_order_addr.clone(), &query_msg)
+        .is_err());
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: Some(app.oracle_addr.clone()),
+            dust_threshold: None,
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // a crossing buy and sell at price 2
+    let submit_pair_of_orders = |app: &mut MockApp, base_amount: u128| {
+        for (sender, direction, denom, amount) in [
+            ("addr0000", OrderDirection::Sell, ORAI_DENOM, base_amount),
+            ("addr0001", OrderDirection::Buy, USDT_DENOM, base_amount * 2),
+        ] {
+            app.execute(
+                Addr::unchecked(sender),
+                limit_order_addr.clone(),
+                &ExecuteMsg::SubmitOrder {
+                    direction,
+                    assets: [
+                        Asset {
+                            info: asset_infos[0].clone(),
+                            amount: Uint128::from(base_amount),
+                        },
+                        Asset {
+                            info: asset_infos[1].clone(),
+                            amount: Uint128::from(base_amount * 2),
+                        },
+                    ],
+                    post_only: None,
+                    client_order_id: None,
+                    fill_callback: None,
+                    expires_at: None,
+                    order_type: None,
+                    min_fill_amount: None,
+                    trigger_price: None,
+                },
+                &[Coin {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(amount),
+                }],
+            )
+            .unwrap();
+        }
+    };
+
+    // dust orders cap the relayer fees at 100 orai + 200 usdt, below the 1000 orai gas cost
+    submit_pair_of_orders(&mut app, 100);
+    let res: MatchingWorthwhileResponse = app.query(limit_order_addr.clone(), &query_msg).unwrap();
+    assert_eq!(
+        res,
+        MatchingWorthwhileResponse {
+            worthwhile: false,
+            expected_reward: Uint128::from(300u128),
+            gas_cost: Uint128::from(1000u128),
+            net_profit: Uint128::zero(),
+        }
+    );
+
+    // larger orders pay the full 300 orai + 600 usdt relayer fee
+    submit_pair_of_orders(&mut app, 10000);
+    let res: MatchingWorthwhileResponse = app.query(limit_order_addr.clone(), &query_msg).unwrap();
+    assert_eq!(
+        res,
+        MatchingWorthwhileResponse {
+            worthwhile: true,
+            expected_reward: Uint128::from(1200u128),
+            gas_cost: Uint128::from(1000u128),
+            net_profit: Uint128::from(200u128),
+        }
+    );
+}
+
+#[test]
+fn claim_executor_reward() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let assets = [
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(10000u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(20000u128),
+        },
+    ];
+    let claim_msg = ExecuteMsg::ClaimExecutorReward {
+        asset_infos: asset_infos.clone(),
+    };
+
+    // nothing accrued yet
+    let res = app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &claim_msg,
+        &[],
+    );
+    app.assert_fail(res);
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: assets.clone(),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &[Coin {
             denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10000u128),
+        }],
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
         },
-        quote_coin_info: AssetInfo::NativeToken {
+        &[Coin {
             denom: USDT_DENOM.to_string(),
+            amount: Uint128::from(20000u128),
+        }],
+    )
+    .unwrap();
+
+    // relayer fees of 300 orai and 600 usdt stay below the auto distribution threshold
+    app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
         },
-        spread: Some(Decimal::percent(10)),
-        min_quote_coin_amount: Uint128::from(10u128),
+        &[],
+    )
+    .unwrap();
+    let executor_balances = |app: &MockApp| {
+        [ORAI_DENOM, USDT_DENOM].map(|denom| {
+            app.query_balance(Addr::unchecked("addr0002"), denom.to_string())
+                .unwrap()
+        })
     };
+    assert_eq!(executor_balances(&app), [Uint128::zero(), Uint128::zero()]);
 
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
+    app.execute(
+        Addr::unchecked("addr0002"),
         limit_order_addr.clone(),
-        &msg,
+        &claim_msg,
         &[],
+    )
+    .unwrap();
+    assert_eq!(
+        executor_balances(&app),
+        [Uint128::from(300u128), Uint128::from(600u128)]
+    );
+
+    // the accrued reward is zeroed, claiming again pays nothing
+    app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &claim_msg,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        executor_balances(&app),
+        [Uint128::from(300u128), Uint128::from(600u128)]
     );
-    (app, limit_order_addr)
 }
 
 #[test]
-fn query_matchable() {
-    let (mut app, limit_order_addr) = mock_basic_query_data();
-
-    /* <----------------------------------- order 1 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(20000u128),
+fn best_effort_matching_skips_unsettleable_orders() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    let assets = [
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(1000000u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(2000000u128),
+        },
+    ];
+
+    // order 1 and 2 sell at the same tick, order 3 buys exactly one of them
+    for bidder in ["addr0003", "addr0000"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(bidder, &coins(1000000u128, ORAI_DENOM)),
+            ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: assets.clone(),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
         )
         .unwrap();
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(2000000u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(30000u128),
-            },
-        ],
-    };
+    // the bidder of order 1 can no longer be paid out
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    let mut broken_order = read_order(deps.as_ref().storage, &pair_key, 1).unwrap();
+    broken_order.bidder_addr = CanonicalAddr::from(vec![1u8; 3]);
+    store_order(deps.as_mut().storage, &pair_key, &broken_order, false).unwrap();
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+    let execute_msg = |best_effort| ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: asset_infos.clone(),
+        limit: None,
+        best_effort,
+    };
+
+    // by default the whole matching reverts
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        execute_msg(None),
+    )
+    .unwrap_err();
+
+    // best effort matches order 2 with order 3 and leaves order 1 open
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        execute_msg(Some(true)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("total_matched_orders", "2"));
+
+    let order = read_order(deps.as_ref().storage, &pair_key, 1).unwrap();
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(order.filled_offer_amount, Uint128::zero());
+    // fulfilled orders are removed from the book
+    for order_id in [2, 3] {
+        assert!(read_order(deps.as_ref().storage, &pair_key, order_id).is_err());
+    }
+}
+
+#[test]
+fn best_effort_matching_skips_overfilled_orders() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: Some(Uint128::from(10u128)),
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    let assets = [
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(1000000u128),
+        },
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(2000000u128),
+        },
+    ];
+
+    // order 1 and 2 sell at the same tick, order 3 buys exactly one of them
+    for bidder in ["addr0003", "addr0000"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(bidder, &coins(1000000u128, ORAI_DENOM)),
+            ExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Sell,
+                assets: assets.clone(),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
         )
         .unwrap();
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(2000000u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets,
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
 
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
-                asset_infos: [
-                    AssetInfo::NativeToken {
+    // order 1 records more filled than it offered, so its remainder can not be computed
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    let mut broken_order = read_order(deps.as_ref().storage, &pair_key, 1).unwrap();
+    broken_order.status = OrderStatus::PartialFilled;
+    broken_order.filled_offer_amount = Uint128::from(1000001u128);
+    store_order(deps.as_mut().storage, &pair_key, &broken_order, false).unwrap();
+
+    let execute_msg = |best_effort| ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: asset_infos.clone(),
+        limit: None,
+        best_effort,
+    };
+
+    // by default the whole matching reverts
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        execute_msg(None),
+    )
+    .unwrap_err();
+
+    // best effort matches order 2 with order 3 and leaves order 1 untouched
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        execute_msg(Some(true)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("total_matched_orders", "2"));
+
+    assert_eq!(
+        read_order(deps.as_ref().storage, &pair_key, 1).unwrap(),
+        broken_order
+    );
+    for order_id in [2, 3] {
+        assert!(read_order(deps.as_ref().storage, &pair_key, order_id).is_err());
+    }
+}
+
+#[test]
+fn migrate_rejects_counter_behind_stored_orders() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            quote_coin_info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
                         denom: ORAI_DENOM.to_string(),
                     },
-                    AssetInfo::NativeToken {
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
                         denom: USDT_DENOM.to_string(),
                     },
-                ],
-            },
-        )
-        .unwrap();
+                    amount: Uint128::from(2000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
 
-    let expected_res = OrderBookMatchableResponse {
-        is_matchable: false,
+    let query_msg = QueryMsg::LastStoredOrderId {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+        ],
     };
-    assert_eq!(res, expected_res);
-    println!("[LOG] [1] orderbook matchable: {}", jsonstr!(res));
+    let res: LastStoredOrderIdResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        LastStoredOrderIdResponse {
+            last_order_id: 1,
+            last_stored_order_id: Some(1),
+        }
+    );
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
 
-    /* <----------------------------------- order 3 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+    // order 1 exists, resetting the counter to 0 would hand out id 1 again
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("last order id is already 1, can not reset it")
+    );
+    init_last_order_id(deps.as_mut().storage).unwrap_err();
+    assert_eq!(read_last_order_id(deps.as_ref().storage).unwrap(), 1);
+
+    // a counter rewound before the guard existed is caught by the migration
+    singleton(deps.as_mut().storage, b"last_order_id")
+        .save(&0u64)
+        .unwrap();
+    let res: LastStoredOrderIdResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+    assert_eq!(res.last_order_id, 0);
+    let err = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("last order id 0 is behind stored order 1")
+    );
+}
+
+#[test]
+fn submit_order_deduplicates_client_order_id() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            quote_coin_info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let submit_msg = ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(10000u128),
+                amount: Uint128::from(1000000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(44800u128),
+                amount: Uint128::from(2000000u128),
             },
         ],
-    };
+        post_only: None,
+        client_order_id: Some("abc".to_string()),
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("client_order_id", "abc")));
+
+    // the retried submission is rejected and no second order is placed
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DuplicateClientOrderId {
+            client_order_id: "abc".to_string(),
+            order_id: 1,
+        }
+    );
+    assert_eq!(read_last_order_id(deps.as_ref().storage).unwrap(), 1);
+
+    // another bidder may use the same id
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg.clone(),
+    )
+    .unwrap();
+
+    // once the window has passed the id can be used again
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86400);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg,
+    )
+    .unwrap();
+    assert_eq!(read_last_order_id(deps.as_ref().storage).unwrap(), 3);
+}
 
-    // offer usdt, ask for orai
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(44800u128),
-            }],
-        )
-        .unwrap();
+#[test]
+fn fill_callback_notified_on_partial_fill() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // order 1 sells with a callback, order 2 buys half of it without one
+    let hook = Hook {
+        contract_addr: Addr::unchecked("dca_bot"),
+        msg: to_binary("dca").unwrap(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(2000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: Some(hook.clone()),
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(500000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos,
+        None,
+        false,
+    )
+    .unwrap();
+
+    // only the order with a callback notifies, with its cumulative fill
+    let callbacks: Vec<&CosmosMsg> = result
+        .messages
+        .iter()
+        .filter(|msg| matches!(msg, CosmosMsg::Wasm(_)))
+        .collect();
+    assert_eq!(
+        callbacks,
+        vec![&FillHookMsg::OrderFilled {
+            order_id: 1,
+            status: OrderStatus::PartialFilled,
+            direction: OrderDirection::Sell,
+            offer_amount: Uint128::from(1000000u128),
+            ask_amount: Uint128::from(2000000u128),
+            filled_offer_amount: Uint128::from(500000u128),
+            filled_ask_amount: Uint128::from(1000000u128),
+            msg: hook.msg,
+        }
+        .into_cosmos_msg(&hook.contract_addr)
+        .unwrap()]
+    );
+}
 
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+#[test]
+fn trade_participants_of_a_match() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    let submit_order = |deps: DepsMut, bidder: &str, direction, base_amount: u128| {
+        let funds = match direction {
+            OrderDirection::Buy => coins(base_amount * 2, USDT_DENOM),
+            OrderDirection::Sell => coins(base_amount, ORAI_DENOM),
+        };
+        execute(
+            deps,
+            mock_env(),
+            mock_info(bidder, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(base_amount),
                     },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(base_amount * 2),
                     },
                 ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
         )
         .unwrap();
-
-    let expected_res = OrderBookMatchableResponse { is_matchable: true };
-    assert_eq!(res, expected_res);
-    println!("[LOG] [2] orderbook matchable: {}", jsonstr!(res));
-
-    /* <----------------------------------- order 4 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(22000u128),
-            },
-        ],
     };
 
-    // offer usdt, ask for orai
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: USDT_DENOM.to_string(),
-                amount: Uint128::from(22000u128),
-            }],
-        )
-        .unwrap();
+    // order 1 and 2 rest on the book, order 3 buys both of them
+    submit_order(deps.as_mut(), "addr0003", OrderDirection::Sell, 1000000);
+    submit_order(deps.as_mut(), "addr0004", OrderDirection::Sell, 1000000);
+    submit_order(deps.as_mut(), "addr0001", OrderDirection::Buy, 2000000);
 
-    let res = app
-        .query::<OrderBookMatchableResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::OrderBookMatchable {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-            },
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
+        },
+    )
+    .unwrap();
+    let trade_ids: Vec<u64> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "trade")
+        .flat_map(|event| event.attributes.iter())
+        .filter(|attr| attr.key == "trade_id")
+        .map(|attr| attr.value.parse().unwrap())
+        .collect();
+    assert_eq!(trade_ids, vec![1, 2]);
+
+    for (trade_id, maker_order_id, maker_addr) in [(1, 1, "addr0003"), (2, 2, "addr0004")] {
+        let res: TradeParticipantsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TradeParticipants {
+                    asset_infos: asset_infos.clone(),
+                    trade_id,
+                },
+            )
+            .unwrap(),
         )
         .unwrap();
+        assert_eq!(
+            res,
+            TradeParticipantsResponse {
+                trade_id,
+                maker_order_id,
+                maker_addr: Addr::unchecked(maker_addr),
+                taker_order_id: 3,
+                taker_addr: Addr::unchecked("addr0001"),
+                taker_direction: OrderDirection::Buy,
+                price: Decimal::from_ratio(2u128, 1u128),
+                base_amount: Uint128::from(1000000u128),
+                quote_amount: Uint128::from(2000000u128),
+            }
+        );
+    }
 
-    let expected_res = OrderBookMatchableResponse { is_matchable: true };
-    assert_eq!(res, expected_res);
-    println!("[LOG] [3] orderbook matchable: {}", jsonstr!(res));
+    // a trade id that was never recorded is not found
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TradeParticipants {
+            asset_infos,
+            trade_id: 3,
+        },
+    )
+    .unwrap_err();
 }
 
 #[test]
-fn remove_orderbook_pair() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ATOM_DENOM.to_string(),
+fn dust_remainder_refunded_after_fill() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: Some(Uint128::from(10u128)),
+            stp_cancel_newest: None,
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+    )
+    .unwrap();
+
+    // order 2 buys all but 5 orai of order 1
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
                     amount: Uint128::from(1000000u128),
                 },
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(2000000u128),
                 },
             ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1999990u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(999995u128),
                 },
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(1999990u128),
                 },
             ],
-        ),
-        (
-            &"addr0002".to_string(),
-            &[
-                Coin {
-                    denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(1000000u128),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    // the 5 orai left on order 1 go back to the bidder and the order leaves the book
+    let fill = result.fills.iter().find(|fill| fill.order_id == 1).unwrap();
+    assert_eq!(fill.status, OrderStatus::Cancel);
+    assert_eq!(fill.filled_offer_amount, Uint128::from(999995u128));
+    assert!(result.messages.contains(&CosmosMsg::Bank(BankMsg::Send {
+        to_address: "addr0000".to_string(),
+        amount: coins(5u128, ORAI_DENOM),
+    })));
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_err());
+}
+
+#[test]
+fn expired_orders_canceled_on_matching() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let now = mock_env().block.time.seconds();
+    let sell_msg = |expires_at| ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(2000000u128),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: Some(expires_at),
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // an order that is already expired is rejected
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        sell_msg(now),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidOrderExpiry { expires_at: now });
+
+    // order 1 expires in 100 seconds, order 2 crosses it and never expires
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        sell_msg(now + 100),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(500000u128),
                 },
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
+                Asset {
+                    info: asset_infos[1].clone(),
                     amount: Uint128::from(1000000u128),
                 },
             ],
-        ),
-    ]);
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    let expired_orders = |env| -> Vec<u64> {
+        let res: OrdersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::ExpiredOrders {
+                    asset_infos: asset_infos.clone(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.orders.iter().map(|order| order.order_id).collect()
+    };
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(100);
+    assert_eq!(expired_orders(mock_env()), Vec::<u64>::new());
+    assert_eq!(expired_orders(expired_env.clone()), vec![1]);
+
+    // once expired, order 1 is refunded instead of matched and order 2 keeps resting
+    let result = match_orders(
+        deps.as_mut(),
+        &expired_env,
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(result.expired_orders, vec![1]);
+    assert!(result.fills.is_empty());
+    assert_eq!(
+        result.messages,
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(1000000u128, ORAI_DENOM),
+        })]
+    );
 
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_err());
+    assert_eq!(
+        read_order(deps.as_ref().storage, &pair_key, 2)
+            .unwrap()
+            .status,
+        OrderStatus::Open
+    );
+}
 
+#[test]
+fn immediate_order_types() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+    ]);
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
     let limit_order_addr = app
         .instantiate(
             code_id,
             Addr::unchecked("addr0000"),
-            &msg,
+            &InstantiateMsg {
+                name: None,
+                version: None,
+                admin: None,
+                commission_rate: None,
+                reward_address: None,
+                maker_commission_rate: None,
+                taker_commission_rate: None,
+            },
             &[],
             "limit order",
         )
         .unwrap();
-
-    // Create pair [orai, atom] for order book
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ATOM_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
+    let asset_infos = [
+        AssetInfo::NativeToken {
             denom: ORAI_DENOM.to_string(),
         },
-        spread: None,
-        min_quote_coin_amount: Uint128::zero(),
-    };
-
-    let _res = app.execute(
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    app.execute(
         Addr::unchecked("addr0000"),
         limit_order_addr.clone(),
-        &msg,
+        &ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
         &[],
-    );
+    )
+    .unwrap();
 
-    /* <----------------------------------- order 1 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
+    // the book only holds 100000 orai for sale
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(100000u128),
                 },
-                amount: Uint128::from(11111u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(200000u128),
                 },
-                amount: Uint128::from(12345u128),
-            },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(11111u128),
-            }],
-        )
-        .unwrap();
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+        &coins(100000u128, ORAI_DENOM),
+    )
+    .unwrap();
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+    let buy_msg = |order_type| ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Buy,
         assets: [
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
-                },
-                amount: Uint128::from(12222u128),
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(200000u128),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(9700u128),
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(400000u128),
             },
         ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: Some(order_type),
+        min_fill_amount: None,
+        trigger_price: None,
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
+    // fill or kill against the thin book reverts entirely
+    let res = app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &buy_msg(OrderType::Fok),
+        &coins(400000u128, USDT_DENOM),
+    );
+    app.assert_fail(res);
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0001"), USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(1000000u128)
+    );
+    assert_eq!(
+        app.query::<LastOrderIdResponse, _>(limit_order_addr.clone(), &QueryMsg::LastOrderId {})
+            .unwrap(),
+        LastOrderIdResponse { last_order_id: 1 }
+    );
+    let order_1: OrderResponse = app
+        .query(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(12222u128),
-            }],
+            &QueryMsg::Order {
+                order_id: 1,
+                asset_infos: asset_infos.clone(),
+            },
         )
         .unwrap();
+    assert_eq!(order_1.status, OrderStatus::Open);
 
-    /* <----------------------------------- order 3 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
+    // immediate or cancel takes the 100000 orai and refunds the unmatched half
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &buy_msg(OrderType::Ioc),
+        &coins(400000u128, USDT_DENOM),
+    )
+    .unwrap();
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0001"), USDT_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(800000u128)
+    );
+    assert!(!app
+        .query_balance(Addr::unchecked("addr0001"), ORAI_DENOM.to_string())
+        .unwrap()
+        .is_zero());
+    for order_id in [1, 2] {
+        assert!(app
+            .query::<OrderResponse, _>(
+                limit_order_addr.clone(),
+                &QueryMsg::Order {
+                    order_id,
+                    asset_infos: asset_infos.clone(),
                 },
-                amount: Uint128::from(14000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+            )
+            .is_err());
+    }
+
+    // a crossing book at 2, the immediate order is matched alone and never settles the resting orders
+    let gtc_msg = |direction, offer_amount: u128, ask_amount: u128, order_type| {
+        let (offer_info, ask_info) = match direction {
+            OrderDirection::Buy => (asset_infos[1].clone(), asset_infos[0].clone()),
+            OrderDirection::Sell => (asset_infos[0].clone(), asset_infos[1].clone()),
+        };
+        ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: offer_info,
+                    amount: Uint128::from(offer_amount),
                 },
-                amount: Uint128::from(13000u128),
-            },
-        ],
+                Asset {
+                    info: ask_info,
+                    amount: Uint128::from(ask_amount),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type,
+            min_fill_amount: None,
+            trigger_price: None,
+        }
     };
-
-    let _res = app
+    app.execute(
+        Addr::unchecked("addr0001"),
+        limit_order_addr.clone(),
+        &gtc_msg(OrderDirection::Buy, 100000, 50000, None),
+        &coins(100000u128, USDT_DENOM),
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &gtc_msg(OrderDirection::Sell, 50000, 100000, None),
+        &coins(50000u128, ORAI_DENOM),
+    )
+    .unwrap();
+    let res = app
         .execute(
-            Addr::unchecked("addr0001"),
+            Addr::unchecked("addr0000"),
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(13000u128),
-            }],
+            &gtc_msg(OrderDirection::Sell, 10000, 20000, Some(OrderType::Ioc)),
+            &coins(10000u128, ORAI_DENOM),
+        )
+        .unwrap();
+    let relayer_fees: Vec<String> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-matched_order")
+        .flat_map(|event| event.attributes.iter())
+        .filter(|attr| attr.key == "relayer_fee")
+        .map(|attr| attr.value.clone())
+        .collect();
+    assert_eq!(relayer_fees, vec!["0".to_string(), "0".to_string()]);
+
+    let order_3: OrderResponse = app
+        .query(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 3,
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(order_3.status, OrderStatus::PartialFilled);
+    assert_eq!(order_3.filled_ask_amount, Uint128::from(10000u128));
+    let order_4: OrderResponse = app
+        .query(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 4,
+                asset_infos: asset_infos.clone(),
+            },
         )
         .unwrap();
+    assert_eq!(order_4.status, OrderStatus::Open);
+    assert!(order_4.filled_offer_amount.is_zero());
+}
 
-    /* <----------------------------------- order 4 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
+#[test]
+fn submit_orders_batch() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        commission_rate: None,
+        reward_address: None,
+        maker_commission_rate: None,
+        taker_commission_rate: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            quote_coin_info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let order_item = |direction, orai_amount: u128, usdt_amount: u128| SubmitOrderItem {
+        direction,
         assets: [
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
+                    denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(1900u128),
+                amount: Uint128::from(orai_amount),
             },
             Asset {
                 info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
+                    denom: USDT_DENOM.to_string(),
                 },
-                amount: Uint128::from(1499u128),
+                amount: Uint128::from(usdt_amount),
             },
         ],
     };
-
-    // offer orai, ask for atom
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0002"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1499u128),
-            }],
-        )
-        .unwrap();
-
-    let order_3 = OrderResponse {
-        order_id: 3u64,
-        bidder_addr: "addr0001".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(13000u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        },
-        ask_asset: Asset {
-            amount: Uint128::from(14000u128),
-            info: AssetInfo::NativeToken {
-                denom: ATOM_DENOM.to_string(),
-            },
+    let orders = vec![
+        order_item(OrderDirection::Sell, 1000u128, 2000u128),
+        order_item(OrderDirection::Sell, 1000u128, 2200u128),
+        order_item(OrderDirection::Buy, 1000u128, 1500u128),
+    ];
+
+    // funds must cover the sum of the paid assets exactly
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrders {
+            orders: orders.clone(),
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
-    };
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Native token balance mismatch between the argument and the transferred"
+        ))
+    );
+
+    // a single order below the minimum quote rejects the whole batch
+    let mut too_small = orders.clone();
+    too_small.push(order_item(OrderDirection::Sell, 1000u128, 5u128));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[
+                Coin::new(3000u128, ORAI_DENOM),
+                Coin::new(1500u128, USDT_DENOM),
+            ],
+        ),
+        ExecuteMsg::SubmitOrders { orders: too_small },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TooSmallQuoteAsset {
+            quote_coin: USDT_DENOM.to_string(),
+            min_quote_amount: Uint128::from(10u128),
+        }
+    );
+    assert_eq!(read_last_order_id(deps.as_ref().storage).unwrap(), 0);
 
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[
+                Coin::new(2000u128, ORAI_DENOM),
+                Coin::new(1500u128, USDT_DENOM),
+            ],
+        ),
+        ExecuteMsg::SubmitOrders { orders },
+    )
+    .unwrap();
     assert_eq!(
-        order_3,
-        app.query::<OrderResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Order {
+        res.attributes
+            .iter()
+            .filter(|attr| attr.key == "order_id")
+            .count(),
+        3
+    );
+    assert_eq!(read_last_order_id(deps.as_ref().storage).unwrap(), 3);
+
+    let order_3: OrderResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Order {
                 order_id: 3,
                 asset_infos: [
                     AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
+                        denom: ORAI_DENOM.to_string(),
                     },
                     AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+                        denom: USDT_DENOM.to_string(),
                     },
                 ],
-            }
+            },
         )
-        .unwrap()
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(order_3.direction, OrderDirection::Buy);
+    assert_eq!(order_3.offer_asset.amount, Uint128::from(1500u128));
+}
+
+#[test]
+fn cancel_order_partial_then_fill_remainder() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(2000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    // cannot cancel more than what is left
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelOrderPartial {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            amount: Uint128::from(1000001u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CancelAmountExceeded {
+            amount: Uint128::from(1000001u128),
+            remaining: Uint128::from(1000000u128),
+        }
     );
 
-    // remove order book for pair [orai, atom]
-    let msg = ExecuteMsg::RemoveOrderBookPair {
-        asset_infos: [
-            AssetInfo::NativeToken {
-                denom: ATOM_DENOM.to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
-        ],
-    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelOrderPartial {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            amount: Uint128::from(400000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(400000u128, ORAI_DENOM),
+        })
+    );
 
-    let res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[],
+    // the rest keeps resting at the same price
+    let order_1: OrderResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Order {
+                order_id: 1,
+                asset_infos: asset_infos.clone(),
+            },
         )
-        .unwrap();
-
-    println!("remove order book pair res: {:?}", res);
-
-    let res = app
-        .query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                ],
-                direction: None,
-                filter: OrderFilter::None,
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(order_1.offer_asset.amount, Uint128::from(600000u128));
+    assert_eq!(order_1.ask_asset.amount, Uint128::from(1200000u128));
+    let ticks: TicksResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Ticks {
+                asset_infos: asset_infos.clone(),
+                direction: OrderDirection::Sell,
                 start_after: None,
+                end: None,
                 limit: None,
                 order_by: None,
             },
         )
-        .unwrap_err();
-    assert_eq!(
-        res,
-        StdError::GenericErr {
-            msg: "Querier contract error: oraiswap_limit_order::orderbook::OrderBook not found"
-                .to_string()
-        }
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(ticks.ticks.len(), 1);
+    assert_eq!(ticks.ticks[0].price, Decimal::from_str("2").unwrap());
+    assert_eq!(ticks.ticks[0].total_orders, 1);
+
+    // a buyer takes exactly the remainder
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1200000u128, USDT_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Buy,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(600000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(1200000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    let fill = result.fills.iter().find(|fill| fill.order_id == 1).unwrap();
+    assert_eq!(fill.status, OrderStatus::Fulfilled);
+    assert_eq!(fill.filled_offer_amount, Uint128::from(600000u128));
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_err());
+}
+
+#[test]
+fn submit_order_emits_only_declared_attributes() {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            quote_coin_info: AssetInfo::NativeToken {
+                denom: USDT_DENOM.to_string(),
+            },
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
+            direction: OrderDirection::Sell,
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: USDT_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(2000000u128),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    assert!(res.messages.is_empty());
+    assert!(res.events.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "submit_order"),
+            attr("pair", format!("{} - {}", ORAI_DENOM, USDT_DENOM)),
+            attr("order_id", "1"),
+            attr("status", "Open"),
+            attr("direction", "Sell"),
+            attr("bidder_addr", "addr0000"),
+            attr("offer_asset", format!("1000000 {}", ORAI_DENOM)),
+            attr("ask_asset", format!("2000000 {}", USDT_DENOM)),
+        ]
     );
-    let res = app
-        .query::<OrderResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Order {
-                order_id: 3,
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
+}
+
+#[test]
+fn query_orderbook_depth() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // (direction, orai amount, usdt amount)
+    let orders = [
+        (OrderDirection::Sell, 1000u128, 3000u128),
+        (OrderDirection::Sell, 1000u128, 2000u128),
+        (OrderDirection::Sell, 500u128, 1000u128),
+        (OrderDirection::Buy, 1000u128, 1000u128),
+        (OrderDirection::Buy, 1000u128, 1500u128),
+    ];
+    for (direction, orai_amount, usdt_amount) in orders {
+        let funds = match direction {
+            OrderDirection::Buy => coins(usdt_amount, USDT_DENOM),
+            OrderDirection::Sell => coins(orai_amount, ORAI_DENOM),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(orai_amount),
                     },
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(usdt_amount),
                     },
                 ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
         )
-        .unwrap_err();
+        .unwrap();
+    }
+
+    let query_depth = |direction, limit| -> Vec<TickDepth> {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OrderBookDepth {
+                    asset_infos: asset_infos.clone(),
+                    direction,
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // sell side from the lowest price
     assert_eq!(
-        res,
-        StdError::GenericErr {
-            msg: "Querier contract error: oraiswap_limit_order::orderbook::OrderBook not found"
-                .to_string()
-        }
+        query_depth(OrderDirection::Sell, None),
+        vec![
+            TickDepth {
+                price: Decimal::from_str("2").unwrap(),
+                total_offer_amount: Uint128::from(1500u128),
+                total_ask_amount: Uint128::from(3000u128),
+                order_count: 2,
+            },
+            TickDepth {
+                price: Decimal::from_str("3").unwrap(),
+                total_offer_amount: Uint128::from(1000u128),
+                total_ask_amount: Uint128::from(3000u128),
+                order_count: 1,
+            },
+        ]
+    );
+
+    // buy side from the highest price
+    assert_eq!(
+        query_depth(OrderDirection::Buy, Some(1)),
+        vec![TickDepth {
+            price: Decimal::from_str("1.5").unwrap(),
+            total_offer_amount: Uint128::from(1500u128),
+            total_ask_amount: Uint128::from(1000u128),
+            order_count: 1,
+        }]
     );
 }
 
 #[test]
-fn orders_querier() {
-    let mut app = MockApp::new(&[
-        (
-            &"addr0000".to_string(),
-            &[
-                Coin {
-                    denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
-                },
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
+fn query_executor_rewards() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // an executor that never matched has a zero reward
+    let query_reward = |deps: Deps, executor: &str| {
+        from_binary::<ExecutorResponse>(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::ExecutorReward {
+                    asset_infos: asset_infos.clone(),
+                    executor: Addr::unchecked(executor),
                 },
-            ],
-        ),
-        (
-            &"addr0001".to_string(),
-            &[
-                Coin {
-                    denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        query_reward(deps.as_ref(), "addr0002"),
+        ExecutorResponse {
+            executor: Addr::unchecked("addr0002"),
+            reward_assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::zero(),
                 },
-                Coin {
-                    denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(1000000000u128),
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::zero(),
                 },
             ],
-        ),
-    ]);
-
-    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+            matched_volume: Uint128::zero(),
+        }
+    );
 
-    let token_addrs = app.set_token_balances(&[
+    for (sender, direction, funds) in [
         (
-            &"assetA".to_string(),
-            &[
-                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
-                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
-            ],
+            "addr0000",
+            OrderDirection::Sell,
+            coins(1000000u128, ORAI_DENOM),
         ),
         (
-            &"assetB".to_string(),
-            &[
-                (&"addr0000".to_string(), &Uint128::from(1000000000u128)),
-                (&"addr0001".to_string(), &Uint128::from(1000000000u128)),
-            ],
+            "addr0001",
+            OrderDirection::Buy,
+            coins(2000000u128, USDT_DENOM),
         ),
-    ]);
-
-    let msg = InstantiateMsg {
-        name: None,
-        version: None,
-        admin: None,
-        commission_rate: None,
-        reward_address: None,
-    };
-    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
-    let limit_order_addr = app
-        .instantiate(
-            code_id,
-            Addr::unchecked("addr0000"),
-            &msg,
-            &[],
-            "limit order",
-        )
-        .unwrap();
-
-    // create order book for pair [orai, atom]
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::NativeToken {
-            denom: ATOM_DENOM.to_string(),
-        },
-        quote_coin_info: AssetInfo::NativeToken {
-            denom: ORAI_DENOM.to_string(),
-        },
-        spread: Some(Decimal::percent(1)),
-        min_quote_coin_amount: Uint128::from(10u128),
-    };
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    // create order book for pair [token_addrs[1], token_addrs[0]]
-    let msg = ExecuteMsg::CreateOrderBookPair {
-        base_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[1].clone(),
-        },
-        quote_coin_info: AssetInfo::Token {
-            contract_addr: token_addrs[0].clone(),
-        },
-        spread: None,
-        min_quote_coin_amount: Uint128::zero(),
-    };
-    let _res = app.execute(
-        Addr::unchecked("addr0000"),
-        limit_order_addr.clone(),
-        &msg,
-        &[],
-    );
-
-    // query orderbooks
-    let res = app
-        .query::<OrderBookResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::OrderBook {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(1000000u128),
                     },
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(2000000u128),
                     },
                 ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
         )
         .unwrap();
-    println!("[LOG] 1st orderbooks :{}", jsonstr!(res));
-
-    // query all orderbooks
-    let res = app
-        .query::<OrderBooksResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::OrderBooks {
+    }
+    match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    // relayer fees stay accrued below the auto distribution threshold
+    let relayer_reward = query_reward(deps.as_ref(), "addr0002");
+    assert!(relayer_reward
+        .reward_assets
+        .iter()
+        .any(|asset| !asset.amount.is_zero()));
+
+    let executors: ExecutorsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Executors {
+                asset_infos: asset_infos.clone(),
                 start_after: None,
                 limit: None,
-                order_by: None,
             },
         )
-        .unwrap();
-
-    println!("orderbooks :{}", jsonstr!(res));
-
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Buy,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ATOM_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(1000000u128),
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(executors.executors.len(), 2);
+    assert!(executors.executors.contains(&relayer_reward));
+
+    // paginate past the first executor
+    let next_executors: ExecutorsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Executors {
+                asset_infos: asset_infos.clone(),
+                start_after: Some(executors.executors[0].executor.clone()),
+                limit: None,
             },
-        ],
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000000u128),
-            }],
         )
-        .unwrap();
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(next_executors.executors, executors.executors[1..].to_vec());
+}
 
-    // user sends token therefore no need to set allowance for limit order contract
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::from(1000000u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Buy,
-            assets: [
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
+#[test]
+fn self_trade_prevention() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // the same bidder sells then buys at the same price
+    for (direction, funds) in [
+        (OrderDirection::Sell, coins(1000000u128, ORAI_DENOM)),
+        (OrderDirection::Buy, coins(2000000u128, USDT_DENOM)),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(1000000u128),
                     },
-                    amount: Uint128::from(1000000u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(2000000u128),
                     },
-                    amount: Uint128::from(1000000u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let match_msg = ExecuteMsg::ExecuteOrderBookPair {
+        asset_infos: asset_infos.clone(),
+        limit: None,
+        best_effort: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        match_msg.clone(),
+    )
+    .unwrap();
+
+    // nothing is transferred and both orders keep resting
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.contains(&attr("self_trade_skipped", "2-1")));
+    assert!(res.attributes.contains(&attr("total_matched_orders", "0")));
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_ok());
+    assert!(read_order(deps.as_ref().storage, &pair_key, 2).is_ok());
+
+    // with stp_cancel_newest the later buy order is refunded and removed
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            reward_address: None,
+            commission_rate: None,
+            min_commission: None,
+            auto_distribute_threshold: None,
+            max_orders_per_match: None,
+            oracle_addr: None,
+            dust_threshold: None,
+            stp_cancel_newest: Some(true),
+            min_commission_rate: None,
+            max_commission_rate: None,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        match_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages
+            .iter()
+            .map(|msg| msg.msg.clone())
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(2000000u128, USDT_DENOM),
+        })]
+    );
+    assert!(res.attributes.contains(&attr("self_trade_skipped", "2-1")));
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_ok());
+    assert!(read_order(deps.as_ref().storage, &pair_key, 2).is_err());
+}
+
+#[test]
+fn self_trade_prevention_checks_actual_counterparty() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
+    // another bidder sells at 1 and the bidder sells at 1.5, then buys everything the book offers at 1 at 2
+    for (bidder, direction, base_amount, quote_amount, funds) in [
+        (
+            "addr0001",
+            OrderDirection::Sell,
+            1000000u128,
+            1000000u128,
+            coins(1000000u128, ORAI_DENOM),
+        ),
+        (
+            "addr0000",
+            OrderDirection::Sell,
+            1000000u128,
+            1500000u128,
+            coins(1000000u128, ORAI_DENOM),
+        ),
+        (
+            "addr0000",
+            OrderDirection::Buy,
+            1000000u128,
+            2000000u128,
+            coins(2000000u128, USDT_DENOM),
+        ),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(bidder, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(base_amount),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(quote_amount),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
         )
         .unwrap();
+    }
 
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::from(12345678u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
-            direction: OrderDirection::Sell,
-            assets: [
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
+    // the buy order crosses the own sell order but is filled by the other bidder first
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
+        },
+    )
+    .unwrap();
+    assert!(!res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "self_trade_skipped"));
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert!(read_order(deps.as_ref().storage, &pair_key, 1).is_err());
+    assert!(read_order(deps.as_ref().storage, &pair_key, 3).is_err());
+    let own_sell_order = read_order(deps.as_ref().storage, &pair_key, 2).unwrap();
+    assert_eq!(own_sell_order.status, OrderStatus::Open);
+    assert!(own_sell_order.filled_offer_amount.is_zero());
+}
+
+#[test]
+fn maker_taker_commission_split() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: Some("0".to_string()),
+            taker_commission_rate: Some("0.002".to_string()),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // order 1 rests first and is the maker, order 2 crosses it as the taker
+    for (sender, direction, funds) in [
+        (
+            "addr0000",
+            OrderDirection::Sell,
+            coins(1000000u128, ORAI_DENOM),
+        ),
+        (
+            "addr0001",
+            OrderDirection::Buy,
+            coins(2000000u128, USDT_DENOM),
+        ),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(1000000u128),
                     },
-                    amount: Uint128::from(11223344u128),
-                },
-                Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(2000000u128),
                     },
-                    amount: Uint128::from(12345678u128),
-                },
-            ],
-        })
-        .unwrap(),
-    };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
         )
         .unwrap();
+    }
 
-    let msg = cw20::Cw20ExecuteMsg::Send {
-        contract: limit_order_addr.to_string(),
-        amount: Uint128::from(22334455u128),
-        msg: to_binary(&Cw20HookMsg::SubmitOrder {
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    let maker_fill = result.fills.iter().find(|fill| fill.order_id == 1).unwrap();
+    let taker_fill = result.fills.iter().find(|fill| fill.order_id == 2).unwrap();
+    assert_eq!(maker_fill.status, OrderStatus::Fulfilled);
+    assert_eq!(taker_fill.status, OrderStatus::Fulfilled);
+    assert_eq!(maker_fill.reward_fee, Uint128::zero());
+    // 0.2% of the 1000000 orai bought
+    assert_eq!(taker_fill.reward_fee, Uint128::from(2000u128));
+    assert_eq!(
+        result.executor_reward,
+        [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(2000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::zero(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn amend_order_grow_and_shrink() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ExecuteMsg::SubmitOrder {
             direction: OrderDirection::Sell,
             assets: [
                 Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(22334455u128),
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000000u128),
                 },
                 Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(22000000u128),
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(2000000u128),
                 },
             ],
-        })
-        .unwrap(),
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        },
+    )
+    .unwrap();
+
+    let query_order = |deps: Deps| -> OrderResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Order {
+                    order_id: 1,
+                    asset_infos: asset_infos.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
     };
-
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0001"),
-            token_addrs[0].clone(),
-            &msg,
-            &[],
+    let query_sell_ticks = |deps: Deps| -> TicksResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Ticks {
+                    asset_infos: asset_infos.clone(),
+                    direction: OrderDirection::Sell,
+                    start_after: None,
+                    end: None,
+                    limit: None,
+                    order_by: None,
+                },
+            )
+            .unwrap(),
         )
-        .unwrap();
+        .unwrap()
+    };
 
-    let order_1 = OrderResponse {
-        order_id: 1u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(1000000u128),
-            info: AssetInfo::NativeToken {
-                denom: ORAI_DENOM.to_string(),
-            },
+    // grow: selling 1200000 orai at 3 needs 200000 more orai
+    let grow_msg = ExecuteMsg::AmendOrder {
+        order_id: 1,
+        asset_infos: asset_infos.clone(),
+        new_price: Decimal::from_str("3").unwrap(),
+        new_ask_amount: Uint128::from(3600000u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        grow_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(200000u128, ORAI_DENOM)),
+        grow_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(200000u128, ORAI_DENOM)),
+        grow_msg,
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    let order = query_order(deps.as_ref());
+    assert_eq!(order.offer_asset.amount, Uint128::from(1200000u128));
+    assert_eq!(order.ask_asset.amount, Uint128::from(3600000u128));
+    let ticks = query_sell_ticks(deps.as_ref());
+    assert_eq!(ticks.ticks.len(), 1);
+    assert_eq!(ticks.ticks[0].price, Decimal::from_str("3").unwrap());
+    assert_eq!(ticks.ticks[0].total_orders, 1);
+
+    // shrink: selling 500000 orai at 2 refunds 700000 orai
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AmendOrder {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            new_price: Decimal::from_str("2").unwrap(),
+            new_ask_amount: Uint128::from(1000000u128),
         },
-        ask_asset: Asset {
-            amount: Uint128::from(1000000u128),
-            info: AssetInfo::NativeToken {
-                denom: ATOM_DENOM.to_string(),
-            },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(700000u128, ORAI_DENOM),
+        })
+    );
+    let order = query_order(deps.as_ref());
+    assert_eq!(order.order_id, 1);
+    assert_eq!(order.offer_asset.amount, Uint128::from(500000u128));
+    assert_eq!(order.ask_asset.amount, Uint128::from(1000000u128));
+    let ticks = query_sell_ticks(deps.as_ref());
+    assert_eq!(ticks.ticks.len(), 1);
+    assert_eq!(ticks.ticks[0].price, Decimal::from_str("2").unwrap());
+
+    // a price too small to derive the offer from is rejected instead of panicking
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AmendOrder {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            new_price: Decimal::from_str("0.0000001").unwrap(),
+            new_ask_amount: Uint128::from(1000000u128),
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
-    };
+    )
+    .unwrap_err();
+    assert_eq!(
+        query_order(deps.as_ref()).offer_asset.amount,
+        Uint128::from(500000u128)
+    );
+}
 
-    let order_2 = OrderResponse {
-        order_id: 2u64,
-        bidder_addr: "addr0000".to_string(),
-        offer_asset: Asset {
-            amount: Uint128::from(1000000u128),
-            info: AssetInfo::Token {
-                contract_addr: token_addrs[0].clone(),
-            },
+#[test]
+fn min_fill_amount_skips_small_fills() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
         },
-        ask_asset: Asset {
-            amount: Uint128::from(1000000u128),
-            info: AssetInfo::Token {
-                contract_addr: token_addrs[1].clone(),
-            },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
         },
-        filled_offer_amount: Uint128::zero(),
-        filled_ask_amount: Uint128::zero(),
-        direction: OrderDirection::Buy,
-        status: OrderStatus::Open,
-    };
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
 
-    let all_order = OrdersResponse {
-        orders: [
-            OrderResponse {
-                order_id: 4u64,
-                direction: OrderDirection::Sell,
-                bidder_addr: "addr0001".to_string(),
-                offer_asset: Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(22334455u128),
-                },
-                ask_asset: Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(22000000u128),
-                },
-                filled_offer_amount: Uint128::zero(),
-                filled_ask_amount: Uint128::zero(),
-                status: OrderStatus::Open,
+    let submit_msg = |direction, orai_amount: u128, min_fill_amount| ExecuteMsg::SubmitOrder {
+        direction,
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(orai_amount),
             },
-            OrderResponse {
-                order_id: 3u64,
-                direction: OrderDirection::Sell,
-                bidder_addr: "addr0001".to_string(),
-                offer_asset: Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    amount: Uint128::from(12345678u128),
-                },
-                ask_asset: Asset {
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    amount: Uint128::from(11223344u128),
-                },
-                filled_offer_amount: Uint128::zero(),
-                filled_ask_amount: Uint128::zero(),
-                status: OrderStatus::Open,
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(orai_amount * 2),
             },
-            OrderResponse {
-                order_id: 2u64,
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount,
+        trigger_price: None,
+    };
+
+    // the minimum fill can not exceed the order itself
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(
+            OrderDirection::Sell,
+            1000000u128,
+            Some(Uint128::from(1000001u128)),
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMinFillAmount {
+            min_fill_amount: Uint128::from(1000001u128),
+            offer_amount: Uint128::from(1000000u128),
+        }
+    );
+
+    // order 1 sells 1000000 orai in fills of at least 500000
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(
+            OrderDirection::Sell,
+            1000000u128,
+            Some(Uint128::from(500000u128)),
+        ),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(200000u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 100000u128, None),
+    )
+    .unwrap();
+
+    // a 100000 orai buyer is too small to fill it
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    assert!(result.fills.is_empty());
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    assert_eq!(
+        read_order(deps.as_ref().storage, &pair_key, 1)
+            .unwrap()
+            .status,
+        OrderStatus::Open
+    );
+
+    // together with another 600000 orai buyer the fill is large enough
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1200000u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 600000u128, None),
+    )
+    .unwrap();
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    let fill = result.fills.iter().find(|fill| fill.order_id == 1).unwrap();
+    assert_eq!(fill.status, OrderStatus::PartialFilled);
+    assert_eq!(fill.filled_offer_amount, Uint128::from(700000u128));
+}
+
+#[test]
+fn query_orders_by_bidder_across_books() {
+    let orai_usdt = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let atom_usdt = [
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+
+    for asset_infos in [&orai_usdt, &atom_usdt] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::CreateOrderBookPair {
+                base_coin_info: asset_infos[0].clone(),
+                quote_coin_info: asset_infos[1].clone(),
+                spread: None,
+                min_quote_coin_amount: Uint128::from(10u128),
+                commission_rate: None,
+                precision: None,
+            },
+        )
+        .unwrap();
+    }
+
+    // order 1 and 3 belong to addr0000 in different books, order 2 to addr0001
+    for (sender, asset_infos) in [
+        ("addr0000", &orai_usdt),
+        ("addr0001", &orai_usdt),
+        ("addr0000", &atom_usdt),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &coins(1000000u128, USDT_DENOM)),
+            ExecuteMsg::SubmitOrder {
                 direction: OrderDirection::Buy,
-                bidder_addr: "addr0000".to_string(),
-                offer_asset: Asset {
-                    amount: Uint128::from(1000000u128),
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                },
-                ask_asset: Asset {
-                    amount: Uint128::from(1000000u128),
-                    info: AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                },
-                filled_offer_amount: Uint128::zero(),
-                filled_ask_amount: Uint128::zero(),
-                status: OrderStatus::Open,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(500000u128),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(1000000u128),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
-        ]
-        .to_vec(),
+        )
+        .unwrap();
+    }
+
+    let query_bidder_orders = |start_after, limit| -> BidderOrdersResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OrdersByBidder {
+                    bidder: "addr0000".to_string(),
+                    start_after,
+                    limit,
+                    order_by: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
     };
 
+    let res = query_bidder_orders(None, None);
     assert_eq!(
-        OrdersResponse {
-            orders: vec![order_2.clone(),],
+        res.orders
+            .iter()
+            .map(|item| (item.order.order_id, item.asset_infos.clone()))
+            .collect::<Vec<_>>(),
+        vec![(1, orai_usdt.clone()), (3, atom_usdt.clone())]
+    );
+    assert_eq!(res.orders[1].order.bidder_addr, "addr0000");
+
+    // the limit applies across books
+    let res = query_bidder_orders(None, Some(1));
+    assert_eq!(res.orders.len(), 1);
+    assert_eq!(res.orders[0].order.order_id, 1);
+    let res = query_bidder_orders(Some(1), Some(1));
+    assert_eq!(res.orders.len(), 1);
+    assert_eq!(res.orders[0].order.order_id, 3);
+}
+
+#[test]
+fn trigger_order_activated_by_mid_price() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
         },
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                ],
-                direction: None,
-                filter: OrderFilter::Bidder("addr0000".to_string()),
-                start_after: None,
-                limit: None,
-                order_by: Some(1),
-            }
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let submit_msg =
+        |direction, orai_amount: u128, usdt_amount: u128, trigger_price| ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(orai_amount),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(usdt_amount),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price,
+        };
+
+    // buy at 1 and sell at 3, so the mid price is 2
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 1000000, 1000000, None),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 1000000, 3000000, None),
+    )
+    .unwrap();
+
+    // an immediate order can not wait for a trigger
+    let mut ioc_msg = submit_msg(
+        OrderDirection::Sell,
+        1000000,
+        1500000,
+        Some(Decimal::from_str("1.8").unwrap()),
+    );
+    if let ExecuteMsg::SubmitOrder { order_type, .. } = &mut ioc_msg {
+        *order_type = Some(OrderType::Ioc);
+    }
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        ioc_msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTriggerOrderType {});
+
+    // sell stop at 1.5 once the mid price falls to 1.8
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(
+            OrderDirection::Sell,
+            1000000,
+            1500000,
+            Some(Decimal::from_str("1.8").unwrap()),
+        ),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("status", "Pending")));
+    // buy stop canceled before it is ever triggered
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2500000u128, USDT_DENOM)),
+        submit_msg(
+            OrderDirection::Buy,
+            1000000,
+            2500000,
+            Some(Decimal::from_str("2.5").unwrap()),
+        ),
+    )
+    .unwrap();
+
+    let query_order = |deps: Deps, order_id| -> OrderResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Order {
+                    order_id,
+                    asset_infos: asset_infos.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let query_pending_orders = |deps: Deps| -> OrdersResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::PendingOrders {
+                    asset_infos: asset_infos.clone(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let query_sell_ticks = |deps: Deps| -> TicksResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Ticks {
+                    asset_infos: asset_infos.clone(),
+                    direction: OrderDirection::Sell,
+                    start_after: None,
+                    end: None,
+                    limit: None,
+                    order_by: None,
+                },
+            )
+            .unwrap(),
         )
         .unwrap()
+    };
+
+    // pending orders stay out of the ticks but are escrowed
+    assert_eq!(query_order(deps.as_ref(), 3).status, OrderStatus::Pending);
+    assert_eq!(query_pending_orders(deps.as_ref()).orders.len(), 2);
+    assert_eq!(query_sell_ticks(deps.as_ref()).ticks.len(), 1);
+    let escrow: Asset = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TotalEscrow {
+                asset_info: asset_infos[0].clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(escrow.amount, Uint128::from(2000000u128));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelOrder {
+            order_id: 4,
+            asset_infos: asset_infos.clone(),
+            refund_to: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(2500000u128, USDT_DENOM),
+        })]
     );
 
-    let test = app
-        .query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                ],
-                direction: Some(OrderDirection::Buy),
-                filter: OrderFilter::None,
-                start_after: None,
-                limit: None,
-                order_by: None,
+    // mid price 2 is still above the trigger
+    let activate_msg = ExecuteMsg::ActivateTriggers {
+        asset_infos: asset_infos.clone(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        activate_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("activated_orders", "")));
+    assert_eq!(query_order(deps.as_ref(), 3).status, OrderStatus::Pending);
+
+    // a sell at 2 moves the mid price down to 1.5
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 1000000, 2000000, None),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        activate_msg,
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("mid_price", "1.5")));
+    assert!(res.attributes.contains(&attr("activated_orders", "3")));
+    assert_eq!(query_order(deps.as_ref(), 3).status, OrderStatus::Open);
+    assert!(query_pending_orders(deps.as_ref()).orders.is_empty());
+    assert_eq!(query_sell_ticks(deps.as_ref()).ticks.len(), 3);
+
+    // cancel all also refunds the trigger orders still waiting
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(2500000u128, USDT_DENOM)),
+        submit_msg(
+            OrderDirection::Buy,
+            1000000,
+            2500000,
+            Some(Decimal::from_str("2.5").unwrap()),
+        ),
+    )
+    .unwrap();
+    assert_eq!(query_pending_orders(deps.as_ref()).orders.len(), 1);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelAllOrders {
+            asset_infos: asset_infos.clone(),
+            direction: Some(OrderDirection::Buy),
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("cancelled_count", "1")));
+    assert!(res
+        .attributes
+        .contains(&attr("quote_refund", format!("2500000{}", USDT_DENOM))));
+    assert!(query_pending_orders(deps.as_ref()).orders.is_empty());
+}
+
+#[test]
+fn pair_commission_rate_override() {
+    let usdt_info = AssetInfo::NativeToken {
+        denom: USDT_DENOM.to_string(),
+    };
+    let orai_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        usdt_info.clone(),
+    ];
+    let atom_infos = [
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        usdt_info,
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+
+    // orai uses the default 0.1%, the volatile atom pair charges 1%
+    for (asset_infos, commission_rate) in [
+        (orai_infos.clone(), None),
+        (atom_infos.clone(), Some("0.01".to_string())),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::CreateOrderBookPair {
+                base_coin_info: asset_infos[0].clone(),
+                quote_coin_info: asset_infos[1].clone(),
+                spread: None,
+                min_quote_coin_amount: Uint128::from(10u128),
+                commission_rate,
+                precision: None,
             },
         )
         .unwrap();
-    println!("[LOG] [1] - query all buy order: {}", jsonstr!(test));
+    }
 
-    let test = app
-        .query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                ],
-                direction: Some(OrderDirection::Sell), //None
-                filter: OrderFilter::None,
-                start_after: None,
-                limit: None,
-                order_by: None,
+    let match_pair = |mut deps: DepsMut, asset_infos: [AssetInfo; 2]| {
+        for (sender, direction, funds) in [
+            (
+                "addr0000",
+                OrderDirection::Sell,
+                coins(1000000u128, asset_infos[0].to_string()),
+            ),
+            (
+                "addr0001",
+                OrderDirection::Buy,
+                coins(2000000u128, USDT_DENOM),
+            ),
+        ] {
+            execute(
+                deps.branch(),
+                mock_env(),
+                mock_info(sender, &funds),
+                ExecuteMsg::SubmitOrder {
+                    direction,
+                    assets: [
+                        Asset {
+                            info: asset_infos[0].clone(),
+                            amount: Uint128::from(1000000u128),
+                        },
+                        Asset {
+                            info: asset_infos[1].clone(),
+                            amount: Uint128::from(2000000u128),
+                        },
+                    ],
+                    post_only: None,
+                    client_order_id: None,
+                    fill_callback: None,
+                    expires_at: None,
+                    order_type: None,
+                    min_fill_amount: None,
+                    trigger_price: None,
+                },
+            )
+            .unwrap();
+        }
+
+        match_orders(
+            deps,
+            &mock_env(),
+            Addr::unchecked("addr0002"),
+            asset_infos,
+            None,
+            false,
+        )
+        .unwrap()
+        .executor_reward
+    };
+
+    let orai_reward = match_pair(deps.as_mut(), orai_infos.clone());
+    let atom_reward = match_pair(deps.as_mut(), atom_infos);
+    // commission on the 1000000 base bought and the 2000000 usdt sold
+    assert_eq!(orai_reward[0].amount, Uint128::from(1000u128));
+    assert_eq!(orai_reward[1].amount, Uint128::from(2000u128));
+    assert_eq!(atom_reward[0].amount, Uint128::from(10000u128));
+    assert_eq!(atom_reward[1].amount, Uint128::from(20000u128));
+
+    let update_msg = ExecuteMsg::UpdatePairCommission {
+        asset_infos: orai_infos.clone(),
+        commission_rate: Some("0.005".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_msg,
+    )
+    .unwrap();
+
+    let orderbook: OrderBookResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OrderBook {
+                asset_infos: orai_infos.clone(),
             },
         )
-        .unwrap();
-    println!("[LOG] [2] - query all sell order: {}", jsonstr!(test));
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(orderbook.commission_rate, Some("0.005".to_string()));
+    let orai_reward = match_pair(deps.as_mut(), orai_infos.clone());
+    assert_eq!(orai_reward[0].amount, Uint128::from(5000u128));
+    assert_eq!(orai_reward[1].amount, Uint128::from(10000u128));
+
+    let update_pair_commission = |deps: DepsMut, commission_rate: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::UpdatePairCommission {
+                asset_infos: orai_infos.clone(),
+                commission_rate: Some(commission_rate.to_string()),
+            },
+        )
+    };
+    let update_bounds = |deps: DepsMut, min: &str, max: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::UpdateConfig {
+                reward_address: None,
+                commission_rate: None,
+                min_commission: None,
+                auto_distribute_threshold: None,
+                max_orders_per_match: None,
+                oracle_addr: None,
+                dust_threshold: None,
+                stp_cancel_newest: None,
+                min_commission_rate: Some(Decimal::from_str(min).unwrap()),
+                max_commission_rate: Some(Decimal::from_str(max).unwrap()),
+            },
+        )
+    };
+
+    // a rate above one is rejected even without bounds
+    assert_eq!(
+        update_pair_commission(deps.as_mut(), "1.5").unwrap_err(),
+        ContractError::CommissionRateOutOfBounds {
+            commission_rate: "1.5".to_string()
+        }
+    );
 
-    let test = app
-        .query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
+    // an empty range and a ceiling above one are rejected
+    for (min, max) in [("0.01", "0.001"), ("0.001", "1.5")] {
+        assert_eq!(
+            update_bounds(deps.as_mut(), min, max).unwrap_err(),
+            ContractError::InvalidCommissionRateBounds {}
+        );
+    }
+
+    update_bounds(deps.as_mut(), "0.001", "0.01").unwrap();
+    for commission_rate in ["0.0001", "0.05"] {
+        assert_eq!(
+            update_pair_commission(deps.as_mut(), commission_rate).unwrap_err(),
+            ContractError::CommissionRateOutOfBounds {
+                commission_rate: commission_rate.to_string()
+            }
+        );
+    }
+    update_pair_commission(deps.as_mut(), "0.002").unwrap();
+}
+
+#[test]
+fn orderbook_matchable_volume() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let query_matchable = |deps: Deps| -> OrderBookMatchableResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::OrderBookMatchable {
+                    asset_infos: asset_infos.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        query_matchable(deps.as_ref()),
+        OrderBookMatchableResponse {
+            is_matchable: false,
+            best_buy_price: None,
+            best_sell_price: None,
+            matchable_volume: Uint128::zero(),
+        }
+    );
+
+    // sell 1000000 orai at 2, buy 600000 orai at 2.5
+    for (sender, direction, orai_amount, usdt_amount, funds) in [
+        (
+            "addr0000",
+            OrderDirection::Sell,
+            1000000u128,
+            2000000u128,
+            coins(1000000u128, ORAI_DENOM),
+        ),
+        (
+            "addr0001",
+            OrderDirection::Buy,
+            600000u128,
+            1500000u128,
+            coins(1500000u128, USDT_DENOM),
+        ),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(orai_amount),
                     },
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(usdt_amount),
                     },
                 ],
-                direction: None,
-                filter: OrderFilter::None,
-                start_after: None,
-                limit: None,
-                order_by: None,
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
         )
         .unwrap();
-    println!("[LOG] [3] - query all order: {}", jsonstr!(test));
+    }
 
+    // the buy side only takes 600000 of the 1000000 orai for sale
     assert_eq!(
-        OrdersResponse {
-            orders: vec![order_1.clone()],
+        query_matchable(deps.as_ref()),
+        OrderBookMatchableResponse {
+            is_matchable: true,
+            best_buy_price: Some(Decimal::from_str("2.5").unwrap()),
+            best_sell_price: Some(Decimal::from_str("2").unwrap()),
+            matchable_volume: Uint128::from(600000u128),
+        }
+    );
+
+    let result = match_orders(
+        deps.as_mut(),
+        &mock_env(),
+        Addr::unchecked("addr0002"),
+        asset_infos.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    let sell_fill = result.fills.iter().find(|fill| fill.order_id == 1).unwrap();
+    assert_eq!(sell_fill.filled_offer_amount, Uint128::from(600000u128));
+}
+
+#[test]
+fn submit_order_price_precision() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
         },
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
-                    },
-                ],
-                direction: None,
-                filter: OrderFilter::None,
-                start_after: None,
-                limit: None,
-                order_by: Some(1),
-            }
-        )
-        .unwrap()
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: Some(Decimal::from_str("0.01").unwrap()),
+        },
+    )
+    .unwrap();
+
+    let submit_msg = |usdt_amount: u128| ExecuteMsg::SubmitOrder {
+        direction: OrderDirection::Sell,
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(usdt_amount),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // 2.505 is between two steps of 0.01
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(2505000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPricePrecision {
+            price: Decimal::from_str("2.505").unwrap(),
+            precision: Decimal::from_str("0.01").unwrap(),
+        }
     );
 
-    // DESC test
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000000u128, ORAI_DENOM)),
+        submit_msg(2500000),
+    )
+    .unwrap();
+    let order = read_order(
+        deps.as_ref().storage,
+        &pair_key(&[
+            asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ]),
+        1,
+    )
+    .unwrap();
+    assert_eq!(order.get_price(), Decimal::from_str("2.5").unwrap());
+
+    // an amended price is held to the same precision
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AmendOrder {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            new_price: Decimal::from_str("2.499").unwrap(),
+            new_ask_amount: Uint128::from(2499000u128),
+        },
+    )
+    .unwrap_err();
     assert_eq!(
-        all_order.clone(),
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[1].clone(),
-                    },
-                    AssetInfo::Token {
-                        contract_addr: token_addrs[0].clone(),
-                    },
-                ],
-                direction: None,
-                filter: OrderFilter::None,
-                start_after: None,
-                limit: None,
-                order_by: Some(2),
-            }
+        err,
+        ContractError::InvalidPricePrecision {
+            price: Decimal::from_str("2.499").unwrap(),
+            precision: Decimal::from_str("0.01").unwrap(),
+        }
+    );
+
+    // a partial cancel whose rounded ask moves the order off the precision is rejected
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelOrderPartial {
+            order_id: 1,
+            asset_infos: asset_infos.clone(),
+            amount: Uint128::from(333333u128),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidPricePrecision { .. }));
+}
+
+#[test]
+fn execute_pair_limit_chunks_matching() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let submit_msg = |direction, orai_amount: u128| ExecuteMsg::SubmitOrder {
+        direction,
+        assets: [
+            Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(orai_amount),
+            },
+            Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(orai_amount * 2),
+            },
+        ],
+        post_only: None,
+        client_order_id: None,
+        fill_callback: None,
+        expires_at: None,
+        order_type: None,
+        min_fill_amount: None,
+        trigger_price: None,
+    };
+
+    // 49 sell orders and a buy order taking all of them, all at price 2
+    for _ in 0..49 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &coins(10000u128, ORAI_DENOM)),
+            submit_msg(OrderDirection::Sell, 10000),
         )
-        .unwrap()
+        .unwrap();
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins(980000u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 490000),
+    )
+    .unwrap();
+
+    // each call loads the buy order and 9 sell orders
+    let mut rounds = vec![];
+    loop {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0002", &[]),
+            ExecuteMsg::ExecuteOrderBookPair {
+                asset_infos: asset_infos.clone(),
+                limit: Some(10),
+                best_effort: None,
+            },
+        )
+        .unwrap();
+        let find_attr = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+        let orders_remaining = find_attr("orders_remaining");
+        rounds.push((find_attr("total_matched_orders"), orders_remaining.clone()));
+        if orders_remaining == "0" || rounds.len() > 10 {
+            break;
+        }
+    }
+    assert_eq!(
+        rounds,
+        [
+            ("10", "41"),
+            ("10", "32"),
+            ("10", "23"),
+            ("10", "14"),
+            ("10", "5"),
+            ("5", "0"),
+        ]
+        .map(|(matched, remaining)| (matched.to_string(), remaining.to_string()))
     );
+}
 
-    // different bidder
-    assert_eq!(
-        OrdersResponse { orders: vec![] },
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
+#[test]
+fn order_filled_events_reconstruct_trade() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    // order 1 sells 1000000 orai at 2, order 2 buys it
+    for (sender, direction, funds) in [
+        (
+            "addr0000",
+            OrderDirection::Sell,
+            coins(1000000u128, ORAI_DENOM),
+        ),
+        (
+            "addr0001",
+            OrderDirection::Buy,
+            coins(2000000u128, USDT_DENOM),
+        ),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &funds),
+            ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(1000000u128),
                     },
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(2000000u128),
                     },
                 ],
-                direction: None,
-                filter: OrderFilter::Bidder("addr0001".to_string()),
-                start_after: None,
-                limit: None,
-                order_by: None,
-            }
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
         )
-        .unwrap()
-    );
+        .unwrap();
+    }
 
-    // start after DESC
-    assert_eq!(
-        OrdersResponse {
-            orders: vec![order_1],
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos,
+            limit: None,
+            best_effort: None,
         },
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
-                    },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .contains(&attr("executor_reward", "1000orai,2000usdt")));
+
+    // order id => (match price, filled offer, filled ask, counterparty order id)
+    let trades: Vec<(String, [String; 4])> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "order_filled")
+        .map(|event| {
+            let find_attr = |key: &str| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            (
+                find_attr("order_id"),
+                [
+                    find_attr("match_price"),
+                    find_attr("filled_offer"),
+                    find_attr("filled_ask"),
+                    find_attr("counterparty_order_id"),
                 ],
-                direction: None,
-                filter: OrderFilter::None,
-                start_after: Some(2u64),
-                limit: None,
-                order_by: Some(2),
-            }
-        )
-        .unwrap()
-    );
+            )
+        })
+        .collect();
+    let trades: Vec<(&str, [&str; 4])> = trades
+        .iter()
+        .map(|(order_id, [price, offer, ask, counterparty])| {
+            (
+                order_id.as_str(),
+                [
+                    price.as_str(),
+                    offer.as_str(),
+                    ask.as_str(),
+                    counterparty.as_str(),
+                ],
+            )
+        })
+        .collect();
 
-    // start after ASC
+    // the buyer paid the 2000000 usdt the seller asked for the 1000000 orai
     assert_eq!(
-        OrdersResponse { orders: vec![] },
-        app.query::<OrdersResponse, _>(
-            limit_order_addr.clone(),
-            &QueryMsg::Orders {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
-                    },
-                ],
-                direction: None,
-                filter: OrderFilter::None,
-                start_after: Some(1u64),
-                limit: None,
-                order_by: Some(1),
-            }
-        )
-        .unwrap()
+        trades,
+        vec![
+            ("2", ["2", "2000000", "1000000", "1"]),
+            ("1", ["2", "1000000", "2000000", "2"]),
+        ]
     );
+}
 
-    // query all ticks
-    let res = app
-        .query::<TicksResponse, _>(
+#[test]
+fn mock_app_limit_order_helpers() {
+    let mut app = MockApp::new(&[
+        (
+            &"addr0000".to_string(),
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+        (
+            &"addr0001".to_string(),
+            &[Coin {
+                denom: USDT_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        ),
+    ]);
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+    app.set_limit_order_contract(Box::new(create_entry_points_testing!(crate)));
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+    let limit_order_addr = app
+        .create_orderbook_pair(asset_infos.clone(), Uint128::from(10u128))
+        .unwrap();
+    assert_eq!(limit_order_addr, app.limit_order_addr);
+
+    // the contract is wired to the oracle of the app
+    let contract_info: ContractInfoResponse = app
+        .query(limit_order_addr.clone(), &QueryMsg::ContractInfo {})
+        .unwrap();
+    assert_eq!(contract_info.oracle_addr, Some(app.oracle_addr.clone()));
+
+    // a crossing sell and buy of 10000 orai at price 2
+    for (sender, direction, denom, amount) in [
+        ("addr0000", OrderDirection::Sell, ORAI_DENOM, 10000u128),
+        ("addr0001", OrderDirection::Buy, USDT_DENOM, 20000u128),
+    ] {
+        app.execute(
+            Addr::unchecked(sender),
             limit_order_addr.clone(),
-            &QueryMsg::Ticks {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
+            &ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(10000u128),
                     },
-                    AssetInfo::NativeToken {
-                        denom: ATOM_DENOM.to_string(),
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(20000u128),
                     },
                 ],
-                direction: OrderDirection::Buy,
-                start_after: None,
-                end: None,
-                limit: None,
-                order_by: Some(1),
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
             },
+            &[Coin {
+                denom: denom.to_string(),
+                amount: Uint128::from(amount),
+            }],
         )
         .unwrap();
+    }
 
-    for tick in res.ticks {
-        let res = app
-            .query::<OrdersResponse, _>(
+    app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // both orders are fulfilled and have left the book
+    for order_id in [1u64, 2u64] {
+        assert!(app
+            .query::<OrderResponse, _>(
                 limit_order_addr.clone(),
-                &QueryMsg::Orders {
-                    asset_infos: [
-                        AssetInfo::NativeToken {
-                            denom: ORAI_DENOM.to_string(),
-                        },
-                        AssetInfo::NativeToken {
-                            denom: ATOM_DENOM.to_string(),
-                        },
-                    ],
-                    direction: None,
-                    filter: OrderFilter::Price(tick.price),
-                    start_after: None,
-                    limit: None,
-                    order_by: Some(1),
+                &QueryMsg::Order {
+                    order_id,
+                    asset_infos: asset_infos.clone(),
                 },
             )
-            .unwrap();
-        println!("{:?}", res);
+            .is_err());
     }
+    assert!(!app
+        .query_balance(Addr::unchecked("addr0000"), USDT_DENOM.to_string())
+        .unwrap()
+        .is_zero());
+    assert!(!app
+        .query_balance(Addr::unchecked("addr0001"), ORAI_DENOM.to_string())
+        .unwrap()
+        .is_zero());
 }
 
 #[test]
-fn test_query_ticks_start_after() {
-    let (mut app, limit_order_addr) = mock_basic_query_data();
-
-    /* <----------------------------------- order 1 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(20000u128),
-            },
-        ],
-    };
+fn orders_at_an_unrepresentable_price_cross() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    // both sides at 1 orai = 1/3 usdt, computed from different amounts
+    for (sender, direction, denom, base_amount, quote_amount) in [
+        (
+            "addr0000",
+            OrderDirection::Sell,
+            ORAI_DENOM,
+            3000000u128,
+            1000000u128,
+        ),
+        (
+            "addr0001",
+            OrderDirection::Buy,
+            USDT_DENOM,
+            300000u128,
+            100000u128,
+        ),
+    ] {
+        app.execute(
+            Addr::unchecked(sender),
             limit_order_addr.clone(),
-            &msg,
+            &ExecuteMsg::SubmitOrder {
+                direction,
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(base_amount),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(quote_amount),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
             &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
+                denom: denom.to_string(),
+                amount: Uint128::from(match direction {
+                    OrderDirection::Sell => base_amount,
+                    OrderDirection::Buy => quote_amount,
+                }),
             }],
         )
         .unwrap();
+    }
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
-            },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(30000u128),
+    // the prices of both sides floor to the same tick, so they cross
+    let res: OrderBookMatchableResponse = app
+        .query(
+            limit_order_addr.clone(),
+            &QueryMsg::OrderBookMatchable {
+                asset_infos: asset_infos.clone(),
             },
-        ],
-    };
+        )
+        .unwrap();
+    assert!(res.is_matchable);
+    assert_eq!(res.best_buy_price, res.best_sell_price);
+    assert_eq!(res.best_buy_price, Some(Decimal::from_ratio(1u128, 3u128)));
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+    app.execute(
+        Addr::unchecked("addr0002"),
+        limit_order_addr.clone(),
+        &ExecuteMsg::ExecuteOrderBookPair {
+            asset_infos: asset_infos.clone(),
+            limit: None,
+            best_effort: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the smaller buy order has left the book, the sell order is partially filled
+    let buy_order = app.query::<OrderResponse, _>(
+        limit_order_addr.clone(),
+        &QueryMsg::Order {
+            order_id: 2,
+            asset_infos: asset_infos.clone(),
+        },
+    );
+    assert!(buy_order.is_err());
+    let sell_order: OrderResponse = app
+        .query(
+            limit_order_addr.clone(),
+            &QueryMsg::Order {
+                order_id: 1,
+                asset_infos,
+            },
         )
         .unwrap();
+    assert_eq!(sell_order.status, OrderStatus::PartialFilled);
+    assert!(!sell_order.filled_offer_amount.is_zero());
+}
 
-    let result = app
-        .query::<TicksResponse, _>(
+#[test]
+fn distribute_executor_bonus_by_matched_volume() {
+    let (mut app, limit_order_addr) = basic_fixture();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    // a crossing sell and buy at price 2 matched by the executor
+    let match_orders = |app: &mut MockApp, executor: &str, base_amount: u128| {
+        for (sender, direction, denom, amount) in [
+            ("addr0000", OrderDirection::Sell, ORAI_DENOM, base_amount),
+            ("addr0001", OrderDirection::Buy, USDT_DENOM, base_amount * 2),
+        ] {
+            app.execute(
+                Addr::unchecked(sender),
+                limit_order_addr.clone(),
+                &ExecuteMsg::SubmitOrder {
+                    direction,
+                    assets: [
+                        Asset {
+                            info: asset_infos[0].clone(),
+                            amount: Uint128::from(base_amount),
+                        },
+                        Asset {
+                            info: asset_infos[1].clone(),
+                            amount: Uint128::from(base_amount * 2),
+                        },
+                    ],
+                    post_only: None,
+                    client_order_id: None,
+                    fill_callback: None,
+                    expires_at: None,
+                    order_type: None,
+                    min_fill_amount: None,
+                    trigger_price: None,
+                },
+                &[Coin {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(amount),
+                }],
+            )
+            .unwrap();
+        }
+        app.execute(
+            Addr::unchecked(executor),
             limit_order_addr.clone(),
-            &QueryMsg::Ticks {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-                direction: OrderDirection::Sell,
-                start_after: Some(Decimal::from_str("3").unwrap()),
-                end: None,
+            &ExecuteMsg::ExecuteOrderBookPair {
+                asset_infos: asset_infos.clone(),
                 limit: None,
-                order_by: Some(2),
+                best_effort: None,
             },
+            &[],
         )
         .unwrap();
-    assert_eq!(result.ticks.len(), 1);
+    };
+    let matched_volume = |app: &MockApp, executor: &str| {
+        app.query::<ExecutorResponse, _>(
+            limit_order_addr.clone(),
+            &QueryMsg::ExecutorReward {
+                asset_infos: asset_infos.clone(),
+                executor: Addr::unchecked(executor),
+            },
+        )
+        .unwrap()
+        .matched_volume
+    };
 
-    let result = app
-        .query::<TicksResponse, _>(
+    match_orders(&mut app, "addr0002", 10000);
+    match_orders(&mut app, "addr0003", 20000);
+    match_orders(&mut app, "addr0003", 10000);
+    assert_eq!(matched_volume(&app, "addr0002"), Uint128::from(10000u128));
+    assert_eq!(matched_volume(&app, "addr0003"), Uint128::from(30000u128));
+
+    let distribute = |app: &mut MockApp, sender: &str| {
+        app.execute(
+            Addr::unchecked(sender),
             limit_order_addr.clone(),
-            &QueryMsg::Ticks {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-                direction: OrderDirection::Sell,
-                start_after: Some(Decimal::from_str("2").unwrap()),
-                end: None,
-                limit: None,
-                order_by: Some(1),
+            &ExecuteMsg::DistributeExecutorBonus {
+                asset_infos: asset_infos.clone(),
+                bonus: Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000u128),
+                },
             },
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
         )
-        .unwrap();
-    assert_eq!(result.ticks.len(), 1);
+    };
+
+    // only admin can distribute the bonus
+    let res = distribute(&mut app, "addr0001");
+    app.assert_fail(res);
+
+    // 1 : 3 by matched volume, relayer fees stay accrued below the auto distribute threshold
+    distribute(&mut app, "addr0000").unwrap();
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0002"), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(250u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0003"), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(750u128)
+    );
+
+    // volumes restart from zero, nothing is left to share
+    assert!(matched_volume(&app, "addr0002").is_zero());
+    assert!(matched_volume(&app, "addr0003").is_zero());
+    let res = distribute(&mut app, "addr0000");
+    app.assert_fail(res);
 }
 
 #[test]
-fn test_unwrap_default_check_sub_uint128() {
-    let result = Uint128::from(0u64)
-        .checked_sub(Uint128::from(1u64))
-        .unwrap_or_default();
-    assert_eq!(result, Uint128::from(0u64));
+fn reject_orders_with_zero_price_or_offer() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+
+    let submit_msg = |direction: OrderDirection, base_amount: u128, quote_amount: u128| {
+        ExecuteMsg::SubmitOrder {
+            direction,
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(base_amount),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(quote_amount),
+                },
+            ],
+            post_only: None,
+            client_order_id: None,
+            fill_callback: None,
+            expires_at: None,
+            order_type: None,
+            min_fill_amount: None,
+            trigger_price: None,
+        }
+    };
+
+    // 10 usdt for 10^20 orai is below 10^-18 usdt per orai, the price floors to zero
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(10u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 100000000000000000000, 10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMustNotBeZero {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(100000000000000000000u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 100000000000000000000, 10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMustNotBeZero {});
+
+    // the smallest representable price is accepted
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(10000000000000000000u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 10000000000000000000, 10),
+    )
+    .unwrap();
+
+    // amending to a tiny ask at a large price derives a zero offer
+    let last_order_id = read_last_order_id(deps.as_ref().storage).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::AmendOrder {
+            order_id: last_order_id,
+            asset_infos: asset_infos.clone(),
+            new_price: Decimal::from_str("1000").unwrap(),
+            new_ask_amount: Uint128::from(10u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMustNotBeZero {});
 }
 
 #[test]
-fn test_query_ticks_with_end() {
-    let (mut app, limit_order_addr) = mock_basic_query_data();
+fn submit_order_by_quote_derives_the_base_amount() {
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: USDT_DENOM.to_string(),
+        },
+    ];
+
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            name: None,
+            version: None,
+            admin: None,
+            commission_rate: None,
+            reward_address: None,
+            maker_commission_rate: None,
+            taker_commission_rate: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset_infos[0].clone(),
+            quote_coin_info: asset_infos[1].clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+    )
+    .unwrap();
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ]);
+    let submit_msg = |direction: OrderDirection, quote_amount: u128, price: &str| {
+        ExecuteMsg::SubmitOrderByQuote {
+            direction,
+            base_coin_info: asset_infos[0].clone(),
+            quote_asset: Asset {
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(quote_amount),
+            },
+            price: Decimal::from_str(price).unwrap(),
+        }
+    };
 
-    /* <----------------------------------- order 1 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
+    // buy 100 usdt worth at exactly 0.3: 330 orai costs 99 usdt, the dust is refunded
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(100u128, USDT_DENOM)),
+        submit_msg(OrderDirection::Buy, 100, "0.3"),
+    )
+    .unwrap();
+    assert!(res.messages.contains(&SubMsg::new(BankMsg::Send {
+        to_address: "addr0000".to_string(),
+        amount: coins(1u128, USDT_DENOM),
+    })));
+    let order_id = read_last_order_id(deps.as_ref().storage).unwrap();
+    let order = read_order(deps.as_ref().storage, &pair_key, order_id).unwrap();
+    assert_eq!(order.direction, OrderDirection::Buy);
+    assert_eq!(order.offer_amount, Uint128::from(99u128));
+    assert_eq!(order.ask_amount, Uint128::from(330u128));
+    assert_eq!(order.get_price(), Decimal::from_str("0.3").unwrap());
+
+    // selling for 1000 usdt at 2 offers 500 orai, short funds are rejected
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(400u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 1000, "2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(600u128, ORAI_DENOM)),
+        submit_msg(OrderDirection::Sell, 1000, "2"),
+    )
+    .unwrap();
+    assert!(res.messages.contains(&SubMsg::new(BankMsg::Send {
+        to_address: "addr0000".to_string(),
+        amount: coins(100u128, ORAI_DENOM),
+    })));
+    let order_id = read_last_order_id(deps.as_ref().storage).unwrap();
+    let order = read_order(deps.as_ref().storage, &pair_key, order_id).unwrap();
+    assert_eq!(order.direction, OrderDirection::Sell);
+    assert_eq!(order.offer_amount, Uint128::from(500u128));
+    assert_eq!(order.ask_amount, Uint128::from(1000u128));
+    assert_eq!(order.get_price(), Decimal::from_ratio(2u128, 1u128));
+}
+
+#[test]
+fn create_orderbook_pair_and_seed() {
+    let mut app = MockApp::new(&[(
+        &APP_OWNER.to_string(),
+        &[
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )]);
+    app.set_limit_order_contract(Box::new(create_entry_points_testing!(crate)));
+    let limit_order_addr = app.limit_order_addr.clone();
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+    ];
+
+    // buy 100 atom at 2 and sell 100 atom at 3
+    let seed_orders: Vec<SubmitOrderItem> = [
+        (OrderDirection::Buy, 100u128, 200u128),
+        (OrderDirection::Sell, 100, 300),
+    ]
+    .into_iter()
+    .map(|(direction, atom_amount, orai_amount)| SubmitOrderItem {
+        direction,
         assets: [
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(atom_amount),
             },
             Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(20000u128),
+                info: asset_infos[1].clone(),
+                amount: Uint128::from(orai_amount),
             },
         ],
+    })
+    .collect();
+    let msg = ExecuteMsg::CreateOrderBookPairAndSeed {
+        base_coin_info: asset_infos[0].clone(),
+        quote_coin_info: asset_infos[1].clone(),
+        spread: None,
+        min_quote_coin_amount: Uint128::from(10u128),
+        commission_rate: Some("0.002".to_string()),
+        precision: Some(Decimal::one()),
+        seed_orders,
     };
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
-            limit_order_addr.clone(),
-            &msg,
-            &[Coin {
+    // funds short of the seed orders revert the pair creation too
+    let res = app.execute(
+        Addr::unchecked(APP_OWNER),
+        limit_order_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(99u128),
+            },
+            Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
-        )
-        .unwrap();
+                amount: Uint128::from(200u128),
+            },
+        ],
+    );
+    app.assert_fail(res);
+    let res = app.query::<OrderBookResponse, _>(
+        limit_order_addr.clone(),
+        &QueryMsg::OrderBook {
+            asset_infos: asset_infos.clone(),
+        },
+    );
+    assert!(res.is_err());
 
-    /* <----------------------------------- order 2 -----------------------------------> */
-    let msg = ExecuteMsg::SubmitOrder {
-        direction: OrderDirection::Sell,
-        assets: [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: ORAI_DENOM.to_string(),
-                },
-                amount: Uint128::from(10000u128),
+    app.execute(
+        Addr::unchecked(APP_OWNER),
+        limit_order_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(100u128),
             },
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: USDT_DENOM.to_string(),
-                },
-                amount: Uint128::from(30000u128),
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(200u128),
             },
         ],
-    };
+    )
+    .unwrap();
 
-    let _res = app
-        .execute(
-            Addr::unchecked("addr0000"),
+    let order_book = app
+        .query::<OrderBookResponse, _>(
             limit_order_addr.clone(),
-            &msg,
-            &[Coin {
-                denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(10000u128),
-            }],
+            &QueryMsg::OrderBook {
+                asset_infos: asset_infos.clone(),
+            },
         )
         .unwrap();
+    assert_eq!(order_book.commission_rate, Some("0.002".to_string()));
+    assert_eq!(order_book.precision, Some(Decimal::one()));
 
-    let result = app
-        .query::<TicksResponse, _>(
+    let best_prices = app
+        .query::<BestPricesResponse, _>(
             limit_order_addr.clone(),
-            &QueryMsg::Ticks {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-                direction: OrderDirection::Sell,
-                start_after: Some(Decimal::from_str("3").unwrap()),
-                end: Some(Decimal::from_str("2").unwrap()),
-                limit: None,
-                order_by: Some(2),
+            &QueryMsg::BestPrices {
+                asset_infos: asset_infos.clone(),
             },
         )
         .unwrap();
-    assert_eq!(result.ticks.len(), 1);
-    assert_eq!(result.ticks[0].price, Decimal::from_str("2").unwrap());
-
-    let result = app
-        .query::<TicksResponse, _>(
+    assert_eq!(
+        best_prices.best_buy,
+        Some(Decimal::from_ratio(2u128, 1u128))
+    );
+    assert_eq!(
+        best_prices.best_sell,
+        Some(Decimal::from_ratio(3u128, 1u128))
+    );
+    let orders = app
+        .query::<OrdersResponse, _>(
             limit_order_addr.clone(),
-            &QueryMsg::Ticks {
-                asset_infos: [
-                    AssetInfo::NativeToken {
-                        denom: ORAI_DENOM.to_string(),
-                    },
-                    AssetInfo::NativeToken {
-                        denom: USDT_DENOM.to_string(),
-                    },
-                ],
-                direction: OrderDirection::Sell,
-                start_after: Some(Decimal::from_str("2").unwrap()),
-                end: Some(Decimal::from_str("3").unwrap()),
+            &QueryMsg::Orders {
+                asset_infos: asset_infos.clone(),
+                direction: None,
+                filter: OrderFilter::Bidder(APP_OWNER.to_string()),
+                start_after: None,
                 limit: None,
-                order_by: Some(1),
+                order_by: None,
             },
         )
         .unwrap();
-    assert_eq!(result.ticks.len(), 1);
-    assert_eq!(result.ticks[0].price, Decimal::from_str("3").unwrap());
+    assert_eq!(orders.orders.len(), 2);
+
+    // the book exists now, seeding it again is rejected
+    let res = app.execute(
+        Addr::unchecked(APP_OWNER),
+        limit_order_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(100u128),
+            },
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(200u128),
+            },
+        ],
+    );
+    app.assert_fail(res);
 }
@@ -1,16 +1,27 @@
-use std::str::FromStr;
+use std::{cell::Cell, str::FromStr};
 
-use cosmwasm_std::{testing::mock_dependencies, Api, Decimal};
+use cosmwasm_std::{
+    attr,
+    testing::{mock_dependencies, mock_env, MockApi, MockStorage},
+    Api, Binary, Decimal, Deps, Record, Storage, Uint128,
+};
+use cosmwasm_storage::{Bucket, ReadonlyBucket};
 use oraiswap::{
-    asset::{AssetInfoRaw, ORAI_DENOM},
-    limit_order::OrderDirection,
+    asset::{AssetInfo, AssetInfoRaw, ORAI_DENOM},
+    limit_order::{MigrateMsg, OrderDirection, OrderFilter},
     testing::ATOM_DENOM,
 };
 
 use crate::{
+    contract::migrate,
+    order::{improve_match_price, query_orders},
     orderbook::{Order, OrderBook},
-    state::{increase_last_order_id, init_last_order_id},
-    tick::query_ticks_prices,
+    state::{
+        increase_last_order_id, init_last_order_id, read_orders, read_orders_with_indexer,
+        remove_order, store_order, store_orderbook, PREFIX_ORDER_BY_BIDDER,
+        PREFIX_ORDER_BY_DIRECTION, PREFIX_ORDER_BY_PRICE, PREFIX_TICK,
+    },
+    tick::{query_tick, query_ticks_prices},
 };
 
 #[test]
@@ -35,56 +46,64 @@ fn initialize() {
             OrderDirection::Buy,
             Decimal::from_str("10.01").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("10.00").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("9.999").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("9.999").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("9.998").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("9.998").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("9.997").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("9.996").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
     ];
 
     let mut ob = OrderBook::new(ask_info, offer_info, None);
@@ -150,35 +169,40 @@ fn buy_orders_at() {
             OrderDirection::Sell,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("1.0").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Buy,
             Decimal::from_str("1.2").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
     ];
 
     let mut ob = OrderBook::new(ask_info, offer_info, None);
@@ -239,35 +263,40 @@ fn sell_orders_at() {
             OrderDirection::Buy,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("1.1").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("1.0").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
         Order::new(
             increase_last_order_id(deps.as_mut().storage).unwrap(),
             bidder_addr.clone(),
             OrderDirection::Sell,
             Decimal::from_str("1.2").unwrap(),
             10000u128.into(),
-        ),
+        )
+        .unwrap(),
     ];
 
     let mut ob = OrderBook::new(ask_info, offer_info, None);
@@ -341,14 +370,16 @@ fn highest_lowest_price() {
                     OrderDirection::Buy,
                     Decimal::from_str("1.1").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
                 Order::new(
                     increase_last_order_id(deps.as_mut().storage).unwrap(),
                     bidder_addr.clone(),
                     OrderDirection::Buy,
                     Decimal::from_str("1.0").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
             ],
             highest_price: Decimal::from_str("1.1").unwrap(),
             lowest_price: Decimal::from_str("1.0").unwrap(),
@@ -362,14 +393,16 @@ fn highest_lowest_price() {
                     OrderDirection::Sell,
                     Decimal::from_str("1.1").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
                 Order::new(
                     increase_last_order_id(deps.as_mut().storage).unwrap(),
                     bidder_addr.clone(),
                     OrderDirection::Sell,
                     Decimal::from_str("1.0").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
             ],
             highest_price: Decimal::from_str("1.1").unwrap(),
             lowest_price: Decimal::from_str("1.0").unwrap(),
@@ -383,28 +416,32 @@ fn highest_lowest_price() {
                     OrderDirection::Sell,
                     Decimal::from_str("1.1").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
                 Order::new(
                     increase_last_order_id(deps.as_mut().storage).unwrap(),
                     bidder_addr.clone(),
                     OrderDirection::Sell,
                     Decimal::from_str("1.0").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
                 Order::new(
                     increase_last_order_id(deps.as_mut().storage).unwrap(),
                     bidder_addr.clone(),
                     OrderDirection::Buy,
                     Decimal::from_str("1.0").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
                 Order::new(
                     increase_last_order_id(deps.as_mut().storage).unwrap(),
                     bidder_addr.clone(),
                     OrderDirection::Buy,
                     Decimal::from_str("0.9").unwrap(),
                     10000u128.into(),
-                ),
+                )
+                .unwrap(),
             ],
             highest_price: Decimal::from_str("1.1").unwrap(),
             lowest_price: Decimal::from_str("0.9").unwrap(),
@@ -453,3 +490,630 @@ fn highest_lowest_price() {
         }
     }
 }
+
+#[test]
+fn order_indexes_consistent() {
+    let mut deps = mock_dependencies();
+
+    let offer_info = AssetInfoRaw::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let ask_info = AssetInfoRaw::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+
+    let bidder_addr = deps.api.addr_canonicalize("addr0000").unwrap();
+    let other_bidder_addr = deps.api.addr_canonicalize("addr0001").unwrap();
+    init_last_order_id(deps.as_mut().storage).unwrap();
+
+    let mut orders = vec![
+        Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            bidder_addr.clone(),
+            OrderDirection::Buy,
+            Decimal::from_str("1.1").unwrap(),
+            10000u128.into(),
+        )
+        .unwrap(),
+        Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            other_bidder_addr.clone(),
+            OrderDirection::Buy,
+            Decimal::from_str("1.1").unwrap(),
+            10000u128.into(),
+        )
+        .unwrap(),
+        Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            bidder_addr.clone(),
+            OrderDirection::Sell,
+            Decimal::from_str("1.2").unwrap(),
+            10000u128.into(),
+        )
+        .unwrap(),
+    ];
+
+    let mut ob = OrderBook::new(ask_info, offer_info, None);
+    for order in orders.iter() {
+        ob.add_order(deps.as_mut().storage, order).unwrap();
+    }
+    let pair_key = &ob.get_pair_key();
+
+    // partially fill an order, the update must keep every index untouched
+    orders[0].fill_order(1000u128.into(), 1100u128.into());
+    orders[0]
+        .match_order(deps.as_mut().storage, pair_key)
+        .unwrap();
+
+    let query_all = |storage: &dyn Storage, namespaces: &[&[u8]]| {
+        read_orders_with_indexer::<OrderDirection>(
+            storage,
+            namespaces,
+            Box::new(|_| true),
+            None,
+            None,
+            Some(cosmwasm_std::Order::Ascending),
+        )
+        .unwrap()
+        .unwrap()
+    };
+
+    let price_key = Decimal::from_str("1.1").unwrap().atomics().to_be_bytes();
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[PREFIX_ORDER_BY_PRICE, pair_key, &price_key]
+        ),
+        orders[0..=1]
+    );
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[PREFIX_ORDER_BY_BIDDER, pair_key, bidder_addr.as_slice()]
+        ),
+        vec![orders[0].clone(), orders[2].clone()]
+    );
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[
+                PREFIX_ORDER_BY_DIRECTION,
+                pair_key,
+                OrderDirection::Buy.as_bytes()
+            ]
+        ),
+        orders[0..=1]
+    );
+    assert_eq!(
+        read_orders(
+            deps.as_ref().storage,
+            pair_key,
+            None,
+            None,
+            Some(cosmwasm_std::Order::Ascending)
+        )
+        .unwrap(),
+        orders
+    );
+    assert_eq!(
+        query_tick(
+            deps.as_ref().storage,
+            pair_key,
+            OrderDirection::Buy,
+            Decimal::from_str("1.1").unwrap()
+        )
+        .unwrap()
+        .total_orders,
+        2
+    );
+
+    // removing an order drops it from every index
+    remove_order(deps.as_mut().storage, pair_key, &orders[0]).unwrap();
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[PREFIX_ORDER_BY_PRICE, pair_key, &price_key]
+        ),
+        orders[1..=1]
+    );
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[PREFIX_ORDER_BY_BIDDER, pair_key, bidder_addr.as_slice()]
+        ),
+        orders[2..=2]
+    );
+    assert_eq!(
+        query_all(
+            deps.as_ref().storage,
+            &[
+                PREFIX_ORDER_BY_DIRECTION,
+                pair_key,
+                OrderDirection::Buy.as_bytes()
+            ]
+        ),
+        orders[1..=1]
+    );
+    assert_eq!(
+        query_tick(
+            deps.as_ref().storage,
+            pair_key,
+            OrderDirection::Buy,
+            Decimal::from_str("1.1").unwrap()
+        )
+        .unwrap()
+        .total_orders,
+        1
+    );
+}
+
+#[test]
+fn match_price_never_worse_than_taker_limit() {
+    let buy_price = Decimal::from_str("1.1").unwrap();
+    let sell_price = Decimal::from_str("1.0").unwrap();
+
+    // prices inside the spread are kept as they are
+    assert_eq!(
+        improve_match_price(buy_price, buy_price, sell_price, OrderDirection::Buy),
+        buy_price
+    );
+    assert_eq!(
+        improve_match_price(sell_price, buy_price, sell_price, OrderDirection::Sell),
+        sell_price
+    );
+
+    // adverse case: a buy taker would pay above its limit
+    assert_eq!(
+        improve_match_price(
+            Decimal::from_str("1.2").unwrap(),
+            buy_price,
+            sell_price,
+            OrderDirection::Buy
+        ),
+        buy_price
+    );
+
+    // adverse case: a sell taker would receive below its limit
+    assert_eq!(
+        improve_match_price(
+            Decimal::from_str("0.9").unwrap(),
+            buy_price,
+            sell_price,
+            OrderDirection::Sell
+        ),
+        sell_price
+    );
+}
+
+#[test]
+fn migrate_recounts_tick_totals() {
+    let mut deps = mock_dependencies();
+
+    let offer_info = AssetInfoRaw::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let ask_info = AssetInfoRaw::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+
+    let bidder_addr = deps.api.addr_canonicalize("addr0000").unwrap();
+    init_last_order_id(deps.as_mut().storage).unwrap();
+
+    let mut ob = OrderBook::new(ask_info, offer_info, None);
+    let pair_key = &ob.get_pair_key();
+    store_orderbook(deps.as_mut().storage, pair_key, &ob).unwrap();
+
+    for (direction, price) in [
+        (OrderDirection::Buy, "1.1"),
+        (OrderDirection::Buy, "1.1"),
+        (OrderDirection::Sell, "1.25"),
+    ] {
+        let order = Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            bidder_addr.clone(),
+            direction,
+            Decimal::from_str(price).unwrap(),
+            10000u128.into(),
+        )
+        .unwrap();
+        ob.add_order(deps.as_mut().storage, &order).unwrap();
+    }
+
+    // corrupt the book: wrong buy total, lost sell total and a buy tick without orders
+    let price_key = |price: &str| Decimal::from_str(price).unwrap().atomics().to_be_bytes();
+    let buy_ticks = &[PREFIX_TICK, pair_key, OrderDirection::Buy.as_bytes()];
+    let sell_ticks = &[PREFIX_TICK, pair_key, OrderDirection::Sell.as_bytes()];
+    Bucket::multilevel(deps.as_mut().storage, buy_ticks)
+        .save(&price_key("1.1"), &5u64)
+        .unwrap();
+    Bucket::multilevel(deps.as_mut().storage, buy_ticks)
+        .save(&price_key("1.0"), &3u64)
+        .unwrap();
+    Bucket::<u64>::multilevel(deps.as_mut().storage, sell_ticks).remove(&price_key("1.25"));
+
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+
+    let total_orders = |storage: &dyn Storage, direction: OrderDirection, price: &str| {
+        query_tick(
+            storage,
+            pair_key,
+            direction,
+            Decimal::from_str(price).unwrap(),
+        )
+        .map(|tick| tick.total_orders)
+    };
+    assert_eq!(
+        total_orders(deps.as_ref().storage, OrderDirection::Buy, "1.1").unwrap(),
+        2
+    );
+    assert_eq!(
+        total_orders(deps.as_ref().storage, OrderDirection::Sell, "1.25").unwrap(),
+        1
+    );
+    assert!(total_orders(deps.as_ref().storage, OrderDirection::Buy, "1.0").is_err());
+    assert_eq!(
+        ob.highest_price(deps.as_ref().storage, OrderDirection::Buy),
+        (Decimal::from_str("1.1").unwrap(), true, 2)
+    );
+}
+
+#[test]
+fn migrate_resumes_after_last_pair_key() {
+    let mut deps = mock_dependencies();
+
+    let bidder_addr = deps.api.addr_canonicalize("addr0000").unwrap();
+    init_last_order_id(deps.as_mut().storage).unwrap();
+
+    let price = Decimal::from_str("1.1").unwrap();
+    let price_key = price.atomics().to_be_bytes();
+    let mut pair_keys = vec![];
+    for denom in [ATOM_DENOM, "usdt"] {
+        let mut ob = OrderBook::new(
+            AssetInfoRaw::NativeToken {
+                denom: denom.to_string(),
+            },
+            AssetInfoRaw::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            None,
+        );
+        let pair_key = ob.get_pair_key();
+        store_orderbook(deps.as_mut().storage, &pair_key, &ob).unwrap();
+        let order = Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            bidder_addr.clone(),
+            OrderDirection::Buy,
+            price,
+            10000u128.into(),
+        )
+        .unwrap();
+        ob.add_order(deps.as_mut().storage, &order).unwrap();
+
+        // corrupt the tick total of every book
+        Bucket::multilevel(
+            deps.as_mut().storage,
+            &[PREFIX_TICK, &pair_key, OrderDirection::Buy.as_bytes()],
+        )
+        .save(&price_key, &5u64)
+        .unwrap();
+        pair_keys.push(pair_key);
+    }
+    pair_keys.sort();
+
+    let total_orders = |storage: &dyn Storage, pair_key: &[u8]| {
+        query_tick(storage, pair_key, OrderDirection::Buy, price)
+            .unwrap()
+            .total_orders
+    };
+
+    // the first page only fixes the first book and reports where to resume
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: None,
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[1],
+        attr(
+            "last_pair_key",
+            Binary::from(pair_keys[0].clone()).to_base64()
+        )
+    );
+    assert_eq!(total_orders(deps.as_ref().storage, &pair_keys[0]), 1);
+    assert_eq!(total_orders(deps.as_ref().storage, &pair_keys[1]), 5);
+
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: Some(Binary::from(pair_keys[0].clone())),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(total_orders(deps.as_ref().storage, &pair_keys[1]), 1);
+
+    // nothing is left after the last book
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            start_after: Some(Binary::from(pair_keys[1].clone())),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "migrate")]);
+}
+
+#[test]
+fn round_price_to_precision() {
+    let mut ob = OrderBook::new(
+        AssetInfoRaw::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfoRaw::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        None,
+    );
+    let price = Decimal::from_str("2.505").unwrap();
+
+    // without precision any price is kept
+    assert_eq!(ob.round_price(price), price);
+
+    ob.precision = Some(Decimal::from_str("0.01").unwrap());
+    assert_eq!(ob.round_price(price), Decimal::from_str("2.5").unwrap());
+    assert_eq!(
+        ob.round_price(Decimal::from_str("2.5").unwrap()),
+        Decimal::from_str("2.5").unwrap()
+    );
+}
+
+#[test]
+fn query_orders_with_each_filter() {
+    let mut deps = mock_dependencies();
+
+    let base_info = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let quote_info = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+
+    let bidder_addr = deps.api.addr_canonicalize("addr0000").unwrap();
+    let other_bidder_addr = deps.api.addr_canonicalize("addr0001").unwrap();
+    init_last_order_id(deps.as_mut().storage).unwrap();
+
+    let mut ob = OrderBook::new(
+        base_info.to_raw(deps.as_ref().api).unwrap(),
+        quote_info.to_raw(deps.as_ref().api).unwrap(),
+        None,
+    );
+    let pair_key = &ob.get_pair_key();
+    store_orderbook(deps.as_mut().storage, pair_key, &ob).unwrap();
+
+    let mut orders = vec![];
+    for (bidder, direction, price) in [
+        (&bidder_addr, OrderDirection::Buy, "1.1"),
+        (&other_bidder_addr, OrderDirection::Buy, "1.1"),
+        (&bidder_addr, OrderDirection::Sell, "1.2"),
+    ] {
+        let order = Order::new(
+            increase_last_order_id(deps.as_mut().storage).unwrap(),
+            bidder.clone(),
+            direction,
+            Decimal::from_str(price).unwrap(),
+            10000u128.into(),
+        )
+        .unwrap();
+        ob.add_order(deps.as_mut().storage, &order).unwrap();
+        orders.push(order);
+    }
+
+    // partially fill an order, every filter must return the updated order
+    orders[0].fill_order(1000u128.into(), 1100u128.into());
+    orders[0]
+        .match_order(deps.as_mut().storage, pair_key)
+        .unwrap();
+
+    let query_ids = |deps: Deps, direction: Option<OrderDirection>, filter: OrderFilter| {
+        let res = query_orders(
+            deps,
+            [base_info.clone(), quote_info.clone()],
+            direction,
+            filter,
+            None,
+            None,
+            Some(1),
+        )
+        .unwrap();
+        res.orders
+            .into_iter()
+            .map(|order| (order.order_id, order.filled_offer_amount))
+            .collect::<Vec<_>>()
+    };
+
+    let bidder = OrderFilter::Bidder("addr0000".to_string());
+    let price = OrderFilter::Price(Decimal::from_str("1.1").unwrap());
+    let all = vec![
+        (1, Uint128::from(1100u128)),
+        (2, 0u128.into()),
+        (3, 0u128.into()),
+    ];
+
+    assert_eq!(
+        query_ids(deps.as_ref(), None, bidder.clone()),
+        vec![all[0], all[2]]
+    );
+    assert_eq!(
+        query_ids(deps.as_ref(), Some(OrderDirection::Sell), bidder.clone()),
+        vec![all[2]]
+    );
+    assert_eq!(query_ids(deps.as_ref(), None, price.clone()), all[0..=1]);
+    assert_eq!(
+        query_ids(deps.as_ref(), Some(OrderDirection::Sell), price.clone()),
+        vec![]
+    );
+    assert_eq!(
+        query_ids(deps.as_ref(), Some(OrderDirection::Buy), OrderFilter::None),
+        all[0..=1]
+    );
+    assert_eq!(query_ids(deps.as_ref(), None, OrderFilter::None), all);
+
+    // the tick index is keyed by price and holds totals, so it never resolves to orders
+    assert_eq!(
+        query_ids(deps.as_ref(), Some(OrderDirection::Buy), OrderFilter::Tick),
+        vec![]
+    );
+
+    // removing an order drops it from every filter
+    remove_order(deps.as_mut().storage, pair_key, &orders[0]).unwrap();
+    assert_eq!(query_ids(deps.as_ref(), None, bidder), vec![all[2]]);
+    assert_eq!(query_ids(deps.as_ref(), None, price), vec![all[1]]);
+    assert_eq!(
+        query_ids(deps.as_ref(), Some(OrderDirection::Buy), OrderFilter::None),
+        vec![all[1]]
+    );
+    assert_eq!(query_ids(deps.as_ref(), None, OrderFilter::None), all[1..]);
+}
+
+/// GasMeteredStorage charges the default cosmos-sdk KVStore gas costs for every storage access
+struct GasMeteredStorage {
+    storage: MockStorage,
+    gas_used: Cell<u64>,
+}
+
+impl GasMeteredStorage {
+    fn new() -> Self {
+        Self {
+            storage: MockStorage::new(),
+            gas_used: Cell::new(0),
+        }
+    }
+
+    fn charge(&self, gas: usize) {
+        self.gas_used.set(self.gas_used.get() + gas as u64);
+    }
+
+    fn take_gas_used(&self) -> u64 {
+        self.gas_used.replace(0)
+    }
+}
+
+impl Storage for GasMeteredStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.storage.get(key);
+        self.charge(1000 + 3 * (key.len() + value.as_ref().map_or(0, |v| v.len())));
+        value
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: cosmwasm_std::Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        Box::new(
+            self.storage
+                .range(start, end, order)
+                .inspect(|(key, value)| self.charge(30 + 3 * (key.len() + value.len()))),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.charge(2000 + 30 * (key.len() + value.len()));
+        self.storage.set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.charge(1000);
+        self.storage.remove(key)
+    }
+}
+
+#[test]
+fn store_order_gas_benchmark() {
+    let mut storage = GasMeteredStorage::new();
+    let api = MockApi::default();
+
+    let bidder_addr = api.addr_canonicalize("addr0000").unwrap();
+    init_last_order_id(&mut storage).unwrap();
+
+    let ob = OrderBook::new(
+        AssetInfoRaw::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        AssetInfoRaw::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        None,
+    );
+    let pair_key = &ob.get_pair_key();
+
+    let mut order = Order::new(
+        increase_last_order_id(&mut storage).unwrap(),
+        bidder_addr,
+        OrderDirection::Buy,
+        Decimal::from_str("1.1").unwrap(),
+        10000u128.into(),
+    )
+    .unwrap();
+    storage.take_gas_used();
+
+    // submit: the order, its tick total and the three secondary indexes
+    store_order(&mut storage, pair_key, &order, true).unwrap();
+    let submit_gas = storage.take_gas_used();
+
+    // update before the early return: every index and the tick total were rewritten unchanged
+    order.fill_order(1000u128.into(), 1100u128.into());
+    let price_key = order.get_price().atomics().to_be_bytes();
+    let order_id_key = &order.order_id.to_be_bytes();
+    store_order(&mut storage, pair_key, &order, false).unwrap();
+    let tick_namespaces = &[PREFIX_TICK, pair_key, order.direction.as_bytes()];
+    let total_tick_orders: u64 = ReadonlyBucket::multilevel(&storage, tick_namespaces)
+        .load(&price_key)
+        .unwrap();
+    Bucket::multilevel(&mut storage, tick_namespaces)
+        .save(&price_key, &total_tick_orders)
+        .unwrap();
+    for namespaces in [
+        [PREFIX_ORDER_BY_PRICE, pair_key, &price_key],
+        [
+            PREFIX_ORDER_BY_BIDDER,
+            pair_key,
+            order.bidder_addr.as_slice(),
+        ],
+        [
+            PREFIX_ORDER_BY_DIRECTION,
+            pair_key,
+            order.direction.as_bytes(),
+        ],
+    ] {
+        Bucket::multilevel(&mut storage, &namespaces)
+            .save(order_id_key, &order.direction)
+            .unwrap();
+    }
+    let update_gas_before = storage.take_gas_used();
+
+    // update after the early return: only the order itself is rewritten
+    store_order(&mut storage, pair_key, &order, false).unwrap();
+    let update_gas_after = storage.take_gas_used();
+
+    // the early return saves the tick total and the three index writes on every partial fill
+    assert_eq!(submit_gas, 40637);
+    assert_eq!(update_gas_before, 42420);
+    assert_eq!(update_gas_after, 17310);
+}
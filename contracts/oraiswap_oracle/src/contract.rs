@@ -5,22 +5,29 @@ use cosmwasm_std::{
     StdResult, Uint128,
 };
 
-use oraiswap::asset::ORAI_DENOM;
+use oraiswap::asset::{compute_tax_amount, ORAI_DENOM};
 use oraiswap::oracle::{
     ContractInfo, ContractInfoResponse, ExchangeRateItem, ExchangeRateResponse,
     ExchangeRatesResponse, ExecuteMsg, MigrateMsg, OracleContractQuery, OracleExchangeQuery,
-    OracleTreasuryQuery, QueryMsg, TaxCapResponse, TaxRateResponse,
+    OracleTreasuryQuery, QueryMsg, TaxCapResponse, TaxRateResponse, TaxResponse,
 };
 
 use oraiswap::error::ContractError;
 use oraiswap::oracle::InstantiateMsg;
 
 // use crate::msg::{ExecuteMsg, InstantiateMsg};
-use crate::state::{CONTRACT_INFO, EXCHANGE_RATES, TAX_CAP, TAX_RATE};
+use crate::state::{
+    FeederPrice, CONTRACT_INFO, EXCHANGE_RATES, EXCHANGE_RATE_UPDATED, FEEDER_PRICES, TAX_CAP,
+    TAX_RATE,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:oraiswap_oracle";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// tax can not take more than 10% of a transfer
+const DEFAULT_MAX_TAX_RATIO: u64 = 10;
+// feeder submissions older than 10 minutes are left out of the median
+const DEFAULT_FEED_TTL: u64 = 600;
 
 // whitelist of denom?
 // base on denom address as ow20 can call burn
@@ -46,7 +53,19 @@ pub fn instantiate(
             .min_rate
             .unwrap_or(Decimal::from_ratio(5u128, 10000u128)), // 0.05%
         max_rate: msg.max_rate.unwrap_or(Decimal::percent(1)), // 1%
+        max_tax_ratio: msg.max_tax_ratio,
+        max_staleness: msg.max_staleness,
+        feeders: msg
+            .feeders
+            .unwrap_or_default()
+            .iter()
+            .map(|feeder| deps.api.addr_canonicalize(feeder.as_str()))
+            .collect::<StdResult<_>>()?,
+        feed_ttl: msg.feed_ttl,
     };
+    if let Some(max_tax_ratio) = info.max_tax_ratio {
+        assert_max_tax_ratio(max_tax_ratio)?;
+    }
     CONTRACT_INFO.save(deps.storage, &info)?;
 
     // defaul is orai/orai 1:1 (no tax), this is for swap Orai native to Orai token
@@ -59,7 +78,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -67,14 +86,29 @@ pub fn execute(
         ExecuteMsg::UpdateExchangeRate {
             denom,
             exchange_rate,
-        } => execute_update_exchange_rate(deps, info, denom, exchange_rate),
+        } => execute_update_exchange_rate(deps, env, info, denom, exchange_rate),
         ExecuteMsg::DeleteExchangeRate { denom } => execute_delete_exchange_rate(deps, info, denom),
         ExecuteMsg::UpdateTaxCap { cap, denom } => execute_update_tax_cap(deps, info, denom, cap),
         ExecuteMsg::UpdateTaxRate { rate } => execute_update_tax_rate(deps, info, rate),
+        ExecuteMsg::UpdateMaxTaxRatio { ratio } => execute_update_max_tax_ratio(deps, info, ratio),
         ExecuteMsg::UpdateAdmin { admin } => execute_update_admin(deps, info, admin),
+        ExecuteMsg::UpdateMaxStaleness { max_staleness } => {
+            execute_update_max_staleness(deps, info, max_staleness)
+        }
+        ExecuteMsg::UpdateFeeders { feeders, feed_ttl } => {
+            execute_update_feeders(deps, info, feeders, feed_ttl)
+        }
+        ExecuteMsg::FeedPrice { denom, rate } => execute_feed_price(deps, env, info, denom, rate),
     }
 }
 
+fn assert_max_tax_ratio(ratio: Decimal) -> StdResult<()> {
+    if ratio > Decimal::one() {
+        return Err(StdError::generic_err("max tax ratio must not exceed 1"));
+    }
+    Ok(())
+}
+
 pub fn execute_update_tax_cap(
     deps: DepsMut,
     info: MessageInfo,
@@ -117,6 +151,109 @@ pub fn execute_update_tax_rate(
     Ok(Response::default())
 }
 
+pub fn execute_update_max_tax_ratio(
+    deps: DepsMut,
+    info: MessageInfo,
+    ratio: Decimal,
+) -> Result<Response, ContractError> {
+    let mut contract_info = CONTRACT_INFO.load(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check authorized
+    if contract_info.admin.ne(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    assert_max_tax_ratio(ratio)?;
+    contract_info.max_tax_ratio = Some(ratio);
+    CONTRACT_INFO.save(deps.storage, &contract_info)?;
+
+    Ok(Response::default())
+}
+
+pub fn execute_update_max_staleness(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_staleness: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut contract_info = CONTRACT_INFO.load(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check authorized
+    if contract_info.admin.ne(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    contract_info.max_staleness = max_staleness;
+    CONTRACT_INFO.save(deps.storage, &contract_info)?;
+
+    Ok(Response::default())
+}
+
+pub fn execute_update_feeders(
+    deps: DepsMut,
+    info: MessageInfo,
+    feeders: Vec<Addr>,
+    feed_ttl: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut contract_info = CONTRACT_INFO.load(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check authorized
+    if contract_info.admin.ne(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // submissions of removed feeders stay in storage but are no longer read
+    contract_info.feeders = feeders
+        .iter()
+        .map(|feeder| deps.api.addr_canonicalize(feeder.as_str()))
+        .collect::<StdResult<_>>()?;
+    if feed_ttl.is_some() {
+        contract_info.feed_ttl = feed_ttl;
+    }
+    CONTRACT_INFO.save(deps.storage, &contract_info)?;
+
+    Ok(Response::default())
+}
+
+pub fn execute_feed_price(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    rate: Decimal,
+) -> Result<Response, ContractError> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    let sender_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // check whitelisted
+    if !contract_info.feeders.contains(&sender_addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // a zero rate would be a division by zero when used as the base rate
+    if rate.is_zero() {
+        return Err(StdError::generic_err("rate must be greater than zero").into());
+    }
+
+    FEEDER_PRICES.save(
+        deps.storage,
+        (denom.as_bytes(), sender_addr.as_slice()),
+        &FeederPrice {
+            rate,
+            last_updated: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        ("action", "feed_price"),
+        ("feeder", info.sender.as_str()),
+        ("denom", &denom),
+        ("rate", &rate.to_string()),
+    ]))
+}
+
 pub fn execute_update_admin(
     deps: DepsMut,
     info: MessageInfo,
@@ -140,6 +277,7 @@ pub fn execute_update_admin(
 
 pub fn execute_update_exchange_rate(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     denom: String,
     exchange_rate: Decimal,
@@ -153,6 +291,7 @@ pub fn execute_update_exchange_rate(
     }
 
     EXCHANGE_RATES.save(deps.storage, denom.as_bytes(), &exchange_rate)?;
+    EXCHANGE_RATE_UPDATED.save(deps.storage, denom.as_bytes(), &env.block.time.seconds())?;
 
     Ok(Response::default())
 }
@@ -171,6 +310,7 @@ pub fn execute_delete_exchange_rate(
     }
 
     EXCHANGE_RATES.remove(deps.storage, denom.as_bytes());
+    EXCHANGE_RATE_UPDATED.remove(deps.storage, denom.as_bytes());
 
     Ok(Response::default())
 }
@@ -181,6 +321,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Treasury(query_data) => match query_data {
             OracleTreasuryQuery::TaxRate {} => to_binary(&query_tax_rate(deps)?),
             OracleTreasuryQuery::TaxCap { denom } => to_binary(&query_tax_cap(deps, denom)?),
+            OracleTreasuryQuery::Tax { amount, denom } => {
+                to_binary(&query_tax(deps, amount, denom)?)
+            }
         },
         QueryMsg::Exchange(query_data) => match query_data {
             OracleExchangeQuery::ExchangeRate {
@@ -188,6 +331,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 quote_denom,
             } => to_binary(&query_exchange_rate(
                 deps,
+                env,
                 base_denom.unwrap_or(ORAI_DENOM.to_string()),
                 quote_denom,
             )?),
@@ -196,6 +340,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 quote_denoms,
             } => to_binary(&query_exchange_rates(
                 deps,
+                env,
                 base_denom.unwrap_or(ORAI_DENOM.to_string()),
                 quote_denoms,
             )?),
@@ -229,14 +374,37 @@ pub fn query_tax_cap(deps: Deps, denom: String) -> StdResult<TaxCapResponse> {
     })
 }
 
+pub fn query_tax(deps: Deps, amount: Uint128, denom: String) -> StdResult<TaxResponse> {
+    if denom == ORAI_DENOM {
+        return Ok(TaxResponse {
+            tax: Uint128::zero(),
+        });
+    }
+
+    let info = CONTRACT_INFO.load(deps.storage)?;
+    let tax_rate = query_tax_rate(deps)?.rate;
+    let tax_cap = query_tax_cap(deps, denom)?.cap;
+
+    Ok(TaxResponse {
+        tax: compute_tax_amount(amount, tax_rate, tax_cap, get_max_tax_ratio(&info))?,
+    })
+}
+
+fn get_max_tax_ratio(info: &ContractInfo) -> Decimal {
+    info.max_tax_ratio
+        .unwrap_or(Decimal::percent(DEFAULT_MAX_TAX_RATIO))
+}
+
 pub fn query_exchange_rate(
     deps: Deps,
+    env: Env,
     base_denom: String,
     quote_denom: String,
 ) -> StdResult<ExchangeRateResponse> {
+    let info = CONTRACT_INFO.load(deps.storage)?;
     // quote = ask, offer = base
-    let base_rate = get_orai_exchange_rate(deps, &base_denom)?;
-    let quote_rate = get_orai_exchange_rate(deps, &quote_denom)?;
+    let base_rate = get_orai_exchange_rate(deps, &env, &info, &base_denom)?;
+    let quote_rate = get_orai_exchange_rate(deps, &env, &info, &quote_denom)?;
 
     let res = ExchangeRateResponse {
         base_denom: base_denom.clone(),
@@ -251,6 +419,7 @@ pub fn query_exchange_rate(
 
 pub fn query_exchange_rates(
     deps: Deps,
+    env: Env,
     base_denom: String,
     quote_denoms: Vec<String>,
 ) -> StdResult<ExchangeRatesResponse> {
@@ -259,10 +428,11 @@ pub fn query_exchange_rates(
         items: vec![],
     };
 
-    let base_rate = get_orai_exchange_rate(deps, &base_denom)?;
+    let info = CONTRACT_INFO.load(deps.storage)?;
+    let base_rate = get_orai_exchange_rate(deps, &env, &info, &base_denom)?;
 
     for quote_denom in quote_denoms {
-        let quote_rate = get_orai_exchange_rate(deps, &quote_denom)?;
+        let quote_rate = get_orai_exchange_rate(deps, &env, &info, &quote_denom)?;
 
         res.items.push(ExchangeRateItem {
             quote_denom,
@@ -275,6 +445,7 @@ pub fn query_exchange_rates(
 
 pub fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
     let info = CONTRACT_INFO.load(deps.storage)?;
+    let max_tax_ratio = get_max_tax_ratio(&info);
     Ok(ContractInfoResponse {
         version: info.version,
         name: info.name,
@@ -282,6 +453,14 @@ pub fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
         creator: deps.api.addr_humanize(&info.creator)?,
         min_rate: info.min_rate,
         max_rate: info.max_rate,
+        max_tax_ratio,
+        max_staleness: info.max_staleness,
+        feeders: info
+            .feeders
+            .iter()
+            .map(|feeder| deps.api.addr_humanize(feeder))
+            .collect::<StdResult<_>>()?,
+        feed_ttl: info.feed_ttl.unwrap_or(DEFAULT_FEED_TTL),
     })
 }
 
@@ -290,12 +469,70 @@ pub fn query_contract_balance(deps: Deps, env: Env, denom: String) -> StdResult<
     deps.querier.query_balance(env.contract.address, &denom)
 }
 
-fn get_orai_exchange_rate(deps: Deps, denom: &str) -> StdResult<Decimal> {
+fn get_orai_exchange_rate(
+    deps: Deps,
+    env: &Env,
+    info: &ContractInfo,
+    denom: &str,
+) -> StdResult<Decimal> {
     if denom == ORAI_DENOM {
         return Ok(Decimal::one());
     }
 
-    EXCHANGE_RATES.load(deps.storage, denom.as_bytes())
+    // the feeders take precedence, the admin rate is the fallback when none of them is fresh
+    if let Some(median_rate) = get_median_feeder_rate(deps, env, info, denom)? {
+        return Ok(median_rate);
+    }
+
+    let exchange_rate = EXCHANGE_RATES.load(deps.storage, denom.as_bytes())?;
+
+    // rates updated before the timestamps were stored have an unknown age, so they are stale
+    if let Some(max_staleness) = info.max_staleness {
+        let last_updated = EXCHANGE_RATE_UPDATED
+            .may_load(deps.storage, denom.as_bytes())?
+            .unwrap_or_default();
+        if env.block.time.seconds() > last_updated + max_staleness {
+            return Err(StdError::generic_err(format!(
+                "Exchange rate of {} is stale, last updated at {}",
+                denom, last_updated
+            )));
+        }
+    }
+
+    Ok(exchange_rate)
+}
+
+fn get_median_feeder_rate(
+    deps: Deps,
+    env: &Env,
+    info: &ContractInfo,
+    denom: &str,
+) -> StdResult<Option<Decimal>> {
+    let feed_ttl = info.feed_ttl.unwrap_or(DEFAULT_FEED_TTL);
+    let mut rates = vec![];
+    for feeder in info.feeders.iter() {
+        if let Some(price) =
+            FEEDER_PRICES.may_load(deps.storage, (denom.as_bytes(), feeder.as_slice()))?
+        {
+            if price.last_updated + feed_ttl >= env.block.time.seconds() {
+                rates.push(price.rate);
+            }
+        }
+    }
+
+    if rates.is_empty() {
+        return Ok(None);
+    }
+
+    rates.sort();
+    let mid = rates.len() / 2;
+    let median_rate = if rates.len() % 2 == 0 {
+        (rates[mid - 1] + rates[mid]) * Decimal::percent(50)
+    } else {
+        rates[mid]
+    };
+
+    Ok(Some(median_rate))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
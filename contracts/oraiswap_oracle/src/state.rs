@@ -1,3 +1,4 @@
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use oraiswap::oracle::ContractInfo;
@@ -10,3 +11,15 @@ pub const TAX_CAP: Map<&[u8], Uint128> = Map::new("tax_cap");
 /// Exchange rate of denom to Orai
 /// (QUOTE_DENOM / ORAI)  / (BASE_DENOM / ORAI) = QUOTE_DENOM / BASE_DENOM
 pub const EXCHANGE_RATES: Map<&[u8], Decimal> = Map::new("exchange_rates");
+/// block time in seconds of the last update of each exchange rate
+pub const EXCHANGE_RATE_UPDATED: Map<&[u8], u64> = Map::new("exchange_rate_updated");
+
+#[cw_serde]
+pub struct FeederPrice {
+    pub rate: Decimal,
+    /// block time in seconds of the submission
+    pub last_updated: u64,
+}
+
+/// latest submission of each feeder, keyed by (denom, feeder)
+pub const FEEDER_PRICES: Map<(&[u8], &[u8]), FeederPrice> = Map::new("feeder_prices");
@@ -1,11 +1,17 @@
-use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, StdError, Uint128,
+    WasmMsg,
+};
 
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::create_entry_points_testing;
-use oraiswap::oracle::{ExecuteMsg, OracleContract};
+use oraiswap::error::ContractError;
+use oraiswap::oracle::{ExecuteMsg, InstantiateMsg, OracleContract};
 use oraiswap::testing::{MockApp, APP_OWNER};
 
+use crate::contract::{execute, instantiate, query_exchange_rate};
+
 fn setup_contract() -> MockApp {
     let mut app = MockApp::new(&[(
         &APP_OWNER.to_string(),
@@ -193,3 +199,258 @@ fn test_asset() {
         })
     );
 }
+
+#[test]
+fn tax_clamped_by_max_tax_ratio() {
+    let mut app = setup_contract();
+
+    // an oracle that accepts an absurd tax rate of 10000%
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    app.oracle_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked(APP_OWNER),
+            &InstantiateMsg {
+                name: None,
+                version: None,
+                admin: None,
+                min_rate: None,
+                max_rate: Some(Decimal::percent(10000)),
+                max_tax_ratio: None,
+                max_staleness: None,
+                feeders: None,
+                feed_ttl: None,
+            },
+            &[],
+            "oracle",
+        )
+        .unwrap();
+    app.set_tax(
+        Decimal::percent(10000),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let orai_oracle = OracleContract(app.oracle_addr.clone());
+    let native_token_asset = Asset {
+        amount: Uint128::from(123123u128),
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    };
+
+    // without the clamp the tax would be 121904, default ratio keeps it at 10%
+    let tax = orai_oracle
+        .query_tax(&app.as_querier(), native_token_asset.amount, "uusd")
+        .unwrap()
+        .tax;
+    assert_eq!(tax, Uint128::from(12312u128));
+    assert_eq!(
+        native_token_asset
+            .compute_tax(&orai_oracle, &app.as_querier())
+            .unwrap(),
+        tax
+    );
+    assert_eq!(
+        native_token_asset
+            .into_msg(
+                Some(&orai_oracle),
+                &app.as_querier(),
+                Addr::unchecked("addr0000")
+            )
+            .unwrap(),
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".into(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(110811u128),
+            }]
+        })
+    );
+
+    // ratio above 1 is rejected
+    assert!(app
+        .execute(
+            Addr::unchecked(APP_OWNER),
+            app.oracle_addr.clone(),
+            &ExecuteMsg::UpdateMaxTaxRatio {
+                ratio: Decimal::percent(101),
+            },
+            &[],
+        )
+        .is_err());
+
+    app.execute(
+        Addr::unchecked(APP_OWNER),
+        app.oracle_addr.clone(),
+        &ExecuteMsg::UpdateMaxTaxRatio {
+            ratio: Decimal::percent(50),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        orai_oracle
+            .query_contract_info::<String>(&app.as_querier())
+            .unwrap()
+            .max_tax_ratio,
+        Decimal::percent(50)
+    );
+    assert_eq!(
+        orai_oracle
+            .query_tax(&app.as_querier(), native_token_asset.amount, "uusd")
+            .unwrap()
+            .tax,
+        Uint128::from(61561u128)
+    );
+}
+
+#[test]
+fn stale_exchange_rate() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        min_rate: None,
+        max_rate: None,
+        max_tax_ratio: None,
+        max_staleness: Some(60),
+        feeders: None,
+        feed_ttl: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(APP_OWNER, &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateExchangeRate {
+        denom: "usdt".to_string(),
+        exchange_rate: Decimal::percent(10),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(APP_OWNER, &[]), msg).unwrap();
+
+    // still fresh at the threshold
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let res = query_exchange_rate(
+        deps.as_ref(),
+        env.clone(),
+        "usdt".to_string(),
+        ORAI_DENOM.to_string(),
+    )
+    .unwrap();
+    assert_eq!(res.item.exchange_rate, Decimal::from_ratio(10u128, 1u128));
+
+    // orai is always 1:1, only the usdt rate is stale
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = query_exchange_rate(
+        deps.as_ref(),
+        env.clone(),
+        ORAI_DENOM.to_string(),
+        "usdt".to_string(),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(format!(
+            "Exchange rate of usdt is stale, last updated at {}",
+            mock_env().block.time.seconds()
+        )))
+    );
+
+    // a new rate refreshes the timestamp
+    let msg = ExecuteMsg::UpdateExchangeRate {
+        denom: "usdt".to_string(),
+        exchange_rate: Decimal::percent(20),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(APP_OWNER, &[]), msg).unwrap();
+    let res = query_exchange_rate(
+        deps.as_ref(),
+        env.clone(),
+        ORAI_DENOM.to_string(),
+        "usdt".to_string(),
+    )
+    .unwrap();
+    assert_eq!(res.item.exchange_rate, Decimal::percent(20));
+
+    // the check can be turned off by the admin
+    let msg = ExecuteMsg::UpdateMaxStaleness {
+        max_staleness: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(APP_OWNER, &[]), msg).unwrap();
+    env.block.time = env.block.time.plus_seconds(1000);
+    assert!(query_exchange_rate(
+        deps.as_ref(),
+        env,
+        ORAI_DENOM.to_string(),
+        "usdt".to_string()
+    )
+    .is_ok());
+}
+
+#[test]
+fn median_of_feeder_prices() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        name: None,
+        version: None,
+        admin: None,
+        min_rate: None,
+        max_rate: None,
+        max_tax_ratio: None,
+        max_staleness: None,
+        feeders: Some(vec![
+            Addr::unchecked("feeder1"),
+            Addr::unchecked("feeder2"),
+            Addr::unchecked("feeder3"),
+        ]),
+        feed_ttl: Some(100),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(APP_OWNER, &[]), msg).unwrap();
+
+    // admin rate, used while no feeder submission is fresh
+    let msg = ExecuteMsg::UpdateExchangeRate {
+        denom: "usdt".to_string(),
+        exchange_rate: Decimal::percent(10),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(APP_OWNER, &[]), msg).unwrap();
+
+    let feed_price = |deps: DepsMut, env: Env, feeder: &str, rate: Decimal| {
+        let msg = ExecuteMsg::FeedPrice {
+            denom: "usdt".to_string(),
+            rate,
+        };
+        execute(deps, env, mock_info(feeder, &[]), msg)
+    };
+    let usdt_rate = |deps: Deps, env: Env| {
+        query_exchange_rate(deps, env, ORAI_DENOM.to_string(), "usdt".to_string())
+            .unwrap()
+            .item
+            .exchange_rate
+    };
+
+    let res = feed_price(deps.as_mut(), mock_env(), "addr0000", Decimal::percent(12));
+    assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+    // feeder3 reports an outlier, the median ignores it
+    feed_price(deps.as_mut(), mock_env(), "feeder1", Decimal::percent(11)).unwrap();
+    feed_price(deps.as_mut(), mock_env(), "feeder2", Decimal::percent(12)).unwrap();
+    feed_price(deps.as_mut(), mock_env(), "feeder3", Decimal::percent(500)).unwrap();
+    assert_eq!(usdt_rate(deps.as_ref(), mock_env()), Decimal::percent(12));
+
+    // once the other submissions expired, the median of the two fresh ones is their average
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(101);
+    feed_price(deps.as_mut(), env.clone(), "feeder1", Decimal::percent(20)).unwrap();
+    assert_eq!(usdt_rate(deps.as_ref(), env.clone()), Decimal::percent(20));
+    feed_price(deps.as_mut(), env.clone(), "feeder2", Decimal::percent(30)).unwrap();
+    assert_eq!(usdt_rate(deps.as_ref(), env.clone()), Decimal::percent(25));
+
+    // a removed feeder is no longer counted
+    let msg = ExecuteMsg::UpdateFeeders {
+        feeders: vec![Addr::unchecked("feeder2"), Addr::unchecked("feeder3")],
+        feed_ttl: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(APP_OWNER, &[]), msg).unwrap();
+    assert_eq!(usdt_rate(deps.as_ref(), env.clone()), Decimal::percent(30));
+
+    // without fresh submissions the admin rate is served
+    env.block.time = env.block.time.plus_seconds(101);
+    assert_eq!(usdt_rate(deps.as_ref(), env), Decimal::percent(10));
+}
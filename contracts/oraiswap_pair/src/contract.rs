@@ -1,23 +1,27 @@
-use crate::state::PAIR_INFO;
+use crate::state::{
+    PriceCumulative, SwapVolume, CURVE, PAIR_INFO, PRICE_CUMULATIVE, PROTOCOL_FEE, SPREAD_POLICY,
+    SWAP_VOLUME, SWAP_VOLUME_LIMIT,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
     from_binary, to_binary, Addr, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Decimal256,
-    Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128,
-    Uint256, WasmMsg,
+    Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    Uint128, Uint256, WasmMsg,
 };
 
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use cw20_base::msg::InstantiateMsg as TokenInstantiateMsg;
-use integer_sqrt::IntegerSquareRoot;
-use oraiswap::asset::{Asset, AssetInfo, PairInfoRaw};
+use oraiswap::asset::{Asset, AssetInfo, PairInfoRaw, ORAI_DENOM};
 use oraiswap::error::ContractError;
+use oraiswap::math::Isqrt;
 use oraiswap::oracle::OracleContract;
 use oraiswap::pair::{
-    compute_offer_amount, compute_swap, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
-    PairResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
-    DEFAULT_COMMISSION_RATE,
+    CumulativePricesResponse, CurveType, Cw20HookMsg, ExecuteMsg, ImbalanceResponse,
+    InstantiateMsg, LpPriceResponse, MigrateMsg, PairResponse, PoolResponse, QueryMsg,
+    ReverseSimulationResponse, SimulationResponse, SpotPriceResponse, SpreadPolicy,
+    DEFAULT_COMMISSION_RATE, MAX_AMP, MINIMUM_LIQUIDITY,
 };
 use oraiswap::querier::query_supply;
 use oraiswap::response::MsgInstantiateContractResponse;
@@ -49,10 +53,49 @@ pub fn instantiate(
         commission_rate: msg
             .commission_rate
             .unwrap_or(DEFAULT_COMMISSION_RATE.to_string()),
+        enabled: true,
     };
 
     PAIR_INFO.save(deps.storage, pair_info)?;
 
+    if let Some(swap_volume_limit) = msg.swap_volume_limit {
+        if swap_volume_limit.window == 0 {
+            return Err(StdError::generic_err("swap volume window must be positive"));
+        }
+        SWAP_VOLUME_LIMIT.save(deps.storage, &swap_volume_limit)?;
+    }
+
+    if let Some(SpreadPolicy::Rebate { rebate_rate, .. }) = msg.spread_policy {
+        if rebate_rate > Decimal::one() {
+            return Err(StdError::generic_err("spread rebate rate cannot exceed 1"));
+        }
+    }
+    SPREAD_POLICY.save(
+        deps.storage,
+        &msg.spread_policy.unwrap_or(SpreadPolicy::Retain),
+    )?;
+
+    if let Some(CurveType::Stable { amp }) = msg.curve {
+        if amp == 0 || amp > MAX_AMP {
+            return Err(StdError::generic_err(format!(
+                "amp must be between 1 and {}",
+                MAX_AMP
+            )));
+        }
+    }
+    CURVE.save(
+        deps.storage,
+        &msg.curve.unwrap_or(CurveType::ConstantProduct),
+    )?;
+
+    if let Some(protocol_fee) = msg.protocol_fee {
+        if protocol_fee.rate > Decimal::one() {
+            return Err(StdError::generic_err("protocol fee rate cannot exceed 1"));
+        }
+        deps.api.addr_validate(protocol_fee.treasury.as_str())?;
+        PROTOCOL_FEE.save(deps.storage, &protocol_fee)?;
+    }
+
     Ok(Response::new().add_submessage(SubMsg::reply_on_success(
         WasmMsg::Instantiate {
             admin: None,
@@ -250,14 +293,38 @@ pub fn provide_liquidity(
         }
     }
 
+    update_price_cumulative(
+        deps.storage,
+        env.block.time.seconds(),
+        [pools[0].amount, pools[1].amount],
+    )?;
+
     // assert slippage tolerance
     assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
 
     let liquidity_token = deps.api.addr_humanize(&pair_info.liquidity_token)?;
-    let total_share = query_supply(&deps.querier, liquidity_token)?;
+    let total_share = query_supply(&deps.querier, liquidity_token.clone())?;
     let share = if total_share == Uint128::zero() {
         // Initial share = collateral amount
-        Uint128::from((deposits[0].u128() * deposits[1].u128()).integer_sqrt())
+        // widened, the product of two large deposits does not fit in 128 bits
+        let share =
+            Uint128::try_from((Uint256::from(deposits[0]) * Uint256::from(deposits[1])).isqrt()?)?;
+        if share <= MINIMUM_LIQUIDITY {
+            return Err(ContractError::MinimumLiquidity {
+                minimum_liquidity: MINIMUM_LIQUIDITY,
+            });
+        }
+
+        // the pair never sends its own LP balance, so these shares stay locked for good
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidity_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: env.contract.address.to_string(),
+                amount: MINIMUM_LIQUIDITY,
+            })?,
+            funds: vec![],
+        }));
+        share - MINIMUM_LIQUIDITY
     } else {
         // min(1, 2)
         // 1. sqrt(deposit_0 * exchange_rate_0_to_1 * deposit_0) * (total_share / sqrt(pool_0 * pool_1))
@@ -317,13 +384,30 @@ pub fn withdraw_liquidity(
         return Err(ContractError::InvalidZeroRatio {});
     }
 
-    let refund_assets: Vec<Asset> = pools
+    update_price_cumulative(
+        deps.storage,
+        env.block.time.seconds(),
+        [pools[0].amount, pools[1].amount],
+    )?;
+
+    // refund_amount = pool_amount * amount / total_share, computed in Uint256 so that
+    // only one rounding happens, and the last withdrawer drains the remaining dust
+    let refund_assets = pools
         .iter()
-        .map(|a| Asset {
-            info: a.info.clone(),
-            amount: a.amount * share_ratio,
+        .map(|a| -> StdResult<Asset> {
+            let refund_amount = if amount == total_share {
+                a.amount
+            } else {
+                Uint128::try_from(
+                    Uint256::from(a.amount) * Uint256::from(amount) / Uint256::from(total_share),
+                )?
+            };
+            Ok(Asset {
+                info: a.info.clone(),
+                amount: refund_amount,
+            })
         })
-        .collect();
+        .collect::<StdResult<Vec<Asset>>>()?;
 
     let oracle_contract = OracleContract(deps.api.addr_humanize(&pair_info.oracle_addr)?);
 
@@ -380,6 +464,7 @@ pub fn swap(
 
     let offer_pool: Asset;
     let ask_pool: Asset;
+    let offer_index: usize;
 
     // If the asset balance is already increased
     // To calculated properly we should subtract user deposit from the pool
@@ -389,24 +474,50 @@ pub fn swap(
             info: pools[0].info.clone(),
         };
         ask_pool = pools[1].clone();
+        offer_index = 0;
     } else if offer_asset.info.eq(&pools[1].info) {
         offer_pool = Asset {
             amount: pools[1].amount.checked_sub(offer_asset.amount)?,
             info: pools[1].info.clone(),
         };
         ask_pool = pools[0].clone();
+        offer_index = 1;
     } else {
         return Err(ContractError::AssetMismatch {});
     }
 
+    consume_swap_volume(
+        deps.storage,
+        env.block.height,
+        offer_index,
+        offer_asset.amount,
+    )?;
+
+    // accumulate with the reserves before this swap
+    let mut reserves = [pools[0].amount, pools[1].amount];
+    reserves[offer_index] = offer_pool.amount;
+    update_price_cumulative(deps.storage, env.block.time.seconds(), reserves)?;
+
     let commission_rate = Decimal256::from_str(&pair_info.commission_rate)?;
+    let curve = CURVE
+        .may_load(deps.storage)?
+        .unwrap_or(CurveType::ConstantProduct);
     let offer_amount = offer_asset.amount;
-    let (return_amount, spread_amount, commission_amount) = compute_swap(
+    let (return_amount, spread_amount, commission_amount) = curve.compute_swap(
         offer_pool.amount,
         ask_pool.amount,
         offer_amount,
         commission_rate,
     )?;
+    let (return_amount, spread_amount) = apply_spread_policy(
+        deps.storage,
+        &curve,
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        return_amount,
+        spread_amount,
+    )?;
 
     // check max spread limit if exist
     assert_max_spread(
@@ -417,6 +528,22 @@ pub fn swap(
         spread_amount,
     )?;
 
+    // the treasury cut of the commission leaves the pool too
+    let protocol_fee = PROTOCOL_FEE.may_load(deps.storage)?;
+    let protocol_fee_amount = protocol_fee
+        .as_ref()
+        .map_or(Uint128::zero(), |protocol_fee| {
+            commission_amount * protocol_fee.rate
+        });
+
+    assert_invariant(
+        &curve,
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        return_amount.checked_add(protocol_fee_amount)?,
+    )?;
+
     // compute tax
     let return_asset = Asset {
         info: ask_pool.info.clone(),
@@ -437,6 +564,21 @@ pub fn swap(
             receiver.clone(),
         )?);
     }
+    if let Some(protocol_fee) = protocol_fee {
+        if !protocol_fee_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: ask_pool.info.clone(),
+                    amount: protocol_fee_amount,
+                }
+                .into_msg(
+                    Some(&oracle_contract),
+                    &deps.querier,
+                    protocol_fee.treasury,
+                )?,
+            );
+        }
+    }
 
     // 1. send collateral token from the contract to a user
     // 2. send inactive commission to collector
@@ -451,20 +593,146 @@ pub fn swap(
         ("tax_amount", &tax_amount.to_string()),
         ("spread_amount", &spread_amount.to_string()),
         ("commission_amount", &commission_amount.to_string()),
+        ("protocol_fee_amount", &protocol_fee_amount.to_string()),
     ]))
 }
 
+/// Move part of the spread back to the trader when the pair rebates small swaps,
+/// returns the adjusted (return_amount, spread_amount)
+fn apply_spread_policy(
+    storage: &dyn Storage,
+    curve: &CurveType,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    spread_amount: Uint128,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let rebate_rate = match SPREAD_POLICY.may_load(storage)? {
+        Some(SpreadPolicy::Rebate {
+            max_offer_amount,
+            rebate_rate,
+        }) if offer_amount <= max_offer_amount => rebate_rate,
+        _ => return Ok((return_amount, spread_amount)),
+    };
+
+    let max_return = curve.max_return(offer_pool, ask_pool, offer_amount)?;
+
+    let rebate = Uint128::min(
+        spread_amount * rebate_rate,
+        max_return.saturating_sub(return_amount),
+    );
+
+    Ok((return_amount + rebate, spread_amount - rebate))
+}
+
+/// Accumulate the prices of the reserves about to change for the time they were in place
+fn update_price_cumulative(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    reserves: [Uint128; 2],
+) -> StdResult<()> {
+    let price_cumulative = match PRICE_CUMULATIVE.may_load(storage)? {
+        Some(price_cumulative) => accumulate_prices(price_cumulative, block_time, reserves),
+        // pairs created before the accumulator start from the current block
+        None => PriceCumulative {
+            last_block_time: block_time,
+            ..PriceCumulative::default()
+        },
+    };
+    PRICE_CUMULATIVE.save(storage, &price_cumulative)
+}
+
+/// like uniswap v2 the sums wrap on overflow, only the difference of two samples is meaningful
+fn accumulate_prices(
+    mut price_cumulative: PriceCumulative,
+    block_time: u64,
+    reserves: [Uint128; 2],
+) -> PriceCumulative {
+    let elapsed = Uint256::from(block_time.saturating_sub(price_cumulative.last_block_time));
+    if !elapsed.is_zero() && !reserves[0].is_zero() && !reserves[1].is_zero() {
+        let price0 = Decimal256::from_ratio(reserves[1], reserves[0]).atomics();
+        let price1 = Decimal256::from_ratio(reserves[0], reserves[1]).atomics();
+        price_cumulative.price0_cumulative = price_cumulative
+            .price0_cumulative
+            .wrapping_add(price0.wrapping_mul(elapsed));
+        price_cumulative.price1_cumulative = price_cumulative
+            .price1_cumulative
+            .wrapping_add(price1.wrapping_mul(elapsed));
+    }
+    price_cumulative.last_block_time = block_time;
+    price_cumulative
+}
+
+/// Track the offer volume of the current window and reject the swap if it exceeds the limit
+fn consume_swap_volume(
+    storage: &mut dyn Storage,
+    height: u64,
+    offer_index: usize,
+    offer_amount: Uint128,
+) -> Result<(), ContractError> {
+    let swap_volume_limit = match SWAP_VOLUME_LIMIT.may_load(storage)? {
+        Some(swap_volume_limit) => swap_volume_limit,
+        // unlimited
+        None => return Ok(()),
+    };
+
+    let mut swap_volume = SWAP_VOLUME.may_load(storage)?.unwrap_or_default();
+
+    // the window has rolled over, reset consumed volume
+    if height >= swap_volume.window_start + swap_volume_limit.window {
+        swap_volume = SwapVolume {
+            window_start: height,
+            volumes: [Uint128::zero(); 2],
+        };
+    }
+
+    let remaining = swap_volume_limit.max_volumes[offer_index]
+        .checked_sub(swap_volume.volumes[offer_index])
+        .unwrap_or_default();
+    if offer_amount > remaining {
+        return Err(ContractError::SwapVolumeExceeded { remaining });
+    }
+
+    swap_volume.volumes[offer_index] += offer_amount;
+    SWAP_VOLUME.save(storage, &swap_volume)?;
+
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Pair {} => Ok(to_binary(&query_pair_info(deps)?)?),
         QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps)?)?),
         QueryMsg::Simulation { offer_asset } => {
             Ok(to_binary(&query_simulation(deps, offer_asset)?)?)
         }
-        QueryMsg::ReverseSimulation { ask_asset } => {
-            Ok(to_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+        QueryMsg::ReverseSimulation {
+            ask_asset,
+            slippage,
+        } => Ok(to_binary(&query_reverse_simulation(
+            deps, ask_asset, slippage,
+        )?)?),
+        QueryMsg::Imbalance {} => Ok(to_binary(&query_imbalance(deps)?)?),
+        QueryMsg::LpPrice { reference_asset } => {
+            Ok(to_binary(&query_lp_price(deps, reference_asset)?)?)
+        }
+        QueryMsg::SpreadPolicy {} => Ok(to_binary(
+            &SPREAD_POLICY
+                .may_load(deps.storage)?
+                .unwrap_or(SpreadPolicy::Retain),
+        )?),
+        QueryMsg::SpotPrice { offer_asset_info } => {
+            Ok(to_binary(&query_spot_price(deps, offer_asset_info)?)?)
         }
+        QueryMsg::CumulativePrices {} => Ok(to_binary(&query_cumulative_prices(deps, env)?)?),
+        QueryMsg::ProtocolFee {} => Ok(to_binary(&PROTOCOL_FEE.may_load(deps.storage)?)?),
+        QueryMsg::Curve {} => Ok(to_binary(
+            &CURVE
+                .may_load(deps.storage)?
+                .unwrap_or(CurveType::ConstantProduct),
+        )?),
     }
 }
 
@@ -492,6 +760,147 @@ pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
     Ok(resp)
 }
 
+pub fn query_cumulative_prices(
+    deps: Deps,
+    env: Env,
+) -> Result<CumulativePricesResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let assets: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    // like uniswap v2 only the sums of the last update are returned, the first block after
+    // the accumulator starts has nothing to add yet
+    let price_cumulative =
+        PRICE_CUMULATIVE
+            .may_load(deps.storage)?
+            .unwrap_or_else(|| PriceCumulative {
+                last_block_time: env.block.time.seconds(),
+                ..PriceCumulative::default()
+            });
+
+    Ok(CumulativePricesResponse {
+        assets,
+        price0_cumulative: price_cumulative.price0_cumulative,
+        price1_cumulative: price_cumulative.price1_cumulative,
+        last_block_time: price_cumulative.last_block_time,
+    })
+}
+
+pub fn query_imbalance(deps: Deps) -> Result<ImbalanceResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let oracle = OracleContract(deps.api.addr_humanize(&pair_info.oracle_addr)?);
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    let mut values = [Uint128::zero(); 2];
+    for (value, pool) in values.iter_mut().zip(pools.iter()) {
+        *value = pool.amount * query_orai_rate(deps, &oracle, &pool.info)?;
+    }
+
+    let total_value = values[0].checked_add(values[1])?;
+    let (deviation, negative) = if total_value.is_zero() {
+        (Decimal::zero(), false)
+    } else {
+        let share = Decimal::from_ratio(values[0], total_value);
+        let half = Decimal::percent(50);
+        if share >= half {
+            (share - half, false)
+        } else {
+            (half - share, true)
+        }
+    };
+
+    Ok(ImbalanceResponse {
+        values,
+        deviation,
+        negative,
+    })
+}
+
+pub fn query_lp_price(
+    deps: Deps,
+    reference_asset: AssetInfo,
+) -> Result<LpPriceResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let total_share = query_supply(
+        &deps.querier,
+        deps.api.addr_humanize(&pair_info.liquidity_token)?,
+    )?;
+    if total_share.is_zero() {
+        return Ok(LpPriceResponse {
+            reference_asset,
+            price: Decimal::zero(),
+        });
+    }
+
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let oracle = OracleContract(deps.api.addr_humanize(&pair_info.oracle_addr)?);
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    let mut total_value = Uint128::zero();
+    for pool in pools.iter() {
+        total_value += pool.amount * query_orai_rate(deps, &oracle, &pool.info)?;
+    }
+
+    // orai value of one LP token converted into the reference asset
+    let price = Decimal::from_ratio(total_value, total_share)
+        .checked_div(query_orai_rate(deps, &oracle, &reference_asset)?)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(LpPriceResponse {
+        reference_asset,
+        price,
+    })
+}
+
+/// the oracle only prices native denoms, the rate of orai -> denom is the orai value of one unit
+fn query_orai_rate(
+    deps: Deps,
+    oracle: &OracleContract,
+    asset_info: &AssetInfo,
+) -> Result<Decimal, ContractError> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => oracle
+            .query_exchange_rate(&deps.querier, ORAI_DENOM.to_string(), denom.to_string())
+            .map(|res| res.item.exchange_rate)
+            .map_err(|_| ContractError::OracleRateNotFound {
+                asset: denom.to_string(),
+            }),
+        AssetInfo::Token { contract_addr } => Err(ContractError::OracleRateNotFound {
+            asset: contract_addr.to_string(),
+        }),
+    }
+}
+
+pub fn query_spot_price(
+    deps: Deps,
+    offer_asset_info: AssetInfo,
+) -> Result<SpotPriceResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+
+    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
+    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+
+    let (offer_pool, ask_pool) = if offer_asset_info.eq(&pools[0].info) {
+        (&pools[0], &pools[1])
+    } else if offer_asset_info.eq(&pools[1].info) {
+        (&pools[1], &pools[0])
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    let commission_rate = Decimal::from_str(&pair_info.commission_rate)?;
+    let curve = CURVE
+        .may_load(deps.storage)?
+        .unwrap_or(CurveType::ConstantProduct);
+    let spot_price = curve.spot_price(offer_pool.amount, ask_pool.amount)?;
+
+    Ok(SpotPriceResponse {
+        spot_price,
+        spot_price_with_commission: spot_price * (Decimal::one() - commission_rate),
+    })
+}
+
 pub fn query_simulation(
     deps: Deps,
     offer_asset: Asset,
@@ -514,12 +923,24 @@ pub fn query_simulation(
     }
 
     let commission_rate = Decimal256::from_str(&pair_info.commission_rate)?;
-    let (return_amount, spread_amount, commission_amount) = compute_swap(
+    let curve = CURVE
+        .may_load(deps.storage)?
+        .unwrap_or(CurveType::ConstantProduct);
+    let (return_amount, spread_amount, commission_amount) = curve.compute_swap(
         offer_pool.amount,
         ask_pool.amount,
         offer_asset.amount,
         commission_rate,
     )?;
+    let (return_amount, spread_amount) = apply_spread_policy(
+        deps.storage,
+        &curve,
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_asset.amount,
+        return_amount,
+        spread_amount,
+    )?;
 
     Ok(SimulationResponse {
         return_amount,
@@ -531,6 +952,7 @@ pub fn query_simulation(
 pub fn query_reverse_simulation(
     deps: Deps,
     ask_asset: Asset,
+    slippage: Option<Decimal>,
 ) -> Result<ReverseSimulationResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
 
@@ -550,12 +972,26 @@ pub fn query_reverse_simulation(
     }
 
     let commission_rate = Decimal256::from_str(&pair_info.commission_rate)?;
-    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
-        offer_pool.amount,
-        ask_pool.amount,
-        ask_asset.amount,
-        commission_rate,
-    )?;
+    let (offer_amount, spread_amount, commission_amount) = CURVE
+        .may_load(deps.storage)?
+        .unwrap_or(CurveType::ConstantProduct)
+        .compute_offer_amount(
+            offer_pool.amount,
+            ask_pool.amount,
+            ask_asset.amount,
+            commission_rate,
+        )?;
+
+    // add slippage buffer so the caller can escrow a safe offer amount for the actual swap
+    let offer_amount = match slippage {
+        Some(slippage) => {
+            if slippage > Decimal::one() {
+                return Err(ContractError::InvalidExceedOneSlippage {});
+            }
+            offer_amount.checked_add(offer_amount * slippage)?
+        }
+        None => offer_amount,
+    };
 
     Ok(ReverseSimulationResponse {
         offer_amount,
@@ -612,6 +1048,33 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// The commission stays in the pool and the return amount is floored,
+/// so the invariant of the curve after a swap can never be lower than before
+pub fn assert_invariant(
+    curve: &CurveType,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+) -> Result<(), ContractError> {
+    let old_k = curve.invariant(offer_pool, ask_pool)?;
+    let new_k = curve.invariant(
+        offer_pool.checked_add(offer_amount)?,
+        ask_pool.checked_sub(return_amount)?,
+    )?;
+
+    // the stable solver is only precise to one unit of D
+    let tolerance = match curve {
+        CurveType::ConstantProduct => Uint256::zero(),
+        CurveType::Stable { .. } => Uint256::one(),
+    };
+    if new_k + tolerance < old_k {
+        return Err(ContractError::InvariantViolation {});
+    }
+
+    Ok(())
+}
+
 fn assert_slippage_tolerance(
     slippage_tolerance: &Option<Decimal>,
     deposits: &[Uint128; 2],
@@ -627,6 +1090,15 @@ fn assert_slippage_tolerance(
         let deposits: [Uint256; 2] = [deposits[0].into(), deposits[1].into()];
         let pools: [Uint256; 2] = [pools[0].amount.into(), pools[1].amount.into()];
 
+        // the first deposit sets the pool ratio, there is no price to slip from yet
+        if pools.iter().any(|pool| pool.is_zero()) {
+            return Ok(());
+        }
+        // a one sided deposit into an existing pool deviates from any ratio
+        if deposits.iter().any(|deposit| deposit.is_zero()) {
+            return Err(ContractError::MaxSlippageAssertion {});
+        }
+
         // Ensure each prices are not dropped as much as slippage tolerance rate
         if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
             > Decimal256::from_ratio(pools[0], pools[1])
@@ -1,9 +1,38 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Uint128, Uint256};
 use cw_storage_plus::Item;
 use oraiswap::asset::PairInfoRaw;
+use oraiswap::pair::{CurveType, ProtocolFee, SpreadPolicy, SwapVolumeLimit};
 
 // put the length bytes at the first for compatibility with legacy singleton store
 pub const PAIR_INFO: Item<PairInfoRaw> = Item::new("\u{0}\u{9}pair_info");
 
+pub const SWAP_VOLUME_LIMIT: Item<SwapVolumeLimit> = Item::new("swap_volume_limit");
+pub const SWAP_VOLUME: Item<SwapVolume> = Item::new("swap_volume");
+pub const SPREAD_POLICY: Item<SpreadPolicy> = Item::new("spread_policy");
+// pairs instantiated before curves were selectable have none, they are constant product
+pub const CURVE: Item<CurveType> = Item::new("curve");
+pub const PROTOCOL_FEE: Item<ProtocolFee> = Item::new("protocol_fee");
+pub const PRICE_CUMULATIVE: Item<PriceCumulative> = Item::new("price_cumulative");
+
+/// consumed offer volume of the current window, indexed by the pair asset_infos order
+#[cw_serde]
+#[derive(Default)]
+pub struct SwapVolume {
+    pub window_start: u64,
+    pub volumes: [Uint128; 2],
+}
+
+/// time weighted sums of the pool prices, updated with the reserves in place before every change
+#[cw_serde]
+#[derive(Default)]
+pub struct PriceCumulative {
+    pub price0_cumulative: Uint256,
+    pub price1_cumulative: Uint256,
+    /// block time in seconds of the last update
+    pub last_block_time: u64,
+}
+
 #[cfg(test)]
 mod test {
 
@@ -41,6 +70,7 @@ mod test {
                 contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
                 liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
                 commission_rate: DEFAULT_COMMISSION_RATE.to_string(),
+                enabled: true,
             },
         )
         .unwrap();
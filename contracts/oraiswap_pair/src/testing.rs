@@ -1,10 +1,20 @@
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cosmwasm_std::{attr, to_binary, Addr, Coin, Decimal, Uint128};
+use std::str::FromStr;
+
+use crate::contract::{assert_invariant, assert_max_spread};
+use cosmwasm_std::{attr, to_binary, Addr, Coin, Decimal, Decimal256, StdResult, Uint128, Uint256};
 use cw20::Cw20ReceiveMsg;
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::create_entry_points_testing;
-use oraiswap::pair::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, PairResponse};
-use oraiswap::testing::{MockApp, ATOM_DENOM};
+use oraiswap::error::ContractError;
+use oraiswap::pair::{
+    compute_offer_amount, compute_swap, CumulativePricesResponse, CurveType, Cw20HookMsg,
+    ExecuteMsg, ImbalanceResponse, InstantiateMsg, LpPriceResponse, PairResponse, PoolResponse,
+    ProtocolFee, QueryMsg, ReverseSimulationResponse, SimulationResponse, SpotPriceResponse,
+    SpreadPolicy, SwapVolumeLimit, DEFAULT_COMMISSION_RATE, MINIMUM_LIQUIDITY,
+};
+use oraiswap::querier::{query_supply, query_token_balance};
+use oraiswap::testing::{MockApp, APP_OWNER, ATOM_DENOM};
 
 #[test]
 fn provide_liquidity_both_native() {
@@ -13,11 +23,11 @@ fn provide_liquidity_both_native() {
         &[
             Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(200u128),
+                amount: Uint128::from(20000u128),
             },
             Coin {
                 denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(200u128),
+                amount: Uint128::from(20000u128),
             },
         ],
     )]);
@@ -46,6 +56,10 @@ fn provide_liquidity_both_native() {
         ],
         token_code_id: app.token_id,
         commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -64,13 +78,13 @@ fn provide_liquidity_both_native() {
                 info: AssetInfo::NativeToken {
                     denom: ATOM_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -85,11 +99,11 @@ fn provide_liquidity_both_native() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
                 Coin {
                     denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
             ],
         )
@@ -106,7 +120,7 @@ fn provide_liquidity() {
         &MOCK_CONTRACT_ADDR.to_string(),
         &[Coin {
             denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(400u128),
+            amount: Uint128::from(40000u128),
         }],
     )]);
     app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
@@ -116,11 +130,11 @@ fn provide_liquidity() {
     app.set_token_balances(&[
         (
             &"liquidity".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100000u128))],
         ),
         (
             &"asset".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100000u128))],
         ),
     ]);
 
@@ -138,6 +152,10 @@ fn provide_liquidity() {
         ],
         token_code_id: app.token_id,
         commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -154,7 +172,7 @@ fn provide_liquidity() {
         asset_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
             expires: None,
         },
         &[],
@@ -168,13 +186,13 @@ fn provide_liquidity() {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -188,18 +206,18 @@ fn provide_liquidity() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
 
-    // set allowance one more 100
+    // set allowance one more 10000
     app.execute(
         Addr::unchecked(MOCK_CONTRACT_ADDR),
         asset_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
             expires: None,
         },
         &[],
@@ -212,20 +230,20 @@ fn provide_liquidity() {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(200u128),
+                amount: Uint128::from(20000u128),
             },
         ],
         slippage_tolerance: None,
         receiver: Some(Addr::unchecked("staking0000")), // try changing receiver
     };
 
-    // only accept 100, then 50 share will be generated with 100 * (100 / 200)
+    // only accept 10000, then 5000 share will be generated with 10000 * (10000 / 20000)
     let _res = app
         .execute(
             Addr::unchecked(MOCK_CONTRACT_ADDR),
@@ -233,7 +251,7 @@ fn provide_liquidity() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(200u128),
+                amount: Uint128::from(20000u128),
             }],
         )
         .unwrap();
@@ -245,13 +263,13 @@ fn provide_liquidity() {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(50u128),
+                amount: Uint128::from(5000u128),
             },
         ],
         slippage_tolerance: None,
@@ -264,7 +282,7 @@ fn provide_liquidity() {
         &msg,
         &[Coin {
             denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
         }],
     );
 
@@ -277,7 +295,7 @@ fn withdraw_liquidity() {
         &"addr0000".to_string(),
         &[Coin {
             denom: ORAI_DENOM.to_string(),
-            amount: Uint128::from(1000u128),
+            amount: Uint128::from(100000u128),
         }],
     )]);
 
@@ -292,7 +310,7 @@ fn withdraw_liquidity() {
 
     app.set_token_balances(&[(
         &"liquidity".to_string(),
-        &[(&"addr0000".to_string(), &Uint128::from(1000u128))],
+        &[(&"addr0000".to_string(), &Uint128::from(100000u128))],
     )]);
 
     let liquidity_addr = app.get_token_addr("liquidity").unwrap();
@@ -309,6 +327,10 @@ fn withdraw_liquidity() {
         ],
         token_code_id: app.token_id,
         commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
     };
 
     let pair_id = app.upload(Box::new(
@@ -325,7 +347,7 @@ fn withdraw_liquidity() {
         liquidity_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr.to_string(),
-            amount: Uint128::from(1000u128),
+            amount: Uint128::from(100000u128),
             expires: None,
         },
         &[],
@@ -338,13 +360,13 @@ fn withdraw_liquidity() {
                 info: AssetInfo::Token {
                     contract_addr: liquidity_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -352,7 +374,7 @@ fn withdraw_liquidity() {
         receiver: Some(pair_addr.clone()),
     };
 
-    // only accept 100, then 50 share will be generated with 100 * (100 / 200)
+    // the first deposit mints sqrt(10000 * 10000) = 10000 shares, 1000 of them locked in the pair
     let _res = app
         .execute(
             Addr::unchecked("addr0000"),
@@ -360,7 +382,7 @@ fn withdraw_liquidity() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
@@ -396,3 +418,1781 @@ fn withdraw_liquidity() {
         )
     );
 }
+
+#[test]
+fn provide_liquidity_locks_minimum_liquidity() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: asset_infos.clone(),
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .unwrap();
+    let PairResponse { info: pair_info } =
+        app.query(pair_addr.clone(), &QueryMsg::Pair {}).unwrap();
+
+    let provide = |app: &mut MockApp, amounts: [u128; 2]| {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(amounts[0]),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(amounts[1]),
+                    },
+                ],
+                slippage_tolerance: None,
+                receiver: None,
+            },
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(amounts[0]),
+                },
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(amounts[1]),
+                },
+            ],
+        )
+    };
+
+    // sqrt(1000 * 1000) would all be locked
+    let res = provide(&mut app, [1000, 1000]);
+    app.assert_fail(res);
+
+    // sqrt(4000 * 9000) = 6000 shares, 1000 of them stay with the pair
+    provide(&mut app, [4000, 9000]).unwrap();
+    let lp_balance = |app: &MockApp, addr: &str| {
+        query_token_balance(
+            &app.as_querier(),
+            pair_info.liquidity_token.clone(),
+            Addr::unchecked(addr),
+        )
+        .unwrap()
+    };
+    assert_eq!(lp_balance(&app, "addr0000"), Uint128::from(5000u128));
+    assert_eq!(lp_balance(&app, pair_addr.as_str()), MINIMUM_LIQUIDITY);
+
+    // the first provider exits completely, the locked shares keep their part of the reserves
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_info.liquidity_token.clone(),
+        &cw20::Cw20ExecuteMsg::Send {
+            contract: pair_addr.to_string(),
+            amount: Uint128::from(5000u128),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_supply(&app.as_querier(), pair_info.liquidity_token.clone()).unwrap(),
+        MINIMUM_LIQUIDITY
+    );
+    let res: PoolResponse = app.query(pair_addr.clone(), &QueryMsg::Pool {}).unwrap();
+    assert_eq!(res.assets[0].amount, Uint128::from(667u128));
+    // the oracle clamps the tax rate to its minimum, so the 4 atom tax on the 7500 refund stays too
+    assert_eq!(res.assets[1].amount, Uint128::from(1504u128));
+
+    // only the LP token can redeem shares, and the pair never sends its own balance
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: pair_addr.to_string(),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+            amount: MINIMUM_LIQUIDITY,
+        }),
+        &[],
+    );
+    app.assert_fail(res);
+    assert_eq!(lp_balance(&app, pair_addr.as_str()), MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn withdraw_liquidity_non_divisible_reserves() {
+    let mut app = MockApp::new(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(100000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(33300u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        ],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("owner"), &msg, &[], "pair")
+        .unwrap();
+
+    // initial share = sqrt(100000 * 33300) = 57706, 1000 of it locked and the rest kept by the pair
+    // so it can be withdrawn directly
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(100000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(33300u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: Some(pair_addr.clone()),
+    };
+    app.execute(
+        Addr::unchecked(MOCK_CONTRACT_ADDR),
+        pair_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(100000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(33300u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    let PairResponse { info: pair_info } =
+        app.query(pair_addr.clone(), &QueryMsg::Pair {}).unwrap();
+
+    // each refund is floored once, the dust and the 3 atom tax of every atom refund
+    // stay for the remaining holders
+    for (share, orai_refund, atom_refund) in [
+        (10000u128, 17329u128, 5770u128),
+        (10000, 17329, 5771),
+        (10000, 17329, 5772),
+        (10000, 17329, 5773),
+        (10000, 17329, 5775),
+        (6706, 11621, 3876),
+    ] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".into(),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {}).unwrap(),
+            amount: Uint128::from(share),
+        });
+        let res = app
+            .execute(
+                pair_info.liquidity_token.clone(),
+                pair_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            res.custom_attrs(1).get(3).expect("no log"),
+            &attr(
+                "refund_assets",
+                format!(
+                    "{}{}, {}{}",
+                    orai_refund, ORAI_DENOM, atom_refund, ATOM_DENOM
+                )
+            )
+        );
+    }
+
+    // only the reserves backing the locked shares are left in the pool
+    assert_eq!(
+        app.query_balance(pair_addr.clone(), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(1734u128)
+    );
+    assert_eq!(
+        app.query_balance(pair_addr, ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(580u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ORAI_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(98266u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(32720u128)
+    );
+}
+
+#[test]
+fn reverse_simulation_with_slippage() {
+    let mut app = MockApp::new(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        ],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("owner"), &msg, &[], "pair")
+        .unwrap();
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+
+    app.execute(
+        Addr::unchecked(MOCK_CONTRACT_ADDR),
+        pair_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    let ask_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+        amount: Uint128::from(10000u128),
+    };
+
+    let exact: ReverseSimulationResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::ReverseSimulation {
+                ask_asset: ask_asset.clone(),
+                slippage: None,
+            },
+        )
+        .unwrap();
+
+    let buffered: ReverseSimulationResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::ReverseSimulation {
+                ask_asset: ask_asset.clone(),
+                slippage: Some(Decimal::percent(10)),
+            },
+        )
+        .unwrap();
+
+    // offer is inflated by 10%, spread and commission are unchanged
+    assert!(buffered.offer_amount > exact.offer_amount);
+    assert_eq!(
+        buffered.offer_amount,
+        exact.offer_amount + exact.offer_amount * Decimal::percent(10)
+    );
+    assert_eq!(buffered.spread_amount, exact.spread_amount);
+    assert_eq!(buffered.commission_amount, exact.commission_amount);
+
+    // slippage can not exceed 100%
+    let res: StdResult<ReverseSimulationResponse> = app.query(
+        pair_addr,
+        &QueryMsg::ReverseSimulation {
+            ask_asset,
+            slippage: Some(Decimal::percent(101)),
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn swap_volume_limit() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    // at most 1000 orai and 1000 atom can be offered every 2 blocks
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        ],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: Some(SwapVolumeLimit {
+            window: 2,
+            max_volumes: [Uint128::from(1000u128), Uint128::from(1000u128)],
+        }),
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .unwrap();
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        },
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    let swap = |app: &mut MockApp, denom: &str, amount: u128| {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: denom.to_string(),
+                    },
+                    amount: Uint128::from(amount),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[Coin {
+                denom: denom.to_string(),
+                amount: Uint128::from(amount),
+            }],
+        )
+    };
+
+    // first swap opens the window
+    swap(&mut app, ORAI_DENOM, 800).unwrap();
+
+    // second large swap in the same window exceeds the remaining 200 orai
+    let res = swap(&mut app, ORAI_DENOM, 300);
+    app.assert_fail(res);
+
+    // the other asset has its own capacity, this also moves to the next window
+    swap(&mut app, ATOM_DENOM, 1000).unwrap();
+
+    // window has rolled over, smaller offers would round the return above the spot price
+    swap(&mut app, ORAI_DENOM, 500).unwrap();
+}
+
+#[test]
+fn query_imbalance() {
+    let mut app = MockApp::new(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(4000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        ],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("owner"), &msg, &[], "pair")
+        .unwrap();
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(4000u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    app.execute(
+        Addr::unchecked(MOCK_CONTRACT_ADDR),
+        pair_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(4000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    // atom has no rate in the oracle yet
+    let err = app
+        .query::<ImbalanceResponse, _>(pair_addr.clone(), &QueryMsg::Imbalance {})
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&format!("No oracle exchange rate found for {}", ATOM_DENOM)));
+
+    let set_atom_rate = |app: &mut MockApp, rate: Decimal| {
+        app.execute(
+            Addr::unchecked(APP_OWNER),
+            app.oracle_addr.clone(),
+            &oraiswap::oracle::ExecuteMsg::UpdateExchangeRate {
+                denom: ATOM_DENOM.to_string(),
+                exchange_rate: rate,
+            },
+            &[],
+        )
+        .unwrap();
+    };
+
+    // 1 atom = 2 orai: 10000 orai vs 8000 orai worth of atom, orai is over-weighted
+    set_atom_rate(&mut app, Decimal::from_ratio(2u128, 1u128));
+    let res: ImbalanceResponse = app
+        .query(pair_addr.clone(), &QueryMsg::Imbalance {})
+        .unwrap();
+    assert_eq!(
+        res.values,
+        [Uint128::from(10000u128), Uint128::from(8000u128)]
+    );
+    assert_eq!(res.deviation.to_string(), "0.055555555555555555");
+    assert!(!res.negative);
+
+    // 1 atom = 5 orai: 10000 orai vs 20000 orai worth of atom, orai is under-weighted
+    set_atom_rate(&mut app, Decimal::from_ratio(5u128, 1u128));
+    let res: ImbalanceResponse = app
+        .query(pair_addr.clone(), &QueryMsg::Imbalance {})
+        .unwrap();
+    assert_eq!(
+        res.values,
+        [Uint128::from(10000u128), Uint128::from(20000u128)]
+    );
+    assert_eq!(res.deviation.to_string(), "0.166666666666666667");
+    assert!(res.negative);
+}
+
+#[test]
+fn swap_invariant_check() {
+    let offer_pool = Uint128::from(1000000u128);
+    let ask_pool = Uint128::from(3000000u128);
+    let offer_amount = Uint128::from(50000u128);
+
+    // the real computation keeps the commission in the pool, so k grows
+    let (return_amount, _, _) = compute_swap(
+        offer_pool,
+        ask_pool,
+        offer_amount,
+        Decimal256::from_str(DEFAULT_COMMISSION_RATE).unwrap(),
+    )
+    .unwrap();
+    assert_invariant(
+        &CurveType::ConstantProduct,
+        offer_pool,
+        ask_pool,
+        offer_amount,
+        return_amount,
+    )
+    .unwrap();
+
+    // a faulty computation paying out at the spot price ignores the price impact
+    let faulty_compute_swap = |offer_pool: Uint128, ask_pool: Uint128, offer_amount: Uint128| {
+        offer_amount.multiply_ratio(ask_pool, offer_pool)
+    };
+    let return_amount = faulty_compute_swap(offer_pool, ask_pool, offer_amount);
+    assert_eq!(
+        assert_invariant(
+            &CurveType::ConstantProduct,
+            offer_pool,
+            ask_pool,
+            offer_amount,
+            return_amount,
+        ),
+        Err(ContractError::InvariantViolation {})
+    );
+}
+
+#[test]
+fn compute_swap_rejects_u128_overflow() {
+    let commission_rate = Decimal256::from_str(DEFAULT_COMMISSION_RATE).unwrap();
+
+    // half-full pools at the edge of u128 still compute exactly
+    let half = Uint128::from(1u128 << 127);
+    assert_eq!(
+        compute_swap(half, half, half, commission_rate).unwrap(),
+        (
+            Uint128::from(84815379955043912018246120902368226706u128),
+            Uint128::from(85070591730234615865843651857942052864u128),
+            Uint128::from(255211775190703847597530955573826158u128),
+        )
+    );
+
+    // the spread is far above u128::MAX
+    let err =
+        compute_swap(Uint128::one(), Uint128::MAX, Uint128::MAX, commission_rate).unwrap_err();
+    assert!(matches!(err, ContractError::ConversionOverflowError(_)));
+
+    // the floored product rounds the return above the spot amount
+    let err = compute_swap(
+        Uint128::from(999802u128),
+        Uint128::from(1000202u128),
+        Uint128::from(300u128),
+        commission_rate,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OverflowError(_)));
+
+    // buying half of a full pool costs about 1.006 times u128::MAX
+    let err = compute_offer_amount(
+        Uint128::MAX,
+        Uint128::MAX,
+        Uint128::MAX.multiply_ratio(1u128, 2u128),
+        commission_rate,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::ConversionOverflowError(_)));
+}
+
+#[test]
+fn max_spread_with_sub_one_belief_price() {
+    // the ask is four times cheaper than the offer, 1000 offer is believed to return 4000
+    let belief_price = Some(Decimal::from_str("0.25").unwrap());
+    let max_spread = Some(Decimal::percent(1));
+    let offer_amount = Uint128::from(1000u128);
+
+    // 0.25% below the belief
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_amount,
+        Uint128::from(3990u128),
+        Uint128::from(10u128),
+    )
+    .unwrap();
+
+    // 2.5% below the belief
+    assert_eq!(
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_amount,
+            Uint128::from(3900u128),
+            Uint128::from(100u128),
+        ),
+        Err(ContractError::MaxSpreadAssertion {})
+    );
+}
+
+#[test]
+fn spread_rebate_policy() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+
+    // both pairs hold 1000000 orai and 1000000 atom
+    let mut create_pair = |spread_policy: Option<SpreadPolicy>| {
+        let msg = InstantiateMsg {
+            oracle_addr: app.oracle_addr.clone(),
+            asset_infos: asset_infos.clone(),
+            token_code_id: app.token_id,
+            commission_rate: None,
+            swap_volume_limit: None,
+            spread_policy,
+            curve: None,
+            protocol_fee: None,
+        };
+        let pair_addr = app
+            .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+            .unwrap();
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::ProvideLiquidity {
+                assets: asset_infos.clone().map(|info| Asset {
+                    info,
+                    amount: Uint128::from(1000000u128),
+                }),
+                slippage_tolerance: None,
+                receiver: None,
+            },
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        )
+        .unwrap();
+        pair_addr
+    };
+    let retain_pair = create_pair(None);
+    let rebate_policy = SpreadPolicy::Rebate {
+        max_offer_amount: Uint128::from(50000u128),
+        rebate_rate: Decimal::percent(20),
+    };
+    let rebate_pair = create_pair(Some(rebate_policy.clone()));
+
+    let policy: SpreadPolicy = app
+        .query(retain_pair.clone(), &QueryMsg::SpreadPolicy {})
+        .unwrap();
+    assert_eq!(policy, SpreadPolicy::Retain);
+    let policy: SpreadPolicy = app
+        .query(rebate_pair.clone(), &QueryMsg::SpreadPolicy {})
+        .unwrap();
+    assert_eq!(policy, rebate_policy);
+
+    let simulate = |app: &MockApp, pair_addr: &Addr, amount: u128| -> SimulationResponse {
+        app.query(
+            pair_addr.clone(),
+            &QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(amount),
+                },
+            },
+        )
+        .unwrap()
+    };
+
+    // a small trade gets 20% of its 99 spread back
+    assert_eq!(
+        simulate(&app, &retain_pair, 10000),
+        SimulationResponse {
+            return_amount: Uint128::from(9872u128),
+            spread_amount: Uint128::from(99u128),
+            commission_amount: Uint128::from(29u128),
+        }
+    );
+    assert_eq!(
+        simulate(&app, &rebate_pair, 10000),
+        SimulationResponse {
+            return_amount: Uint128::from(9891u128),
+            spread_amount: Uint128::from(80u128),
+            commission_amount: Uint128::from(29u128),
+        }
+    );
+
+    // a trade above max_offer_amount is not rebated
+    assert_eq!(
+        simulate(&app, &rebate_pair, 100000),
+        simulate(&app, &retain_pair, 100000)
+    );
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        rebate_pair.clone(),
+        &ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(10000u128),
+            },
+            belief_price: None,
+            max_spread: None,
+            to: Some(Addr::unchecked("trader")),
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10000u128),
+        }],
+    )
+    .unwrap();
+    // the trader pays the 5 atom oracle tax on the rebated return
+    assert_eq!(
+        app.query_balance(Addr::unchecked("trader"), ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(9886u128)
+    );
+}
+
+#[test]
+fn stable_curve_slippage() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+
+    // both pairs hold 1000000 orai and 1000000 atom
+    let mut create_pair = |curve: Option<CurveType>| {
+        let msg = InstantiateMsg {
+            oracle_addr: app.oracle_addr.clone(),
+            asset_infos: asset_infos.clone(),
+            token_code_id: app.token_id,
+            commission_rate: None,
+            swap_volume_limit: None,
+            spread_policy: None,
+            curve,
+            protocol_fee: None,
+        };
+        let pair_addr = app
+            .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+            .unwrap();
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::ProvideLiquidity {
+                assets: asset_infos.clone().map(|info| Asset {
+                    info,
+                    amount: Uint128::from(1000000u128),
+                }),
+                slippage_tolerance: None,
+                receiver: None,
+            },
+            &[
+                Coin {
+                    denom: ORAI_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Coin {
+                    denom: ATOM_DENOM.to_string(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+        )
+        .unwrap();
+        pair_addr
+    };
+    let product_pair = create_pair(None);
+    let stable_pair = create_pair(Some(CurveType::Stable { amp: 100 }));
+
+    let curve: CurveType = app.query(stable_pair.clone(), &QueryMsg::Curve {}).unwrap();
+    assert_eq!(curve, CurveType::Stable { amp: 100 });
+
+    let simulate = |app: &MockApp, pair_addr: &Addr| -> SimulationResponse {
+        app.query(
+            pair_addr.clone(),
+            &QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(100000u128),
+                },
+            },
+        )
+        .unwrap()
+    };
+
+    // a 10% trade loses 9% to price impact on the constant product, almost nothing on the stable curve
+    assert_eq!(
+        simulate(&app, &product_pair),
+        SimulationResponse {
+            return_amount: Uint128::from(90638u128),
+            spread_amount: Uint128::from(9090u128),
+            commission_amount: Uint128::from(272u128),
+        }
+    );
+    assert_eq!(
+        simulate(&app, &stable_pair),
+        SimulationResponse {
+            return_amount: Uint128::from(99650u128),
+            spread_amount: Uint128::from(51u128),
+            commission_amount: Uint128::from(299u128),
+        }
+    );
+
+    let res: ReverseSimulationResponse = app
+        .query(
+            stable_pair.clone(),
+            &QueryMsg::ReverseSimulation {
+                ask_asset: Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(99650u128),
+                },
+                slippage: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::from(100000u128),
+            spread_amount: Uint128::from(51u128),
+            commission_amount: Uint128::from(299u128),
+        }
+    );
+
+    // the swap keeps the stable invariant
+    app.execute(
+        Addr::unchecked("addr0000"),
+        stable_pair.clone(),
+        &ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(100000u128),
+            },
+            belief_price: None,
+            max_spread: None,
+            to: Some(Addr::unchecked("trader")),
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(100000u128),
+        }],
+    )
+    .unwrap();
+    // the trader pays the 50 atom oracle tax on the return
+    assert_eq!(
+        app.query_balance(Addr::unchecked("trader"), ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(99600u128)
+    );
+
+    // amp must be positive
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: asset_infos.clone(),
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: Some(CurveType::Stable { amp: 0 }),
+        protocol_fee: None,
+    };
+    assert!(app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .is_err());
+}
+
+#[test]
+fn protocol_fee_to_treasury() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+
+    let protocol_fee = ProtocolFee {
+        rate: Decimal::percent(50),
+        treasury: Addr::unchecked("treasury"),
+    };
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: asset_infos.clone(),
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: Some(protocol_fee.clone()),
+    };
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .unwrap();
+    let res: Option<ProtocolFee> = app
+        .query(pair_addr.clone(), &QueryMsg::ProtocolFee {})
+        .unwrap();
+    assert_eq!(res, Some(protocol_fee));
+
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: asset_infos.clone().map(|info| Asset {
+                info,
+                amount: Uint128::from(1000000u128),
+            }),
+            slippage_tolerance: None,
+            receiver: None,
+        },
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    // 29 commission, the treasury takes half of it and the trader return is unchanged
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(10000u128),
+            },
+            belief_price: None,
+            max_spread: None,
+            to: Some(Addr::unchecked("trader")),
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(10000u128),
+        }],
+    )
+    .unwrap();
+    // both transfers pay the oracle tax, 5 atom on the return and 1 atom on the 14 fee
+    assert_eq!(
+        app.query_balance(Addr::unchecked("trader"), ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(9867u128)
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("treasury"), ATOM_DENOM.to_string())
+            .unwrap(),
+        Uint128::from(13u128)
+    );
+
+    // the pool keeps the other half, so its product still grows
+    let res: PoolResponse = app.query(pair_addr, &QueryMsg::Pool {}).unwrap();
+    assert_eq!(res.assets[0].amount, Uint128::from(1010000u128));
+    assert_eq!(res.assets[1].amount, Uint128::from(990120u128));
+    assert!(
+        res.assets[0].amount.full_mul(res.assets[1].amount)
+            > Uint128::from(1000000u128).full_mul(Uint128::from(1000000u128))
+    );
+}
+
+#[test]
+fn provide_liquidity_slippage_tolerance() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: asset_infos.clone(),
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .unwrap();
+
+    let provide = |app: &mut MockApp, amounts: [u128; 2], slippage_tolerance: Decimal| {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: asset_infos[0].clone(),
+                        amount: Uint128::from(amounts[0]),
+                    },
+                    Asset {
+                        info: asset_infos[1].clone(),
+                        amount: Uint128::from(amounts[1]),
+                    },
+                ],
+                slippage_tolerance: Some(slippage_tolerance),
+                receiver: None,
+            },
+            &[ORAI_DENOM, ATOM_DENOM]
+                .iter()
+                .zip(amounts)
+                .filter(|(_, amount)| *amount > 0)
+                .map(|(denom, amount)| Coin {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(amount),
+                })
+                .collect::<Vec<Coin>>(),
+        )
+    };
+
+    // the first deposit sets the ratio whatever the tolerance is
+    provide(&mut app, [1000000, 1000000], Decimal::percent(1)).unwrap();
+
+    // shift the ratio with a large swap of orai
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::Swap {
+            offer_asset: Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(100000u128),
+            },
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(100000u128),
+        }],
+    )
+    .unwrap();
+    let pool: PoolResponse = app.query(pair_addr.clone(), &QueryMsg::Pool {}).unwrap();
+
+    // depositing at the old 1:1 ratio is now about 20% off
+    let res = provide(&mut app, [10000, 10000], Decimal::percent(5));
+    app.assert_fail(res);
+
+    // a one sided deposit can not match the pool ratio
+    let res = provide(&mut app, [10000, 0], Decimal::percent(5));
+    app.assert_fail(res);
+
+    // the old ratio is accepted by a tolerance wide enough
+    provide(&mut app, [10000, 10000], Decimal::percent(20)).unwrap();
+
+    // depositing at the current ratio passes a tight tolerance
+    let pool_after: PoolResponse = app.query(pair_addr.clone(), &QueryMsg::Pool {}).unwrap();
+    assert_ne!(pool.assets, pool_after.assets);
+    let atom_amount = Uint128::from(10000u128)
+        .multiply_ratio(pool_after.assets[1].amount, pool_after.assets[0].amount);
+    provide(&mut app, [10000, atom_amount.u128()], Decimal::percent(1)).unwrap();
+}
+
+#[test]
+fn query_lp_price() {
+    let mut app = MockApp::new(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(4000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [orai.clone(), atom.clone()],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("owner"), &msg, &[], "pair")
+        .unwrap();
+
+    // no LP token minted yet
+    let res: LpPriceResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::LpPrice {
+                reference_asset: orai.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.price, Decimal::zero());
+
+    // 10000 orai and 4000 atom mint sqrt(10000 * 4000) = 6324 LP tokens, locked ones included
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: orai.clone(),
+                amount: Uint128::from(10000u128),
+            },
+            Asset {
+                info: atom.clone(),
+                amount: Uint128::from(4000u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    app.execute(
+        Addr::unchecked(MOCK_CONTRACT_ADDR),
+        pair_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(4000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    // 1 atom = 2 orai, the reserves are worth 18000 orai
+    app.execute(
+        Addr::unchecked(APP_OWNER),
+        app.oracle_addr.clone(),
+        &oraiswap::oracle::ExecuteMsg::UpdateExchangeRate {
+            denom: ATOM_DENOM.to_string(),
+            exchange_rate: Decimal::from_ratio(2u128, 1u128),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: LpPriceResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::LpPrice {
+                reference_asset: orai,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.price.to_string(), "2.846299810246679316");
+
+    let res: LpPriceResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::LpPrice {
+                reference_asset: atom,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.price.to_string(), "1.423149905123339658");
+}
+
+#[test]
+fn cumulative_prices_twap() {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(10000000u128),
+            },
+        ],
+    )]);
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    ];
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: asset_infos.clone(),
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "pair")
+        .unwrap();
+
+    // 1 orai = 2 atom
+    app.execute(
+        Addr::unchecked("addr0000"),
+        pair_addr.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(2000000u128),
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        },
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(2000000u128),
+            },
+        ],
+    )
+    .unwrap();
+    let provided: CumulativePricesResponse = app
+        .query(pair_addr.clone(), &QueryMsg::CumulativePrices {})
+        .unwrap();
+    assert_eq!(provided.price0_cumulative, Uint256::zero());
+    assert_eq!(provided.price1_cumulative, Uint256::zero());
+
+    let swap = |app: &mut MockApp, info: &AssetInfo, denom: &str| {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: info.clone(),
+                    amount: Uint128::from(100000u128),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            },
+            &[Coin {
+                denom: denom.to_string(),
+                amount: Uint128::from(100000u128),
+            }],
+        )
+        .unwrap();
+    };
+
+    // the initial reserves were in place for an hour and one block before the swap
+    app.advance_time(3600);
+    swap(&mut app, &asset_infos[0], ORAI_DENOM);
+    let sample_a: CumulativePricesResponse = app
+        .query(pair_addr.clone(), &QueryMsg::CumulativePrices {})
+        .unwrap();
+    let elapsed = sample_a.last_block_time - provided.last_block_time;
+    assert_eq!(elapsed, 3605);
+    assert_eq!(
+        sample_a.price0_cumulative,
+        Decimal256::from_ratio(2u128, 1u128).atomics() * Uint256::from(elapsed)
+    );
+    assert_eq!(
+        sample_a.price1_cumulative,
+        Decimal256::from_ratio(1u128, 2u128).atomics() * Uint256::from(elapsed)
+    );
+
+    // the next block moves the reserves again, sample b covers the reserves after the first swap
+    let reserves = [sample_a.assets[0].amount, sample_a.assets[1].amount];
+    swap(&mut app, &asset_infos[1], ATOM_DENOM);
+    let sample_b: CumulativePricesResponse = app
+        .query(pair_addr.clone(), &QueryMsg::CumulativePrices {})
+        .unwrap();
+    let elapsed = Uint256::from(sample_b.last_block_time - sample_a.last_block_time);
+    let twap0 =
+        Decimal256::new((sample_b.price0_cumulative - sample_a.price0_cumulative) / elapsed);
+    let twap1 =
+        Decimal256::new((sample_b.price1_cumulative - sample_a.price1_cumulative) / elapsed);
+    assert_eq!(twap0, Decimal256::from_ratio(reserves[1], reserves[0]));
+    assert_eq!(twap1, Decimal256::from_ratio(reserves[0], reserves[1]));
+    assert!(twap0 < Decimal256::from_ratio(2u128, 1u128));
+}
+
+#[test]
+fn query_spot_price() {
+    let mut app = MockApp::new(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(2000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_tax(
+        Decimal::zero(),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(1000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let msg = InstantiateMsg {
+        oracle_addr: app.oracle_addr.clone(),
+        asset_infos: [orai.clone(), atom.clone()],
+        token_code_id: app.token_id,
+        commission_rate: None,
+        swap_volume_limit: None,
+        spread_policy: None,
+        curve: None,
+        protocol_fee: None,
+    };
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let pair_addr = app
+        .instantiate(code_id, Addr::unchecked("owner"), &msg, &[], "pair")
+        .unwrap();
+
+    // an empty pool has no price
+    let res: StdResult<SpotPriceResponse> = app.query(
+        pair_addr.clone(),
+        &QueryMsg::SpotPrice {
+            offer_asset_info: orai.clone(),
+        },
+    );
+    assert!(res.is_err());
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [
+            Asset {
+                info: orai.clone(),
+                amount: Uint128::from(1000u128),
+            },
+            Asset {
+                info: atom.clone(),
+                amount: Uint128::from(2000u128),
+            },
+        ],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    app.execute(
+        Addr::unchecked(MOCK_CONTRACT_ADDR),
+        pair_addr.clone(),
+        &msg,
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(1000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(2000u128),
+            },
+        ],
+    )
+    .unwrap();
+
+    // too small for a simulation, but the spot price is exact
+    let res: SpotPriceResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::SpotPrice {
+                offer_asset_info: orai,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.spot_price, Decimal::from_ratio(2u128, 1u128));
+    assert_eq!(res.spot_price_with_commission.to_string(), "1.994");
+
+    let res: SpotPriceResponse = app
+        .query(
+            pair_addr.clone(),
+            &QueryMsg::SpotPrice {
+                offer_asset_info: atom,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.spot_price, Decimal::from_ratio(1u128, 2u128));
+    assert_eq!(res.spot_price_with_commission.to_string(), "0.4985");
+
+    let res: StdResult<SpotPriceResponse> = app.query(
+        pair_addr,
+        &QueryMsg::SpotPrice {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+    );
+    assert!(res.is_err());
+}
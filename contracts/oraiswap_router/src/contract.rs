@@ -1,23 +1,29 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult, Uint128,
+    from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdError, StdResult, Uint128,
 };
 use oraiswap::error::ContractError;
 
-use crate::operations::{execute_swap_operation, execute_swap_operations};
+use crate::operations::{
+    execute_swap_operation, execute_swap_operations, order_book_swap_reply, order_book_tick_orders,
+    ORDER_BOOK_SWAP_REPLY_ID,
+};
 use crate::state::{Config, CONFIG};
 
 use cw20::Cw20ReceiveMsg;
 use oraiswap::asset::{Asset, AssetInfo, PairInfo};
+use oraiswap::limit_order::{PairLimitsResponse, QueryMsg as LimitOrderQueryMsg};
 use oraiswap::oracle::OracleContract;
 use oraiswap::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
 use oraiswap::querier::{query_pair_config, query_pair_info};
 use oraiswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBookFill, QueryMsg,
+    SimulateSwapOperationsResponse, SwapOperation, SwapOperationSimulation,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -32,6 +38,11 @@ pub fn instantiate(
         &Config {
             factory_addr: deps.api.addr_canonicalize(msg.factory_addr.as_str())?,
             factory_addr_v2: deps.api.addr_canonicalize(msg.factory_addr_v2.as_str())?,
+            max_route_length: msg.max_route_length,
+            limit_order_addr: msg
+                .limit_order_addr
+                .map(|addr| deps.api.addr_canonicalize(addr.as_str()))
+                .transpose()?,
         },
     )?;
 
@@ -39,10 +50,31 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if let Some(max_route_length) = msg.max_route_length {
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.max_route_length = Some(max_route_length);
+            Ok(config)
+        })?;
+    }
+    if let Some(limit_order_addr) = msg.limit_order_addr {
+        let limit_order_addr = deps.api.addr_canonicalize(limit_order_addr.as_str())?;
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.limit_order_addr = Some(limit_order_addr);
+            Ok(config)
+        })?;
+    }
     Ok(Response::default())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        ORDER_BOOK_SWAP_REPLY_ID => order_book_swap_reply(deps, env),
+        _ => Err(StdError::generic_err(format!("unknown reply id {}", msg.id)).into()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -133,6 +165,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let resp = ConfigResponse {
         factory_addr: deps.api.addr_humanize(&state.factory_addr)?,
         factory_addr_v2: deps.api.addr_humanize(&state.factory_addr_v2)?,
+        max_route_length: state.max_route_length(),
+        limit_order_addr: state
+            .limit_order_addr
+            .map(|addr| deps.api.addr_humanize(&addr))
+            .transpose()?,
     };
 
     Ok(resp)
@@ -152,65 +189,169 @@ fn simulate_swap_operations(
             ContractError::NoSwapOperation {}.to_string(),
         ));
     }
+    if operations_len > config.max_route_length() as usize {
+        return Err(StdError::generic_err(
+            ContractError::RouteTooLong {
+                length: operations_len,
+                max_route_length: config.max_route_length(),
+            }
+            .to_string(),
+        ));
+    }
 
     let mut offer_amount = offer_amount;
-    for operation in operations.into_iter() {
+    let mut hops = Vec::with_capacity(operations_len);
+    for (index, operation) in operations.into_iter().enumerate() {
         let pair_config = query_pair_config(&deps.querier, factory_addr.clone())
             .or_else(|_| query_pair_config(&deps.querier, factory_addr_v2.clone()))?;
         let oracle_contract = OracleContract(pair_config.oracle_addr);
-        match operation {
-            SwapOperation::OraiSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => {
-                let pair_info = query_pair_info(
-                    &deps.querier,
-                    factory_addr.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )
-                .or_else(|_| -> StdResult<PairInfo> {
-                    query_pair_info(
-                        &deps.querier,
-                        factory_addr_v2.clone(),
-                        &[offer_asset_info.clone(), ask_asset_info.clone()],
-                    )
-                })?;
-
-                let return_asset = Asset {
+        let offer_asset_info = operation.get_offer_asset_info();
+        let ask_asset_info = operation.get_target_asset_info();
+        let pair_info = query_pair_info(
+            &deps.querier,
+            factory_addr.clone(),
+            &[offer_asset_info.clone(), ask_asset_info.clone()],
+        )
+        .or_else(|_| -> StdResult<PairInfo> {
+            query_pair_info(
+                &deps.querier,
+                factory_addr_v2.clone(),
+                &[offer_asset_info.clone(), ask_asset_info.clone()],
+            )
+        })?;
+
+        let return_asset = Asset {
+            info: offer_asset_info.clone(),
+            amount: offer_amount,
+        };
+
+        // Deduct tax before querying simulation, with native token only
+        offer_amount =
+            offer_amount.checked_sub(return_asset.compute_tax(&oracle_contract, &deps.querier)?)?;
+
+        let order_book_fill = match operation {
+            SwapOperation::OraiSwap { .. } => None,
+            SwapOperation::OrderBookSwap { .. } => Some(simulate_order_book_fill(
+                deps,
+                &config,
+                &pair_info.contract_addr,
+                &Asset {
                     info: offer_asset_info.clone(),
                     amount: offer_amount,
-                };
-
-                // Deduct tax before querying simulation, with native token only
-                offer_amount = offer_amount
-                    .checked_sub(return_asset.compute_tax(&oracle_contract, &deps.querier)?)?;
-
-                let mut res: SimulationResponse = deps.querier.query_wasm_smart(
-                    pair_info.contract_addr,
-                    &PairQueryMsg::Simulation {
-                        offer_asset: Asset {
-                            info: offer_asset_info,
-                            amount: offer_amount,
-                        },
+                },
+                &ask_asset_info,
+            )?),
+        };
+        let pool_offer_amount = match order_book_fill.as_ref() {
+            Some(fill) => offer_amount.checked_sub(fill.offer_amount)?,
+            None => offer_amount,
+        };
+
+        let mut res = if pool_offer_amount.is_zero() {
+            SimulationResponse {
+                return_amount: Uint128::zero(),
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            }
+        } else {
+            deps.querier.query_wasm_smart(
+                pair_info.contract_addr,
+                &PairQueryMsg::Simulation {
+                    offer_asset: Asset {
+                        info: offer_asset_info,
+                        amount: pool_offer_amount,
                     },
-                )?;
+                },
+            )?
+        };
 
-                let return_asset = Asset {
-                    info: ask_asset_info,
-                    amount: res.return_amount,
-                };
+        let return_asset = Asset {
+            info: ask_asset_info.clone(),
+            amount: res.return_amount,
+        };
 
-                // Deduct tax after querying simulation, with native token only
-                res.return_amount = res
-                    .return_amount
-                    .checked_sub(return_asset.compute_tax(&oracle_contract, &deps.querier)?)?;
+        // Deduct tax after querying simulation, with native token only
+        res.return_amount = res
+            .return_amount
+            .checked_sub(return_asset.compute_tax(&oracle_contract, &deps.querier)?)?;
 
-                offer_amount = res.return_amount;
-            }
+        let mut return_amount = res.return_amount;
+        if let Some(fill) = order_book_fill.as_ref() {
+            // the router pays out the fill of the last operation, which is taxed too
+            let fill_asset = Asset {
+                info: ask_asset_info,
+                amount: fill.return_amount,
+            };
+            return_amount += if index + 1 == operations_len {
+                fill.return_amount
+                    .checked_sub(fill_asset.compute_tax(&oracle_contract, &deps.querier)?)?
+            } else {
+                fill.return_amount
+            };
         }
+
+        hops.push(SwapOperationSimulation {
+            operation,
+            offer_amount,
+            return_amount,
+            spread_amount: res.spread_amount,
+            commission_amount: res.commission_amount,
+            order_book_fill,
+        });
+        offer_amount = return_amount;
     }
 
     Ok(SimulateSwapOperationsResponse {
         amount: offer_amount,
+        hops,
     })
 }
+
+/// Estimate the fill of the order book operation: the router orders against the ticks beating the pool
+/// are filled at the tick prices less the commission
+fn simulate_order_book_fill(
+    deps: Deps,
+    config: &Config,
+    pair_addr: &Addr,
+    offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<OrderBookFill> {
+    let limit_order_addr = match config.limit_order_addr.as_ref() {
+        Some(limit_order_addr) => deps.api.addr_humanize(limit_order_addr)?,
+        None => {
+            return Err(StdError::generic_err(
+                ContractError::LimitOrderNotConfigured {}.to_string(),
+            ))
+        }
+    };
+    let mut fill = OrderBookFill {
+        offer_amount: Uint128::zero(),
+        return_amount: Uint128::zero(),
+    };
+    let tick_orders = order_book_tick_orders(
+        deps,
+        &limit_order_addr,
+        pair_addr,
+        offer_asset,
+        ask_asset_info,
+    )?;
+    if tick_orders.is_empty() {
+        return Ok(fill);
+    }
+    for tick_order in tick_orders {
+        fill.offer_amount += tick_order.offer_amount;
+        fill.return_amount += tick_order.ask_amount;
+    }
+
+    let pair_limits: PairLimitsResponse = deps.querier.query_wasm_smart(
+        limit_order_addr,
+        &LimitOrderQueryMsg::PairLimits {
+            asset_infos: [offer_asset.info.clone(), ask_asset_info.clone()],
+        },
+    )?;
+    let commission_rate = Decimal::from_str(&pair_limits.commission_rate)?;
+    // the router order is matched immediately and pays no relayer fee
+    fill.return_amount -= fill.return_amount * commission_rate;
+
+    Ok(fill)
+}
@@ -2,19 +2,27 @@ use std::collections::HashMap;
 
 use cosmwasm_std::{
     to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult, Uint128, WasmMsg,
+    StdResult, SubMsg, Uint128, WasmMsg,
 };
 use oraiswap::error::ContractError;
 
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, OrderBookSwapContext, CONFIG, ORDER_BOOK_SWAP_CONTEXT};
 
 use cw20::Cw20ExecuteMsg;
 use oraiswap::asset::{Asset, AssetInfo, PairInfo};
+use oraiswap::limit_order::{
+    Cw20HookMsg as LimitOrderCw20HookMsg, ExecuteMsg as LimitOrderExecuteMsg, OrderBookResponse,
+    OrderDirection, OrderType, QueryMsg as LimitOrderQueryMsg, TickDepth,
+};
 use oraiswap::oracle::OracleContract;
-use oraiswap::pair::{ExecuteMsg as PairExecuteMsg, PairExecuteMsgCw20};
+use oraiswap::pair::{
+    ExecuteMsg as PairExecuteMsg, PairExecuteMsgCw20, QueryMsg as PairQueryMsg, SimulationResponse,
+};
 use oraiswap::querier::{query_pair_config, query_pair_info, query_token_balance};
 use oraiswap::router::{ExecuteMsg, SwapOperation};
 
+pub const ORDER_BOOK_SWAP_REPLY_ID: u64 = 1;
+
 /// Execute swap operation
 /// swap all offer asset to ask asset
 pub fn execute_swap_operation(
@@ -35,52 +43,367 @@ pub fn execute_swap_operation(
         .or_else(|_| query_pair_config(&deps.querier, factory_addr_v2.clone()))?;
     let oracle_contract = OracleContract(pair_config.oracle_addr.clone());
 
-    let messages: Vec<CosmosMsg> = match operation {
-        SwapOperation::OraiSwap {
-            offer_asset_info,
+    let offer_asset_info = operation.get_offer_asset_info();
+    let ask_asset_info = operation.get_target_asset_info();
+    let pair_info: PairInfo = query_pair_info(
+        &deps.querier,
+        factory_addr,
+        &[offer_asset_info.clone(), ask_asset_info.clone()],
+    )
+    .or_else(|_| -> StdResult<PairInfo> {
+        query_pair_info(
+            &deps.querier,
+            factory_addr_v2.clone(),
+            &[offer_asset_info.clone(), ask_asset_info.clone()],
+        )
+    })?;
+
+    let amount = match offer_asset_info.clone() {
+        AssetInfo::NativeToken { denom } => {
+            deps.querier
+                .query_balance(env.contract.address.clone(), &denom)?
+                .amount
+        }
+        AssetInfo::Token { contract_addr } => query_token_balance(
+            &deps.querier,
+            contract_addr.into(),
+            env.contract.address.clone(),
+        )?,
+    };
+    let offer_asset: Asset = Asset {
+        info: offer_asset_info,
+        amount,
+    };
+
+    match operation {
+        // swap token in smart contract
+        SwapOperation::OraiSwap { .. } => Ok(Response::new().add_message(asset_into_swap_msg(
+            deps.as_ref(),
+            &oracle_contract,
+            pair_info.contract_addr,
+            offer_asset,
+            None,
+            to,
+        )?)),
+        SwapOperation::OrderBookSwap { .. } => order_book_swap(
+            deps,
+            env,
+            &config,
+            &oracle_contract,
+            pair_info.contract_addr,
+            offer_asset,
             ask_asset_info,
-        } => {
-            let pair_info: PairInfo = query_pair_info(
-                &deps.querier,
-                factory_addr,
-                &[offer_asset_info.clone(), ask_asset_info.clone()],
-            )
-            .or_else(|_| -> StdResult<PairInfo> {
-                query_pair_info(
-                    &deps.querier,
-                    factory_addr_v2.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )
-            })?;
-
-            let amount = match offer_asset_info.clone() {
-                AssetInfo::NativeToken { denom } => {
-                    deps.querier
-                        .query_balance(env.contract.address, &denom)?
-                        .amount
-                }
-                AssetInfo::Token { contract_addr } => {
-                    query_token_balance(&deps.querier, contract_addr.into(), env.contract.address)?
-                }
-            };
-            let offer_asset: Asset = Asset {
-                info: offer_asset_info,
-                amount,
-            };
+            to,
+        ),
+    }
+}
+
+/// Order the router submits against a single tick of the order book
+pub struct TickOrder {
+    pub offer_amount: Uint128,
+    pub ask_amount: Uint128,
+}
+
+/// Walk the opposite side of the book while its ticks beat the pool average price for the whole offer.
+/// Every tick gets its own order at the tick price, the walk stops at the first order the book would reject
+pub fn order_book_tick_orders(
+    deps: Deps,
+    limit_order_addr: &Addr,
+    pair_addr: &Addr,
+    offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<Vec<TickOrder>> {
+    let asset_infos = [offer_asset.info.clone(), ask_asset_info.clone()];
+    let mut tick_orders = vec![];
+
+    let pool_simulation: SimulationResponse = deps.querier.query_wasm_smart(
+        pair_addr,
+        &PairQueryMsg::Simulation {
+            offer_asset: offer_asset.clone(),
+        },
+    )?;
+    if pool_simulation.return_amount.is_zero() {
+        return Ok(tick_orders);
+    }
+
+    let order_book: OrderBookResponse = deps.querier.query_wasm_smart(
+        limit_order_addr,
+        &LimitOrderQueryMsg::OrderBook {
+            asset_infos: asset_infos.clone(),
+        },
+    )?;
+    let selling = order_book.base_coin_info == offer_asset.info;
+    // quote per base, the pool average price for the whole offer
+    let limit_price = if selling {
+        Decimal::from_ratio(pool_simulation.return_amount, offer_asset.amount)
+    } else {
+        Decimal::from_ratio(offer_asset.amount, pool_simulation.return_amount)
+    };
+
+    let ticks: Vec<TickDepth> = deps.querier.query_wasm_smart(
+        limit_order_addr,
+        &LimitOrderQueryMsg::OrderBookDepth {
+            asset_infos,
+            direction: if selling {
+                OrderDirection::Buy
+            } else {
+                OrderDirection::Sell
+            },
+            limit: None,
+        },
+    )?;
+    let mut remaining_amount = offer_asset.amount;
+    for tick in ticks {
+        // best price first, the rest of the ticks do not beat the pool either
+        if remaining_amount.is_zero()
+            || (selling && tick.price < limit_price)
+            || (!selling && tick.price > limit_price)
+        {
+            break;
+        }
+
+        let (base_amount, quote_amount) = if selling {
+            // buy orders ask for the base asset
+            let base_amount = remaining_amount.min(tick.total_ask_amount);
+            (base_amount, base_amount * tick.price)
+        } else {
+            // sell orders offer the base asset, the quote is rounded up so the order still crosses the tick
+            let base_amount = remaining_amount
+                .multiply_ratio(Decimal::one().atomics(), tick.price.atomics())
+                .min(tick.total_offer_amount);
+            let mut quote_amount = base_amount * tick.price;
+            if Decimal::from_ratio(quote_amount, base_amount) < tick.price {
+                quote_amount += Uint128::one();
+            }
+            (base_amount, quote_amount)
+        };
+        if base_amount.is_zero() {
+            break;
+        }
+
+        // the order book rejects the order, the rest of the offer goes to the pool
+        let price = Decimal::from_ratio(quote_amount, base_amount);
+        let off_precision = match order_book.precision {
+            Some(precision) if !precision.is_zero() => {
+                price.atomics().u128() % precision.atomics().u128() != 0
+            }
+            _ => false,
+        };
+        if quote_amount < order_book.min_quote_coin_amount || off_precision {
+            break;
+        }
+
+        let (offer_amount, ask_amount) = if selling {
+            (base_amount, quote_amount)
+        } else {
+            (quote_amount, base_amount)
+        };
+        remaining_amount -= offer_amount;
+        tick_orders.push(TickOrder {
+            offer_amount,
+            ask_amount,
+        });
+    }
+
+    Ok(tick_orders)
+}
 
-            // swap token in smart contract
-            vec![asset_into_swap_msg(
+/// Submit an immediate or cancel order for every tick beating the pool, best tick first,
+/// so each tick is filled at its own price. The reply to the last order swaps the rest on the pool
+#[allow(clippy::too_many_arguments)]
+fn order_book_swap(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    oracle_contract: &OracleContract,
+    pair_addr: Addr,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let limit_order_addr = match config.limit_order_addr.as_ref() {
+        Some(limit_order_addr) => deps.api.addr_humanize(limit_order_addr)?,
+        None => return Err(ContractError::LimitOrderNotConfigured {}),
+    };
+
+    // deduct tax first
+    let amount = offer_asset
+        .amount
+        .checked_sub(offer_asset.compute_tax(oracle_contract, &deps.querier)?)?;
+
+    let tick_orders = order_book_tick_orders(
+        deps.as_ref(),
+        &limit_order_addr,
+        &pair_addr,
+        &Asset {
+            info: offer_asset.info.clone(),
+            amount,
+        },
+        &ask_asset_info,
+    )?;
+
+    // no tick beats the pool, the whole offer goes to the pool
+    if tick_orders.is_empty() {
+        return Ok(Response::new()
+            .add_message(asset_into_swap_msg(
                 deps.as_ref(),
-                &oracle_contract,
-                pair_info.contract_addr,
-                offer_asset,
+                oracle_contract,
+                pair_addr,
+                offer_asset.clone(),
                 None,
                 to,
-            )?]
-        }
+            )?)
+            .add_attributes([
+                ("action", "order_book_swap"),
+                ("pool_offer_amount", &offer_asset.amount.to_string()),
+            ]));
+    }
+
+    let order_book: OrderBookResponse = deps.querier.query_wasm_smart(
+        limit_order_addr.clone(),
+        &LimitOrderQueryMsg::OrderBook {
+            asset_infos: [offer_asset.info.clone(), ask_asset_info.clone()],
+        },
+    )?;
+    // paying the base asset sells it, paying the quote asset buys the base asset
+    let direction = if order_book.base_coin_info == offer_asset.info {
+        OrderDirection::Sell
+    } else {
+        OrderDirection::Buy
     };
 
-    Ok(Response::new().add_messages(messages))
+    // an order rejected by the order book leaves its offer to the pool
+    let mut messages = vec![];
+    for tick_order in tick_orders.iter() {
+        let assets = [
+            Asset {
+                info: offer_asset.info.clone(),
+                amount: tick_order.offer_amount,
+            },
+            Asset {
+                info: ask_asset_info.clone(),
+                amount: tick_order.ask_amount,
+            },
+        ];
+        let submit_order_msg = match &offer_asset.info {
+            AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                contract_addr: limit_order_addr.to_string(),
+                msg: to_binary(&LimitOrderExecuteMsg::SubmitOrder {
+                    direction,
+                    assets,
+                    post_only: None,
+                    client_order_id: None,
+                    fill_callback: None,
+                    expires_at: None,
+                    order_type: Some(OrderType::Ioc),
+                    min_fill_amount: None,
+                    trigger_price: None,
+                })?,
+                funds: vec![Coin {
+                    denom: denom.clone(),
+                    amount: tick_order.offer_amount,
+                }],
+            },
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: limit_order_addr.to_string(),
+                    amount: tick_order.offer_amount,
+                    msg: to_binary(&LimitOrderCw20HookMsg::SubmitOrder {
+                        direction,
+                        assets,
+                        post_only: None,
+                        client_order_id: None,
+                        fill_callback: None,
+                        expires_at: None,
+                        order_type: Some(OrderType::Ioc),
+                        min_fill_amount: None,
+                        trigger_price: None,
+                        belief_price: None,
+                        max_spread: None,
+                    })?,
+                })?,
+                funds: vec![],
+            },
+        };
+        messages.push(SubMsg::reply_always(
+            submit_order_msg,
+            ORDER_BOOK_SWAP_REPLY_ID,
+        ));
+    }
+
+    ORDER_BOOK_SWAP_CONTEXT.save(
+        deps.storage,
+        &OrderBookSwapContext {
+            prev_ask_balance: ask_asset_info.query_pool(&deps.querier, env.contract.address)?,
+            offer_asset_info: offer_asset.info,
+            ask_asset_info,
+            pair_addr,
+            oracle_addr: oracle_contract.addr(),
+            to,
+            pending_orders: messages.len() as u32,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attribute("action", "order_book_swap"))
+}
+
+/// Swap the offer left unfilled by the order book on the pool once the last order replied,
+/// the fill of the last operation is paid out to the receiver
+pub fn order_book_swap_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut context = ORDER_BOOK_SWAP_CONTEXT.load(deps.storage)?;
+    if context.pending_orders > 1 {
+        context.pending_orders -= 1;
+        ORDER_BOOK_SWAP_CONTEXT.save(deps.storage, &context)?;
+        return Ok(Response::new());
+    }
+    ORDER_BOOK_SWAP_CONTEXT.remove(deps.storage);
+    let oracle_contract = OracleContract(context.oracle_addr);
+
+    let pool_offer_amount = context
+        .offer_asset_info
+        .query_pool(&deps.querier, env.contract.address.clone())?;
+    let order_book_return_amount = context
+        .ask_asset_info
+        .query_pool(&deps.querier, env.contract.address)?
+        .checked_sub(context.prev_ask_balance)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !pool_offer_amount.is_zero() {
+        messages.push(asset_into_swap_msg(
+            deps.as_ref(),
+            &oracle_contract,
+            context.pair_addr,
+            Asset {
+                info: context.offer_asset_info,
+                amount: pool_offer_amount,
+            },
+            None,
+            context.to.clone(),
+        )?);
+    }
+    if let Some(to) = context.to {
+        if !order_book_return_amount.is_zero() {
+            messages.push(
+                Asset {
+                    info: context.ask_asset_info,
+                    amount: order_book_return_amount,
+                }
+                .into_msg(Some(&oracle_contract), &deps.querier, to)?,
+            );
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        ("action", "order_book_swap_reply"),
+        (
+            "order_book_return_amount",
+            &order_book_return_amount.to_string(),
+        ),
+        ("pool_offer_amount", &pool_offer_amount.to_string()),
+    ]))
 }
 
 pub fn execute_swap_operations(
@@ -96,6 +419,15 @@ pub fn execute_swap_operations(
         return Err(ContractError::NoSwapOperation {});
     }
 
+    // reject long routes up front, before any swap message is dispatched
+    let max_route_length = CONFIG.load(deps.storage)?.max_route_length();
+    if operations_len > max_route_length as usize {
+        return Err(ContractError::RouteTooLong {
+            length: operations_len,
+            max_route_length,
+        });
+    }
+
     // Assert the operations are properly set
     assert_operations(&operations)?;
 
@@ -196,12 +528,10 @@ fn asset_into_swap_msg(
 pub fn assert_operations(operations: &[SwapOperation]) -> StdResult<()> {
     let mut ask_asset_map: HashMap<String, bool> = HashMap::new();
     for operation in operations.iter() {
-        let (offer_asset, ask_asset) = match operation {
-            SwapOperation::OraiSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => (offer_asset_info.clone(), ask_asset_info.clone()),
-        };
+        let (offer_asset, ask_asset) = (
+            operation.get_offer_asset_info(),
+            operation.get_target_asset_info(),
+        );
 
         ask_asset_map.remove(&offer_asset.to_string());
         ask_asset_map.insert(ask_asset.to_string(), true);
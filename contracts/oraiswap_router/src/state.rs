@@ -1,17 +1,49 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::CanonicalAddr;
+use cosmwasm_std::{Addr, CanonicalAddr, Uint128};
 use cw_storage_plus::Item;
+use oraiswap::asset::AssetInfo;
 
 #[cw_serde]
 pub struct Config {
     pub factory_addr: CanonicalAddr,
     pub factory_addr_v2: CanonicalAddr,
+    // none for routers instantiated before the limit, use the default
+    pub max_route_length: Option<u32>,
+    // order book operations are rejected while it is not set
+    #[serde(default)]
+    pub limit_order_addr: Option<CanonicalAddr>,
+}
+
+pub const DEFAULT_MAX_ROUTE_LENGTH: u32 = 5;
+
+impl Config {
+    pub fn max_route_length(&self) -> u32 {
+        self.max_route_length.unwrap_or(DEFAULT_MAX_ROUTE_LENGTH)
+    }
 }
 
 // put the length bytes at the first for compatibility with legacy singleton store
 pub const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
 
+/// order book operation waiting for the replies of its orders, to swap the unfilled offer on the pool
+#[cw_serde]
+pub struct OrderBookSwapContext {
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+    pub pair_addr: Addr,
+    pub oracle_addr: Addr,
+    // router balance of the ask asset before the order
+    pub prev_ask_balance: Uint128,
+    // receiver of the last operation, the fill of other operations stays in the router
+    pub to: Option<Addr>,
+    // orders of the operation still waiting for their reply
+    pub pending_orders: u32,
+}
+
+pub const ORDER_BOOK_SWAP_CONTEXT: Item<OrderBookSwapContext> =
+    Item::new("order_book_swap_context");
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -36,6 +68,8 @@ mod test {
             &Config {
                 factory_addr: deps.api.addr_canonicalize("addr0000").unwrap(),
                 factory_addr_v2: deps.api.addr_canonicalize("addr0000_v2").unwrap(),
+                max_route_length: None,
+                limit_order_addr: None,
             },
         )
         .unwrap();
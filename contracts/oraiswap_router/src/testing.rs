@@ -1,12 +1,24 @@
-use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, to_binary, Addr, Coin, Decimal, StdError, Uint128};
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::create_entry_points_testing;
+use oraiswap::error::ContractError;
+use oraiswap::limit_order::{
+    ExecuteMsg as LimitOrderExecuteMsg, InstantiateMsg as LimitOrderInstantiateMsg, OrderDirection,
+    OrderResponse, OrderStatus, QueryMsg as LimitOrderQueryMsg,
+};
+use oraiswap::oracle::OracleContract;
+use oraiswap::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
+use oraiswap::querier::query_token_balance;
 use oraiswap::router::{
-    ExecuteMsg, InstantiateMsg, QueryMsg, SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+    SimulateSwapOperationsResponse, SwapOperation, SwapOperationSimulation,
 };
 
 use oraiswap::testing::{MockApp, ATOM_DENOM};
 
+use crate::contract::{execute, instantiate, query};
+
 #[test]
 fn simulate_swap_operations_test() {
     let mut app = MockApp::new(&[(
@@ -14,11 +26,11 @@ fn simulate_swap_operations_test() {
         &[
             Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(100000u128),
             },
             Coin {
                 denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(100000u128),
             },
         ],
     )]);
@@ -66,13 +78,13 @@ fn simulate_swap_operations_test() {
                 info: AssetInfo::NativeToken {
                     denom: ATOM_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -87,11 +99,11 @@ fn simulate_swap_operations_test() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
                 Coin {
                     denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
             ],
         )
@@ -100,6 +112,8 @@ fn simulate_swap_operations_test() {
     let msg = InstantiateMsg {
         factory_addr: app.factory_addr.clone(),
         factory_addr_v2: Addr::unchecked("addr0000_v2"),
+        max_route_length: None,
+        limit_order_addr: None,
     };
 
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
@@ -110,7 +124,7 @@ fn simulate_swap_operations_test() {
         .unwrap();
 
     let msg = QueryMsg::SimulateSwapOperations {
-        offer_amount: Uint128::from(100u128),
+        offer_amount: Uint128::from(10000u128),
         operations: vec![SwapOperation::OraiSwap {
             offer_asset_info: AssetInfo::NativeToken {
                 denom: ORAI_DENOM.to_string(),
@@ -125,6 +139,206 @@ fn simulate_swap_operations_test() {
     println!("{:?}", res);
 }
 
+/// orai -> asset -> atom route, the asset/atom pool is deeper
+fn setup_two_hop_route() -> (MockApp, Addr, Addr, Addr, Addr) {
+    let mut app = MockApp::new(&[(
+        &"addr0000".to_string(),
+        &[
+            Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(100000u128),
+            },
+            Coin {
+                denom: ATOM_DENOM.to_string(),
+                amount: Uint128::from(100000u128),
+            },
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    app.set_factory_and_pair_contract(
+        Box::new(
+            create_entry_points_testing!(oraiswap_factory)
+                .with_reply(oraiswap_factory::contract::reply),
+        ),
+        Box::new(
+            create_entry_points_testing!(oraiswap_pair).with_reply(oraiswap_pair::contract::reply),
+        ),
+    );
+    // set tax rate as 0.3%
+    app.set_tax(
+        Decimal::permille(3),
+        &[
+            (&ORAI_DENOM.to_string(), &Uint128::from(10000000u128)),
+            (&ATOM_DENOM.to_string(), &Uint128::from(10000000u128)),
+        ],
+    );
+
+    let asset_addr = app.create_token("asset");
+    app.set_token_balances(&[(
+        &"asset".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let asset = AssetInfo::Token {
+        contract_addr: asset_addr.clone(),
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let pair_addr1 = app.create_pair([orai.clone(), asset.clone()]).unwrap();
+    let pair_addr2 = app.create_pair([atom.clone(), asset.clone()]).unwrap();
+
+    for (pair_addr, native_info, amount) in [
+        (pair_addr1.clone(), orai.clone(), 10000u128),
+        (pair_addr2.clone(), atom.clone(), 50000u128),
+    ] {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            asset_addr.clone(),
+            &cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_addr.to_string(),
+                amount: Uint128::from(amount),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let msg = oraiswap::pair::ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: native_info.clone(),
+                    amount: Uint128::from(amount),
+                },
+                Asset {
+                    info: asset.clone(),
+                    amount: Uint128::from(amount),
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        };
+        app.execute(
+            Addr::unchecked("addr0000"),
+            pair_addr,
+            &msg,
+            &[Coin {
+                denom: native_info.to_string(),
+                amount: Uint128::from(amount),
+            }],
+        )
+        .unwrap();
+    }
+
+    let msg = InstantiateMsg {
+        factory_addr: app.factory_addr.clone(),
+        factory_addr_v2: Addr::unchecked("addr0000_v2"),
+        max_route_length: None,
+        limit_order_addr: None,
+    };
+    let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
+    let router_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "router")
+        .unwrap();
+
+    (app, router_addr, pair_addr1, pair_addr2, asset_addr)
+}
+
+#[test]
+fn simulate_two_hop_swap_operations() {
+    // A -> B -> C is orai -> asset -> atom
+    let (app, router_addr, pair_addr1, pair_addr2, asset_addr) = setup_two_hop_route();
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let asset = AssetInfo::Token {
+        contract_addr: asset_addr,
+    };
+    let atom = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+
+    let operations = vec![
+        SwapOperation::OraiSwap {
+            offer_asset_info: orai.clone(),
+            ask_asset_info: asset.clone(),
+        },
+        SwapOperation::OraiSwap {
+            offer_asset_info: asset.clone(),
+            ask_asset_info: atom.clone(),
+        },
+    ];
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000u128),
+        operations: operations.clone(),
+    };
+    let res: SimulateSwapOperationsResponse = app.query(router_addr, &msg).unwrap();
+
+    // each hop matches the simulation of its pair, fed with the return of the previous hop
+    let first_hop: SimulationResponse = app
+        .query(
+            pair_addr1,
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: orai,
+                    amount: Uint128::from(1000u128),
+                },
+            },
+        )
+        .unwrap();
+    let second_hop: SimulationResponse = app
+        .query(
+            pair_addr2,
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: asset,
+                    amount: first_hop.return_amount,
+                },
+            },
+        )
+        .unwrap();
+    // atom is taxed when it leaves the pair, the cw20 asset is not
+    let tax = OracleContract(app.oracle_addr.clone())
+        .query_tax(&app.as_querier(), second_hop.return_amount, ATOM_DENOM)
+        .unwrap()
+        .tax;
+    assert!(!tax.is_zero());
+
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: second_hop.return_amount - tax,
+            hops: vec![
+                SwapOperationSimulation {
+                    operation: operations[0].clone(),
+                    offer_amount: Uint128::from(1000u128),
+                    return_amount: first_hop.return_amount,
+                    spread_amount: first_hop.spread_amount,
+                    commission_amount: first_hop.commission_amount,
+                    order_book_fill: None,
+                },
+                SwapOperationSimulation {
+                    operation: operations[1].clone(),
+                    offer_amount: first_hop.return_amount,
+                    return_amount: second_hop.return_amount - tax,
+                    spread_amount: second_hop.spread_amount,
+                    commission_amount: second_hop.commission_amount,
+                    order_book_fill: None,
+                },
+            ],
+        }
+    );
+    // the shallow first pool takes the larger spread
+    assert!(res.hops[0].spread_amount > res.hops[1].spread_amount);
+}
+
 #[test]
 fn execute_swap_operations() {
     let mut app = MockApp::new(&[(
@@ -132,11 +346,11 @@ fn execute_swap_operations() {
         &[
             Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(100000u128),
             },
             Coin {
                 denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(1000u128),
+                amount: Uint128::from(100000u128),
             },
         ],
     )]);
@@ -199,13 +413,13 @@ fn execute_swap_operations() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -218,7 +432,7 @@ fn execute_swap_operations() {
         asset_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr1.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
             expires: None,
         },
         &[],
@@ -232,7 +446,7 @@ fn execute_swap_operations() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
@@ -243,13 +457,13 @@ fn execute_swap_operations() {
                 info: AssetInfo::NativeToken {
                     denom: ATOM_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -262,7 +476,7 @@ fn execute_swap_operations() {
         asset_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr2.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
             expires: None,
         },
         &[],
@@ -276,7 +490,7 @@ fn execute_swap_operations() {
             &msg,
             &[Coin {
                 denom: ATOM_DENOM.to_string(),
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
@@ -284,6 +498,8 @@ fn execute_swap_operations() {
     let msg = InstantiateMsg {
         factory_addr: app.factory_addr.clone(),
         factory_addr_v2: Addr::unchecked("addr0000_v2"),
+        max_route_length: None,
+        limit_order_addr: None,
     };
 
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
@@ -333,11 +549,11 @@ fn execute_swap_operations() {
             &[
                 Coin {
                     denom: ORAI_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
                 Coin {
                     denom: ATOM_DENOM.to_string(),
-                    amount: Uint128::from(100u128),
+                    amount: Uint128::from(10000u128),
                 },
             ],
         )
@@ -345,3 +561,368 @@ fn execute_swap_operations() {
 
     println!("{:?}", res.events);
 }
+
+#[test]
+fn execute_swap_operations_route_too_long() {
+    let mut deps = mock_dependencies();
+    let instantiate_msg = |max_route_length| InstantiateMsg {
+        factory_addr: Addr::unchecked("addr0000"),
+        factory_addr_v2: Addr::unchecked("addr0000_v2"),
+        max_route_length,
+        limit_order_addr: None,
+    };
+
+    // default limit
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        instantiate_msg(None),
+    )
+    .unwrap();
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.max_route_length, 5);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        instantiate_msg(Some(2)),
+    )
+    .unwrap();
+
+    // none of these pairs exist, so the route is rejected before any swap is attempted
+    let operation = SwapOperation::OraiSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: ATOM_DENOM.to_string(),
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &coins(1000u128, ORAI_DENOM)),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations: vec![operation.clone(), operation.clone(), operation.clone()],
+            minimum_receive: None,
+            to: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RouteTooLong {
+            length: 3,
+            max_route_length: 2,
+        }
+    );
+
+    // simulation applies the same limit
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(100u128),
+            operations: vec![operation.clone(), operation.clone(), operation],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            ContractError::RouteTooLong {
+                length: 3,
+                max_route_length: 2,
+            }
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn execute_swap_operations_minimum_receive_reverts_route() {
+    let (mut app, router_addr, _, pair_addr2, asset_addr) = setup_two_hop_route();
+    let operations = vec![
+        SwapOperation::OraiSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: asset_addr.clone(),
+            },
+        },
+        SwapOperation::OraiSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: asset_addr.clone(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: ATOM_DENOM.to_string(),
+            },
+        },
+    ];
+    let simulate = |app: &MockApp| {
+        let msg = QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(1000u128),
+            operations: operations.clone(),
+        };
+        app.query::<SimulateSwapOperationsResponse, _>(router_addr.clone(), &msg)
+            .unwrap()
+            .amount
+    };
+    let quoted_amount = simulate(&app);
+
+    // the price of the last hop moves after the route was quoted
+    app.execute(
+        Addr::unchecked("addr0000"),
+        asset_addr.clone(),
+        &cw20::Cw20ExecuteMsg::Send {
+            contract: pair_addr2.to_string(),
+            amount: Uint128::from(20000u128),
+            msg: to_binary(&oraiswap::pair::PairExecuteMsgCw20::Swap {
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let moved_amount = simulate(&app);
+    assert!(moved_amount < quoted_amount);
+
+    let orai_balance = app
+        .query_balance(Addr::unchecked("addr0000"), ORAI_DENOM.to_string())
+        .unwrap();
+    let atom_balance = app
+        .query_balance(Addr::unchecked("addr0000"), ATOM_DENOM.to_string())
+        .unwrap();
+
+    // the end output falls short of the quote, the whole route reverts
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: Some(quoted_amount),
+        to: None,
+    };
+    let res = app.execute(
+        Addr::unchecked("addr0000"),
+        router_addr.clone(),
+        &msg,
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    app.assert_fail(res);
+
+    // the offered orai is left intact and nothing was received
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ORAI_DENOM.to_string())
+            .unwrap(),
+        orai_balance
+    );
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ATOM_DENOM.to_string())
+            .unwrap(),
+        atom_balance
+    );
+
+    // with the new quote as the minimum the route goes through
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations,
+        minimum_receive: Some(moved_amount),
+        to: None,
+    };
+    app.execute(
+        Addr::unchecked("addr0000"),
+        router_addr,
+        &msg,
+        &[Coin {
+            denom: ORAI_DENOM.to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    )
+    .unwrap();
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr0000"), ATOM_DENOM.to_string())
+            .unwrap(),
+        atom_balance + moved_amount
+    );
+}
+
+#[test]
+fn execute_order_book_swap_fills_better_orders_first() {
+    let (mut app, _, pair_addr1, _, asset_addr) = setup_two_hop_route();
+    let orai = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let asset = AssetInfo::Token {
+        contract_addr: asset_addr.clone(),
+    };
+
+    let code_id = app.upload(Box::new(create_entry_points_testing!(oraiswap_limit_order)));
+    let limit_order_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr0000"),
+            &LimitOrderInstantiateMsg {
+                name: None,
+                version: None,
+                admin: None,
+                commission_rate: None,
+                reward_address: None,
+                maker_commission_rate: None,
+                taker_commission_rate: None,
+            },
+            &[],
+            "limit order",
+        )
+        .unwrap();
+    // order book for pair [asset, orai]
+    app.execute(
+        Addr::unchecked("addr0000"),
+        limit_order_addr.clone(),
+        &LimitOrderExecuteMsg::CreateOrderBookPair {
+            base_coin_info: asset.clone(),
+            quote_coin_info: orai.clone(),
+            spread: None,
+            min_quote_coin_amount: Uint128::from(10u128),
+            commission_rate: None,
+            precision: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // 1 asset = 0.9 orai beats the pool, 1 asset = 0.2 orai does not
+    for (asset_amount, orai_amount) in [(5000u128, 4500u128), (1000u128, 200u128)] {
+        app.execute(
+            Addr::unchecked("addr0000"),
+            limit_order_addr.clone(),
+            &LimitOrderExecuteMsg::SubmitOrder {
+                direction: OrderDirection::Buy,
+                assets: [
+                    Asset {
+                        info: asset.clone(),
+                        amount: Uint128::from(asset_amount),
+                    },
+                    Asset {
+                        info: orai.clone(),
+                        amount: Uint128::from(orai_amount),
+                    },
+                ],
+                post_only: None,
+                client_order_id: None,
+                fill_callback: None,
+                expires_at: None,
+                order_type: None,
+                min_fill_amount: None,
+                trigger_price: None,
+            },
+            &[Coin {
+                denom: ORAI_DENOM.to_string(),
+                amount: Uint128::from(orai_amount),
+            }],
+        )
+        .unwrap();
+    }
+
+    let msg = InstantiateMsg {
+        factory_addr: app.factory_addr.clone(),
+        factory_addr_v2: Addr::unchecked("addr0000_v2"),
+        max_route_length: None,
+        limit_order_addr: Some(limit_order_addr.clone()),
+    };
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let router_addr = app
+        .instantiate(code_id, Addr::unchecked("addr0000"), &msg, &[], "router")
+        .unwrap();
+
+    let offer_amount = Uint128::from(10000u128);
+    let operations = vec![SwapOperation::OrderBookSwap {
+        asset_infos: [asset.clone(), orai.clone()],
+    }];
+    let pool_only: SimulationResponse = app
+        .query(
+            pair_addr1.clone(),
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: asset.clone(),
+                    amount: offer_amount,
+                },
+            },
+        )
+        .unwrap();
+    let res: SimulateSwapOperationsResponse = app
+        .query(
+            router_addr.clone(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    // only the order at 0.9 beats the pool
+    let fill = res.hops[0].order_book_fill.clone().unwrap();
+    assert_eq!(fill.offer_amount, Uint128::from(5000u128));
+    assert!(!fill.return_amount.is_zero());
+    assert_eq!(res.hops[0].offer_amount, offer_amount);
+    assert!(res.amount > pool_only.return_amount);
+
+    let pool_balance =
+        query_token_balance(&app.as_querier(), asset_addr.clone(), pair_addr1.clone()).unwrap();
+
+    let res = app
+        .execute(
+            Addr::unchecked("addr0000"),
+            asset_addr.clone(),
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: router_addr.to_string(),
+                amount: offer_amount,
+                msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: Some("addr0001".to_string()),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // the order at 0.9 is filled and has left the book, the order at 0.2 is still open
+    let query_order = |order_id: u64| {
+        app.query::<OrderResponse, _>(
+            limit_order_addr.clone(),
+            &LimitOrderQueryMsg::Order {
+                order_id,
+                asset_infos: [asset.clone(), orai.clone()],
+            },
+        )
+    };
+    assert!(query_order(1).is_err());
+    let open_order = query_order(2).unwrap();
+    assert_eq!(open_order.status, OrderStatus::Open);
+    assert!(open_order.filled_ask_amount.is_zero());
+
+    // the rest of the offer went to the pool
+    assert_eq!(
+        query_token_balance(&app.as_querier(), asset_addr.clone(), pair_addr1).unwrap(),
+        pool_balance + Uint128::from(5000u128)
+    );
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "pool_offer_amount" && attr.value == "5000")));
+
+    // the receiver got more than the pool alone would have paid
+    let received = app
+        .query_balance(Addr::unchecked("addr0001"), ORAI_DENOM.to_string())
+        .unwrap();
+    assert!(received > pool_only.return_amount);
+}
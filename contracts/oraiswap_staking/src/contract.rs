@@ -8,28 +8,34 @@ use crate::legacy::v1::{
 };
 use crate::rewards::{
     deposit_reward, process_reward_assets, query_all_reward_infos, query_reward_info,
-    withdraw_reward, withdraw_reward_others,
+    withdraw_all_rewards, withdraw_reward, withdraw_reward_others, DEFAULT_LIMIT, MAX_LIMIT,
+};
+use crate::staking::{
+    auto_stake, auto_stake_hook, bond, claim_unbonded, compound, compound_hook,
+    compound_provide_hook, compound_refund, query_unbonding, unbond, COMPOUND_REPLY_ID,
 };
-use crate::staking::{auto_stake, auto_stake_hook, bond, unbond};
 use crate::state::{
     read_all_pool_infos, read_config, read_finish_migrate_store_status, read_pool_info,
-    read_rewards_per_sec, remove_pool_info, stakers_read, store_config,
+    read_pool_infos, read_rewards_per_sec, remove_pool_info, stakers_read, store_config,
     store_finish_migrate_store_status, store_pool_info, store_rewards_per_sec, Config,
     MigrationParams, PoolInfo,
 };
 
 use cosmwasm_std::{
     from_binary, to_binary, Addr, Api, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, Uint128,
 };
 use oraiswap::asset::{Asset, AssetRaw, ORAI_DENOM};
 use oraiswap::staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OldStoreType,
-    PoolInfoResponse, QueryMsg, QueryPoolInfoResponse, RewardsPerSecResponse,
+    PoolAprResponse, PoolInfoResponse, QueryMsg, QueryPoolInfoResponse, RewardAprResponse,
+    RewardsPerSecResponse,
 };
 
 use cw20::Cw20ReceiveMsg;
 
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -48,6 +54,11 @@ pub fn instantiate(
             factory_addr: deps.api.addr_canonicalize(msg.factory_addr.as_str())?,
             // default base_denom pass to factory is orai token
             base_denom: msg.base_denom.unwrap_or(ORAI_DENOM.to_string()),
+            unbonding_period: msg.unbonding_period.unwrap_or_default(),
+            router_addr: msg
+                .router_addr
+                .map(|router_addr| deps.api.addr_canonicalize(router_addr.as_str()))
+                .transpose()?,
         },
     )?;
     // set to true to enable normal execute handling when instantiate
@@ -59,17 +70,27 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::UpdateConfig {
             rewarder,
             owner,
             migrate_store_status,
-        } => update_config(deps, info, owner, rewarder, migrate_store_status),
+            unbonding_period,
+            router_addr,
+        } => update_config(
+            deps,
+            info,
+            owner,
+            rewarder,
+            migrate_store_status,
+            unbonding_period,
+            router_addr,
+        ),
         ExecuteMsg::UpdateRewardsPerSec {
             staking_token,
             assets,
-        } => update_rewards_per_sec(deps, info, staking_token, assets),
-        ExecuteMsg::DepositReward { rewards } => deposit_reward(deps, info, rewards),
+        } => update_rewards_per_sec(deps, env, info, staking_token, assets),
+        ExecuteMsg::DepositReward { rewards } => deposit_reward(deps, env, info, rewards),
         ExecuteMsg::RegisterAsset { staking_token } => register_asset(deps, info, staking_token),
         ExecuteMsg::DeprecateStakingToken {
             staking_token,
@@ -79,7 +100,11 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             staking_token,
             amount,
         } => unbond(deps, env, info.sender, staking_token, amount),
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, env, info.sender),
         ExecuteMsg::Withdraw { staking_token } => withdraw_reward(deps, env, info, staking_token),
+        ExecuteMsg::WithdrawAll { start_after, limit } => {
+            withdraw_all_rewards(deps, env, info, start_after, limit)
+        }
         ExecuteMsg::WithdrawOthers {
             staking_token,
             staker_addrs,
@@ -100,17 +125,52 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             staker_addr,
             prev_staking_token_amount,
         ),
+        ExecuteMsg::Compound {
+            staking_token,
+            minimum_receive,
+        } => compound(deps, env, info, staking_token, minimum_receive),
+        ExecuteMsg::CompoundHook {
+            staking_token,
+            staker_addr,
+            minimum_receive,
+        } => compound_hook(deps, env, info, staking_token, staker_addr, minimum_receive),
+        ExecuteMsg::CompoundProvideHook {
+            staking_token,
+            staker_addr,
+            prev_balances,
+            minimum_receive,
+        } => compound_provide_hook(
+            deps,
+            env,
+            info,
+            staking_token,
+            staker_addr,
+            prev_balances,
+            minimum_receive,
+        ),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        COMPOUND_REPLY_ID => compound_refund(deps, msg.result.unwrap_err()),
+        _ => Err(StdError::generic_err(format!(
+            "unknown reply id {}",
+            msg.id
+        ))),
     }
 }
 
 pub fn receive_cw20(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
     validate_migrate_store_status(deps.storage)?;
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond {}) => {
+        Ok(Cw20HookMsg::Bond { lock_for }) => {
             // check permission
             let token_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
 
@@ -130,9 +190,11 @@ pub fn receive_cw20(
 
             bond(
                 deps,
+                env,
                 Addr::unchecked(cw20_msg.sender),
                 info.sender,
                 cw20_msg.amount,
+                lock_for,
             )
         }
         Err(_) => Err(StdError::generic_err("invalid cw20 hook message")),
@@ -145,6 +207,8 @@ pub fn update_config(
     owner: Option<Addr>,
     rewarder: Option<Addr>,
     migrate_store_status: Option<bool>,
+    unbonding_period: Option<u64>,
+    router_addr: Option<Addr>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -160,6 +224,15 @@ pub fn update_config(
         config.rewarder = deps.api.addr_canonicalize(rewarder.as_str())?;
     }
 
+    // entries already queued keep their release time
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+
+    if let Some(router_addr) = router_addr {
+        config.router_addr = Some(deps.api.addr_canonicalize(router_addr.as_str())?);
+    }
+
     if let Some(migrate_store_status) = migrate_store_status {
         store_finish_migrate_store_status(deps.storage, migrate_store_status)?;
     }
@@ -172,6 +245,7 @@ pub fn update_config(
 // may need to call withdraw from backend side by querying all stakers with pagination in case out of gas
 fn update_rewards_per_sec(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     staking_token: Addr,
     assets: Vec<Asset>,
@@ -203,6 +277,7 @@ fn update_rewards_per_sec(
             &staker_addr_raw,
             &Some(asset_key.clone()),
             false,
+            env.block.time.seconds(),
         )?;
     }
 
@@ -240,6 +315,8 @@ fn register_asset(deps: DepsMut, info: MessageInfo, staking_token: Addr) -> StdR
             reward_index: Decimal::zero(),
             pending_reward: Uint128::zero(),
             migration_params: None,
+            reward_indexes: vec![],
+            boosted_bond_amount: Uint128::zero(),
         },
     )?;
 
@@ -279,6 +356,7 @@ fn deprecate_staking_token(
     pool_info.migration_params = Some(MigrationParams {
         index_snapshot: pool_info.reward_index,
         deprecated_staking_token,
+        index_snapshots: pool_info.reward_indexes.clone(),
     });
     let new_asset_key = deps
         .api
@@ -307,6 +385,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::RewardsPerSec { staking_token } => {
             to_binary(&query_rewards_per_sec(deps, staking_token)?)
         }
+        QueryMsg::PoolApr { staking_token } => to_binary(&query_pool_apr(deps, staking_token)?),
         QueryMsg::RewardInfo {
             staker_addr,
             staking_token,
@@ -324,6 +403,10 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             order,
         )?),
         QueryMsg::GetPoolsInformation {} => to_binary(&query_get_pools_infomation(deps)?),
+        QueryMsg::Pools { start_after, limit } => {
+            to_binary(&query_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::Unbonding { staker } => to_binary(&query_unbonding(deps, staker)?),
         QueryMsg::QueryOldStore { store_type } => query_old_store(deps, store_type),
     }
 }
@@ -336,6 +419,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         oracle_addr: deps.api.addr_humanize(&state.oracle_addr)?,
         factory_addr: deps.api.addr_humanize(&state.factory_addr)?,
         base_denom: state.base_denom,
+        unbonding_period: state.unbonding_period,
+        router_addr: state
+            .router_addr
+            .map(|router_addr| deps.api.addr_humanize(&router_addr))
+            .transpose()?,
     };
 
     Ok(resp)
@@ -373,6 +461,35 @@ pub fn query_rewards_per_sec(deps: Deps, staking_token: Addr) -> StdResult<Rewar
     Ok(RewardsPerSecResponse { assets })
 }
 
+pub fn query_pool_apr(deps: Deps, staking_token: Addr) -> StdResult<PoolAprResponse> {
+    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?.to_vec();
+    let pool_info = read_pool_info(deps.storage, &asset_key)?;
+    let raw_assets = read_rewards_per_sec(deps.storage, &asset_key).unwrap_or_default();
+
+    let aprs = raw_assets
+        .into_iter()
+        .map(|raw_asset| {
+            let asset = raw_asset.to_normal(deps.api)?;
+            let apr = if pool_info.total_bond_amount.is_zero() {
+                Decimal::zero()
+            } else {
+                let yearly_rewards = asset.amount.checked_mul(SECONDS_PER_YEAR.into())?;
+                Decimal::checked_from_ratio(yearly_rewards, pool_info.total_bond_amount)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?
+            };
+            Ok(RewardAprResponse {
+                info: asset.info,
+                apr,
+            })
+        })
+        .collect::<StdResult<Vec<RewardAprResponse>>>()?;
+
+    Ok(PoolAprResponse {
+        staking_token,
+        aprs,
+    })
+}
+
 pub fn parse_read_all_pool_infos(
     api: &dyn Api,
     pool_infos: Vec<(Vec<u8>, PoolInfo)>,
@@ -410,6 +527,21 @@ pub fn query_get_pools_infomation(deps: Deps) -> StdResult<Vec<QueryPoolInfoResp
     parse_read_all_pool_infos(deps.api, pool_infos)
 }
 
+pub fn query_pools(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<QueryPoolInfoResponse>> {
+    let start_after = start_after
+        .map(|a| deps.api.addr_canonicalize(a.as_str()))
+        .transpose()?
+        .map(|c| c.to_vec());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let pool_infos = read_pool_infos(deps.storage, start_after, limit)?;
+    parse_read_all_pool_infos(deps.api, pool_infos)
+}
+
 pub fn query_old_store(deps: Deps, old_store_type: OldStoreType) -> StdResult<Binary> {
     match old_store_type {
         OldStoreType::Pools {} => {
@@ -2,8 +2,9 @@ use std::convert::TryFrom;
 
 use crate::contract::validate_migrate_store_status;
 use crate::state::{
+    lock_expiries_read, lock_expiries_store, lock_snapshots_read, lock_snapshots_store,
     read_config, read_is_migrated, read_pool_info, read_rewards_per_sec, rewards_read,
-    rewards_store, stakers_read, store_pool_info, PoolInfo, RewardInfo,
+    rewards_store, stakers_read, store_pool_info, LockSnapshot, PoolInfo, RewardIndex, RewardInfo,
 };
 use cosmwasm_std::{
     Addr, Api, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
@@ -13,12 +14,13 @@ use oraiswap::asset::{Asset, AssetRaw};
 use oraiswap::querier::calc_range_start;
 use oraiswap::staking::{RewardInfoResponse, RewardInfoResponseItem, RewardMsg};
 
-const DEFAULT_LIMIT: u32 = 10;
-const MAX_LIMIT: u32 = 30;
+pub(crate) const DEFAULT_LIMIT: u32 = 10;
+pub(crate) const MAX_LIMIT: u32 = 30;
 
 // deposit_reward must be from reward token contract
 pub fn deposit_reward(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     rewards: Vec<RewardMsg>,
 ) -> StdResult<Response> {
@@ -38,6 +40,14 @@ pub fn deposit_reward(
             .addr_canonicalize(reward_msg.staking_token.as_str())?;
         let mut pool_info: PoolInfo = read_pool_info(deps.storage, &asset_key)?;
 
+        // the locks that ended do not boost this deposit anymore
+        expire_pool_locks(
+            deps.storage,
+            &asset_key,
+            &mut pool_info,
+            env.block.time.seconds(),
+        )?;
+
         let mut normal_reward = reward_msg.total_accumulation_amount;
 
         // normal rewards are array of Assets
@@ -45,10 +55,21 @@ pub fn deposit_reward(
             pool_info.pending_reward += normal_reward;
         } else {
             normal_reward += pool_info.pending_reward;
-            let normal_reward_per_bond =
-                Decimal::from_ratio(normal_reward, pool_info.total_bond_amount);
+            // locked bonds count with their reward weight
+            let normal_reward_per_bond = Decimal::from_ratio(
+                normal_reward,
+                pool_info.total_bond_amount + pool_info.boosted_bond_amount,
+            );
             pool_info.reward_index = pool_info.reward_index + normal_reward_per_bond;
             pool_info.pending_reward = Uint128::zero();
+
+            let rewards_per_sec =
+                read_rewards_per_sec(deps.storage, &asset_key).unwrap_or_default();
+            update_reward_indexes(
+                &mut pool_info.reward_indexes,
+                &rewards_per_sec,
+                normal_reward_per_bond,
+            );
         }
 
         store_pool_info(deps.storage, &asset_key, &pool_info)?;
@@ -65,7 +86,7 @@ pub fn deposit_reward(
 // withdraw all rewards or single reward depending on asset_token
 pub fn withdraw_reward(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     staking_token: Option<Addr>,
 ) -> StdResult<Response> {
@@ -78,7 +99,13 @@ pub fn withdraw_reward(
             .ok()
     });
 
-    let reward_assets = process_reward_assets(deps.storage, &staker_addr, &asset_key, true)?;
+    let reward_assets = process_reward_assets(
+        deps.storage,
+        &staker_addr,
+        &asset_key,
+        true,
+        env.block.time.seconds(),
+    )?;
 
     let messages = reward_assets
         .into_iter()
@@ -94,9 +121,81 @@ pub fn withdraw_reward(
         .add_attribute("action", "withdraw_reward"))
 }
 
+/// withdraw_all_rewards pays the rewards of at most limit pools of the sender, the last_staking_token
+/// attribute is the start_after of the next page
+pub fn withdraw_all_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    validate_migrate_store_status(deps.storage)?;
+    let staker_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(
+        start_after
+            .map(|a| deps.api.addr_canonicalize(a.as_str()).map(|a| a.to_vec()))
+            .transpose()?,
+    );
+
+    let asset_keys = rewards_read(deps.storage, &staker_addr)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|item| item.0))
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+    let mut reward_assets: Vec<AssetRaw> = vec![];
+    for asset_key in asset_keys.iter() {
+        for rw in process_reward_assets(
+            deps.storage,
+            &staker_addr,
+            &Some(asset_key.to_vec()),
+            true,
+            env.block.time.seconds(),
+        )? {
+            update_reward_assets_amount(&mut reward_assets, rw.clone(), rw.amount);
+        }
+    }
+
+    let reward_assets = reward_assets
+        .into_iter()
+        .map(|ra| ra.to_normal(deps.api))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let messages = reward_assets
+        .iter()
+        .map(|ra| ra.into_msg(None, &deps.querier, info.sender.clone()))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    let mut attributes = vec![
+        ("action", "withdraw_all_rewards".to_string()),
+        ("pools", asset_keys.len().to_string()),
+        (
+            "reward_assets",
+            reward_assets
+                .iter()
+                .map(|ra| ra.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+    ];
+    if let Some(asset_key) = asset_keys.last() {
+        attributes.push((
+            "last_staking_token",
+            deps.api
+                .addr_humanize(&asset_key.to_vec().into())?
+                .to_string(),
+        ));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
 pub fn withdraw_reward_others(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     staker_addrs: Vec<Addr>,
     staker_addr: Option<Addr>,
@@ -120,12 +219,41 @@ pub fn withdraw_reward_others(
     // withdraw reward for each staker
     for staker_addr in staker_addrs {
         let staker_addr_raw = deps.api.addr_canonicalize(staker_addr.as_str())?;
-        process_reward_assets(deps.storage, &staker_addr_raw, &asset_key, false)?;
+        process_reward_assets(
+            deps.storage,
+            &staker_addr_raw,
+            &asset_key,
+            false,
+            env.block.time.seconds(),
+        )?;
     }
 
     Ok(Response::new().add_attribute("action", "withdraw_reward_others"))
 }
 
+/// adds the reward per bond of each asset, weighted by its rewards per sec, to the pool indexes
+fn update_reward_indexes(
+    reward_indexes: &mut Vec<RewardIndex>,
+    rewards_per_sec: &[AssetRaw],
+    reward_per_bond: Decimal,
+) {
+    let total_amount: Uint128 = rewards_per_sec.iter().map(|rw| rw.amount).sum();
+    for rw in rewards_per_sec {
+        // ignore empty weight
+        if rw.amount.is_zero() {
+            continue;
+        }
+        let index = reward_per_bond * Decimal::from_ratio(rw.amount, total_amount);
+        match reward_indexes.iter_mut().find(|ri| ri.info.eq(&rw.info)) {
+            Some(reward_index) => reward_index.index = reward_index.index + index,
+            None => reward_indexes.push(RewardIndex {
+                info: rw.info.clone(),
+                index,
+            }),
+        }
+    }
+}
+
 fn update_reward_assets_amount(reward_assets: &mut Vec<AssetRaw>, rw: AssetRaw, amount: Uint128) {
     match reward_assets.iter_mut().find(|ra| ra.info.eq(&rw.info)) {
         None => {
@@ -146,6 +274,7 @@ pub fn process_reward_assets(
     staker_addr: &CanonicalAddr,
     asset_key: &Option<Vec<u8>>,
     do_withdraw: bool,
+    block_time: u64,
 ) -> StdResult<Vec<AssetRaw>> {
     let rewards_bucket = rewards_read(storage, staker_addr);
 
@@ -168,41 +297,46 @@ pub fn process_reward_assets(
 
     for reward_pair in reward_pairs {
         let (asset_key, mut reward_info) = reward_pair;
-        let pool_info: PoolInfo = read_pool_info(storage, &asset_key)?;
+        let mut pool_info: PoolInfo = read_pool_info(storage, &asset_key)?;
 
         // Withdraw reward to pending reward
         // if the lp token was migrated, and the user did not close their position yet, cap the reward at the snapshot
-        let pool_index = if pool_info.migration_params.is_some()
-            && !read_is_migrated(storage, &asset_key, staker_addr)
-        {
-            pool_info.migration_params.unwrap().index_snapshot
-        } else {
-            pool_info.reward_index
+        let should_migrate = pool_info.migration_params.is_some()
+            && !read_is_migrated(storage, &asset_key, staker_addr);
+        let (pool_index, pool_indexes) = match &pool_info.migration_params {
+            Some(migration_params) if should_migrate => (
+                migration_params.index_snapshot,
+                migration_params.index_snapshots.clone(),
+            ),
+            _ => (pool_info.reward_index, pool_info.reward_indexes.clone()),
         };
 
-        before_share_change(pool_index, &mut reward_info)?;
-
-        if !reward_info.pending_reward.is_zero() {
-            // calculate and accumulate the reward amount
-            let rewards_per_sec = read_rewards_per_sec(storage, &asset_key)?;
-            // now calculate weight
-            let total_amount: Uint128 = rewards_per_sec.iter().map(|rw| rw.amount).sum();
-
-            for rw in rewards_per_sec {
-                // ignore empty weight
-                if rw.amount.is_zero() {
-                    continue;
-                }
-                let amount =
-                    reward_info.pending_reward * Decimal::from_ratio(rw.amount, total_amount);
-
-                // update pending_withdraw, first time push it, later update the amount
-                update_reward_assets_amount(&mut reward_info.pending_withdraw, rw, amount);
-            }
-
-            // reset pending_reward
-            reward_info.pending_reward = Uint128::zero();
+        if expire_lock(
+            storage,
+            &asset_key,
+            &mut pool_info,
+            pool_index,
+            &pool_indexes,
+            &mut reward_info,
+            block_time,
+        )? {
+            store_pool_info(storage, &asset_key, &pool_info)?;
         }
+        before_share_change(
+            storage,
+            &asset_key,
+            pool_index,
+            &pool_indexes,
+            &mut reward_info,
+        )?;
+
+        // update pending_withdraw with the reward of each asset, first time push it, later update the amount
+        for rw in std::mem::take(&mut reward_info.pending_rewards) {
+            update_reward_assets_amount(&mut reward_info.pending_withdraw, rw.clone(), rw.amount);
+        }
+
+        // reset pending_reward
+        reward_info.pending_reward = Uint128::zero();
 
         // if withdraw, then update reward_assets to create MsgSend
         if do_withdraw {
@@ -223,13 +357,219 @@ pub fn process_reward_assets(
     Ok(reward_assets)
 }
 
+/// bond amount counted in the reward index math, boosted while the position is locked
+pub fn weighted_bond_amount(reward_info: &RewardInfo) -> Uint128 {
+    match &reward_info.lock {
+        Some(lock) => reward_info.bond_amount * lock.reward_weight,
+        None => reward_info.bond_amount,
+    }
+}
+
+/// boost the position adds on top of its bond amount
+pub fn boosted_amount(reward_info: &RewardInfo) -> StdResult<Uint128> {
+    Ok(weighted_bond_amount(reward_info).checked_sub(reward_info.bond_amount)?)
+}
+
+/// counts the boost of a lock in the pool until a deposit passes its end time
+pub fn add_lock_expiry(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    end_time: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let mut expiries = lock_expiries_store(storage, asset_key);
+    let key = end_time.to_be_bytes();
+    let boosted_amount = expiries.may_load(&key)?.unwrap_or_default();
+    expiries.save(&key, &(boosted_amount + amount))
+}
+
+/// takes the boost of a lock back out of its end time, when the lock changes before it ends
+pub fn remove_lock_expiry(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    end_time: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let mut expiries = lock_expiries_store(storage, asset_key);
+    let key = end_time.to_be_bytes();
+    let boosted_amount = expiries.load(&key)?.checked_sub(amount)?;
+    if boosted_amount.is_zero() {
+        expiries.remove(&key);
+        Ok(())
+    } else {
+        expiries.save(&key, &boosted_amount)
+    }
+}
+
+/// drops the boost of the locks that ended from the pool, the pool indexes are snapshotted
+/// so their positions still settle the rewards until now at the boosted weight
+fn expire_pool_locks(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    pool_info: &mut PoolInfo,
+    block_time: u64,
+) -> StdResult<()> {
+    let end = (block_time + 1).to_be_bytes();
+    let expiries = lock_expiries_read(storage, asset_key)
+        .range(None, Some(&end), Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, Uint128)>>>()?;
+
+    for (end_time, boosted_amount) in expiries {
+        pool_info.boosted_bond_amount =
+            pool_info.boosted_bond_amount.checked_sub(boosted_amount)?;
+        lock_snapshots_store(storage, asset_key).save(
+            &end_time,
+            &LockSnapshot {
+                index_snapshot: pool_info.reward_index,
+                index_snapshots: pool_info.reward_indexes.clone(),
+                boosted_amount,
+            },
+        )?;
+        lock_expiries_store(storage, asset_key).remove(&end_time);
+    }
+
+    Ok(())
+}
+
+/// drops a lock whose end time a deposit already passed, its rewards are settled at the boosted
+/// weight up to the snapshot of that deposit, or up to pool_index when it is the earlier one.
+/// Returns the boost the position had
+pub fn settle_lock_snapshot(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    pool_index: Decimal,
+    pool_indexes: &[RewardIndex],
+    reward_info: &mut RewardInfo,
+) -> StdResult<Option<Uint128>> {
+    let end_time = match &reward_info.lock {
+        Some(lock) => lock.end_time,
+        None => return Ok(None),
+    };
+    let snapshot =
+        match lock_snapshots_read(storage, asset_key).may_load(&end_time.to_be_bytes())? {
+            Some(snapshot) => snapshot,
+            None => return Ok(None),
+        };
+
+    let boosted_amount = boosted_amount(reward_info)?;
+    // a deprecated position is capped at the migration snapshot, which may be the earlier one
+    if snapshot.index_snapshot < pool_index {
+        before_share_change(
+            storage,
+            asset_key,
+            snapshot.index_snapshot,
+            &snapshot.index_snapshots,
+            reward_info,
+        )?;
+    } else {
+        before_share_change(storage, asset_key, pool_index, pool_indexes, reward_info)?;
+    }
+    reward_info.lock = None;
+
+    Ok(Some(boosted_amount))
+}
+
+/// drops the boost of an expired lock, the rewards earned until its end time are settled with it.
+/// Returns whether the pool boosted amount changed
+pub fn expire_lock(
+    storage: &mut dyn Storage,
+    asset_key: &[u8],
+    pool_info: &mut PoolInfo,
+    pool_index: Decimal,
+    pool_indexes: &[RewardIndex],
+    reward_info: &mut RewardInfo,
+    block_time: u64,
+) -> StdResult<bool> {
+    let end_time = match &reward_info.lock {
+        Some(lock) if lock.end_time <= block_time => lock.end_time,
+        _ => return Ok(false),
+    };
+
+    // a deposit already dropped the boost from the pool
+    if let Some(boosted_amount) =
+        settle_lock_snapshot(storage, asset_key, pool_index, pool_indexes, reward_info)?
+    {
+        let mut snapshots = lock_snapshots_store(storage, asset_key);
+        let key = end_time.to_be_bytes();
+        let mut snapshot = snapshots.load(&key)?;
+        snapshot.boosted_amount = snapshot.boosted_amount.checked_sub(boosted_amount)?;
+        if snapshot.boosted_amount.is_zero() {
+            snapshots.remove(&key);
+        } else {
+            snapshots.save(&key, &snapshot)?;
+        }
+        return Ok(false);
+    }
+
+    // no deposit since the end time, so the rewards until now are still boosted
+    before_share_change(storage, asset_key, pool_index, pool_indexes, reward_info)?;
+    let boosted_amount = boosted_amount(reward_info)?;
+    remove_lock_expiry(storage, asset_key, end_time, boosted_amount)?;
+    pool_info.boosted_bond_amount = pool_info.boosted_bond_amount.checked_sub(boosted_amount)?;
+    reward_info.lock = None;
+
+    Ok(true)
+}
+
 // withdraw reward to pending reward
-pub fn before_share_change(pool_index: Decimal, reward_info: &mut RewardInfo) -> StdResult<()> {
-    let pending_reward = (reward_info.bond_amount * pool_index)
-        .checked_sub(reward_info.bond_amount * reward_info.index)?;
+pub fn before_share_change(
+    storage: &dyn Storage,
+    asset_key: &[u8],
+    pool_index: Decimal,
+    pool_indexes: &[RewardIndex],
+    reward_info: &mut RewardInfo,
+) -> StdResult<()> {
+    let bond_amount = weighted_bond_amount(reward_info);
+    let pending_reward = (bond_amount * pool_index).checked_sub(bond_amount * reward_info.index)?;
 
     reward_info.index = pool_index;
     reward_info.pending_reward += pending_reward;
+
+    match &reward_info.indexes {
+        Some(indexes) => {
+            for pool_reward_index in pool_indexes {
+                // an asset missing from the staker indexes was first rewarded after they were stored
+                let index = indexes
+                    .iter()
+                    .find(|ri| ri.info.eq(&pool_reward_index.info))
+                    .map_or(Decimal::zero(), |ri| ri.index);
+                let amount =
+                    (bond_amount * pool_reward_index.index).checked_sub(bond_amount * index)?;
+                if !amount.is_zero() {
+                    update_reward_assets_amount(
+                        &mut reward_info.pending_rewards,
+                        AssetRaw {
+                            info: pool_reward_index.info.clone(),
+                            amount,
+                        },
+                        amount,
+                    );
+                }
+            }
+        }
+        None => {
+            // records stored before the per asset indexes split their pending reward by the weights once
+            let rewards_per_sec = read_rewards_per_sec(storage, asset_key).unwrap_or_default();
+            let total_amount: Uint128 = rewards_per_sec.iter().map(|rw| rw.amount).sum();
+            for rw in rewards_per_sec {
+                // ignore empty weight
+                if rw.amount.is_zero() {
+                    continue;
+                }
+                let amount =
+                    reward_info.pending_reward * Decimal::from_ratio(rw.amount, total_amount);
+                update_reward_assets_amount(&mut reward_info.pending_rewards, rw, amount);
+            }
+        }
+    }
+
+    reward_info.indexes = Some(pool_indexes.to_vec());
     Ok(())
 }
 
@@ -307,18 +647,33 @@ fn _read_reward_infos_response(
             let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
             let pool_info = read_pool_info(storage, &asset_key)?;
 
-            let (pool_index, should_migrate) = if pool_info.migration_params.is_some()
+            let (pool_index, pool_indexes, should_migrate) = if pool_info.migration_params.is_some()
                 && !read_is_migrated(storage, &asset_key, staker_addr)
             {
+                let migration_params = pool_info.migration_params.unwrap();
                 (
-                    pool_info.migration_params.unwrap().index_snapshot,
+                    migration_params.index_snapshot,
+                    migration_params.index_snapshots,
                     Some(true),
                 )
             } else {
-                (pool_info.reward_index, None)
+                (pool_info.reward_index, pool_info.reward_indexes, None)
             };
 
-            before_share_change(pool_index, &mut reward_info)?;
+            settle_lock_snapshot(
+                storage,
+                &asset_key,
+                pool_index,
+                &pool_indexes,
+                &mut reward_info,
+            )?;
+            before_share_change(
+                storage,
+                &asset_key,
+                pool_index,
+                &pool_indexes,
+                &mut reward_info,
+            )?;
 
             let pending_withdraw = reward_info
                 .pending_withdraw
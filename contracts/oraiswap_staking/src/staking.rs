@@ -1,45 +1,75 @@
 use crate::contract::validate_migrate_store_status;
-use crate::rewards::before_share_change;
+use crate::rewards::{
+    add_lock_expiry, before_share_change, boosted_amount, expire_lock, process_reward_assets,
+    remove_lock_expiry,
+};
 use crate::state::{
-    read_config, read_is_migrated, read_pool_info, rewards_read, rewards_store, stakers_store,
-    store_is_migrated, store_pool_info, Config, PoolInfo, RewardInfo,
+    read_compound_context, read_config, read_is_migrated, read_pool_info, remove_compound_context,
+    rewards_read, rewards_store, stakers_store, store_compound_context, store_is_migrated,
+    store_pool_info, unbonding_entry_key, unbonding_queue_read, unbonding_queue_store, BondLock,
+    CompoundContext, Config, PoolInfo, RewardInfo, UnbondingEntry,
 };
 use cosmwasm_std::{
-    attr, to_binary, Addr, Api, CanonicalAddr, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, coin, to_binary, Addr, Api, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    WasmMsg,
 };
-use cw20::Cw20ExecuteMsg;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 use oraiswap::asset::{Asset, AssetInfo, PairInfo};
 use oraiswap::pair::ExecuteMsg as PairExecuteMsg;
-use oraiswap::querier::{query_pair_info, query_token_balance};
-use oraiswap::staking::ExecuteMsg;
+use oraiswap::querier::{query_pair_info, query_pair_info_from_pair, query_token_balance};
+use oraiswap::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+use oraiswap::staking::{ExecuteMsg, UnbondingEntryResponse, UnbondingResponse};
+
+pub const COMPOUND_REPLY_ID: u64 = 1;
+/// locks of a year or longer get the full boost
+const MAX_LOCK_DURATION: u64 = 365 * 24 * 60 * 60;
+const MAX_REWARD_WEIGHT: Decimal = Decimal::raw(2_500_000_000_000_000_000);
 
 pub fn bond(
     deps: DepsMut,
+    env: Env,
     staker_addr: Addr,
     staking_token: Addr,
     amount: Uint128,
+    lock_for: Option<u64>,
 ) -> StdResult<Response> {
     let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
-    _increase_bond_amount(
+    let lock = _increase_bond_amount(
         deps.storage,
         deps.api,
         &staker_addr_raw,
         staking_token.clone(),
         amount,
+        env.block.time.seconds(),
+        lock_for,
     )?;
 
-    Ok(Response::new().add_attributes([
-        ("action", "bond"),
-        ("staker_addr", staker_addr.as_str()),
-        ("staking_token", staking_token.as_str()),
-        ("amount", &amount.to_string()),
-    ]))
+    let mut attributes = vec![
+        attr("action", "bond"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("staking_token", staking_token.as_str()),
+        attr("amount", &amount.to_string()),
+    ];
+    if let Some(lock) = lock {
+        attributes.push(attr("lock_end_time", lock.end_time.to_string()));
+        attributes.push(attr("reward_weight", lock.reward_weight.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// 1x without a lock, growing linearly to MAX_REWARD_WEIGHT for a lock of MAX_LOCK_DURATION
+fn lock_reward_weight(lock_duration: u64) -> Decimal {
+    let lock_ratio = Decimal::from_ratio(lock_duration.min(MAX_LOCK_DURATION), MAX_LOCK_DURATION);
+    Decimal::one() + (MAX_REWARD_WEIGHT - Decimal::one()) * lock_ratio
 }
 
 pub fn unbond(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     staker_addr: Addr,
     staking_token: Addr,
     amount: Uint128,
@@ -52,18 +82,47 @@ pub fn unbond(
         &staker_addr_raw,
         &staking_token,
         amount,
+        env.block.time.seconds(),
     )?;
 
     let staking_token_addr = deps.api.addr_humanize(&staking_token)?;
-    let mut messages = vec![WasmMsg::Execute {
-        contract_addr: staking_token_addr.to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Transfer {
-            recipient: staker_addr.to_string(),
-            amount,
-        })?,
-        funds: vec![],
+    let config: Config = read_config(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "unbond"),
+        attr("staker_addr", staker_addr.as_str()),
+        attr("amount", &amount.to_string()),
+        attr("staking_token", staking_token_addr.as_str()),
+    ];
+
+    if config.unbonding_period == 0 {
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: staking_token_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker_addr.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        );
+    } else {
+        // the tokens stay in the contract until claimed with ClaimUnbonded
+        let release_time = env.block.time.seconds() + config.unbonding_period;
+        let key = unbonding_entry_key(&staking_token, release_time);
+        let mut unbonding_queue = unbonding_queue_store(deps.storage, &staker_addr_raw);
+        let mut entry = unbonding_queue
+            .may_load(&key)?
+            .unwrap_or_else(|| UnbondingEntry {
+                staking_token: staking_token.clone(),
+                amount: Uint128::zero(),
+                release_time,
+            });
+        entry.amount += amount;
+        unbonding_queue.save(&key, &entry)?;
+        attributes.push(attr("release_time", release_time.to_string()));
     }
-    .into()];
 
     // withdraw pending_withdraw assets (accumulated when changing reward_per_sec)
     messages.extend(
@@ -73,14 +132,83 @@ pub fn unbond(
             .collect::<StdResult<Vec<CosmosMsg>>>()?,
     );
 
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+pub fn claim_unbonded(deps: DepsMut, env: Env, staker_addr: Addr) -> StdResult<Response> {
+    validate_migrate_store_status(deps.storage)?;
+    let staker_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(staker_addr.as_str())?;
+    let block_time = env.block.time.seconds();
+
+    let matured_entries = unbonding_queue_read(deps.storage, &staker_addr_raw)
+        .range(None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(_, entry)| entry.release_time <= block_time)
+        })
+        .collect::<StdResult<Vec<(Vec<u8>, UnbondingEntry)>>>()?;
+    if matured_entries.is_empty() {
+        return Err(StdError::generic_err("No unbonded tokens to claim"));
+    }
+
+    // one transfer per staking token
+    let mut claims: Vec<(CanonicalAddr, Uint128)> = vec![];
+    let mut unbonding_queue = unbonding_queue_store(deps.storage, &staker_addr_raw);
+    for (key, entry) in matured_entries {
+        unbonding_queue.remove(&key);
+        match claims
+            .iter_mut()
+            .find(|(staking_token, _)| *staking_token == entry.staking_token)
+        {
+            Some((_, amount)) => *amount += entry.amount,
+            None => claims.push((entry.staking_token, entry.amount)),
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut claimed = vec![];
+    for (staking_token, amount) in claims {
+        let staking_token_addr = deps.api.addr_humanize(&staking_token)?;
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: staking_token_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker_addr.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        );
+        claimed.push(format!("{}{}", amount, staking_token_addr));
+    }
+
     Ok(Response::new().add_messages(messages).add_attributes([
-        attr("action", "unbond"),
-        attr("staker_addr", staker_addr.as_str()),
-        attr("amount", &amount.to_string()),
-        attr("staking_token", staking_token_addr.as_str()),
+        ("action", "claim_unbonded"),
+        ("staker_addr", staker_addr.as_str()),
+        ("claimed", &claimed.join(", ")),
     ]))
 }
 
+pub fn query_unbonding(deps: Deps, staker: Addr) -> StdResult<UnbondingResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(staker.as_str())?;
+    let entries = unbonding_queue_read(deps.storage, &staker_addr_raw)
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (_, entry) = item?;
+            Ok(UnbondingEntryResponse {
+                staking_token: deps.api.addr_humanize(&entry.staking_token)?,
+                amount: entry.amount,
+                release_time: entry.release_time,
+            })
+        })
+        .collect::<StdResult<Vec<UnbondingEntryResponse>>>()?;
+
+    Ok(UnbondingResponse { staker, entries })
+}
+
 pub fn auto_stake(
     deps: DepsMut,
     env: Env,
@@ -198,11 +326,334 @@ pub fn auto_stake_hook(
     }
 
     // stake all lp tokens received, compare with staking token amount before liquidity provision was executed
-    let current_staking_token_amount =
-        query_token_balance(&deps.querier, staking_token.clone(), env.contract.address)?;
+    let current_staking_token_amount = query_token_balance(
+        &deps.querier,
+        staking_token.clone(),
+        env.contract.address.clone(),
+    )?;
     let amount_to_stake = current_staking_token_amount.checked_sub(prev_staking_token_amount)?;
 
-    bond(deps, staker_addr, staking_token, amount_to_stake)
+    bond(deps, env, staker_addr, staking_token, amount_to_stake, None)
+}
+
+pub fn compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    minimum_receive: Option<[Uint128; 2]>,
+) -> StdResult<Response> {
+    validate_migrate_store_status(deps.storage)?;
+    let config: Config = read_config(deps.storage)?;
+    if config.router_addr.is_none() {
+        return Err(StdError::generic_err("Router is not configured"));
+    }
+
+    let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let asset_key = deps.api.addr_canonicalize(staking_token.as_str())?.to_vec();
+    let reward_assets: Vec<_> = process_reward_assets(
+        deps.storage,
+        &staker_addr_raw,
+        &Some(asset_key),
+        true,
+        env.block.time.seconds(),
+    )?
+    .into_iter()
+    .filter(|ra| !ra.amount.is_zero())
+    .collect();
+    if reward_assets.is_empty() {
+        return Err(StdError::generic_err("No rewards to compound"));
+    }
+
+    store_compound_context(
+        deps.storage,
+        &CompoundContext {
+            staker: staker_addr_raw,
+            reward_assets,
+        },
+    )?;
+
+    // the whole swap, provide and bond chain runs in one sub message, so a failure
+    // reverts all of it and the reply refunds the withdrawn rewards
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::CompoundHook {
+                    staking_token: staking_token.clone(),
+                    staker_addr: info.sender.clone(),
+                    minimum_receive,
+                })?,
+                funds: vec![],
+            },
+            COMPOUND_REPLY_ID,
+        ))
+        .add_attributes([
+            ("action", "compound"),
+            ("staker_addr", info.sender.as_str()),
+            ("staking_token", staking_token.as_str()),
+        ]))
+}
+
+pub fn compound_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    staker_addr: Addr,
+    minimum_receive: Option<[Uint128; 2]>,
+) -> StdResult<Response> {
+    // only can be called by itself
+    validate_migrate_store_status(deps.storage)?;
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let config: Config = read_config(deps.storage)?;
+    let router_addr = match config.router_addr {
+        Some(router_addr) => deps.api.addr_humanize(&router_addr)?,
+        None => return Err(StdError::generic_err("Router is not configured")),
+    };
+
+    // removing it here is reverted as well if the compound fails
+    let context = read_compound_context(deps.storage)?;
+    remove_compound_context(deps.storage);
+
+    let oraiswap_pair = query_lp_pair_info(&deps.querier, staking_token.clone())?;
+    let asset_infos = oraiswap_pair.asset_infos;
+
+    // balances without the rewards, what is above them after the swaps gets provided
+    let mut prev_balances = [Uint128::zero(); 2];
+    for (prev_balance, asset_info) in prev_balances.iter_mut().zip(asset_infos.iter()) {
+        *prev_balance = asset_info.query_pool(&deps.querier, env.contract.address.clone())?;
+    }
+
+    let mut msgs = vec![];
+    for reward_asset in context.reward_assets {
+        let reward_asset = reward_asset.to_normal(deps.api)?;
+        let half = reward_asset.amount.multiply_ratio(1u128, 2u128);
+
+        // a reward that is one of the pair assets keeps half and swaps the rest,
+        // any other reward is split between both assets
+        let swaps = match asset_infos.iter().position(|a| a.eq(&reward_asset.info)) {
+            Some(index) => {
+                prev_balances[index] = prev_balances[index].checked_sub(reward_asset.amount)?;
+                vec![(asset_infos[1 - index].clone(), half)]
+            }
+            None => vec![
+                (asset_infos[0].clone(), half),
+                (asset_infos[1].clone(), reward_asset.amount - half),
+            ],
+        };
+
+        for (ask_asset_info, amount) in swaps {
+            if amount.is_zero() {
+                continue;
+            }
+            msgs.push(router_swap_msg(
+                &router_addr,
+                reward_asset.info.clone(),
+                ask_asset_info,
+                amount,
+            )?);
+        }
+    }
+
+    msgs.push(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::CompoundProvideHook {
+            staking_token,
+            staker_addr,
+            prev_balances,
+            minimum_receive,
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "compound_hook"))
+}
+
+pub fn compound_provide_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_token: Addr,
+    staker_addr: Addr,
+    prev_balances: [Uint128; 2],
+    minimum_receive: Option<[Uint128; 2]>,
+) -> StdResult<Response> {
+    // only can be called by itself
+    validate_migrate_store_status(deps.storage)?;
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let oraiswap_pair = query_lp_pair_info(&deps.querier, staking_token.clone())?;
+
+    let mut amounts = [Uint128::zero(); 2];
+    for ((amount, asset_info), prev_balance) in amounts
+        .iter_mut()
+        .zip(oraiswap_pair.asset_infos.iter())
+        .zip(prev_balances)
+    {
+        *amount = asset_info
+            .query_pool(&deps.querier, env.contract.address.clone())?
+            .checked_sub(prev_balance)?;
+    }
+    // the swaps have run, so a sandwiched swap shows up as less than the staker accepts
+    if let Some(minimum_receive) = minimum_receive {
+        if amounts
+            .iter()
+            .zip(minimum_receive.iter())
+            .any(|(amount, minimum)| amount < minimum)
+        {
+            return Err(StdError::generic_err(format!(
+                "Compound amounts {}, {} are below the minimum receive {}, {}",
+                amounts[0], amounts[1], minimum_receive[0], minimum_receive[1]
+            )));
+        }
+    }
+    let [asset_info_0, asset_info_1] = oraiswap_pair.asset_infos;
+    let assets = [
+        Asset {
+            info: asset_info_0,
+            amount: amounts[0],
+        },
+        Asset {
+            info: asset_info_1,
+            amount: amounts[1],
+        },
+    ];
+
+    let mut msgs = vec![];
+    let mut funds = vec![];
+    for asset in assets.iter() {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => {
+                if !asset.amount.is_zero() {
+                    funds.push(coin(asset.amount.u128(), denom));
+                }
+            }
+            AssetInfo::Token { contract_addr } => {
+                msgs.push(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: oraiswap_pair.contract_addr.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                });
+            }
+        }
+    }
+
+    let prev_staking_token_amount = query_token_balance(
+        &deps.querier,
+        staking_token.clone(),
+        env.contract.address.clone(),
+    )?;
+
+    msgs.push(WasmMsg::Execute {
+        contract_addr: oraiswap_pair.contract_addr.to_string(),
+        msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+            assets: assets.clone(),
+            slippage_tolerance: None,
+            receiver: None,
+        })?,
+        funds,
+    });
+
+    msgs.push(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::AutoStakeHook {
+            staking_token,
+            staker_addr,
+            prev_staking_token_amount,
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new().add_messages(msgs).add_attributes([
+        ("action", "compound_provide_hook"),
+        ("assets", &format!("{}, {}", assets[0], assets[1])),
+    ]))
+}
+
+/// pays out the rewards of a failed compound
+pub fn compound_refund(deps: DepsMut, error: String) -> StdResult<Response> {
+    let context = read_compound_context(deps.storage)?;
+    remove_compound_context(deps.storage);
+
+    let staker_addr = deps.api.addr_humanize(&context.staker)?;
+    let messages = context
+        .reward_assets
+        .into_iter()
+        .map(|ra| {
+            Ok(ra
+                .to_normal(deps.api)?
+                .into_msg(None, &deps.querier, staker_addr.clone())?)
+        })
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        ("action", "compound_refund"),
+        ("staker_addr", staker_addr.as_str()),
+        ("error", &error),
+    ]))
+}
+
+/// the pair contract is the minter of its LP token
+fn query_lp_pair_info(querier: &QuerierWrapper, staking_token: Addr) -> StdResult<PairInfo> {
+    let minter: Option<MinterResponse> =
+        querier.query_wasm_smart(staking_token.clone(), &Cw20QueryMsg::Minter {})?;
+    let minter = minter.ok_or_else(|| StdError::generic_err("Invalid staking token"))?;
+
+    let pair_info = query_pair_info_from_pair(querier, Addr::unchecked(minter.minter))?;
+    if pair_info.liquidity_token != staking_token {
+        return Err(StdError::generic_err("Invalid staking token"));
+    }
+
+    Ok(pair_info)
+}
+
+fn router_swap_msg(
+    router_addr: &Addr,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    amount: Uint128,
+) -> StdResult<WasmMsg> {
+    let operations = vec![SwapOperation::OraiSwap {
+        offer_asset_info: offer_asset_info.clone(),
+        ask_asset_info,
+    }];
+
+    Ok(match offer_asset_info {
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: router_addr.to_string(),
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: None,
+                to: None,
+            })?,
+            funds: vec![coin(amount.u128(), denom)],
+        },
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: router_addr.to_string(),
+                amount,
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                })?,
+            })?,
+            funds: vec![],
+        },
+    })
 }
 
 fn _increase_bond_amount(
@@ -211,7 +662,9 @@ fn _increase_bond_amount(
     staker_addr: &CanonicalAddr,
     staking_token: Addr,
     amount: Uint128,
-) -> StdResult<()> {
+    block_time: u64,
+    lock_for: Option<u64>,
+) -> StdResult<Option<BondLock>> {
     let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
     let mut pool_info = read_pool_info(storage, &asset_key)?;
     let mut reward_info: RewardInfo = rewards_read(storage, staker_addr)
@@ -222,6 +675,9 @@ fn _increase_bond_amount(
             bond_amount: Uint128::zero(),
             pending_reward: Uint128::zero(),
             pending_withdraw: vec![],
+            indexes: Some(vec![]),
+            pending_rewards: vec![],
+            lock: None,
         });
 
     // check if the position should be migrated
@@ -237,13 +693,53 @@ fn _increase_bond_amount(
     }
 
     // Withdraw reward to pending reward; before changing share
-    before_share_change(pool_info.reward_index, &mut reward_info)?;
+    let (pool_index, pool_indexes) = (pool_info.reward_index, pool_info.reward_indexes.clone());
+    expire_lock(
+        storage,
+        &asset_key,
+        &mut pool_info,
+        pool_index,
+        &pool_indexes,
+        &mut reward_info,
+        block_time,
+    )?;
+    before_share_change(
+        storage,
+        &asset_key,
+        pool_index,
+        &pool_indexes,
+        &mut reward_info,
+    )?;
+    let prev_boosted_amount = boosted_amount(&reward_info)?;
+    if let Some(lock) = &reward_info.lock {
+        remove_lock_expiry(storage, &asset_key, lock.end_time, prev_boosted_amount)?;
+    }
 
     // Increase total bond amount
     pool_info.total_bond_amount += amount;
 
     reward_info.bond_amount += amount;
 
+    // the lock covers the whole position, its weight follows the remaining lock time
+    if let Some(lock_for) = lock_for.filter(|lock_for| *lock_for > 0) {
+        let end_time = reward_info
+            .lock
+            .as_ref()
+            .map_or(0, |lock| lock.end_time)
+            .max(block_time + lock_for);
+        reward_info.lock = Some(BondLock {
+            reward_weight: lock_reward_weight(end_time - block_time),
+            end_time,
+        });
+    }
+    let boosted_amount = boosted_amount(&reward_info)?;
+    pool_info.boosted_bond_amount =
+        (pool_info.boosted_bond_amount + boosted_amount).checked_sub(prev_boosted_amount)?;
+    let lock = reward_info.lock.clone();
+    if let Some(lock) = &lock {
+        add_lock_expiry(storage, &asset_key, lock.end_time, boosted_amount)?;
+    }
+
     rewards_store(storage, staker_addr).save(&asset_key, &reward_info)?;
     store_pool_info(storage, &asset_key, &pool_info)?;
 
@@ -253,7 +749,7 @@ fn _increase_bond_amount(
         stakers_bucket.save(staker_addr, &true)?;
     }
 
-    Ok(())
+    Ok(lock)
 }
 
 fn _decrease_bond_amount(
@@ -262,6 +758,7 @@ fn _decrease_bond_amount(
     staker_addr: &CanonicalAddr,
     staking_token: &Addr,
     amount: Uint128,
+    block_time: u64,
 ) -> StdResult<(CanonicalAddr, Vec<Asset>)> {
     let asset_key = api.addr_canonicalize(staking_token.as_str())?.to_vec();
     let mut pool_info: PoolInfo = read_pool_info(storage, &asset_key)?;
@@ -274,18 +771,48 @@ fn _decrease_bond_amount(
     // if the lp token was migrated, and the user did not close their position yet, cap the reward at the snapshot
     let should_migrate =
         !read_is_migrated(storage, &asset_key, staker_addr) && pool_info.migration_params.is_some();
-    let (pool_index, staking_token) = if should_migrate {
+    let (pool_index, pool_indexes, staking_token) = if should_migrate {
         let migraton_params = pool_info.migration_params.clone().unwrap();
         (
             migraton_params.index_snapshot,
+            migraton_params.index_snapshots,
             migraton_params.deprecated_staking_token,
         )
     } else {
-        (pool_info.reward_index, pool_info.staking_token.clone())
+        (
+            pool_info.reward_index,
+            pool_info.reward_indexes.clone(),
+            pool_info.staking_token.clone(),
+        )
     };
 
-    // Distribute reward to pending reward; before changing share
-    before_share_change(pool_index, &mut reward_info)?;
+    // Distribute reward to pending reward; before changing share.
+    // A deprecated position drops its boost too, so it does not stay in the pool once it exits
+    expire_lock(
+        storage,
+        &asset_key,
+        &mut pool_info,
+        pool_index,
+        &pool_indexes,
+        &mut reward_info,
+        block_time,
+    )?;
+    before_share_change(
+        storage,
+        &asset_key,
+        pool_index,
+        &pool_indexes,
+        &mut reward_info,
+    )?;
+
+    if let Some(lock) = &reward_info.lock {
+        if lock.end_time > block_time {
+            return Err(StdError::generic_err(format!(
+                "The bond is locked until {}",
+                lock.end_time
+            )));
+        }
+    }
 
     // Decrease total bond amount
     if !should_migrate {
@@ -300,7 +827,10 @@ fn _decrease_bond_amount(
         store_is_migrated(storage, &asset_key, staker_addr)?;
     }
 
-    if reward_info.pending_reward.is_zero() && reward_info.bond_amount.is_zero() {
+    if reward_info.pending_reward.is_zero()
+        && reward_info.pending_rewards.is_empty()
+        && reward_info.bond_amount.is_zero()
+    {
         // if pending_withdraw is not empty, then return reward_assets to withdraw money
         reward_assets = reward_info
             .pending_withdraw
@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use oraiswap::asset::AssetRaw;
+use oraiswap::asset::{AssetInfoRaw, AssetRaw};
+use oraiswap::querier::calc_range_start;
 
 use cosmwasm_std::{CanonicalAddr, Decimal, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
@@ -10,6 +11,10 @@ pub static PREFIX_REWARD: &[u8] = b"reward_v3";
 pub static PREFIX_STAKER: &[u8] = b"staker_v3";
 pub static PREFIX_IS_MIGRATED: &[u8] = b"is_migrated_v3";
 pub static PREFIX_REWARDS_PER_SEC: &[u8] = b"rewards_per_sec_v3";
+pub static PREFIX_UNBONDING_QUEUE: &[u8] = b"unbonding_queue";
+pub static KEY_COMPOUND_CONTEXT: &[u8] = b"compound_context";
+pub static PREFIX_LOCK_EXPIRY: &[u8] = b"lock_expiry";
+pub static PREFIX_LOCK_SNAPSHOT: &[u8] = b"lock_snapshot";
 // a key to validate if we have finished migrating the store. Only allow staking functionalities when we have finished migrating
 pub static KEY_MIGRATE_STORE_CHECK: &[u8] = b"migrate_store_check";
 
@@ -20,6 +25,12 @@ pub struct Config {
     pub oracle_addr: CanonicalAddr,
     pub factory_addr: CanonicalAddr,
     pub base_denom: String,
+    /// configs stored before the unbonding period existed pay out on unbond
+    #[serde(default)]
+    pub unbonding_period: u64,
+    /// router used by Compound to swap rewards into the pair assets
+    #[serde(default)]
+    pub router_addr: Option<CanonicalAddr>,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -48,12 +59,26 @@ pub struct PoolInfo {
     pub total_bond_amount: Uint128,
     pub reward_index: Decimal,
     pub migration_params: Option<MigrationParams>,
+    /// reward index of each reward asset, the deposits are split by the rewards per sec weights
+    #[serde(default)]
+    pub reward_indexes: Vec<RewardIndex>,
+    /// weight locked bonds add on top of total_bond_amount in the reward index math
+    #[serde(default)]
+    pub boosted_bond_amount: Uint128,
 }
 
 #[cw_serde]
 pub struct MigrationParams {
     pub index_snapshot: Decimal,
     pub deprecated_staking_token: CanonicalAddr,
+    #[serde(default)]
+    pub index_snapshots: Vec<RewardIndex>,
+}
+
+#[cw_serde]
+pub struct RewardIndex {
+    pub info: AssetInfoRaw,
+    pub index: Decimal,
 }
 
 pub fn remove_pool_info(storage: &mut dyn Storage, asset_key: &[u8]) {
@@ -78,6 +103,21 @@ pub fn read_all_pool_infos(storage: &dyn Storage) -> StdResult<Vec<(Vec<u8>, Poo
         .collect()
 }
 
+pub fn read_pool_infos(
+    storage: &dyn Storage,
+    start_after: Option<Vec<u8>>,
+    limit: usize,
+) -> StdResult<Vec<(Vec<u8>, PoolInfo)>> {
+    ReadonlyBucket::<PoolInfo>::new(storage, PREFIX_POOL_INFO)
+        .range(
+            calc_range_start(start_after).as_deref(),
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
 #[cw_serde]
 pub struct RewardInfo {
     pub native_token: bool,
@@ -86,6 +126,64 @@ pub struct RewardInfo {
     pub pending_reward: Uint128,
     // this is updated by the owner of this contract, when changing the reward_per_sec
     pub pending_withdraw: Vec<AssetRaw>,
+    /// index of each reward asset, None for records stored before rewards were tracked per asset
+    #[serde(default)]
+    pub indexes: Option<Vec<RewardIndex>>,
+    /// pending_reward split by reward asset
+    #[serde(default)]
+    pub pending_rewards: Vec<AssetRaw>,
+    #[serde(default)]
+    pub lock: Option<BondLock>,
+}
+
+/// a locked position can not be unbonded before end_time, and earns rewards as reward_weight times its bond amount
+#[cw_serde]
+pub struct BondLock {
+    pub reward_weight: Decimal,
+    pub end_time: u64,
+}
+
+/// pool indexes at the first deposit after some locks ended, their positions earn the boost up to it
+#[cw_serde]
+pub struct LockSnapshot {
+    pub index_snapshot: Decimal,
+    pub index_snapshots: Vec<RewardIndex>,
+    /// boost of the positions that did not settle their expired lock yet
+    pub boosted_amount: Uint128,
+}
+
+/// returns a bucket with the boost of the locks of a pool by end time, until a deposit passes it
+pub fn lock_expiries_store<'a>(
+    storage: &'a mut dyn Storage,
+    asset_key: &[u8],
+) -> Bucket<'a, Uint128> {
+    Bucket::multilevel(storage, &[PREFIX_LOCK_EXPIRY, asset_key])
+}
+
+/// returns a bucket with the boost of the locks of a pool by end time, until a deposit passes it
+/// (read-only version for queries)
+pub fn lock_expiries_read<'a>(
+    storage: &'a dyn Storage,
+    asset_key: &[u8],
+) -> ReadonlyBucket<'a, Uint128> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_LOCK_EXPIRY, asset_key])
+}
+
+/// returns a bucket with the lock snapshots of a pool by end time
+pub fn lock_snapshots_store<'a>(
+    storage: &'a mut dyn Storage,
+    asset_key: &[u8],
+) -> Bucket<'a, LockSnapshot> {
+    Bucket::multilevel(storage, &[PREFIX_LOCK_SNAPSHOT, asset_key])
+}
+
+/// returns a bucket with the lock snapshots of a pool by end time
+/// (read-only version for queries)
+pub fn lock_snapshots_read<'a>(
+    storage: &'a dyn Storage,
+    asset_key: &[u8],
+) -> ReadonlyBucket<'a, LockSnapshot> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_LOCK_SNAPSHOT, asset_key])
 }
 
 /// returns a bucket with all rewards owned by this staker (query it by staker)
@@ -138,3 +236,54 @@ pub fn read_rewards_per_sec(storage: &dyn Storage, asset_key: &[u8]) -> StdResul
         ReadonlyBucket::new(storage, PREFIX_REWARDS_PER_SEC);
     weight_bucket.load(asset_key)
 }
+
+#[cw_serde]
+pub struct UnbondingEntry {
+    pub staking_token: CanonicalAddr,
+    pub amount: Uint128,
+    pub release_time: u64,
+}
+
+/// entries are keyed by staking_token + release_time, so unbonds of the same token in one block are merged
+pub fn unbonding_entry_key(staking_token: &CanonicalAddr, release_time: u64) -> Vec<u8> {
+    [staking_token.as_slice(), &release_time.to_be_bytes()].concat()
+}
+
+/// returns a bucket with all unbonding entries of this staker
+pub fn unbonding_queue_store<'a>(
+    storage: &'a mut dyn Storage,
+    staker: &[u8],
+) -> Bucket<'a, UnbondingEntry> {
+    Bucket::multilevel(storage, &[PREFIX_UNBONDING_QUEUE, staker])
+}
+
+/// returns a bucket with all unbonding entries of this staker
+/// (read-only version for queries)
+pub fn unbonding_queue_read<'a>(
+    storage: &'a dyn Storage,
+    staker: &[u8],
+) -> ReadonlyBucket<'a, UnbondingEntry> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_UNBONDING_QUEUE, staker])
+}
+
+/// rewards withdrawn by an in-flight Compound, refunded to the staker if the compound fails
+#[cw_serde]
+pub struct CompoundContext {
+    pub staker: CanonicalAddr,
+    pub reward_assets: Vec<AssetRaw>,
+}
+
+pub fn store_compound_context(
+    storage: &mut dyn Storage,
+    context: &CompoundContext,
+) -> StdResult<()> {
+    singleton(storage, KEY_COMPOUND_CONTEXT).save(context)
+}
+
+pub fn read_compound_context(storage: &dyn Storage) -> StdResult<CompoundContext> {
+    singleton_read(storage, KEY_COMPOUND_CONTEXT).load()
+}
+
+pub fn remove_compound_context(storage: &mut dyn Storage) {
+    singleton::<CompoundContext>(storage, KEY_COMPOUND_CONTEXT).remove()
+}
@@ -2,13 +2,16 @@ use crate::contract::{execute, instantiate, query};
 use cosmwasm_std::testing::{
     mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
 };
-use cosmwasm_std::{attr, coin, from_binary, to_binary, Addr, Decimal, Order, StdError, Uint128};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, Decimal, Deps, Order, StdError, Uint128,
+};
 use cw20::Cw20ReceiveMsg;
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
 use oraiswap::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg,
-    RewardInfoResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolAprResponse, PoolInfoResponse,
+    QueryMsg, QueryPoolInfoResponse, RewardAprResponse, RewardInfoResponse,
 };
+use oraiswap::testing::ATOM_DENOM;
 
 #[test]
 fn proper_initialization() {
@@ -21,6 +24,8 @@ fn proper_initialization() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -38,6 +43,8 @@ fn proper_initialization() {
             oracle_addr: Addr::unchecked("oracle"),
             factory_addr: Addr::unchecked("factory"),
             base_denom: ORAI_DENOM.to_string(),
+            unbonding_period: 0,
+            router_addr: None,
         },
         config
     );
@@ -54,6 +61,8 @@ fn update_config() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -65,6 +74,8 @@ fn update_config() {
         owner: Some(Addr::unchecked("owner2")),
         rewarder: None,
         migrate_store_status: Some(true),
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -80,6 +91,8 @@ fn update_config() {
             oracle_addr: Addr::unchecked("oracle"),
             factory_addr: Addr::unchecked("factory"),
             base_denom: ORAI_DENOM.to_string(),
+            unbonding_period: 0,
+            router_addr: None,
         },
         config
     );
@@ -90,6 +103,8 @@ fn update_config() {
         rewarder: None,
         owner: None,
         migrate_store_status: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -110,6 +125,8 @@ fn test_register() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -172,6 +189,8 @@ fn test_query_staker_pagination() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -203,7 +222,7 @@ fn test_query_staker_pagination() {
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: format!("addr{}", i),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
         });
         let info = mock_info("staking", &[]);
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -231,3 +250,165 @@ fn test_query_staker_pagination() {
         println!("{:?}", staker_addrs);
     }
 }
+
+#[test]
+fn test_query_pools_pagination() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let staking_tokens: Vec<Addr> = (0..5)
+        .map(|i| Addr::unchecked(format!("staking{}", i)))
+        .collect();
+    for staking_token in staking_tokens.iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: staking_token.clone(),
+        };
+        let info = mock_info("owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let query_pools = |start_after: Option<Addr>| -> Vec<QueryPoolInfoResponse> {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Pools {
+                    start_after,
+                    limit: Some(3),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let first_page = query_pools(None);
+    assert_eq!(first_page.len(), 3);
+    let second_page = query_pools(first_page.last().map(|p| p.pool_info.staking_token.clone()));
+    assert_eq!(second_page.len(), 2);
+
+    // both pages together list every pool exactly once
+    let mut listed: Vec<Addr> = first_page
+        .into_iter()
+        .chain(second_page)
+        .map(|p| {
+            assert_eq!(p.asset_key, p.pool_info.staking_token.to_string());
+            p.pool_info.staking_token
+        })
+        .collect();
+    listed.sort();
+    assert_eq!(listed, staking_tokens);
+}
+
+#[test]
+fn test_query_pool_apr() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("reward"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let orai_info = AssetInfo::NativeToken {
+        denom: ORAI_DENOM.to_string(),
+    };
+    let atom_info = AssetInfo::NativeToken {
+        denom: ATOM_DENOM.to_string(),
+    };
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: orai_info.clone(),
+                amount: Uint128::from(1u128),
+            },
+            Asset {
+                info: atom_info.clone(),
+                amount: Uint128::from(2u128),
+            },
+        ],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let query_apr = |deps: Deps| -> PoolAprResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::PoolApr {
+                    staking_token: Addr::unchecked("staking"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // nothing bonded yet
+    assert_eq!(
+        query_apr(deps.as_ref()),
+        PoolAprResponse {
+            staking_token: Addr::unchecked("staking"),
+            aprs: vec![
+                RewardAprResponse {
+                    info: orai_info.clone(),
+                    apr: Decimal::zero(),
+                },
+                RewardAprResponse {
+                    info: atom_info.clone(),
+                    apr: Decimal::zero(),
+                },
+            ],
+        }
+    );
+
+    // bond four years worth of seconds, 1 orai per second is a quarter per bonded token a year
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(4u128 * 365 * 24 * 60 * 60),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+
+    assert_eq!(
+        query_apr(deps.as_ref()),
+        PoolAprResponse {
+            staking_token: Addr::unchecked("staking"),
+            aprs: vec![
+                RewardAprResponse {
+                    info: orai_info,
+                    apr: Decimal::percent(25),
+                },
+                RewardAprResponse {
+                    info: atom_info,
+                    apr: Decimal::percent(50),
+                },
+            ],
+        }
+    );
+}
@@ -1,5 +1,5 @@
 use crate::contract::{execute, instantiate, query};
-use crate::state::{read_pool_info, store_pool_info};
+use crate::state::{read_pool_info, store_pool_info, MigrationParams};
 use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
 use cosmwasm_std::{coin, from_binary, to_binary, Addr, Api, Decimal, SubMsg, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -24,6 +24,8 @@ fn test_deprecate() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -66,7 +68,7 @@ fn test_deprecate() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -195,7 +197,7 @@ fn test_deprecate() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
@@ -244,7 +246,7 @@ fn test_deprecate() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("new_staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -276,7 +278,7 @@ fn test_deprecate() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "newaddr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("new_staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -305,3 +307,78 @@ fn test_deprecate() {
         }
     );
 }
+
+#[test]
+fn test_deprecate_locked_position() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens locked for a year
+    let lock_for = 365 * 24 * 60 * 60;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            lock_for: Some(lock_for),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    let mut pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.boosted_bond_amount, Uint128::from(150u128));
+
+    // the position is deprecated before it unbonds
+    pool_info.migration_params = Some(MigrationParams {
+        index_snapshot: pool_info.reward_index,
+        deprecated_staking_token: pool_info.staking_token.clone(),
+        index_snapshots: pool_info.reward_indexes.clone(),
+    });
+    store_pool_info(&mut deps.storage, &asset_key, &pool_info).unwrap();
+
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info("addr", &[]);
+    let _err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+
+    // exiting the deprecated pool after the lock ended removes its boost from the pool
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(lock_for);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".into(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+    let pool_info = read_pool_info(&deps.storage, &asset_key).unwrap();
+    assert_eq!(pool_info.boosted_bond_amount, Uint128::zero());
+}
@@ -39,6 +39,9 @@ fn test_rewards_store_with_pending_withdraw() {
                         amount: Uint128::zero(),
                     },
                 ],
+                indexes: None,
+                pending_rewards: vec![],
+                lock: None,
             },
         )
         .unwrap();
@@ -68,6 +71,8 @@ fn test_validate_migrate_store_status() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
     let owner = mock_info("owner", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
@@ -82,6 +87,8 @@ fn test_validate_migrate_store_status() {
             rewarder: None,
             owner: None,
             migrate_store_status: Some(false),
+            unbonding_period: None,
+            router_addr: None,
         },
     )
     .unwrap();
@@ -105,6 +112,8 @@ fn test_validate_migrate_store_status_with_execute_msg() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
     let owner = mock_info("owner", &[]);
     let empty_addr = Addr::unchecked("");
@@ -118,6 +127,8 @@ fn test_validate_migrate_store_status_with_execute_msg() {
             rewarder: None,
             owner: None,
             migrate_store_status: Some(false),
+            unbonding_period: None,
+            router_addr: None,
         },
     )
     .unwrap();
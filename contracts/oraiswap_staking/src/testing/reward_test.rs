@@ -1,9 +1,16 @@
 use crate::contract::{execute, instantiate, query};
-use crate::state::{read_pool_info, rewards_read, store_pool_info, PoolInfo, RewardInfo};
-use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-use cosmwasm_std::{coin, from_binary, to_binary, Addr, Api, Decimal, Uint128};
-use cw20::Cw20ReceiveMsg;
-use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
+use crate::state::{
+    read_pool_info, rewards_read, rewards_store, store_pool_info, PoolInfo, RewardIndex, RewardInfo,
+};
+use cosmwasm_std::testing::{
+    mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, CosmosMsg, Decimal, OwnedDeps, SubMsg,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use oraiswap::asset::{Asset, AssetInfo, AssetInfoRaw, AssetRaw, ORAI_DENOM};
 use oraiswap::create_entry_points_testing;
 use oraiswap::staking::{
     Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, RewardInfoResponse,
@@ -26,6 +33,8 @@ fn test_deposit_reward() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -70,7 +79,7 @@ fn test_deposit_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -156,6 +165,8 @@ fn test_deposit_reward_when_no_bonding() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -274,6 +285,8 @@ fn test_before_share_changes() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -315,7 +328,7 @@ fn test_before_share_changes() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -341,6 +354,10 @@ fn test_before_share_changes() {
             index: Decimal::zero(),
             native_token: false,
             pending_withdraw: vec![],
+            // bonded before the first deposit
+            indexes: Some(vec![]),
+            pending_rewards: vec![],
+            lock: None,
         },
         reward_info
     );
@@ -349,7 +366,7 @@ fn test_before_share_changes() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -363,6 +380,36 @@ fn test_before_share_changes() {
             index: Decimal::from_ratio(100u128, 100u128),
             native_token: false,
             pending_withdraw: vec![],
+            // the deposit is split 1:2 between orai and atom
+            indexes: Some(vec![
+                RewardIndex {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    index: Decimal::raw(333333333333333333),
+                },
+                RewardIndex {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    index: Decimal::raw(666666666666666666),
+                },
+            ]),
+            pending_rewards: vec![
+                AssetRaw {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(33u128),
+                },
+                AssetRaw {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(66u128),
+                },
+            ],
+            lock: None,
         },
         reward_info
     );
@@ -394,6 +441,35 @@ fn test_before_share_changes() {
             index: Decimal::from_ratio(150u128, 100u128),
             native_token: false,
             pending_withdraw: vec![],
+            indexes: Some(vec![
+                RewardIndex {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    index: Decimal::raw(499999999999999999),
+                },
+                RewardIndex {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    index: Decimal::raw(999999999999999999),
+                },
+            ]),
+            pending_rewards: vec![
+                AssetRaw {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ORAI_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(66u128),
+                },
+                AssetRaw {
+                    info: AssetInfoRaw::NativeToken {
+                        denom: ATOM_DENOM.to_string(),
+                    },
+                    amount: Uint128::from(132u128),
+                },
+            ],
+            lock: None,
         },
         reward_info
     );
@@ -444,6 +520,8 @@ fn test_withdraw() {
         oracle_addr: app.oracle_addr.clone(),
         factory_addr: app.factory_addr.clone(),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let code_id = app.upload(Box::new(create_entry_points_testing!(crate)));
@@ -521,7 +599,7 @@ fn test_withdraw() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
 
     let _res = app
@@ -592,6 +670,8 @@ fn test_update_rewards_per_sec() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
     let staking_token = Addr::unchecked("staking_token");
 
@@ -634,7 +714,7 @@ fn test_update_rewards_per_sec() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info(staking_token.as_str(), &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -737,6 +817,8 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -778,7 +860,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -822,7 +904,7 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr1".into(),
         amount: Uint128::from(300u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -924,3 +1006,248 @@ fn test_update_rewards_per_sec_with_multiple_bond() {
         }
     );
 }
+
+#[test]
+fn test_withdraw_all() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let staking_tokens = [
+        Addr::unchecked("staking_token1"),
+        Addr::unchecked("staking_token2"),
+        Addr::unchecked("staking_token3"),
+    ];
+
+    // the staker bonds 100 tokens in each pool rewarded in orai
+    for staking_token in staking_tokens.iter() {
+        let msg = ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: staking_token.clone(),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 100u128.into(),
+            }],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::RegisterAsset {
+            staking_token: staking_token.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr".into(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
+        });
+        let info = mock_info(staking_token.as_str(), &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let deposit_rewards = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let msg = ExecuteMsg::DepositReward {
+            rewards: staking_tokens
+                .iter()
+                .map(|staking_token| RewardMsg {
+                    staking_token: staking_token.clone(),
+                    total_accumulation_amount: Uint128::from(100u128),
+                })
+                .collect(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    };
+    let withdraw_all = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                        start_after: Option<Addr>,
+                        limit: Option<u32>| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr", &[]),
+            ExecuteMsg::WithdrawAll { start_after, limit },
+        )
+        .unwrap()
+    };
+    let send_orai = |amount: u128| {
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(amount, ORAI_DENOM)],
+        }))]
+    };
+
+    // one call pays the rewards of all three pools at once
+    deposit_rewards(&mut deps);
+    let res = withdraw_all(&mut deps, None, None);
+    assert_eq!(res.messages, send_orai(300));
+    assert_eq!(res.attributes[1], attr("pools", "3"));
+
+    // a limited call pays a page, the next page starts after the last paid pool
+    deposit_rewards(&mut deps);
+    let res = withdraw_all(&mut deps, None, Some(2));
+    assert_eq!(res.messages, send_orai(200));
+    let last_staking_token = Addr::unchecked(res.attributes[3].value.clone());
+    let res = withdraw_all(&mut deps, Some(last_staking_token), Some(2));
+    assert_eq!(res.messages, send_orai(100));
+    assert_eq!(res.attributes[1], attr("pools", "1"));
+
+    // everything was paid
+    let res = withdraw_all(&mut deps, None, None);
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn test_multiple_reward_assets() {
+    let mut deps = mock_dependencies_with_balance(&[
+        coin(10000000000u128, ORAI_DENOM),
+        coin(20000000000u128, ATOM_DENOM),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+    let _res = instantiate(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // rewards are split 1:1:2 between orai, atom and the reward token
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ORAI_DENOM.to_string(),
+                },
+                amount: 1u128.into(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: 1u128.into(),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("reward"),
+                },
+                amount: 2u128.into(),
+            },
+        ],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let bond = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, staker: &str| {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("staking", &[]), msg).unwrap();
+    };
+    let deposit_reward = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let msg = ExecuteMsg::DepositReward {
+            rewards: vec![RewardMsg {
+                staking_token: Addr::unchecked("staking"),
+                total_accumulation_amount: Uint128::from(400u128),
+            }],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("rewarder", &[]), msg).unwrap();
+    };
+    let withdraw = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, staker: &str| {
+        let msg = ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(staker, &[]), msg)
+            .unwrap()
+            .messages
+    };
+    let rewards = |staker: &str, orai: u128, atom: u128, reward: u128| {
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: staker.to_string(),
+                amount: vec![coin(orai, ORAI_DENOM)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: staker.to_string(),
+                amount: vec![coin(atom, ATOM_DENOM)],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "reward".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker.to_string(),
+                    amount: Uint128::from(reward),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    };
+
+    // addr earns the whole first deposit, both stakers share the second one
+    bond(&mut deps, "addr");
+    deposit_reward(&mut deps);
+    bond(&mut deps, "addr1");
+    deposit_reward(&mut deps);
+
+    let pool_info = read_pool_info(
+        &deps.storage,
+        &deps.api.addr_canonicalize("staking").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pool_info
+            .reward_indexes
+            .iter()
+            .map(|ri| ri.index)
+            .collect::<Vec<Decimal>>(),
+        vec![
+            Decimal::percent(150),
+            Decimal::percent(150),
+            Decimal::percent(300)
+        ]
+    );
+
+    assert_eq!(withdraw(&mut deps, "addr"), rewards("addr", 150, 150, 300));
+    assert_eq!(withdraw(&mut deps, "addr1"), rewards("addr1", 50, 50, 100));
+
+    // a record stored before the per asset indexes splits its pending reward by the weights
+    let staker_raw = deps.api.addr_canonicalize("legacy").unwrap();
+    let asset_key = deps.api.addr_canonicalize("staking").unwrap();
+    rewards_store(&mut deps.storage, &staker_raw)
+        .save(
+            &asset_key,
+            &RewardInfo {
+                native_token: false,
+                index: pool_info.reward_index,
+                bond_amount: Uint128::zero(),
+                pending_reward: Uint128::from(80u128),
+                pending_withdraw: vec![],
+                indexes: None,
+                pending_rewards: vec![],
+                lock: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(withdraw(&mut deps, "legacy"), rewards("legacy", 20, 20, 40));
+}
@@ -4,8 +4,8 @@ use cosmwasm_std::testing::{
     mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
 };
 use cosmwasm_std::{
-    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, StdError,
-    SubMsg, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env,
+    StdError, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use oraiswap::asset::{Asset, AssetInfo, ORAI_DENOM};
@@ -13,7 +13,7 @@ use oraiswap::create_entry_points_testing;
 use oraiswap::pair::PairResponse;
 use oraiswap::staking::{
     Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfoResponse, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem, RewardMsg,
+    RewardInfoResponseItem, RewardMsg, UnbondingEntryResponse, UnbondingResponse,
 };
 use oraiswap::testing::{AttributeUtil, MockApp, ATOM_DENOM};
 
@@ -48,6 +48,8 @@ fn test_query_all_pool_keys() {
             total_bond_amount: amount.clone(),
             reward_index: Decimal::zero(),
             migration_params: None,
+            reward_indexes: vec![],
+            boosted_bond_amount: Uint128::zero(),
         };
         store_pool_info(storage, &asset_key, &pool_info).unwrap();
     }
@@ -75,6 +77,8 @@ fn test_bond_tokens() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -90,7 +94,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
 
     let info = mock_info("staking", &[]);
@@ -145,7 +149,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr2".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -186,6 +190,8 @@ fn test_unbond() {
         oracle_addr: Addr::unchecked("oracle"),
         factory_addr: Addr::unchecked("factory"),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let info = mock_info("addr", &[]);
@@ -224,7 +230,7 @@ fn test_unbond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
     });
     let info = mock_info("staking", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -345,6 +351,171 @@ fn test_unbond() {
     );
 }
 
+#[test]
+fn test_unbond_with_unbonding_period() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: Some(100),
+        router_addr: None,
+    };
+
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
+    });
+    let info = mock_info("staking", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env_after = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    };
+    let unbond = |amount: u128| ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(amount),
+    };
+    let start = mock_env().block.time.seconds();
+
+    // unbonded tokens are queued instead of transferred
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        unbond(60),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes.last(),
+        Some(&attr("release_time", (start + 100).to_string()))
+    );
+    let _res = execute(
+        deps.as_mut(),
+        env_after(10),
+        mock_info("addr", &[]),
+        unbond(40),
+    )
+    .unwrap();
+
+    let res: UnbondingResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Unbonding {
+                staker: Addr::unchecked("addr"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.entries,
+        vec![
+            UnbondingEntryResponse {
+                staking_token: Addr::unchecked("staking"),
+                amount: Uint128::from(60u128),
+                release_time: start + 100,
+            },
+            UnbondingEntryResponse {
+                staking_token: Addr::unchecked("staking"),
+                amount: Uint128::from(40u128),
+                release_time: start + 110,
+            },
+        ]
+    );
+
+    // nothing has matured yet
+    let res = execute(
+        deps.as_mut(),
+        env_after(50),
+        mock_info("addr", &[]),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("No unbonded tokens to claim"));
+
+    // only the first entry has matured
+    let res = execute(
+        deps.as_mut(),
+        env_after(105),
+        mock_info("addr", &[]),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(60u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env_after(110),
+        mock_info("addr", &[]),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr".to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // the queue is empty and can not be claimed twice
+    let res: UnbondingResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Unbonding {
+                staker: Addr::unchecked("addr"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.entries.is_empty());
+    let res = execute(
+        deps.as_mut(),
+        env_after(200),
+        mock_info("addr", &[]),
+        ExecuteMsg::ClaimUnbonded {},
+    );
+    assert!(res.is_err());
+}
+
 #[test]
 fn test_auto_stake() {
     let mut app = MockApp::new(&[(&"addr".to_string(), &[coin(10000000000u128, ORAI_DENOM)])]);
@@ -399,7 +570,7 @@ fn test_auto_stake() {
         asset_addr.clone(),
         &cw20::Cw20ExecuteMsg::IncreaseAllowance {
             spender: pair_addr.to_string(),
-            amount: Uint128::from(100u128),
+            amount: Uint128::from(10000u128),
             expires: None,
         },
         &[],
@@ -414,13 +585,13 @@ fn test_auto_stake() {
                 info: AssetInfo::NativeToken {
                     denom: ORAI_DENOM.to_string(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
             Asset {
                 info: AssetInfo::Token {
                     contract_addr: asset_addr.clone(),
                 },
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             },
         ],
         slippage_tolerance: None,
@@ -434,7 +605,7 @@ fn test_auto_stake() {
             &msg,
             &[Coin {
                 denom: ORAI_DENOM.to_string(),
-                amount: Uint128::from(100u128),
+                amount: Uint128::from(10000u128),
             }],
         )
         .unwrap();
@@ -448,6 +619,8 @@ fn test_auto_stake() {
         oracle_addr: app.oracle_addr.clone(),
         factory_addr: app.factory_addr.clone(),
         base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
     };
 
     let staking_addr = app
@@ -619,3 +792,482 @@ fn test_auto_stake() {
         }
     );
 }
+
+#[test]
+fn test_compound() {
+    let mut app = MockApp::new(&[(
+        &"addr".to_string(),
+        &[
+            coin(10000000000u128, ORAI_DENOM),
+            coin(10000000000u128, ATOM_DENOM),
+        ],
+    )]);
+
+    app.set_oracle_contract(Box::new(create_entry_points_testing!(oraiswap_oracle)));
+
+    app.set_token_contract(Box::new(create_entry_points_testing!(oraiswap_token)));
+
+    app.set_factory_and_pair_contract(
+        Box::new(
+            create_entry_points_testing!(oraiswap_factory)
+                .with_reply(oraiswap_factory::contract::reply),
+        ),
+        Box::new(
+            create_entry_points_testing!(oraiswap_pair).with_reply(oraiswap_pair::contract::reply),
+        ),
+    );
+
+    let asset_addr = app.create_token("asset");
+    let reward_addr = app.create_token("reward");
+    app.set_token_balances(&[(
+        &"asset".to_string(),
+        &[(&"addr".to_string(), &Uint128::from(10000000000u128))],
+    )]);
+
+    let asset_infos = [
+        AssetInfo::NativeToken {
+            denom: ORAI_DENOM.to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: asset_addr.clone(),
+        },
+    ];
+
+    let pair_addr = app.create_pair(asset_infos.clone()).unwrap();
+    let PairResponse { info: pair_info } = app
+        .query(pair_addr.clone(), &oraiswap::pair::QueryMsg::Pair {})
+        .unwrap();
+
+    app.execute(
+        Addr::unchecked("addr"),
+        asset_addr.clone(),
+        &cw20::Cw20ExecuteMsg::IncreaseAllowance {
+            spender: pair_addr.to_string(),
+            amount: Uint128::from(1000000u128),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute(
+        Addr::unchecked("addr"),
+        pair_addr.clone(),
+        &oraiswap::pair::ExecuteMsg::ProvideLiquidity {
+            assets: [
+                Asset {
+                    info: asset_infos[0].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+                Asset {
+                    info: asset_infos[1].clone(),
+                    amount: Uint128::from(1000000u128),
+                },
+            ],
+            slippage_tolerance: None,
+            receiver: None,
+        },
+        &[coin(1000000u128, ORAI_DENOM)],
+    )
+    .unwrap();
+
+    let router_id = app.upload(Box::new(create_entry_points_testing!(oraiswap_router)));
+    let router_addr = app
+        .instantiate(
+            router_id,
+            Addr::unchecked("addr"),
+            &oraiswap::router::InstantiateMsg {
+                factory_addr: app.factory_addr.clone(),
+                factory_addr_v2: app.factory_addr.clone(),
+                max_route_length: None,
+                limit_order_addr: None,
+            },
+            &[],
+            "router",
+        )
+        .unwrap();
+
+    let code_id = app.upload(Box::new(
+        create_entry_points_testing!(crate).with_reply(crate::contract::reply),
+    ));
+    let staking_addr = app
+        .instantiate(
+            code_id,
+            Addr::unchecked("addr"),
+            &InstantiateMsg {
+                owner: Some(Addr::unchecked("owner")),
+                rewarder: reward_addr.clone(),
+                minter: Some(Addr::unchecked("mint")),
+                oracle_addr: app.oracle_addr.clone(),
+                factory_addr: app.factory_addr.clone(),
+                base_denom: None,
+                unbonding_period: None,
+                router_addr: None,
+            },
+            &[],
+            "staking",
+        )
+        .unwrap();
+
+    // the rewards are paid from the staking contract balance
+    app.set_balances_from(
+        Addr::unchecked("addr"),
+        &[
+            (
+                &ORAI_DENOM.to_string(),
+                &[(&staking_addr.to_string(), &Uint128::from(1000000u128))],
+            ),
+            (
+                &ATOM_DENOM.to_string(),
+                &[(&staking_addr.to_string(), &Uint128::from(1000000u128))],
+            ),
+        ],
+    );
+
+    app.execute(
+        Addr::unchecked("owner"),
+        staking_addr.clone(),
+        &ExecuteMsg::RegisterAsset {
+            staking_token: pair_info.liquidity_token.clone(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("owner"),
+        staking_addr.clone(),
+        &ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: pair_info.liquidity_token.clone(),
+            assets: vec![Asset {
+                info: asset_infos[0].clone(),
+                amount: Uint128::from(100u128),
+            }],
+        },
+        &[],
+    )
+    .unwrap();
+
+    // bond 10000 LP tokens
+    app.execute(
+        Addr::unchecked("addr"),
+        pair_info.liquidity_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: staking_addr.to_string(),
+            amount: Uint128::from(10000u128),
+            msg: to_binary(&Cw20HookMsg::Bond { lock_for: None }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let deposit_reward = ExecuteMsg::DepositReward {
+        rewards: vec![RewardMsg {
+            staking_token: pair_info.liquidity_token.clone(),
+            total_accumulation_amount: Uint128::from(20000u128),
+        }],
+    };
+    app.execute(
+        reward_addr.clone(),
+        staking_addr.clone(),
+        &deposit_reward,
+        &[],
+    )
+    .unwrap();
+
+    let compound = ExecuteMsg::Compound {
+        staking_token: pair_info.liquidity_token.clone(),
+        minimum_receive: None,
+    };
+
+    // compound is disabled until the router is set
+    let res = app.execute(
+        Addr::unchecked("addr"),
+        staking_addr.clone(),
+        &compound,
+        &[],
+    );
+    app.assert_fail(res);
+
+    app.execute(
+        Addr::unchecked("owner"),
+        staking_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            rewarder: None,
+            owner: None,
+            migrate_store_status: None,
+            unbonding_period: None,
+            router_addr: Some(router_addr.clone()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the hooks can only be called by the contract itself
+    let res = app.execute(
+        Addr::unchecked("addr"),
+        staking_addr.clone(),
+        &ExecuteMsg::CompoundHook {
+            staking_token: pair_info.liquidity_token.clone(),
+            staker_addr: Addr::unchecked("addr"),
+            minimum_receive: None,
+        },
+        &[],
+    );
+    app.assert_fail(res);
+
+    // the swaps can not give that much, so the rewards are paid out instead
+    let orai_balance = app
+        .query_balance(Addr::unchecked("addr"), ORAI_DENOM.to_string())
+        .unwrap();
+    let res = app
+        .execute(
+            Addr::unchecked("addr"),
+            staking_addr.clone(),
+            &ExecuteMsg::Compound {
+                staking_token: pair_info.liquidity_token.clone(),
+                minimum_receive: Some([Uint128::from(20000u128); 2]),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("action", "compound_refund"))));
+    assert!(
+        app.query_balance(Addr::unchecked("addr"), ORAI_DENOM.to_string())
+            .unwrap()
+            > orai_balance
+    );
+
+    app.execute(
+        reward_addr.clone(),
+        staking_addr.clone(),
+        &deposit_reward,
+        &[],
+    )
+    .unwrap();
+    app.execute(
+        Addr::unchecked("addr"),
+        staking_addr.clone(),
+        &compound,
+        &[],
+    )
+    .unwrap();
+
+    let query_bond_amount = |app: &MockApp| {
+        let res: RewardInfoResponse = app
+            .query(
+                staking_addr.clone(),
+                &QueryMsg::RewardInfo {
+                    staker_addr: Addr::unchecked("addr"),
+                    staking_token: Some(pair_info.liquidity_token.clone()),
+                },
+            )
+            .unwrap();
+        (
+            res.reward_infos[0].bond_amount,
+            res.reward_infos[0].pending_reward,
+        )
+    };
+
+    // half of the 20000 orai rewards is swapped for the asset and both are provided
+    let (bond_amount, pending_reward) = query_bond_amount(&app);
+    assert!(bond_amount > Uint128::from(10000u128));
+    assert_eq!(pending_reward, Uint128::zero());
+
+    // nothing left to compound
+    let res = app.execute(
+        Addr::unchecked("addr"),
+        staking_addr.clone(),
+        &compound,
+        &[],
+    );
+    app.assert_fail(res);
+
+    // there is no atom pair, so the swap fails and the rewards are paid out instead
+    app.execute(
+        Addr::unchecked("owner"),
+        staking_addr.clone(),
+        &ExecuteMsg::UpdateRewardsPerSec {
+            staking_token: pair_info.liquidity_token.clone(),
+            assets: vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: ATOM_DENOM.to_string(),
+                },
+                amount: Uint128::from(100u128),
+            }],
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute(
+        reward_addr.clone(),
+        staking_addr.clone(),
+        &deposit_reward,
+        &[],
+    )
+    .unwrap();
+
+    let atom_balance = app
+        .query_balance(Addr::unchecked("addr"), ATOM_DENOM.to_string())
+        .unwrap();
+    let (_, pending_reward) = query_bond_amount(&app);
+    assert!(!pending_reward.is_zero());
+    let res = app
+        .execute(
+            Addr::unchecked("addr"),
+            staking_addr.clone(),
+            &compound,
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .contains(&attr("action", "compound_refund"))));
+    assert_eq!(
+        app.query_balance(Addr::unchecked("addr"), ATOM_DENOM.to_string())
+            .unwrap(),
+        atom_balance + pending_reward
+    );
+    assert_eq!(query_bond_amount(&app), (bond_amount, Uint128::zero()));
+}
+
+#[test]
+fn test_bond_with_lock() {
+    let mut deps = mock_dependencies_with_balance(&[coin(10000000000u128, ORAI_DENOM)]);
+
+    let msg = InstantiateMsg {
+        owner: Some(Addr::unchecked("owner")),
+        rewarder: Addr::unchecked("rewarder"),
+        minter: Some(Addr::unchecked("mint")),
+        oracle_addr: Addr::unchecked("oracle"),
+        factory_addr: Addr::unchecked("factory"),
+        base_denom: None,
+        unbonding_period: None,
+        router_addr: None,
+    };
+    let info = mock_info("addr", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateRewardsPerSec {
+        staking_token: Addr::unchecked("staking"),
+        assets: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: ORAI_DENOM.to_string(),
+            },
+            amount: 100u128.into(),
+        }],
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        staking_token: Addr::unchecked("staking"),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // addr bonds without a lock, addr1 locks for a year and gets the full boost
+    let lock_for = 365 * 24 * 60 * 60;
+    let lock_end_time = mock_env().block.time.seconds() + lock_for;
+    for (staker, lock_for) in [("addr", None), ("addr1", Some(lock_for))] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Bond { lock_for }).unwrap(),
+        });
+        let info = mock_info("staking", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        if lock_for.is_some() {
+            assert_eq!(
+                res.attributes[4..],
+                [
+                    attr("lock_end_time", lock_end_time.to_string()),
+                    attr("reward_weight", "2.5"),
+                ]
+            );
+        }
+    }
+
+    let deposit_reward = |deps: DepsMut, env: Env, amount: u128| {
+        let msg = ExecuteMsg::DepositReward {
+            rewards: vec![RewardMsg {
+                staking_token: Addr::unchecked("staking"),
+                total_accumulation_amount: Uint128::from(amount),
+            }],
+        };
+        let info = mock_info("rewarder", &[]);
+        execute(deps, env, info, msg).unwrap();
+    };
+    let withdraw = |deps: DepsMut, env: Env, staker: &str| {
+        let msg = ExecuteMsg::Withdraw {
+            staking_token: Some(Addr::unchecked("staking")),
+        };
+        execute(deps, env, mock_info(staker, &[]), msg)
+            .unwrap()
+            .messages
+    };
+    let reward = |staker: &str, amount: u128| {
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: staker.to_string(),
+            amount: vec![coin(amount, ORAI_DENOM)],
+        }))]
+    };
+
+    // the locked bond is weighted 2.5x: 350 is split 100 / 250
+    deposit_reward(deps.as_mut(), mock_env(), 350);
+    assert_eq!(
+        withdraw(deps.as_mut(), mock_env(), "addr"),
+        reward("addr", 100)
+    );
+    assert_eq!(
+        withdraw(deps.as_mut(), mock_env(), "addr1"),
+        reward("addr1", 250)
+    );
+
+    // cannot unbond before the lock ends
+    let msg = ExecuteMsg::Unbond {
+        staking_token: Addr::unchecked("staking"),
+        amount: Uint128::from(100u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr1", &[]),
+        msg.clone(),
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err(format!(
+            "The bond is locked until {}",
+            lock_end_time
+        )))
+    );
+
+    // the lock ended before this deposit, so it is not boosted even though addr1 did not settle yet
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(lock_for + 1);
+    deposit_reward(deps.as_mut(), env.clone(), 200);
+    assert_eq!(
+        withdraw(deps.as_mut(), env.clone(), "addr"),
+        reward("addr", 100)
+    );
+    assert_eq!(
+        withdraw(deps.as_mut(), env.clone(), "addr1"),
+        reward("addr1", 100)
+    );
+
+    let res = execute(deps.as_mut(), env, mock_info("addr1", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "staking".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr1".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
@@ -5,13 +5,32 @@ use crate::oracle::OracleContract;
 use crate::querier::query_token_balance;
 
 use cosmwasm_std::{
-    coin, to_binary, Addr, Api, BankMsg, CanonicalAddr, CosmosMsg, Decimal, MessageInfo,
+    coin, to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal, MessageInfo,
     QuerierWrapper, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
 pub const ORAI_DENOM: &str = "orai";
 
+/// tax deducted from amount, bounded by the cap and by max_tax_ratio of the amount
+/// so a misconfigured oracle can not swallow the whole transfer
+pub fn compute_tax_amount(
+    amount: Uint128,
+    tax_rate: Decimal,
+    tax_cap: Uint128,
+    max_tax_ratio: Decimal,
+) -> StdResult<Uint128> {
+    let tax = amount.checked_sub(amount * (Decimal::one() / (tax_rate + Decimal::one())))?;
+    Ok(tax.min(tax_cap).min(amount * max_tax_ratio))
+}
+
+fn add_coin(coins: &mut Vec<Coin>, denom: &str, amount: Uint128) {
+    match coins.iter_mut().find(|coin| coin.denom == denom) {
+        Some(coin) => coin.amount += amount,
+        None => coins.push(coin(amount.u128(), denom)),
+    }
+}
+
 #[cw_serde]
 pub struct Asset {
     pub info: AssetInfo,
@@ -39,16 +58,8 @@ impl Asset {
             if denom == ORAI_DENOM {
                 Ok(Uint128::from(0u64))
             } else {
-                // get oracle params from oracle contract
-                let tax_rate = oracle_contract.query_tax_rate(querier)?.rate;
-                let tax_cap = oracle_contract
-                    .query_tax_cap(querier, denom.to_string())?
-                    .cap;
-
-                Ok(std::cmp::min(
-                    amount.checked_sub(amount * (Decimal::one() / (tax_rate + Decimal::one())))?,
-                    tax_cap,
-                ))
+                // oracle applies its tax rate, cap and max tax ratio
+                Ok(oracle_contract.query_tax(querier, amount, denom)?.tax)
             }
         } else {
             Ok(Uint128::from(0u64))
@@ -116,6 +127,34 @@ impl Asset {
         }
     }
 
+    /// the native funds must match the native assets exactly, summed by denom,
+    /// any fund that is not part of the assets is rejected as well
+    pub fn assert_sent_native_tokens(
+        assets: &[Asset],
+        message_info: &MessageInfo,
+    ) -> StdResult<()> {
+        let mut expected: Vec<Coin> = vec![];
+        for asset in assets {
+            if let AssetInfo::NativeToken { denom } = &asset.info {
+                add_coin(&mut expected, denom, asset.amount);
+            }
+        }
+        let mut sent: Vec<Coin> = vec![];
+        for fund in message_info.funds.iter() {
+            add_coin(&mut sent, &fund.denom, fund.amount);
+        }
+        expected.retain(|coin| !coin.amount.is_zero());
+        sent.retain(|coin| !coin.amount.is_zero());
+
+        if expected.len() != sent.len() || expected.iter().any(|coin| !sent.contains(coin)) {
+            return Err(StdError::generic_err(
+                "Native token balance mismatch between the argument and the transferred",
+            ));
+        }
+
+        Ok(())
+    }
+
     pub fn to_raw(&self, api: &dyn Api) -> StdResult<AssetRaw> {
         Ok(AssetRaw {
             info: match &self.info {
@@ -286,6 +325,13 @@ pub struct PairInfo {
 
     pub oracle_addr: Addr,
     pub commission_rate: String,
+    /// set by the factory owner, a disabled pair is left out of the factory listing
+    #[serde(default = "default_pair_enabled")]
+    pub enabled: bool,
+}
+
+fn default_pair_enabled() -> bool {
+    true
 }
 
 #[cw_serde]
@@ -297,6 +343,8 @@ pub struct PairInfoRaw {
     // oracle contract
     pub oracle_addr: CanonicalAddr,
     pub commission_rate: String,
+    #[serde(default = "default_pair_enabled")]
+    pub enabled: bool,
 }
 
 impl PairInfoRaw {
@@ -310,6 +358,7 @@ impl PairInfoRaw {
                 self.asset_infos[1].to_normal(api)?,
             ],
             commission_rate: self.commission_rate.clone(),
+            enabled: self.enabled,
         })
     }
 
@@ -1,8 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo};
 use cw20::Cw20ReceiveMsg;
 
 #[cw_serde]
@@ -17,6 +17,13 @@ pub struct TokenRatio {
     pub ratio: Decimal,
 }
 
+/// amount is clamped to the converter balance, the whole balance is withdrawn when it is not given
+#[cw_serde]
+pub struct WithdrawAsset {
+    pub info: AssetInfo,
+    pub amount: Option<Uint128>,
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {}
 #[cw_serde]
@@ -32,19 +39,43 @@ pub enum ExecuteMsg {
     UpdateConfig {
         owner: Addr,
     },
-    Convert {},
+    /// halts or resumes every conversion, registered pairs are kept
+    SetPaused {
+        paused: bool,
+    },
+    /// to_asset picks one of the registered targets, otherwise the default one is used
+    Convert {
+        to_asset: Option<AssetInfo>,
+        /// receives the converted asset instead of the sender
+        recipient: Option<Addr>,
+        /// fails the conversion of any sent coin that would pay out less, guarding against ratio updates
+        minimum_receive: Option<Uint128>,
+    },
     UpdatePair {
         from: TokenInfo,
         to: TokenInfo,
+        /// explicit ratio overriding the one derived from decimals, e.g. a wrapped token premium
+        ratio: Option<Decimal>,
+        /// makes to the default target of a plain Convert, true when omitted
+        set_default: Option<bool>,
     },
+    /// removes a single target when to is given, otherwise every target of from
     UnregisterPair {
         from: TokenInfo,
+        to: Option<TokenInfo>,
     },
     ConvertReverse {
         from_asset: AssetInfo,
+        /// receives the converted asset instead of the sender
+        recipient: Option<Addr>,
+    },
+    /// converts the funds of route[0] hop by hop along the registered ratios, paying out only the last asset.
+    /// The route must start from a native token, visit each asset once and have at most 5 assets
+    ConvertRoute {
+        route: Vec<AssetInfo>,
     },
     WithdrawTokens {
-        asset_infos: Vec<AssetInfo>,
+        assets: Vec<WithdrawAsset>,
     },
 }
 
@@ -54,22 +85,66 @@ pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
     #[returns(ConvertInfoResponse)]
-    ConvertInfo { asset_info: AssetInfo },
+    ConvertInfo {
+        asset_info: AssetInfo,
+        to_asset: Option<AssetInfo>,
+    },
+    /// all registered from assets that convert into to_asset
+    #[returns(ConvertSourcesResponse)]
+    ConvertSources { to_asset: AssetInfo },
+    /// every registered from asset with its default ratio, ordered by the raw asset key
+    #[returns(ConvertPairsResponse)]
+    ConvertPairs {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    /// current holdings of the converter, available to fund conversions
+    #[returns(BalancesResponse)]
+    Balances { asset_infos: Vec<AssetInfo> },
 }
 
 #[cw_serde]
 pub enum Cw20HookMsg {
-    Convert {},
-    ConvertReverse { from: AssetInfo },
+    Convert {
+        to_asset: Option<AssetInfo>,
+        recipient: Option<Addr>,
+        minimum_receive: Option<Uint128>,
+    },
+    ConvertReverse {
+        from: AssetInfo,
+        recipient: Option<Addr>,
+    },
 }
 
 // We define a custom struct for each query response
 #[cw_serde]
 pub struct ConfigResponse {
     pub owner: Addr,
+    pub paused: bool,
 }
 
 #[cw_serde]
 pub struct ConvertInfoResponse {
     pub token_ratio: TokenRatio,
 }
+
+#[cw_serde]
+pub struct ConvertPairResponse {
+    pub from: AssetInfo,
+    pub token_ratio: TokenRatio,
+}
+
+#[cw_serde]
+pub struct ConvertPairsResponse {
+    pub pairs: Vec<ConvertPairResponse>,
+}
+
+#[cw_serde]
+pub struct ConvertSourcesResponse {
+    pub from_assets: Vec<AssetInfo>,
+}
+
+#[cw_serde]
+pub struct BalancesResponse {
+    pub balances: Vec<Asset>,
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{ConversionOverflowError, Decimal, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -9,6 +9,9 @@ pub enum ContractError {
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
+    #[error("{0}")]
+    ConversionOverflowError(#[from] ConversionOverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -50,9 +53,42 @@ pub enum ContractError {
         swap_amount: Uint128,
     },
 
+    #[error("Swap volume exceeds the remaining capacity of the current window: {remaining}")]
+    SwapVolumeExceeded { remaining: Uint128 },
+
+    #[error("Swap would decrease the invariant of the pool")]
+    InvariantViolation {},
+
+    #[error("Stable swap invariant did not converge")]
+    StableSwapNotConverged {},
+
+    #[error("Initial liquidity must mint more than {minimum_liquidity} shares")]
+    MinimumLiquidity { minimum_liquidity: Uint128 },
+
+    #[error("Commission rate {commission_rate} is outside of the allowed bounds")]
+    CommissionRateOutOfBounds { commission_rate: String },
+
+    #[error("Commission rate bounds must satisfy min <= max <= 1")]
+    InvalidCommissionRateBounds {},
+
+    #[error("Commission rate can only be chosen once its bounds are configured")]
+    CommissionRateNotBounded {},
+
+    #[error("No oracle exchange rate found for {asset}")]
+    OracleRateNotFound { asset: String },
+
     #[error("must provide operations")]
     NoSwapOperation {},
 
+    #[error("route has {length} operations, the max is {max_route_length}")]
+    RouteTooLong {
+        length: usize,
+        max_route_length: u32,
+    },
+
+    #[error("limit order contract is not configured")]
+    LimitOrderNotConfigured {},
+
     #[error("invalid cw20 hook message")]
     InvalidCw20HookMessage {},
 
@@ -62,9 +98,24 @@ pub enum ContractError {
     #[error("Order book pair already exists")]
     OrderBookAlreadyExists {},
 
+    #[error("Order book base and quote must be different assets recoverable from the pair key")]
+    InvalidOrderBookPair {},
+
     #[error("Order asset must not be zero")]
     AssetMustNotBeZero {},
 
+    #[error("Cancel amount {amount} exceeds the unfilled offer amount {remaining}")]
+    CancelAmountExceeded { amount: Uint128, remaining: Uint128 },
+
+    #[error("Order {order_id} has been partially filled and can not be amended")]
+    AmendFilledOrder { order_id: u64 },
+
+    #[error("Minimum fill amount {min_fill_amount} exceeds the offer amount {offer_amount}")]
+    InvalidMinFillAmount {
+        min_fill_amount: Uint128,
+        offer_amount: Uint128,
+    },
+
     #[error("Order {order_id} has already fulfilled")]
     OrderFulfilled { order_id: u64 },
 
@@ -73,6 +124,30 @@ pub enum ContractError {
         quote_coin: String,
         min_quote_amount: Uint128,
     },
+    #[error("Fill or kill order could not be fully matched")]
+    FillOrKillUnfulfilled {},
+
+    #[error("Trigger order must be good till canceled")]
+    InvalidTriggerOrderType {},
+
+    #[error("Order expiry {expires_at} is not in the future")]
+    InvalidOrderExpiry { expires_at: u64 },
+
+    #[error("Price {price} is not a multiple of the pair precision {precision}")]
+    InvalidPricePrecision { price: Decimal, precision: Decimal },
+
+    #[error("Client order id {client_order_id} was already used by order {order_id}")]
+    DuplicateClientOrderId {
+        client_order_id: String,
+        order_id: u64,
+    },
+
+    #[error("No executor has matched volume on the pair to share the bonus")]
+    NoMatchedVolume {},
+
+    #[error("Post-only order would take liquidity from the order book")]
+    WouldTakeLiquidity {},
+
     #[error("The contract upgrading process has not completed yet. Please come back after a while, thank you for your patience!")]
     ContractUpgrade {},
 }
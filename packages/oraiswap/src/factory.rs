@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{Addr, Binary, Decimal};
 
 use crate::asset::{AssetInfo, PairInfo};
 
@@ -19,16 +19,28 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
+        /// default commission rate of new pairs
+        commission_rate: Option<String>,
+        /// every pair commission rate must stay within these bounds, min <= max <= 1
+        min_commission_rate: Option<Decimal>,
+        max_commission_rate: Option<Decimal>,
     },
     /// CreatePair instantiates pair contract
     CreatePair {
         /// Asset infos
         asset_infos: [AssetInfo; 2],
         pair_admin: Option<String>,
+        /// overrides the default commission rate for this pair, only once both bounds are configured
+        commission_rate: Option<String>,
     },
     AddPair {
         pair_info: PairInfo,
     },
+    /// UpdatePairStatus disables a pair in the registry without deleting it, or enables it again
+    UpdatePairStatus {
+        asset_infos: [AssetInfo; 2],
+        enabled: bool,
+    },
     MigrateContract {
         contract_addr: String,
         new_code_id: u64,
@@ -47,6 +59,8 @@ pub enum QueryMsg {
     Pairs {
         start_after: Option<[AssetInfo; 2]>,
         limit: Option<u32>,
+        /// disabled pairs are left out unless it is true
+        include_disabled: Option<bool>,
     },
 }
 
@@ -57,6 +71,9 @@ pub struct ConfigResponse {
     pub oracle_addr: Addr,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    pub commission_rate: String,
+    pub min_commission_rate: Option<Decimal>,
+    pub max_commission_rate: Option<Decimal>,
 }
 
 /// We currently take no arguments for migrations
@@ -1,6 +1,8 @@
 use crate::asset::{Asset, AssetInfo};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, CanonicalAddr, Decimal, Uint128};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CanonicalAddr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg,
+};
 use cw20::Cw20ReceiveMsg;
 
 #[cw_serde]
@@ -11,6 +13,39 @@ pub struct ContractInfo {
     pub admin: CanonicalAddr,
     pub commission_rate: String,
     pub reward_address: CanonicalAddr,
+    // policy applied when a fill's commission is below the minimum unit
+    pub min_commission: Option<MinCommission>,
+    // accrued executor reward paid out right in the matching transaction, default is 1000000
+    pub auto_distribute_threshold: Option<Uint128>,
+    // maximum number of orders loaded by one matching call, unbounded when not set
+    pub max_orders_per_match: Option<u64>,
+    // oracle used to value executor rewards against gas costs
+    pub oracle_addr: Option<CanonicalAddr>,
+    // partially filled orders left with less offer than this are refunded and removed
+    pub dust_threshold: Option<Uint128>,
+    // cancel the newer order of a self-trade instead of only skipping it in the matching round
+    pub stp_cancel_newest: Option<bool>,
+    // commission of the resting and the incoming side, commission_rate is used when not set
+    pub maker_commission_rate: Option<String>,
+    pub taker_commission_rate: Option<String>,
+    // bounds of the per-pair commission rates, only a rate above one is rejected when not set
+    pub min_commission_rate: Option<Decimal>,
+    pub max_commission_rate: Option<Decimal>,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum MinCommissionPolicy {
+    /// leave the fill on the book until it is large enough to pay commission
+    Skip,
+    /// charge the minimum unit as commission
+    RoundUp,
+}
+
+#[cw_serde]
+pub struct MinCommission {
+    pub unit: Uint128,
+    pub policy: MinCommissionPolicy,
 }
 
 #[cw_serde]
@@ -29,6 +64,19 @@ impl OrderDirection {
     }
 }
 
+/// time in force of a submitted order
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum OrderType {
+    /// rest on the book until filled, canceled or expired
+    #[default]
+    Gtc,
+    /// match what crosses the book right away and refund the remainder
+    Ioc,
+    /// match the whole order right away or revert
+    Fok,
+}
+
 #[cw_serde]
 #[derive(Copy)]
 pub enum OrderStatus {
@@ -36,6 +84,8 @@ pub enum OrderStatus {
     PartialFilled,
     Fulfilled,
     Cancel,
+    /// trigger order waiting for the mid price to cross its trigger price
+    Pending,
 }
 
 impl OrderStatus {
@@ -45,6 +95,7 @@ impl OrderStatus {
             OrderStatus::PartialFilled => &[1u8],
             OrderStatus::Fulfilled => &[2u8],
             OrderStatus::Cancel => &[3u8],
+            OrderStatus::Pending => &[4u8],
         }
     }
 }
@@ -61,6 +112,8 @@ pub struct InstantiateMsg {
     pub admin: Option<Addr>,
     pub commission_rate: Option<String>,
     pub reward_address: Option<Addr>,
+    pub maker_commission_rate: Option<String>,
+    pub taker_commission_rate: Option<String>,
 }
 
 #[cw_serde]
@@ -74,6 +127,21 @@ pub enum ExecuteMsg {
     UpdateConfig {
         reward_address: Option<Addr>,
         commission_rate: Option<String>,
+        min_commission: Option<MinCommission>,
+        auto_distribute_threshold: Option<Uint128>,
+        max_orders_per_match: Option<u64>,
+        oracle_addr: Option<Addr>,
+        dust_threshold: Option<Uint128>,
+        stp_cancel_newest: Option<bool>,
+        /// every pair commission rate must stay within these bounds, min <= max <= 1
+        min_commission_rate: Option<Decimal>,
+        max_commission_rate: Option<Decimal>,
+    },
+
+    /// Restrict matching to listed executors, anyone can match while the list is empty
+    UpdateExecutors {
+        add: Vec<Addr>,
+        remove: Vec<Addr>,
     },
 
     CreateOrderBookPair {
@@ -81,6 +149,29 @@ pub enum ExecuteMsg {
         quote_coin_info: AssetInfo,
         spread: Option<Decimal>,
         min_quote_coin_amount: Uint128,
+        /// overrides the commission rates of the contract for this pair
+        commission_rate: Option<String>,
+        /// order prices must be a multiple of it, any price is accepted if none
+        precision: Option<Decimal>,
+    },
+
+    /// Create multiple order book pairs at once, fails if any of them already exists
+    CreateOrderBookPairs {
+        pairs: Vec<CreatePairParams>,
+    },
+
+    /// Create an order book and place the seed orders of the admin on it in the same tx.
+    /// The funds must pay every seed order exactly, nothing is created if any seed fails
+    CreateOrderBookPairAndSeed {
+        base_coin_info: AssetInfo,
+        quote_coin_info: AssetInfo,
+        spread: Option<Decimal>,
+        min_quote_coin_amount: Uint128,
+        /// overrides the commission rates of the contract for this pair
+        commission_rate: Option<String>,
+        /// order prices must be a multiple of it, any price is accepted if none
+        precision: Option<Decimal>,
+        seed_orders: Vec<SubmitOrderItem>,
     },
 
     ///////////////////////
@@ -89,17 +180,103 @@ pub enum ExecuteMsg {
     SubmitOrder {
         direction: OrderDirection, // default is buy, with sell then it is reversed
         assets: [Asset; 2],
+        /// reject the order instead of resting it if it would cross the book
+        post_only: Option<bool>,
+        /// retrying with the same id within a day is rejected instead of placing the order twice
+        client_order_id: Option<String>,
+        /// contract notified with FillHookMsg::OrderFilled whenever the order is filled
+        fill_callback: Option<Hook>,
+        /// block time in seconds after which the order is canceled instead of matched
+        expires_at: Option<u64>,
+        /// default is Gtc
+        order_type: Option<OrderType>,
+        /// smallest fill in offer asset the order accepts, unless the fill completes the order.
+        /// unlike min_quote_coin_amount, which bounds the size of the whole order, it bounds each fill
+        min_fill_amount: Option<Uint128>,
+        /// keep the order pending until the mid price reaches it: at or above for a buy, at or below for a sell
+        trigger_price: Option<Decimal>,
+    },
+
+    /// Submit a gtc order by its quote amount and limit price, the base amount is derived by the
+    /// contract. A buy pays quote_asset, a sell asks for it. Funds above the derived offer are refunded
+    SubmitOrderByQuote {
+        direction: OrderDirection,
+        base_coin_info: AssetInfo,
+        quote_asset: Asset,
+        price: Decimal,
+    },
+
+    /// Submit a batch of orders at once, fails if any of them is rejected
+    SubmitOrders {
+        orders: Vec<SubmitOrderItem>,
     },
 
     CancelOrder {
         order_id: u64,
         asset_infos: [AssetInfo; 2],
+        /// refund the unfilled offer to this address instead of the bidder
+        refund_to: Option<Addr>,
+    },
+
+    /// Cancel every unfilled order of the sender on the pair, optionally only one side of the book.
+    /// At most 100 orders are cancelled per call
+    CancelAllOrders {
+        asset_infos: [AssetInfo; 2],
+        direction: Option<OrderDirection>,
+    },
+
+    /// Cancel `amount` of the unfilled offer, the rest of the order keeps resting
+    CancelOrderPartial {
+        order_id: u64,
+        asset_infos: [AssetInfo; 2],
+        amount: Uint128,
+    },
+
+    /// Change the price and size of an open order, keeping its order id.
+    /// A bigger offer must be paid with the funds, a smaller one is refunded
+    AmendOrder {
+        order_id: u64,
+        asset_infos: [AssetInfo; 2],
+        new_price: Decimal,
+        new_ask_amount: Uint128,
+    },
+
+    /// Admin force-cancels an order, refunding the unfilled offer to its bidder
+    AdminCancelOrder {
+        asset_infos: [AssetInfo; 2],
+        order_id: u64,
     },
 
     /// Arbitrager execute order book pair
     ExecuteOrderBookPair {
         asset_infos: [AssetInfo; 2],
+        /// most orders and ticks per side loaded in this call, the orders_remaining attribute tells whether to call again
         limit: Option<u32>,
+        /// leave orders that can not be settled open instead of reverting the whole matching
+        best_effort: Option<bool>,
+    },
+
+    /// Open the pending trigger orders of the pair whose trigger price the mid price has crossed
+    ActivateTriggers {
+        asset_infos: [AssetInfo; 2],
+    },
+
+    /// Executor withdraws its whole accrued reward of the pair, regardless of the auto distribution threshold
+    ClaimExecutorReward {
+        asset_infos: [AssetInfo; 2],
+    },
+
+    /// Admin shares the sent native bonus between the executors of the pair by their matched volume,
+    /// then resets the volumes
+    DistributeExecutorBonus {
+        asset_infos: [AssetInfo; 2],
+        bonus: Asset,
+    },
+
+    /// Admin sets the commission rate of a pair, none falls back to the contract rates
+    UpdatePairCommission {
+        asset_infos: [AssetInfo; 2],
+        commission_rate: Option<String>,
     },
 
     /// Arbitrager remove order book
@@ -108,14 +285,91 @@ pub enum ExecuteMsg {
     },
 }
 
+#[cw_serde]
+pub struct SubmitOrderItem {
+    pub direction: OrderDirection,
+    pub assets: [Asset; 2],
+}
+
+#[cw_serde]
+pub struct CreatePairParams {
+    pub base_coin_info: AssetInfo,
+    pub quote_coin_info: AssetInfo,
+    pub spread: Option<Decimal>,
+    pub min_quote_coin_amount: Uint128,
+    pub commission_rate: Option<String>,
+    pub precision: Option<Decimal>,
+}
+
 #[cw_serde]
 pub enum Cw20HookMsg {
     SubmitOrder {
         direction: OrderDirection,
         assets: [Asset; 2],
+        post_only: Option<bool>,
+        client_order_id: Option<String>,
+        fill_callback: Option<Hook>,
+        expires_at: Option<u64>,
+        order_type: Option<OrderType>,
+        min_fill_amount: Option<Uint128>,
+        trigger_price: Option<Decimal>,
+        /// with max_spread, reject the order if the mid price of the book moved further
+        /// than max_spread away from belief_price, like the protection of an amm swap
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+    },
+    /// the sent amount pays the derived offer, the surplus is refunded
+    SubmitOrderByQuote {
+        direction: OrderDirection,
+        base_coin_info: AssetInfo,
+        quote_asset: Asset,
+        price: Decimal,
+    },
+    /// the sent amount pays the offer increase of a cw20 order
+    AmendOrder {
+        order_id: u64,
+        asset_infos: [AssetInfo; 2],
+        new_price: Decimal,
+        new_ask_amount: Uint128,
+    },
+    /// the sent amount is the bonus shared between the executors of the pair, admin only
+    DistributeExecutorBonus { asset_infos: [AssetInfo; 2] },
+}
+
+/// callback registered on an order, msg is passed back untouched to the contract
+#[cw_serde]
+pub struct Hook {
+    pub contract_addr: Addr,
+    pub msg: Binary,
+}
+
+/// message sent to a fill callback, amounts are cumulative over the order lifetime
+#[cw_serde]
+pub enum FillHookMsg {
+    OrderFilled {
+        order_id: u64,
+        status: OrderStatus,
+        direction: OrderDirection,
+        offer_amount: Uint128,
+        ask_amount: Uint128,
+        filled_offer_amount: Uint128,
+        filled_ask_amount: Uint128,
+        msg: Binary,
     },
 }
 
+impl FillHookMsg {
+    /// wrap the fill into a message executed on the callback contract
+    pub fn into_cosmos_msg(self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&self)?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
 #[cw_serde]
 pub enum OrderFilter {
     Bidder(String), // filter by bidder
@@ -151,6 +405,14 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<i32>, // convert OrderBy to i32
     },
+    /// orders of a bidder in every order book, paginated by order id
+    #[returns(BidderOrdersResponse)]
+    OrdersByBidder {
+        bidder: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order_by: Option<i32>, // convert OrderBy to i32
+    },
     #[returns(TickResponse)]
     Tick {
         price: Decimal,
@@ -168,10 +430,78 @@ pub enum QueryMsg {
     },
     #[returns(LastOrderIdResponse)]
     LastOrderId {},
+    /// maker and taker of a trade recorded by the matching, trade ids are listed in the trade events
+    #[returns(TradeParticipantsResponse)]
+    TradeParticipants {
+        asset_infos: [AssetInfo; 2],
+        trade_id: u64,
+    },
+    /// the order id counter next to the highest order id stored for the pair, to check before a migration
+    #[returns(LastStoredOrderIdResponse)]
+    LastStoredOrderId { asset_infos: [AssetInfo; 2] },
     #[returns(OrderBookMatchableResponse)]
     OrderBookMatchable { asset_infos: [AssetInfo; 2] },
+    /// whether the relayer fees of matching now exceed the gas cost, both valued in orai.
+    /// gas_price is in orai per gas unit
+    #[returns(MatchingWorthwhileResponse)]
+    MatchingWorthwhile {
+        asset_infos: [AssetInfo; 2],
+        gas_price: Decimal,
+        estimated_gas: u64,
+    },
     #[returns(Decimal)]
     MidPrice { asset_infos: [AssetInfo; 2] },
+    /// total unfilled offer amount of all orders paid by asset_info across all pairs
+    #[returns(Asset)]
+    TotalEscrow { asset_info: AssetInfo },
+    /// bid-ask spread of the order book, None when either side is empty
+    #[returns(Option<SpreadResponse>)]
+    Spread { asset_infos: [AssetInfo; 2] },
+    /// top of both sides in one call, the fields of an empty side are None
+    #[returns(BestPricesResponse)]
+    BestPrices { asset_infos: [AssetInfo; 2] },
+    /// best ticks of both sides with their unfilled volume, max_ticks is applied per side
+    #[returns(BookSnapshotResponse)]
+    BookSnapshot {
+        asset_infos: [AssetInfo; 2],
+        max_ticks: Option<u32>,
+    },
+    /// per pair thresholds and fees a client needs before submitting an order
+    #[returns(PairLimitsResponse)]
+    PairLimits { asset_infos: [AssetInfo; 2] },
+    /// unfilled amounts aggregated per tick, best price first, at most 100 ticks
+    #[returns(Vec<TickDepth>)]
+    OrderBookDepth {
+        asset_infos: [AssetInfo; 2],
+        direction: OrderDirection,
+        limit: Option<u32>,
+    },
+    /// reward accrued by an executor on the pair and not distributed yet
+    #[returns(ExecutorResponse)]
+    ExecutorReward {
+        asset_infos: [AssetInfo; 2],
+        executor: Addr,
+    },
+    #[returns(ExecutorsResponse)]
+    Executors {
+        asset_infos: [AssetInfo; 2],
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// orders past their expiry that the next matching round will cancel
+    #[returns(OrdersResponse)]
+    ExpiredOrders {
+        asset_infos: [AssetInfo; 2],
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// trigger orders not activated yet, they are kept out of the ticks until then
+    #[returns(OrdersResponse)]
+    PendingOrders {
+        asset_infos: [AssetInfo; 2],
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -183,6 +513,16 @@ pub struct ContractInfoResponse {
     pub admin: Addr,
     pub commission_rate: String,
     pub reward_address: Addr,
+    pub min_commission: Option<MinCommission>,
+    pub auto_distribute_threshold: Option<Uint128>,
+    pub max_orders_per_match: Option<u64>,
+    pub oracle_addr: Option<Addr>,
+    pub dust_threshold: Option<Uint128>,
+    pub stp_cancel_newest: Option<bool>,
+    pub maker_commission_rate: Option<String>,
+    pub taker_commission_rate: Option<String>,
+    pub min_commission_rate: Option<Decimal>,
+    pub max_commission_rate: Option<Decimal>,
 }
 
 #[cw_serde]
@@ -201,8 +541,12 @@ pub struct OrderResponse {
 pub struct OrderBookResponse {
     pub base_coin_info: AssetInfo,
     pub quote_coin_info: AssetInfo,
+    /// every price of the book is the amount of this asset (the quote) paid for one unit of the base
+    pub price_denominated_in: AssetInfo,
     pub spread: Option<Decimal>,
     pub min_quote_coin_amount: Uint128,
+    pub commission_rate: Option<String>,
+    pub precision: Option<Decimal>,
 }
 
 #[cw_serde]
@@ -215,6 +559,30 @@ pub struct OrdersResponse {
     pub orders: Vec<OrderResponse>,
 }
 
+#[cw_serde]
+pub struct ExecutorResponse {
+    pub executor: Addr,
+    pub reward_assets: [Asset; 2],
+    /// base amount matched since the last bonus distribution
+    pub matched_volume: Uint128,
+}
+
+#[cw_serde]
+pub struct ExecutorsResponse {
+    pub executors: Vec<ExecutorResponse>,
+}
+
+#[cw_serde]
+pub struct BidderOrderResponse {
+    pub asset_infos: [AssetInfo; 2],
+    pub order: OrderResponse,
+}
+
+#[cw_serde]
+pub struct BidderOrdersResponse {
+    pub orders: Vec<BidderOrderResponse>,
+}
+
 #[cw_serde]
 pub struct TickResponse {
     pub price: Decimal,
@@ -226,16 +594,108 @@ pub struct TicksResponse {
     pub ticks: Vec<TickResponse>,
 }
 
+#[cw_serde]
+pub struct PairLimitsResponse {
+    pub base_coin_info: AssetInfo,
+    pub quote_coin_info: AssetInfo,
+    pub min_quote_coin_amount: Uint128,
+    pub spread: Option<Decimal>,
+    pub commission_rate: String,
+    // flat fee taken from the ask amount of each filled order, in base asset for buy orders
+    // and its quote equivalent at the order price for sell orders
+    pub relayer_fee: Uint128,
+}
+
+#[cw_serde]
+pub struct SnapshotTickResponse {
+    pub price: Decimal,
+    pub total_orders: u64,
+    // unfilled offer amount of all orders at this price
+    pub volume: Uint128,
+}
+
+#[cw_serde]
+pub struct TickDepth {
+    pub price: Decimal,
+    // unfilled amounts of all orders at this price
+    pub total_offer_amount: Uint128,
+    pub total_ask_amount: Uint128,
+    pub order_count: u64,
+}
+
+#[cw_serde]
+pub struct BookSnapshotResponse {
+    // best price first on both sides
+    pub buy_ticks: Vec<SnapshotTickResponse>,
+    pub sell_ticks: Vec<SnapshotTickResponse>,
+    pub total_orders: u64,
+}
+
 #[cw_serde]
 pub struct LastOrderIdResponse {
     pub last_order_id: u64,
 }
 
+#[cw_serde]
+pub struct TradeParticipantsResponse {
+    pub trade_id: u64,
+    pub maker_order_id: u64,
+    pub maker_addr: Addr,
+    pub taker_order_id: u64,
+    pub taker_addr: Addr,
+    pub taker_direction: OrderDirection,
+    /// quote per base
+    pub price: Decimal,
+    pub base_amount: Uint128,
+    pub quote_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LastStoredOrderIdResponse {
+    pub last_order_id: u64,
+    /// none when the pair has no order
+    pub last_stored_order_id: Option<u64>,
+}
+
 #[cw_serde]
 pub struct OrderBookMatchableResponse {
     pub is_matchable: bool,
+    /// prices the matching starts from, none if nothing crosses
+    pub best_buy_price: Option<Decimal>,
+    pub best_sell_price: Option<Decimal>,
+    /// base amount crossing at those prices, so keepers can size the gas of the matching
+    pub matchable_volume: Uint128,
 }
 
-/// We currently take no arguments for migrations
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MatchingWorthwhileResponse {
+    pub worthwhile: bool,
+    pub expected_reward: Uint128,
+    pub gas_cost: Uint128,
+    // zero when the gas cost is not covered
+    pub net_profit: Uint128,
+}
+
+#[cw_serde]
+pub struct SpreadResponse {
+    pub best_buy: Decimal,
+    pub best_sell: Decimal,
+    // (best_sell - best_buy) / mid_price, zero if the book is crossed
+    pub spread_pct: Decimal,
+}
+
+#[cw_serde]
+pub struct BestPricesResponse {
+    pub best_buy: Option<Decimal>,
+    pub best_sell: Option<Decimal>,
+    // best_sell - best_buy, zero if the book is crossed
+    pub spread: Option<Decimal>,
+    pub spread_pct: Option<Decimal>,
+}
+
+/// order books are migrated in pages, a page resumes after the last_pair_key attribute of the previous one
+#[cw_serde]
+pub struct MigrateMsg {
+    pub start_after: Option<Binary>,
+    pub limit: Option<u32>,
+}
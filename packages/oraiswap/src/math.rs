@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use cosmwasm_std::{Decimal, Decimal256, StdError, StdResult, Uint128, Uint256, Uint512};
 
 pub trait Converter128 {
     fn checked_div_decimal(&self, denominator: Decimal) -> StdResult<Self>
@@ -14,3 +14,136 @@ impl Converter128 for Uint128 {
             .map(|coeff| self.clone() * coeff)
     }
 }
+
+pub trait Isqrt {
+    /// square root rounded down
+    fn isqrt(&self) -> StdResult<Self>
+    where
+        Self: Sized;
+}
+
+impl Isqrt for Uint256 {
+    fn isqrt(&self) -> StdResult<Uint256> {
+        Ok(Uint256::try_from(isqrt(Uint512::from(*self)))?)
+    }
+}
+
+/// named apart from the inherent approximate Decimal256::sqrt, which would win in method calls
+pub trait Sqrt {
+    /// square root rounded down to the last decimal place
+    fn floor_sqrt(&self) -> StdResult<Self>
+    where
+        Self: Sized;
+}
+
+impl Sqrt for Decimal256 {
+    fn floor_sqrt(&self) -> StdResult<Decimal256> {
+        // sqrt(atomics / 10^18) * 10^18 = sqrt(atomics * 10^18), widened so it can not overflow
+        let atomics = self
+            .atomics()
+            .full_mul(Uint256::from(10u128.pow(Decimal256::DECIMAL_PLACES)));
+        Ok(Decimal256::new(Uint256::try_from(isqrt(atomics))?))
+    }
+}
+
+/// Newton's method from the first power of two above the root, so every iteration
+/// goes down and it stops at the floor of the root in a handful of integer steps
+fn isqrt(n: Uint512) -> Uint512 {
+    if n.is_zero() {
+        return n;
+    }
+
+    let mut bits = 0u32;
+    let mut rest = n;
+    while !rest.is_zero() {
+        rest = rest >> 1;
+        bits += 1;
+    }
+
+    let mut x = Uint512::one() << ((bits + 1) / 2);
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn assert_floor_root(n: Uint512, root: Uint512) {
+        assert!(root * root <= n);
+        assert!(n < (root + Uint512::one()) * (root + Uint512::one()));
+    }
+
+    // deterministic pseudo random values spread over every magnitude of Uint256
+    fn samples() -> Vec<Uint256> {
+        let mut seed = 0x2545_f491_4f6c_dd1du128;
+        let mut values = vec![
+            Uint256::zero(),
+            Uint256::one(),
+            Uint256::from(2u128),
+            Uint256::from(3u128),
+            Uint256::from(4u128),
+            Uint256::from(u128::MAX),
+            Uint256::MAX,
+        ];
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed
+        };
+        for shift in 0..256u32 {
+            let value = (Uint256::from(next()) << 128) + Uint256::from(next());
+            values.push(value >> shift);
+            values.push(Uint256::one() << shift);
+        }
+        values
+    }
+
+    #[test]
+    fn uint256_isqrt() {
+        assert_eq!(
+            Uint256::from(10000u128).isqrt().unwrap(),
+            Uint256::from(100u128)
+        );
+        assert_eq!(
+            Uint256::from(10001u128).isqrt().unwrap(),
+            Uint256::from(100u128)
+        );
+        assert_eq!(
+            Uint256::from(9999u128).isqrt().unwrap(),
+            Uint256::from(99u128)
+        );
+
+        for n in samples() {
+            let root = n.isqrt().unwrap();
+            assert_floor_root(Uint512::from(n), Uint512::from(root));
+        }
+    }
+
+    #[test]
+    fn decimal256_floor_sqrt() {
+        assert_eq!(
+            Decimal256::from_str("2.25").unwrap().floor_sqrt().unwrap(),
+            Decimal256::from_str("1.5").unwrap()
+        );
+        assert_eq!(
+            Decimal256::from_str("2").unwrap().floor_sqrt().unwrap(),
+            Decimal256::from_str("1.414213562373095048").unwrap()
+        );
+
+        for n in samples() {
+            let root = Decimal256::new(n).floor_sqrt().unwrap();
+            assert_floor_root(
+                n.full_mul(Uint256::from(10u128.pow(Decimal256::DECIMAL_PLACES))),
+                Uint512::from(root.atomics()),
+            );
+        }
+    }
+}
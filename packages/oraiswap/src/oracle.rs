@@ -14,6 +14,14 @@ pub struct InstantiateMsg {
     pub admin: Option<Addr>,
     pub min_rate: Option<Decimal>,
     pub max_rate: Option<Decimal>,
+    /// max fraction of a transfer that can be deducted as tax, default is 10%
+    pub max_tax_ratio: Option<Decimal>,
+    /// max age in seconds of an exchange rate before queries reject it, no check if not set
+    pub max_staleness: Option<u64>,
+    /// whitelisted feeders, the exchange rate is the median of their fresh submissions
+    pub feeders: Option<Vec<Addr>>,
+    /// seconds a feeder submission is counted in the median, default is 10 minutes
+    pub feed_ttl: Option<u64>,
 }
 
 #[cw_serde]
@@ -36,6 +44,23 @@ pub enum ExecuteMsg {
     UpdateTaxRate {
         rate: Decimal,
     },
+    UpdateMaxTaxRatio {
+        ratio: Decimal,
+    },
+    /// none disables the staleness check
+    UpdateMaxStaleness {
+        max_staleness: Option<u64>,
+    },
+    /// replace the whitelisted feeders, keep the current ttl if feed_ttl is not given
+    UpdateFeeders {
+        feeders: Vec<Addr>,
+        feed_ttl: Option<u64>,
+    },
+    /// submit the exchange rate of denom to Orai, only for whitelisted feeders
+    FeedPrice {
+        denom: String,
+        rate: Decimal,
+    },
 }
 
 /// QueryMsg is defines available query datas
@@ -55,6 +80,9 @@ pub enum OracleTreasuryQuery {
     TaxRate {},
     #[returns(TaxCapResponse)]
     TaxCap { denom: String },
+    /// preview the tax deducted when transferring amount of denom
+    #[returns(TaxResponse)]
+    Tax { amount: Uint128, denom: String },
 }
 
 #[cw_serde]
@@ -93,6 +121,12 @@ pub struct TaxCapResponse {
     pub cap: Uint128,
 }
 
+/// TaxResponse is data format returned from TreasuryRequest::Tax query
+#[cw_serde]
+pub struct TaxResponse {
+    pub tax: Uint128,
+}
+
 /// ExchangeRateItem is data format returned from OracleRequest::ExchangeRates query
 #[cw_serde]
 pub struct ExchangeRateItem {
@@ -125,6 +159,17 @@ pub struct ContractInfo {
     // constraint
     pub min_rate: Decimal,
     pub max_rate: Decimal,
+    // none for contracts instantiated before the tax clamp, use the default ratio
+    pub max_tax_ratio: Option<Decimal>,
+    // max age in seconds of an exchange rate, none to serve rates of any age
+    #[serde(default)]
+    pub max_staleness: Option<u64>,
+    // whitelisted price feeders
+    #[serde(default)]
+    pub feeders: Vec<CanonicalAddr>,
+    // none for the default ttl of the feeder submissions
+    #[serde(default)]
+    pub feed_ttl: Option<u64>,
 }
 
 /// ContractInfoResponse is data format returned from WasmRequest::ContractInfo query
@@ -137,6 +182,10 @@ pub struct ContractInfoResponse {
     pub admin: Addr,
     pub min_rate: Decimal,
     pub max_rate: Decimal,
+    pub max_tax_ratio: Decimal,
+    pub max_staleness: Option<u64>,
+    pub feeders: Vec<Addr>,
+    pub feed_ttl: u64,
 }
 
 /// We currently take no arguments for migrations
@@ -203,6 +252,20 @@ impl OracleContract {
         self.query(querier, request)
     }
 
+    pub fn query_tax<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        amount: Uint128,
+        denom: T,
+    ) -> StdResult<TaxResponse> {
+        let request = QueryMsg::Treasury(OracleTreasuryQuery::Tax {
+            amount,
+            denom: denom.into(),
+        });
+
+        self.query(querier, request)
+    }
+
     // this is for CEX
     pub fn query_exchange_rate<T: Into<String>>(
         &self,
@@ -5,7 +5,7 @@ use crate::{
     error::ContractError,
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal256, StdError, Uint256};
+use cosmwasm_std::{Decimal256, StdError, Uint256, Uint512};
 
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
@@ -14,6 +14,9 @@ use cw20::Cw20ReceiveMsg;
 /// in the future need to update ?
 pub const DEFAULT_COMMISSION_RATE: &str = "0.003";
 
+/// LP shares locked in the pair on the first deposit, so the share price can not be inflated by a donation
+pub const MINIMUM_LIQUIDITY: Uint128 = Uint128::new(1000);
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Asset infos
@@ -25,6 +28,62 @@ pub struct InstantiateMsg {
     pub oracle_addr: Addr,
 
     pub commission_rate: Option<String>,
+
+    /// Optional swap volume cap per window, default is unlimited
+    pub swap_volume_limit: Option<SwapVolumeLimit>,
+
+    /// Who keeps the spread of a swap, default is the pool
+    pub spread_policy: Option<SpreadPolicy>,
+
+    /// Invariant used to price swaps, default is the constant product
+    pub curve: Option<CurveType>,
+
+    /// Share of the commission sent to a treasury, default is none
+    pub protocol_fee: Option<ProtocolFee>,
+}
+
+/// ProtocolFee routes rate of the commission of every swap to the treasury, the rest stays in the pool
+#[cw_serde]
+pub struct ProtocolFee {
+    pub rate: Decimal,
+    pub treasury: Addr,
+}
+
+/// highest amplification coefficient accepted for a stable pair
+pub const MAX_AMP: u64 = 10000;
+
+/// Newton iterations allowed before the stable swap solvers give up
+const STABLE_MAX_ITERATIONS: usize = 255;
+
+/// CurveType decides the invariant the pool prices swaps with
+#[cw_serde]
+pub enum CurveType {
+    /// x * y = k
+    ConstantProduct,
+    /// StableSwap invariant for pegged assets, a higher amp keeps the price flat around 1:1 for longer
+    Stable { amp: u64 },
+}
+
+/// SpreadPolicy decides how the spread (price impact) of a swap is split between the pool and the trader
+#[cw_serde]
+pub enum SpreadPolicy {
+    /// the whole spread stays in the pool for liquidity providers
+    Retain,
+    /// swaps offering at most max_offer_amount get rebate_rate of their spread back,
+    /// capped so the constant product never decreases
+    Rebate {
+        max_offer_amount: Uint128,
+        rebate_rate: Decimal,
+    },
+}
+
+/// SwapVolumeLimit caps the offer volume that can be swapped within a window of blocks
+#[cw_serde]
+pub struct SwapVolumeLimit {
+    /// window length in blocks, 1 means per block
+    pub window: u64,
+    /// maximum offer amount per window, indexed by the pair asset_infos order
+    pub max_volumes: [Uint128; 2],
 }
 
 #[cw_serde]
@@ -75,8 +134,30 @@ pub enum QueryMsg {
     Pool {},
     #[returns(SimulationResponse)]
     Simulation { offer_asset: Asset },
+    /// when slippage is given, the returned offer amount is inflated by the buffer
     #[returns(ReverseSimulationResponse)]
-    ReverseSimulation { ask_asset: Asset },
+    ReverseSimulation {
+        ask_asset: Asset,
+        slippage: Option<Decimal>,
+    },
+    /// how far the oracle value of the reserves is from an even 50/50 split
+    #[returns(ImbalanceResponse)]
+    Imbalance {},
+    #[returns(SpreadPolicy)]
+    SpreadPolicy {},
+    #[returns(CurveType)]
+    Curve {},
+    #[returns(Option<ProtocolFee>)]
+    ProtocolFee {},
+    /// marginal price of offer_asset_info in the other asset, for quoting without an amount
+    #[returns(SpotPriceResponse)]
+    SpotPrice { offer_asset_info: AssetInfo },
+    /// price sums accumulated up to the last swap or liquidity change, sample twice for a time weighted average
+    #[returns(CumulativePricesResponse)]
+    CumulativePrices {},
+    /// value of one LP token in reference_asset, priced with the oracle exchange rates
+    #[returns(LpPriceResponse)]
+    LpPrice { reference_asset: AssetInfo },
 }
 
 // We define a custom struct for each query response
@@ -107,6 +188,42 @@ pub struct ReverseSimulationResponse {
     pub commission_amount: Uint128,
 }
 
+/// SpotPriceResponse returns the ask amount paid per offer unit for an infinitely small swap
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal,
+    /// spot_price after the commission is deducted
+    pub spot_price_with_commission: Decimal,
+}
+
+/// ImbalanceResponse values both reserves in orai using the oracle exchange rates
+#[cw_serde]
+pub struct ImbalanceResponse {
+    pub values: [Uint128; 2],
+    /// absolute distance of the first asset's value share from 50%
+    pub deviation: Decimal,
+    /// true when the first asset holds less than half of the pool value
+    pub negative: bool,
+}
+
+/// the TWAP between two samples is (cumulative_b - cumulative_a) / (last_block_time_b - last_block_time_a)
+#[cw_serde]
+pub struct CumulativePricesResponse {
+    pub assets: [Asset; 2],
+    /// sum of the asset 1 per asset 0 price in Decimal256 atomics for every second, wraps on overflow
+    pub price0_cumulative: Uint256,
+    /// sum of the asset 0 per asset 1 price in Decimal256 atomics for every second, wraps on overflow
+    pub price1_cumulative: Uint256,
+    pub last_block_time: u64,
+}
+
+#[cw_serde]
+pub struct LpPriceResponse {
+    pub reference_asset: AssetInfo,
+    /// zero while no LP token has been minted
+    pub price: Decimal,
+}
+
 /// We currently take no arguments for migrations
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -130,25 +247,23 @@ pub fn compute_swap(
     // ask_amount = (ask_pool - cp / (offer_pool + offer_amount)) * (1 - commission_rate)
     let cp = offer_pool * ask_pool;
 
-    let return_amount = ask_pool - cp / (offer_pool + offer_amount);
+    let return_amount = ask_pool.checked_sub(cp / (offer_pool + offer_amount))?;
 
-    // calculate spread & commission
-    let spread_amount = offer_amount.multiply_ratio(ask_pool, offer_pool) - return_amount;
+    // calculate spread & commission, rounding can push the return above the spot amount
+    let spread_amount = offer_amount
+        .multiply_ratio(ask_pool, offer_pool)
+        .checked_sub(return_amount)?;
 
     let commission_amount = return_amount * commission_rate;
 
     // commission will be absorbed to pool
-    let return_amount = return_amount - commission_amount;
+    let return_amount = return_amount.checked_sub(commission_amount)?;
+
+    // results past u128::MAX for large pools are rejected instead of being truncated
     Ok((
-        return_amount
-            .try_into()
-            .map_err(|err| StdError::from(err))?,
-        spread_amount
-            .try_into()
-            .map_err(|err| StdError::from(err))?,
-        commission_amount
-            .try_into()
-            .map_err(|err| StdError::from(err))?,
+        Uint128::try_from(return_amount)?,
+        Uint128::try_from(spread_amount)?,
+        Uint128::try_from(commission_amount)?,
     ))
 }
 
@@ -191,12 +306,227 @@ pub fn compute_offer_amount(
     }
 
     Ok((
-        offer_amount.try_into().map_err(|err| StdError::from(err))?,
-        spread_amount
-            .try_into()
-            .map_err(|err| StdError::from(err))?,
-        commission_amount
+        Uint128::try_from(offer_amount)?,
+        Uint128::try_from(spread_amount)?,
+        Uint128::try_from(commission_amount)?,
+    ))
+}
+
+impl CurveType {
+    pub fn compute_swap(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        offer_amount: Uint128,
+        commission_rate: Decimal256,
+    ) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+        match self {
+            CurveType::ConstantProduct => {
+                compute_swap(offer_pool, ask_pool, offer_amount, commission_rate)
+            }
+            CurveType::Stable { amp } => {
+                compute_stable_swap(*amp, offer_pool, ask_pool, offer_amount, commission_rate)
+            }
+        }
+    }
+
+    pub fn compute_offer_amount(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        ask_amount: Uint128,
+        commission_rate: Decimal256,
+    ) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+        match self {
+            CurveType::ConstantProduct => {
+                compute_offer_amount(offer_pool, ask_pool, ask_amount, commission_rate)
+            }
+            CurveType::Stable { amp } => {
+                compute_stable_offer_amount(*amp, offer_pool, ask_pool, ask_amount, commission_rate)
+            }
+        }
+    }
+
+    /// the largest return of offer_amount that keeps the invariant, rounding the remaining ask pool up
+    pub fn max_return(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        offer_amount: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let new_offer_pool = offer_pool.checked_add(offer_amount)?;
+        let min_ask_pool = match self {
+            CurveType::ConstantProduct => {
+                let k = Uint256::from(offer_pool) * Uint256::from(ask_pool);
+                let new_offer_pool = Uint256::from(new_offer_pool);
+                (k + new_offer_pool - Uint256::one()) / new_offer_pool
+            }
+            CurveType::Stable { amp } => {
+                let d = stable_invariant(*amp, offer_pool.into(), ask_pool.into())?;
+                stable_y(*amp, new_offer_pool.into(), d)? + Uint256::one()
+            }
+        };
+
+        Ok(Uint256::from(ask_pool)
+            .saturating_sub(min_ask_pool)
             .try_into()
-            .map_err(|err| StdError::from(err))?,
+            .map_err(StdError::from)?)
+    }
+
+    /// the ask amount paid per offer unit as the offer amount goes to zero
+    pub fn spot_price(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+    ) -> Result<Decimal, ContractError> {
+        if offer_pool.is_zero() || ask_pool.is_zero() {
+            return Err(ContractError::OfferPoolIsZero {});
+        }
+
+        match self {
+            CurveType::ConstantProduct => Ok(Decimal::from_ratio(ask_pool, offer_pool)),
+            CurveType::Stable { amp } => {
+                // -dy/dx of the invariant, both sides divided by x * y to stay within Uint512:
+                // (16 * amp * x * y + D^3 / x) / (16 * amp * x * y + D^3 / y)
+                let d = Uint512::from(stable_invariant(*amp, offer_pool.into(), ask_pool.into())?);
+                let (x, y) = (Uint512::from(offer_pool), Uint512::from(ask_pool));
+                let d_cube = d * d * d;
+                let ann_xy = Uint512::from(*amp as u128 * 16) * x * y;
+                let numerator = ann_xy + d_cube / x;
+                let denominator = ann_xy + d_cube / y;
+
+                let atomics = numerator * Uint512::from(Decimal::one().atomics()) / denominator;
+                Ok(Decimal::new(atomics.try_into().map_err(StdError::from)?))
+            }
+        }
+    }
+
+    /// the value the pool must not lose on a swap, x * y for the constant product and D for the stable curve
+    pub fn invariant(&self, pool0: Uint128, pool1: Uint128) -> Result<Uint256, ContractError> {
+        match self {
+            CurveType::ConstantProduct => Ok(Uint256::from(pool0) * Uint256::from(pool1)),
+            CurveType::Stable { amp } => stable_invariant(*amp, pool0.into(), pool1.into()),
+        }
+    }
+}
+
+/// D of the two coin StableSwap invariant
+/// amp * 4 * (x + y) + D = amp * 4 * D + D^3 / (4 * x * y), solved with Newton's method
+pub fn stable_invariant(amp: u64, x: Uint256, y: Uint256) -> Result<Uint256, ContractError> {
+    if x.is_zero() || y.is_zero() {
+        return Ok(Uint256::zero());
+    }
+
+    // the products below reach D^3, out of reach of Uint256 for large pools
+    let (x, y) = (Uint512::from(x), Uint512::from(y));
+    let two = Uint512::from(2u128);
+    let three = Uint512::from(3u128);
+    let ann = Uint512::from(amp as u128 * 4);
+    let sum = x + y;
+
+    let mut d = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let d_p = d * d / (x * two) * d / (y * two);
+        let prev_d = d;
+        d = (ann * sum + d_p * two) * d / ((ann - Uint512::one()) * d + d_p * three);
+        if d.max(prev_d) - d.min(prev_d) <= Uint512::one() {
+            return Ok(Uint256::try_from(d).map_err(StdError::from)?);
+        }
+    }
+
+    Err(ContractError::StableSwapNotConverged {})
+}
+
+/// the balance of the other coin keeping the invariant at d once one coin balance is x
+fn stable_y(amp: u64, x: Uint256, d: Uint256) -> Result<Uint256, ContractError> {
+    let (x, d) = (Uint512::from(x), Uint512::from(d));
+    let two = Uint512::from(2u128);
+    let ann = Uint512::from(amp as u128 * 4);
+
+    // y^2 + (b - D) * y = c
+    let c = d * d / (x * two) * d / (ann * two);
+    let b = x + d / ann;
+
+    let mut y = d;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let prev_y = y;
+        y = (y * y + c) / (y * two + b).checked_sub(d)?;
+        if y.max(prev_y) - y.min(prev_y) <= Uint512::one() {
+            return Ok(Uint256::try_from(y).map_err(StdError::from)?);
+        }
+    }
+
+    Err(ContractError::StableSwapNotConverged {})
+}
+
+pub fn compute_stable_swap(
+    amp: u64,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    commission_rate: Decimal256,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    if offer_pool.is_zero() {
+        return Err(ContractError::OfferPoolIsZero {});
+    }
+
+    let d = stable_invariant(amp, offer_pool.into(), ask_pool.into())?;
+    let new_ask_pool = stable_y(amp, offer_pool.checked_add(offer_amount)?.into(), d)?;
+
+    // one unit less than the solver precision so the invariant can not shrink
+    let return_amount = Uint256::from(ask_pool).saturating_sub(new_ask_pool + Uint256::one());
+
+    // at the peg one offer unit returns one ask unit
+    let spread_amount = Uint256::from(offer_amount).saturating_sub(return_amount);
+
+    let commission_amount = return_amount * commission_rate;
+
+    // commission will be absorbed to pool
+    let return_amount = return_amount - commission_amount;
+    Ok((
+        Uint128::try_from(return_amount)?,
+        Uint128::try_from(spread_amount)?,
+        Uint128::try_from(commission_amount)?,
+    ))
+}
+
+pub fn compute_stable_offer_amount(
+    amp: u64,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    ask_amount: Uint128,
+    commission_rate: Decimal256,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    let ask_amount: Uint256 = ask_amount.into();
+
+    let before_commission_deduction = ask_amount
+        * (Decimal256::one()
+            .checked_div(Decimal256::one().checked_sub(commission_rate)?)
+            .map_err(|err| StdError::generic_err(err.to_string()))?);
+
+    let d = stable_invariant(amp, offer_pool.into(), ask_pool.into())?;
+    let new_offer_pool = stable_y(
+        amp,
+        Uint256::from(ask_pool).checked_sub(before_commission_deduction)?,
+        d,
+    )?;
+
+    // rounded up by the solver precision, the mirror of compute_stable_swap
+    let offer_amount = (new_offer_pool + Uint256::one()).checked_sub(offer_pool.into())?;
+
+    // at the peg one offer unit returns one ask unit
+    let spread_amount = offer_amount.saturating_sub(before_commission_deduction);
+
+    let commission_amount = before_commission_deduction * commission_rate;
+
+    // check small amount swap
+    if commission_amount.is_zero() {
+        return Err(ContractError::TooSmallOfferAmount {});
+    }
+
+    Ok((
+        Uint128::try_from(offer_amount)?,
+        Uint128::try_from(spread_amount)?,
+        Uint128::try_from(commission_amount)?,
     ))
 }
@@ -78,6 +78,7 @@ pub fn reverse_simulate(
         pair_addr,
         &PairQueryMsg::ReverseSimulation {
             ask_asset: ask_asset.clone(),
+            slippage: None,
         },
     )
 }
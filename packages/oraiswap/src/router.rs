@@ -9,10 +9,17 @@ use crate::asset::AssetInfo;
 pub struct InstantiateMsg {
     pub factory_addr: Addr,
     pub factory_addr_v2: Addr,
+    /// max operations in a route, default is 5
+    pub max_route_length: Option<u32>,
+    /// limit order contract used by the order book operations
+    pub limit_order_addr: Option<Addr>,
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub max_route_length: Option<u32>,
+    pub limit_order_addr: Option<Addr>,
+}
 
 #[cw_serde]
 pub enum SwapOperation {
@@ -21,12 +28,25 @@ pub enum SwapOperation {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
     },
+    /// fill against the limit orders priced better than the pool, the unfilled remainder
+    /// is swapped on the pool. asset_infos is [offer, ask]
+    OrderBookSwap { asset_infos: [AssetInfo; 2] },
 }
 
 impl SwapOperation {
+    pub fn get_offer_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::OraiSwap {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+            SwapOperation::OrderBookSwap { asset_infos } => asset_infos[0].clone(),
+        }
+    }
+
     pub fn get_target_asset_info(&self) -> AssetInfo {
         match self {
             SwapOperation::OraiSwap { ask_asset_info, .. } => ask_asset_info.clone(),
+            SwapOperation::OrderBookSwap { asset_infos } => asset_infos[1].clone(),
         }
     }
 }
@@ -83,12 +103,38 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub factory_addr: Addr,
     pub factory_addr_v2: Addr,
+    pub max_route_length: u32,
+    pub limit_order_addr: Option<Addr>,
 }
 
 // We define a custom struct for each query response
 #[cw_serde]
 pub struct SimulateSwapOperationsResponse {
+    /// return amount of the whole route
     pub amount: Uint128,
+    /// simulation of each operation, in the route order
+    pub hops: Vec<SwapOperationSimulation>,
+}
+
+#[cw_serde]
+pub struct SwapOperationSimulation {
+    pub operation: SwapOperation,
+    /// offer amount after tax
+    pub offer_amount: Uint128,
+    /// return amount after tax, offered to the next operation
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+    /// part of the operation filled by the limit order book, spread and commission are
+    /// those of the pool part. None for the pool operations
+    pub order_book_fill: Option<OrderBookFill>,
+}
+
+/// estimate of the limit order fill, at the tick prices less the commission
+#[cw_serde]
+pub struct OrderBookFill {
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
 }
 
 #[cw_serde]
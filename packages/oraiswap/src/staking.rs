@@ -13,6 +13,10 @@ pub struct InstantiateMsg {
     pub oracle_addr: Addr,
     pub factory_addr: Addr,
     pub base_denom: Option<String>,
+    /// seconds unbonded tokens wait before they can be claimed, default is 0 (paid out on unbond)
+    pub unbonding_period: Option<u64>,
+    /// router used to swap rewards when compounding, Compound is disabled until it is set
+    pub router_addr: Option<Addr>,
 }
 
 #[cw_serde]
@@ -26,6 +30,8 @@ pub enum ExecuteMsg {
         rewarder: Option<Addr>,
         owner: Option<Addr>,
         migrate_store_status: Option<bool>,
+        unbonding_period: Option<u64>,
+        router_addr: Option<Addr>,
     },
     RegisterAsset {
         staking_token: Addr,
@@ -52,11 +58,18 @@ pub enum ExecuteMsg {
         staking_token: Addr,
         amount: Uint128,
     },
+    /// Transfer the unbonded tokens whose unbonding period is over
+    ClaimUnbonded {},
     /// Withdraw pending rewards
     Withdraw {
         // If the asset token is not given, then all rewards are withdrawn
         staking_token: Option<Addr>,
     },
+    /// Withdraw pending rewards of the staker's pools page by page, ordered by staking token
+    WithdrawAll {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
     // Withdraw for others in this pool, such as when rewards per second are changed for the pool
     WithdrawOthers {
         staking_token: Option<Addr>,
@@ -74,12 +87,35 @@ pub enum ExecuteMsg {
         staker_addr: Addr,
         prev_staking_token_amount: Uint128,
     },
+    /// Withdraws the pending rewards of the pool, swaps them into the pair assets,
+    /// provides liquidity and bonds the LP tokens. The rewards are paid out instead if any step fails
+    Compound {
+        staking_token: Addr,
+        /// minimum amounts of the pair assets to provide, guards the swaps against slippage
+        minimum_receive: Option<[Uint128; 2]>,
+    },
+    /// Hook to swap the withdrawn rewards into the pair assets
+    CompoundHook {
+        staking_token: Addr,
+        staker_addr: Addr,
+        minimum_receive: Option<[Uint128; 2]>,
+    },
+    /// Hook to provide the swapped assets as liquidity
+    CompoundProvideHook {
+        staking_token: Addr,
+        staker_addr: Addr,
+        prev_balances: [Uint128; 2],
+        minimum_receive: Option<[Uint128; 2]>,
+    },
 }
 
 #[cw_serde]
 pub enum Cw20HookMsg {
     // this call from LP token contract
-    Bond {},
+    Bond {
+        /// seconds to lock the whole position for, boosting its rewards up to 2.5x for a year
+        lock_for: Option<u64>,
+    },
 }
 
 /// We currently take no arguments for migrations
@@ -103,6 +139,9 @@ pub enum QueryMsg {
     PoolInfo { staking_token: Addr },
     #[returns(RewardsPerSecResponse)]
     RewardsPerSec { staking_token: Addr },
+    /// yearly rewards of the pool per bonded token, for each reward asset
+    #[returns(PoolAprResponse)]
+    PoolApr { staking_token: Addr },
     #[returns(RewardInfoResponse)]
     RewardInfo {
         staker_addr: Addr,
@@ -119,6 +158,15 @@ pub enum QueryMsg {
     },
     #[returns(Vec<QueryPoolInfoResponse>)]
     GetPoolsInformation {},
+    /// paginated version of GetPoolsInformation, start_after is a staking token
+    #[returns(Vec<QueryPoolInfoResponse>)]
+    Pools {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// tokens of the staker still waiting for their unbonding period, or ready to be claimed
+    #[returns(UnbondingResponse)]
+    Unbonding { staker: Addr },
     #[returns(Binary)]
     QueryOldStore { store_type: OldStoreType },
 }
@@ -131,6 +179,8 @@ pub struct ConfigResponse {
     pub oracle_addr: Addr,
     pub factory_addr: Addr,
     pub base_denom: String,
+    pub unbonding_period: u64,
+    pub router_addr: Option<Addr>,
 }
 
 #[cw_serde]
@@ -138,6 +188,19 @@ pub struct RewardsPerSecResponse {
     pub assets: Vec<Asset>,
 }
 
+#[cw_serde]
+pub struct PoolAprResponse {
+    pub staking_token: Addr,
+    /// zero while nothing is bonded
+    pub aprs: Vec<RewardAprResponse>,
+}
+
+#[cw_serde]
+pub struct RewardAprResponse {
+    pub info: AssetInfo,
+    pub apr: Decimal,
+}
+
 // We define a custom struct for each query response
 #[cw_serde]
 pub struct PoolInfoResponse {
@@ -167,6 +230,20 @@ pub struct RewardInfoResponseItem {
     pub should_migrate: Option<bool>,
 }
 
+#[cw_serde]
+pub struct UnbondingResponse {
+    pub staker: Addr,
+    pub entries: Vec<UnbondingEntryResponse>,
+}
+
+#[cw_serde]
+pub struct UnbondingEntryResponse {
+    pub staking_token: Addr,
+    pub amount: Uint128,
+    /// block time in seconds from which the amount can be claimed
+    pub release_time: u64,
+}
+
 #[cw_serde]
 pub struct RewardMsg {
     pub staking_token: Addr,
@@ -1,8 +1,8 @@
 use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_schema::serde::Serialize;
 use cosmwasm_std::{
-    coin, Addr, AllBalanceResponse, Attribute, BalanceResponse, BankQuery, Coin, Decimal, Empty,
-    QuerierWrapper, QueryRequest, StdResult, Uint128,
+    coin, Addr, AllBalanceResponse, Attribute, BalanceResponse, BankQuery, BlockInfo, Coin,
+    Decimal, Empty, QuerierWrapper, QueryRequest, StdResult, Uint128,
 };
 use std::collections::HashMap;
 
@@ -41,6 +41,7 @@ pub struct MockApp {
     pub token_id: u64,
     pub oracle_addr: Addr,
     pub factory_addr: Addr,
+    pub limit_order_addr: Addr,
 }
 
 impl MockApp {
@@ -76,6 +77,7 @@ impl MockApp {
             token_id: 0,
             oracle_addr: Addr::unchecked(""),
             factory_addr: Addr::unchecked(""),
+            limit_order_addr: Addr::unchecked(""),
             token_map: HashMap::new(),
         }
     }
@@ -123,6 +125,23 @@ impl MockApp {
         Ok(response)
     }
 
+    pub fn block_info(&self) -> BlockInfo {
+        self.app.block_info()
+    }
+
+    // move the clock forward without producing blocks, for intervals, unbonding and expiry
+    pub fn advance_time(&mut self, seconds: u64) {
+        self.app
+            .update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
+    // produce n blocks the same way every execute does, 5 seconds each
+    pub fn advance_blocks(&mut self, n: u64) {
+        for _ in 0..n {
+            self.app.update_block(next_block);
+        }
+    }
+
     pub fn query<T: DeserializeOwned, U: Serialize>(
         &self,
         contract_addr: Addr,
@@ -143,6 +162,10 @@ impl MockApp {
                     admin: None,
                     min_rate: None,
                     max_rate: None,
+                    max_tax_ratio: None,
+                    max_staleness: None,
+                    feeders: None,
+                    feed_ttl: None,
                 },
                 &[],
                 "oracle",
@@ -174,6 +197,77 @@ impl MockApp {
             .unwrap();
     }
 
+    pub fn set_limit_order_contract(&mut self, code: Box<dyn Contract<Empty>>) {
+        let code_id = self.upload(code);
+        self.limit_order_addr = self
+            .instantiate(
+                code_id,
+                Addr::unchecked(APP_OWNER),
+                &crate::limit_order::InstantiateMsg {
+                    name: None,
+                    version: None,
+                    admin: None,
+                    commission_rate: None,
+                    reward_address: None,
+                    maker_commission_rate: None,
+                    taker_commission_rate: None,
+                },
+                &[],
+                "limit order",
+            )
+            .unwrap();
+
+        // value the relayer fees with the oracle if it is already deployed
+        if !self.oracle_addr.as_str().is_empty() {
+            self.execute(
+                Addr::unchecked(APP_OWNER),
+                self.limit_order_addr.clone(),
+                &crate::limit_order::ExecuteMsg::UpdateConfig {
+                    reward_address: None,
+                    commission_rate: None,
+                    min_commission: None,
+                    auto_distribute_threshold: None,
+                    max_orders_per_match: None,
+                    oracle_addr: Some(self.oracle_addr.clone()),
+                    dust_threshold: None,
+                    stp_cancel_newest: None,
+                    min_commission_rate: None,
+                    max_commission_rate: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+    }
+
+    // configure the order book of [base, quote] on the limit order contract
+    pub fn create_orderbook_pair(
+        &mut self,
+        asset_infos: [AssetInfo; 2],
+        min_quote_coin_amount: Uint128,
+    ) -> Option<Addr> {
+        if !self.limit_order_addr.as_str().is_empty() {
+            let [base_coin_info, quote_coin_info] = asset_infos;
+            self.execute(
+                Addr::unchecked(APP_OWNER),
+                self.limit_order_addr.clone(),
+                &crate::limit_order::ExecuteMsg::CreateOrderBookPair {
+                    base_coin_info,
+                    quote_coin_info,
+                    spread: None,
+                    min_quote_coin_amount,
+                    commission_rate: None,
+                    precision: None,
+                },
+                &[],
+            )
+            .unwrap();
+            return Some(self.limit_order_addr.clone());
+        }
+
+        None
+    }
+
     // configure the oraiswap pair
     pub fn create_pairs(&mut self, asset_infos_list: &[[AssetInfo; 2]]) {
         for asset_infos in asset_infos_list.iter() {
@@ -190,6 +284,7 @@ impl MockApp {
                     &crate::factory::ExecuteMsg::CreatePair {
                         asset_infos: asset_infos.clone(),
                         pair_admin: Some("admin".to_string()),
+                        commission_rate: None,
                     },
                     &[],
                 )
@@ -425,14 +520,72 @@ impl MockApp {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::{testing::MOCK_CONTRACT_ADDR, Addr, Coin, Uint128};
+    use cosmwasm_std::testing::{mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coin, Addr, Coin, Uint128};
 
     use crate::{
-        asset::AssetInfo,
+        asset::{Asset, AssetInfo, ORAI_DENOM},
         querier::{query_supply, query_token_balance},
         testing::MockApp,
     };
 
+    #[test]
+    fn assert_sent_native_tokens() {
+        let native = |denom: &str, amount: u128| Asset {
+            info: AssetInfo::NativeToken {
+                denom: denom.to_string(),
+            },
+            amount: Uint128::from(amount),
+        };
+        // two orders paid in orai, one in usdt and a cw20 one that is not part of the funds
+        let assets = [
+            native(ORAI_DENOM, 100),
+            native(ORAI_DENOM, 50),
+            native("usdt", 30),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset"),
+                },
+                amount: Uint128::from(10u128),
+            },
+        ];
+
+        // exact match, summed by denom
+        let info = mock_info("addr0000", &[coin(150, ORAI_DENOM), coin(30, "usdt")]);
+        assert!(Asset::assert_sent_native_tokens(&assets, &info).is_ok());
+
+        // shortfall of a denom or a missing denom
+        let info = mock_info("addr0000", &[coin(100, ORAI_DENOM), coin(30, "usdt")]);
+        assert!(Asset::assert_sent_native_tokens(&assets, &info).is_err());
+        let info = mock_info("addr0000", &[coin(150, ORAI_DENOM)]);
+        assert!(Asset::assert_sent_native_tokens(&assets, &info).is_err());
+
+        // surplus of a denom or an extra denom
+        let info = mock_info("addr0000", &[coin(151, ORAI_DENOM), coin(30, "usdt")]);
+        assert!(Asset::assert_sent_native_tokens(&assets, &info).is_err());
+        let info = mock_info(
+            "addr0000",
+            &[coin(150, ORAI_DENOM), coin(30, "usdt"), coin(1, "atom")],
+        );
+        assert!(Asset::assert_sent_native_tokens(&assets, &info).is_err());
+    }
+
+    #[test]
+    fn advance_clock() {
+        let mut app = MockApp::new(&[]);
+        let start = app.block_info();
+
+        app.advance_time(3600);
+        let block = app.block_info();
+        assert_eq!(block.time, start.time.plus_seconds(3600));
+        assert_eq!(block.height, start.height);
+
+        app.advance_blocks(10);
+        let block = app.block_info();
+        assert_eq!(block.time, start.time.plus_seconds(3600 + 50));
+        assert_eq!(block.height, start.height + 10);
+    }
+
     #[test]
     fn token_balance_querier() {
         let mut app = MockApp::new(&[]);